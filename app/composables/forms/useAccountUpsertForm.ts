@@ -1,8 +1,9 @@
-import type { AccountDto, AccountTypeName, InstitutionSummaryDto } from "~/generated/bindings";
+import type { AccountClassification, AccountDto, AccountTypeSummaryDto, InstitutionSummaryDto } from "~/generated/bindings";
 import { parseDate } from "@internationalized/date";
 
 interface UseAccountUpsertFormParams {
   institutions: Ref<InstitutionSummaryDto[] | undefined>
+  accountTypes: Ref<AccountTypeSummaryDto[] | undefined>
   getDefaultInstitutionId?: () => number | null | undefined
 }
 
@@ -27,17 +28,25 @@ function normalizeInstitutionName(name: string) {
   return name.trim().toLowerCase();
 }
 
+// Built-in types that usually hold money owed; picking one suggests a liability.
+const LIABILITY_ACCOUNT_TYPE_NAMES = new Set(["credit card", "loan"]);
+
+export function suggestedAccountClassification(accountTypeName: string): AccountClassification {
+  return LIABILITY_ACCOUNT_TYPE_NAMES.has(accountTypeName.trim().toLowerCase()) ? "liability" : "asset";
+}
+
 export function useAccountUpsertForm(params: UseAccountUpsertFormParams) {
   const settings = useSettings();
   const defaults = (): RequiredOrUndefined<AccountFormInputValues> => {
     return {
-      account_type: "current",
+      account_type_id: undefined,
       currency_code: settings.value.default_display_currency_code,
       institution: undefined,
       name: undefined,
       account_classification: "asset",
       opened_date: undefined,
-      closed_date: undefined
+      closed_date: undefined,
+      tags: []
     };
   };
 
@@ -131,22 +140,16 @@ export function useAccountUpsertForm(params: UseAccountUpsertFormParams) {
   });
 
   const accountTypeItems = computed(() => {
-    const values: AccountTypeName[] = [
-      "current",
-      "savings",
-      "credit_card",
-      "isa",
-      "investment",
-      "pension",
-      "cash",
-      "loan"
-    ];
-    return values.map((value) => ({
-      label: ACCOUNT_TYPE_META[value].label,
-      value
+    return (params.accountTypes.value ?? []).map((accountType) => ({
+      label: accountType.name,
+      value: accountType.id
     }));
   });
 
+  function firstAccountTypeId() {
+    return params.accountTypes.value?.[0]?.id;
+  }
+
   const accountClassificationItems = [
     {
       label: "Asset",
@@ -163,25 +166,33 @@ export function useAccountUpsertForm(params: UseAccountUpsertFormParams) {
   function reset() {
     Object.assign(state, defaults());
     setExistingInstitution(defaultInstitutionFallback(institutionItems, params.getDefaultInstitutionId));
+    state.account_type_id = firstAccountTypeId();
     institutionSearchTerm.value = "";
   }
 
   function hydrateFromAccount(account: AccountDto) {
     state.institution = { kind: "existing", id: account.institution.id };
     state.name = account.name;
-    state.account_type = account.account_type.name;
+    state.account_type_id = account.account_type.id;
     state.currency_code = account.currency_code;
     state.account_classification = account.account_classification;
     state.opened_date = account.opened_date == null ? undefined : parseDate(account.opened_date);
     state.closed_date = account.closed_date == null ? undefined : parseDate(account.closed_date);
+    state.tags = account.tags.map((tag) => tag.name);
     institutionSearchTerm.value = "";
   }
 
-  watch(() => state.account_type, (type) => {
-    if (!type) return;
-    state.account_classification = type === "credit_card" || type === "loan" ? "liability" : "asset";
+  watch(() => state.account_type_id, (typeId) => {
+    const accountType = params.accountTypes.value?.find((t) => t.id === typeId);
+    if (!accountType) return;
+    state.account_classification = suggestedAccountClassification(accountType.name);
   }, { flush: "sync" });
 
+  watch(params.accountTypes, (accountTypes) => {
+    if (accountTypes?.some((t) => t.id === state.account_type_id)) return;
+    state.account_type_id = firstAccountTypeId();
+  }, { immediate: true });
+
   watch(institutionItems, (items) => {
     if (state.institution?.kind === "new") {
       const createdInstitutionId = findExistingInstitutionId(state.institution.input.name);
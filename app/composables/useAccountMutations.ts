@@ -1,4 +1,4 @@
-import type { AccountUpsertInput } from "~/generated/bindings";
+import type { AccountUpsertInput, SnapshotImportOptionsInput, SnapshotImportSourceInput } from "~/generated/bindings";
 
 import { useMutation, useQueryClient } from "@tanstack/vue-query";
 
@@ -10,6 +10,8 @@ export const useAccountMutations = () => {
     await Promise.all([
       queryClient.invalidateQueries({ queryKey: queryKeys.accounts.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.institutions.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.accountTypes.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.tags.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.dashboard.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.search.prefixes.root() })
     ]);
@@ -20,6 +22,19 @@ export const useAccountMutations = () => {
     onSuccess: invalidateAccountWrites
   }));
 
+  const createAccountFromImport = proxyRefs(useMutation({
+    mutationFn: async ({
+      account,
+      input,
+      options
+    }: {
+      account: AccountUpsertInput
+      input: SnapshotImportSourceInput
+      options: SnapshotImportOptionsInput
+    }) => api.accountCreateImportCommit(account, input, options),
+    onSuccess: invalidateAccountWrites
+  }));
+
   const updateAccount = proxyRefs(useMutation({
     mutationFn: async ({ accountId, input }: { accountId: number, input: AccountUpsertInput }) =>
       api.accountsUpdate(accountId, input),
@@ -35,6 +50,7 @@ export const useAccountMutations = () => {
 
   return {
     createAccount,
+    createAccountFromImport,
     updateAccount,
     deleteAccount,
     invalidateAccountWrites
@@ -2,8 +2,10 @@ import type {
   AccountSnapshotsCreateInput,
   AccountSnapshotsDeleteInput,
   AccountSnapshotUpdateInput,
+  CsvSnapshotImportSourceInput,
   SnapshotImportOptionsInput,
-  SnapshotImportSourceInput
+  SnapshotImportSourceInput,
+  WideSnapshotImportOptionsInput
 } from "~/generated/bindings";
 
 import { useMutation, useQueryClient } from "@tanstack/vue-query";
@@ -59,10 +61,24 @@ export const useAccountSnapshotMutations = () => {
     onSuccess: invalidateSnapshotWrites
   }));
 
+  const importWideSnapshots = proxyRefs(useMutation({
+    mutationFn: async ({ input, options }: { input: CsvSnapshotImportSourceInput, options: WideSnapshotImportOptionsInput }) =>
+      api.snapshotWideImportCommit(input, options),
+    onSuccess: invalidateSnapshotWrites
+  }));
+
+  const rollbackImport = proxyRefs(useMutation({
+    mutationFn: async ({ accountId, batchId }: { accountId: number, batchId: number }) =>
+      api.accountSnapshotImportBatchRollback(accountId, batchId),
+    onSuccess: invalidateSnapshotWrites
+  }));
+
   return {
     createSnapshots,
     updateSnapshot,
     deleteSnapshots,
-    importSnapshots
+    importSnapshots,
+    importWideSnapshots,
+    rollbackImport
   };
 };
@@ -0,0 +1,41 @@
+import type { AccountTypeDeleteInput, AccountTypeUpsertInput } from "~/generated/bindings";
+
+import { useMutation, useQueryClient } from "@tanstack/vue-query";
+
+export const useAccountTypeMutations = () => {
+  const api = useApi();
+  const queryClient = useQueryClient();
+
+  const invalidateAccountTypeWrites = async () => {
+    await Promise.all([
+      queryClient.invalidateQueries({ queryKey: queryKeys.accountTypes.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.institutions.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.dashboard.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.search.prefixes.root() })
+    ]);
+  };
+
+  const createAccountType = proxyRefs(useMutation({
+    mutationFn: async (input: AccountTypeUpsertInput) => api.accountTypesCreate(input),
+    onSuccess: invalidateAccountTypeWrites
+  }));
+
+  const updateAccountType = proxyRefs(useMutation({
+    mutationFn: async ({ typeId, input }: { typeId: number, input: AccountTypeUpsertInput }) =>
+      api.accountTypesUpdate(typeId, input),
+    onSuccess: invalidateAccountTypeWrites
+  }));
+
+  const deleteAccountType = proxyRefs(useMutation({
+    mutationFn: async ({ typeId, input }: { typeId: number, input: AccountTypeDeleteInput }) =>
+      api.accountTypesDelete(typeId, input),
+    onSuccess: invalidateAccountTypeWrites
+  }));
+
+  return {
+    createAccountType,
+    updateAccountType,
+    deleteAccountType
+  };
+};
@@ -47,12 +47,24 @@ export type AnalyticsEventObject
     | "account_create"
     | "account_delete"
     | "account_update"
+    | "backup"
+    | "backup_archive"
+    | "backup_archive_restore"
+    | "backup_restore"
     | "csv_import"
     | "csv_preview"
+    | "data_export"
+    | "data_import"
+    | "data_import_preview"
+    | "database_check"
+    | "database_encryption"
+    | "database_repair"
     | "institution"
     | "institution_create"
     | "institution_delete"
     | "institution_update"
+    | "ofx_import"
+    | "ofx_preview"
     | "onboarding_skip_button"
     | "onboarding_step"
     | "result"
@@ -63,7 +75,9 @@ export type AnalyticsEventObject
     | "snapshot"
     | "snapshot_create"
     | "snapshot_delete"
-    | "snapshot_update";
+    | "snapshot_update"
+    | "wide_import"
+    | "wide_preview";
 
 export type AnalyticsEventVerb
   = | "click"
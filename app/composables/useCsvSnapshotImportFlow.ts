@@ -1,46 +1,64 @@
 import type { Component } from "vue";
 import type {
-  CsvSnapshotImportInspectionDto,
+  AccountUpsertInput,
+  CsvTransactionsSnapshotImportAnchor,
   CurrencyCode,
+  SnapshotImportInspectionDto,
   SnapshotImportOptionsInput,
   SnapshotImportPreviewDto,
+  SnapshotImportProfileDto,
   SnapshotImportSourceInput
 } from "~/generated/bindings";
-import type { SnapshotImportFlowDefinition } from "~/utils/snapshot-import-flows";
+import type { CsvSnapshotImportFormatOverrides, SnapshotImportFlowDefinition, SnapshotImportOptionsFor } from "~/utils/snapshot-import-flows";
 import SnapshotImportCsvFileStep from "~/components/Accounts/Snapshots/Import/Csv/FileStep.vue";
 import SnapshotImportCsvOptionsStep from "~/components/Accounts/Snapshots/Import/Csv/OptionsStep.vue";
 import SnapshotImportReviewStep from "~/components/Accounts/Snapshots/Import/ReviewStep.vue";
 import { ApiCommandError } from "~/composables/useApi";
 import { reportHandledError } from "~/utils/error-reporting";
+import { isSpreadsheetFileName, readCsvFileContent } from "~/utils/snapshot-import-flows";
 
 const csvFileStepComponent = markRaw(SnapshotImportCsvFileStep) as Component;
 const csvOptionsStepComponent = markRaw(SnapshotImportCsvOptionsStep) as Component;
 const reviewStepComponent = markRaw(SnapshotImportReviewStep) as Component;
 
+/** CSV and spreadsheet inspections share columns, guesses and profiles. */
+type TabularSnapshotImportInspection = Extract<SnapshotImportInspectionDto, { kind: "csv" | "spreadsheet" }>;
+
 interface UseCsvSnapshotImportFlowParams {
   accountId: Ref<number | null>
+  newAccount: Ref<AccountUpsertInput | null>
   currencyCode: Ref<CurrencyCode>
   setErrorMessage: (message: string | null) => void
-  onComplete: () => void
+  onComplete: (accountId: number) => void
 }
 
 export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams): SnapshotImportFlowDefinition {
   const api = useApi();
-  const { importSnapshots } = useAccountSnapshotMutations();
+  const target = useSnapshotImportTarget(params);
   const { captureAnalyticsEvent } = useAnalytics();
 
   const selectedFile = ref<File | null>(null);
   const sourceFileName = ref("");
-  const sourceFileContent = ref("");
+  const sourceFileContent = ref<number[]>([]);
   const sourceHasHeaderRow = ref(true);
   const hasHeaderRow = ref(true);
-  const inspection = ref<CsvSnapshotImportInspectionDto | null>(null);
+  const emptyFormatOverrides: CsvSnapshotImportFormatOverrides = { encoding: null, delimiter: null, quote: null };
+  const sourceFormatOverrides = ref<CsvSnapshotImportFormatOverrides>(emptyFormatOverrides);
+  const formatOverrides = ref<CsvSnapshotImportFormatOverrides>(emptyFormatOverrides);
+  // Worksheet chosen for a spreadsheet; `null` reads the first one.
+  const sourceSheet = ref<string | null>(null);
+  const sheet = ref<string | null>(null);
+  const inspection = ref<TabularSnapshotImportInspection | null>(null);
+  // Set when the amount column holds transactions rather than balances.
+  const transactionAnchor = ref<CsvTransactionsSnapshotImportAnchor | null>(null);
+  // Blank means the options are not saved as a profile after importing.
+  const profileName = ref("");
   const preview = ref<SnapshotImportPreviewDto | null>(null);
   const inspectPending = ref(false);
   const previewPending = ref(false);
   let inspectRequestId = 0;
 
-  const options = ref<SnapshotImportOptionsInput>({
+  const options = ref<SnapshotImportOptionsFor<"csv">>({
     source: {
       kind: "csv",
       date_column: "",
@@ -58,18 +76,28 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     overwrite_existing_confirmed: false
   });
 
-  const sourceInput = computed<SnapshotImportSourceInput | null>(() => {
-    if (!sourceFileName.value || !sourceFileContent.value) return null;
+  const requestOptions = computed<SnapshotImportOptionsInput>(() => {
+    const anchor = transactionAnchor.value;
+    if (anchor == null) return options.value;
 
+    const { source, ...policies } = options.value;
     return {
-      kind: "csv",
-      file_name: sourceFileName.value,
-      content: sourceFileContent.value,
-      has_header_row: sourceHasHeaderRow.value
+      ...policies,
+      source: {
+        kind: "csv_transactions",
+        csv: source,
+        anchor
+      }
     };
   });
 
-  const busy = computed(() => inspectPending.value || previewPending.value || importSnapshots.isPending);
+  const sourceInput = computed<SnapshotImportSourceInput | null>(() => {
+    if (!sourceFileName.value || sourceFileContent.value.length === 0) return null;
+
+    return buildSourceInput(sourceFileName.value, sourceFileContent.value, sourceHasHeaderRow.value, sourceFormatOverrides.value, sourceSheet.value);
+  });
+
+  const busy = computed(() => inspectPending.value || previewPending.value || target.isPending.value);
 
   const hasPendingOverwrites = computed(() => (preview.value?.summary.overwrite_count ?? 0) > 0);
 
@@ -83,6 +111,9 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     return summary != null && summary.skip_count === summary.total_rows;
   });
 
+  // A new account is still created when every row is skipped.
+  const isCompleteNoop = computed(() => hasOnlySkippedRows.value && !target.isNewAccount.value);
+
   const columnItems = computed(() => {
     return (inspection.value?.columns ?? []).map((column) => ({
       label: column.name,
@@ -96,10 +127,12 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
       return;
     }
 
+    formatOverrides.value = emptyFormatOverrides;
+    sheet.value = null;
     await inspectFile(file);
   });
 
-  watch(hasHeaderRow, async () => {
+  watch([hasHeaderRow, formatOverrides, sheet], async () => {
     if (selectedFile.value == null) return;
     await inspectFile(selectedFile.value);
   });
@@ -108,36 +141,46 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     const requestId = ++inspectRequestId;
     const fileName = file.name;
     const fileHasHeaderRow = hasHeaderRow.value;
+    const fileFormatOverrides = formatOverrides.value;
+    const fileSheet = sheet.value;
 
     inspectPending.value = true;
     params.setErrorMessage(null);
     sourceFileName.value = "";
-    sourceFileContent.value = "";
+    sourceFileContent.value = [];
     inspection.value = null;
     preview.value = null;
 
     try {
-      const fileContent = await file.text();
+      const fileContent = await readCsvFileContent(file);
       if (!isCurrentInspectRequest(requestId, file, fileHasHeaderRow)) return;
 
-      const input: SnapshotImportSourceInput = {
-        kind: "csv",
-        file_name: fileName,
-        content: fileContent,
-        has_header_row: fileHasHeaderRow
-      };
+      const input = buildSourceInput(fileName, fileContent, fileHasHeaderRow, fileFormatOverrides, fileSheet);
 
-      const inspected = await api.accountSnapshotImportInspect(input);
+      const inspected = await api.accountSnapshotImportInspect(params.accountId.value, input);
       if (!isCurrentInspectRequest(requestId, file, fileHasHeaderRow)) return;
 
-      if (inspected.kind !== "csv") {
+      if (inspected.kind === "ofx" || inspected.kind !== input.kind) {
         throw new Error("Import source returned an unexpected inspection result");
       }
 
       sourceFileName.value = fileName;
       sourceFileContent.value = fileContent;
       sourceHasHeaderRow.value = fileHasHeaderRow;
+      sourceFormatOverrides.value = fileFormatOverrides;
+      sourceSheet.value = inspected.kind === "spreadsheet" ? inspected.sheet : null;
       inspection.value = inspected;
+      profileName.value = inspected.profile?.name ?? "";
+      if (inspected.profile) {
+        applyProfile(inspected.profile);
+        return;
+      }
+      if (inspected.known_profile?.confidence === "high") {
+        transactionAnchor.value = inspected.known_profile.amount_kind === "transactions"
+          ? { kind: "latest", balance_minor: 0 }
+          : null;
+      }
+
       options.value = {
         source: {
           kind: "csv",
@@ -171,12 +214,88 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     }
   }
 
+  function buildSourceInput(
+    fileName: string,
+    content: number[],
+    fileHasHeaderRow: boolean,
+    fileFormatOverrides: CsvSnapshotImportFormatOverrides,
+    fileSheet: string | null
+  ): Extract<SnapshotImportSourceInput, { kind: "csv" | "spreadsheet" }> {
+    if (isSpreadsheetFileName(fileName)) {
+      return {
+        kind: "spreadsheet",
+        file_name: fileName,
+        content,
+        sheet: fileSheet,
+        has_header_row: fileHasHeaderRow
+      };
+    }
+
+    return {
+      kind: "csv",
+      file_name: fileName,
+      content,
+      has_header_row: fileHasHeaderRow,
+      ...fileFormatOverrides
+    };
+  }
+
+  function applyProfile(profile: SnapshotImportProfileDto) {
+    const { source, ...policies } = profile.options;
+    if (source.kind === "ofx") return;
+
+    const csv = source.kind === "csv_transactions" ? source.csv : source;
+    transactionAnchor.value = source.kind === "csv_transactions" ? source.anchor : null;
+    options.value = {
+      ...policies,
+      source: {
+        ...csv,
+        kind: "csv"
+      },
+      overwrite_existing_confirmed: false
+    };
+  }
+
+  async function deleteProfile() {
+    const profile = inspection.value?.profile;
+    if (params.accountId.value == null || inspection.value == null || profile == null) return;
+
+    params.setErrorMessage(null);
+
+    try {
+      await api.accountSnapshotImportProfileDelete(params.accountId.value, profile.id);
+      inspection.value = { ...inspection.value, profile: null };
+      profileName.value = "";
+    } catch (error) {
+      if (!(error instanceof ApiCommandError)) {
+        reportHandledError(error, { source: "csv_import_profile_delete" });
+      }
+
+      params.setErrorMessage(error instanceof Error ? error.message : "Failed to forget import profile");
+    }
+  }
+
+  // The import has already succeeded, so a failed save is reported without failing it.
+  async function saveProfile(accountId: number) {
+    const name = profileName.value.trim();
+    if (name === "" || inspection.value == null) return;
+
+    try {
+      await api.accountSnapshotImportProfileSave(accountId, {
+        name,
+        columns: inspection.value.columns.map((column) => column.name),
+        options: requestOptions.value);
+    } catch (error) {
+      reportHandledError(error, { source: "csv_import_profile_save" });
+    }
+  }
+
   function isCurrentInspectRequest(requestId: number, file: File, fileHasHeaderRow: boolean) {
     return requestId === inspectRequestId && selectedFile.value === file && hasHeaderRow.value === fileHasHeaderRow;
   }
 
   async function loadPreview() {
-    if (params.accountId.value == null || sourceInput.value == null) return false;
+    if (!target.isReady.value || sourceInput.value == null) return false;
     const startedAt = performance.now();
 
     previewPending.value = true;
@@ -188,7 +307,7 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     };
 
     try {
-      preview.value = await api.accountSnapshotImportPreview(params.accountId.value, sourceInput.value, options.value);
+      preview.value = await target.preview(sourceInput.value, requestOptions.value);
       captureAnalyticsEvent("snapshot_import:csv_preview_generate", csvImportSummaryProperties(), {
         operationStartedAt: startedAt
       });
@@ -213,18 +332,19 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
   }
 
   async function complete() {
-    if (params.accountId.value == null || sourceInput.value == null || preview.value == null) return false;
+    if (!target.isReady.value || sourceInput.value == null || preview.value == null) return false;
     const startedAt = performance.now();
     const analyticsProperties = csvImportSummaryProperties();
 
-    if (hasOnlySkippedRows.value) {
+    if (isCompleteNoop.value && params.accountId.value != null) {
       params.setErrorMessage(null);
       captureAnalyticsEvent("snapshot_import:csv_import_end", {
         ...analyticsProperties
       }, {
         operationStartedAt: startedAt
       });
-      params.onComplete();
+      await saveProfile(params.accountId.value);
+      params.onComplete(params.accountId.value);
       return true;
     }
 
@@ -242,11 +362,7 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     params.setErrorMessage(null);
 
     try {
-      const result = await importSnapshots.mutateAsync({
-        accountId: params.accountId.value,
-        input: sourceInput.value,
-        options: options.value
-      });
+      const { accountId, result } = await target.commit(sourceInput.value, requestOptions.value);
       captureAnalyticsEvent("snapshot_import:csv_import_end", {
         ...analyticsProperties,
         snapshot_create_count: result.created_count,
@@ -255,7 +371,8 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
       }, {
         operationStartedAt: startedAt
       });
-      params.onComplete();
+      await saveProfile(accountId);
+      params.onComplete(accountId);
       return true;
     } catch (error) {
       captureAnalyticsEvent("snapshot_import:csv_import_fail", {
@@ -278,8 +395,12 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     inspectRequestId += 1;
     selectedFile.value = null;
     sourceFileName.value = "";
-    sourceFileContent.value = "";
+    sourceFileContent.value = [];
     sourceHasHeaderRow.value = hasHeaderRow.value;
+    formatOverrides.value = emptyFormatOverrides;
+    sourceFormatOverrides.value = emptyFormatOverrides;
+    sheet.value = null;
+    sourceSheet.value = null;
     inspection.value = null;
     preview.value = null;
     inspectPending.value = false;
@@ -289,10 +410,16 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
     inspectRequestId += 1;
     selectedFile.value = null;
     sourceFileName.value = "";
-    sourceFileContent.value = "";
+    sourceFileContent.value = [];
     sourceHasHeaderRow.value = true;
     hasHeaderRow.value = true;
+    formatOverrides.value = emptyFormatOverrides;
+    sourceFormatOverrides.value = emptyFormatOverrides;
+    sheet.value = null;
+    sourceSheet.value = null;
     inspection.value = null;
+    transactionAnchor.value = null;
+    profileName.value = "";
     preview.value = null;
     inspectPending.value = false;
     previewPending.value = false;
@@ -335,27 +462,30 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
       has_overwrites: summary.overwrite_count > 0,
       has_invalid_rows: summary.invalid_count > 0,
       has_overwrite_confirmation: options.value.overwrite_existing_confirmed,
+      has_transaction_amounts: transactionAnchor.value != null,
+      is_spreadsheet: inspection.value?.kind === "spreadsheet",
+      is_new_account: target.isNewAccount.value,
       is_noop: summary.total_rows > 0 && summary.skip_count === summary.total_rows
     };
   }
 
   return {
     id: "csv",
-    label: "CSV file",
-    description: "Import dates and balances from a local CSV export.",
+    label: "CSV or spreadsheet",
+    description: "Import dates and balances, or transactions, from a local CSV, Excel or OpenDocument file.",
     icon: "i-lucide-file-spreadsheet",
     completeLabel: "Import snapshots",
     isBusy: () => busy.value,
     reset,
     canComplete: () => {
-      return params.accountId.value != null
+      return target.isReady.value
         && sourceInput.value != null
         && preview.value != null
         && preview.value.summary.invalid_count === 0
         && (hasImportableRows.value || hasOnlySkippedRows.value)
         && (!hasPendingOverwrites.value || options.value.overwrite_existing_confirmed);
     },
-    isCompleteNoop: () => hasOnlySkippedRows.value,
+    isCompleteNoop: () => isCompleteNoop.value,
     complete,
     steps: () => [
       {
@@ -368,6 +498,7 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
         props: () => ({
           selectedFile: selectedFile.value,
           hasHeaderRow: hasHeaderRow.value,
+          formatOverrides: formatOverrides.value,
           inspection: inspection.value,
           busy: busy.value,
           "onUpdate:selectedFile": (file: File | null) => {
@@ -375,7 +506,14 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
           },
           "onUpdate:hasHeaderRow": (value: boolean) => {
             hasHeaderRow.value = value;
-          }
+          },
+          "onUpdate:formatOverrides": (value: CsvSnapshotImportFormatOverrides) => {
+            formatOverrides.value = value;
+          },
+          "onUpdate:sheet": (value: string) => {
+            sheet.value = value;
+          },
+          onDeleteProfile: deleteProfile
         })
       },
       {
@@ -384,18 +522,28 @@ export function useCsvSnapshotImportFlow(params: UseCsvSnapshotImportFlowParams)
         icon: "i-lucide-sliders-horizontal",
         component: csvOptionsStepComponent,
         canContinue: () => {
-          return params.accountId.value != null
+          return target.isReady.value
             && sourceInput.value != null
             && options.value.source.date_column !== ""
-            && options.value.source.amount_column !== "";
+            && options.value.source.amount_column !== ""
+            && (transactionAnchor.value?.kind !== "on_date" || transactionAnchor.value.date !== "");
         },
         beforeNext: loadPreview,
         props: () => ({
           modelValue: options.value,
+          transactionAnchor: transactionAnchor.value,
+          profileName: profileName.value,
           columnItems: columnItems.value,
+          currencyCode: params.currencyCode.value,
           busy: busy.value,
-          "onUpdate:modelValue": (value: SnapshotImportOptionsInput) => {
+          "onUpdate:modelValue": (value: SnapshotImportOptionsFor<"csv">) => {
             options.value = value;
+          },
+          "onUpdate:transactionAnchor": (value: CsvTransactionsSnapshotImportAnchor | null) => {
+            transactionAnchor.value = value;
+          },
+          "onUpdate:profileName": (value: string) => {
+            profileName.value = value;
           }
         })
       },
@@ -0,0 +1,24 @@
+import type { DatabaseStatusDto } from "~/generated/bindings";
+import { useQueryClient } from "@tanstack/vue-query";
+
+export function useDatabaseStatus() {
+  return useState<DatabaseStatusDto | null>("databaseStatus", () => null);
+}
+
+export function useDatabaseUnlock() {
+  const api = useApi();
+  const queryClient = useQueryClient();
+  const status = useDatabaseStatus();
+  const settings = useNullableSettings();
+
+  // Settings cannot be read while the database is locked, so they load here instead of
+  // in the settings plugin.
+  async function unlock(passphrase: string) {
+    await api.databaseUnlock(passphrase);
+    settings.value = await api.settingsGet();
+    queryClient.setQueryData(queryKeys.settings.get(), settings.value);
+    status.value = await api.databaseStatus();
+  }
+
+  return { unlock };
+}
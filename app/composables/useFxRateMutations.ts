@@ -0,0 +1,36 @@
+import type { FxRateCsvImportInput, FxRatesDeleteInput, FxRateWriteInput } from "~/generated/bindings";
+
+import { useMutation, useQueryClient } from "@tanstack/vue-query";
+
+export const useFxRateMutations = () => {
+  const api = useApi();
+  const queryClient = useQueryClient();
+
+  const invalidateFxRateWrites = async () => {
+    await Promise.all([
+      queryClient.invalidateQueries({ queryKey: queryKeys.fxRates.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.dashboard.prefixes.root() })
+    ]);
+  };
+
+  const upsertFxRate = proxyRefs(useMutation({
+    mutationFn: async (input: FxRateWriteInput) => api.fxRatesUpsert(input),
+    onSuccess: invalidateFxRateWrites
+  }));
+
+  const deleteFxRates = proxyRefs(useMutation({
+    mutationFn: async (input: FxRatesDeleteInput) => api.fxRatesDelete(input),
+    onSuccess: invalidateFxRateWrites
+  }));
+
+  const importFxRates = proxyRefs(useMutation({
+    mutationFn: async (input: FxRateCsvImportInput) => api.fxRatesImportCsv(input),
+    onSuccess: invalidateFxRateWrites
+  }));
+
+  return {
+    upsertFxRate,
+    deleteFxRates,
+    importFxRates
+  };
+};
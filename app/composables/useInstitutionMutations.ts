@@ -10,6 +10,8 @@ export const useInstitutionMutations = () => {
     await Promise.all([
       queryClient.invalidateQueries({ queryKey: queryKeys.institutions.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.accounts.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.accountTypes.prefixes.root() }),
+      queryClient.invalidateQueries({ queryKey: queryKeys.tags.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.dashboard.prefixes.root() }),
       queryClient.invalidateQueries({ queryKey: queryKeys.search.prefixes.root() })
     ]);
@@ -41,7 +41,7 @@ export function useLocaleFormatters() {
   }
 
   function formatCurrencyMinor(minor: number, currencyCode: CurrencyCode, options: Intl.NumberFormatOptions = {}) {
-    return formatCurrency(convertCurrencyMinorUnitsToMajorAmount(minor), currencyCode, options);
+    return formatCurrency(convertCurrencyMinorUnitsToMajorAmount(minor, currencyCode), currencyCode, options);
   }
 
   function formatDate(value: Date | string | null | undefined, options: Intl.DateTimeFormatOptions, fallback = "—") {
@@ -0,0 +1,348 @@
+import type { Component } from "vue";
+import type {
+  AccountUpsertInput,
+  CurrencyCode,
+  OfxSnapshotImportInspectionDto,
+  SnapshotImportPreviewDto,
+  SnapshotImportSourceInput
+} from "~/generated/bindings";
+import type { SnapshotImportFlowDefinition, SnapshotImportOptionsFor } from "~/utils/snapshot-import-flows";
+import SnapshotImportOfxFileStep from "~/components/Accounts/Snapshots/Import/Ofx/FileStep.vue";
+import SnapshotImportOfxOptionsStep from "~/components/Accounts/Snapshots/Import/Ofx/OptionsStep.vue";
+import SnapshotImportReviewStep from "~/components/Accounts/Snapshots/Import/ReviewStep.vue";
+import { ApiCommandError } from "~/composables/useApi";
+import { reportHandledError } from "~/utils/error-reporting";
+
+const ofxFileStepComponent = markRaw(SnapshotImportOfxFileStep) as Component;
+const ofxOptionsStepComponent = markRaw(SnapshotImportOfxOptionsStep) as Component;
+const reviewStepComponent = markRaw(SnapshotImportReviewStep) as Component;
+
+interface UseOfxSnapshotImportFlowParams {
+  accountId: Ref<number | null>
+  newAccount: Ref<AccountUpsertInput | null>
+  currencyCode: Ref<CurrencyCode>
+  setErrorMessage: (message: string | null) => void
+  onComplete: (accountId: number) => void
+}
+
+export function useOfxSnapshotImportFlow(params: UseOfxSnapshotImportFlowParams): SnapshotImportFlowDefinition {
+  const api = useApi();
+  const target = useSnapshotImportTarget(params);
+  const { captureAnalyticsEvent } = useAnalytics();
+
+  const selectedFile = ref<File | null>(null);
+  const sourceFileName = ref("");
+  const sourceFileContent = ref("");
+  const inspection = ref<OfxSnapshotImportInspectionDto | null>(null);
+  const preview = ref<SnapshotImportPreviewDto | null>(null);
+  const inspectPending = ref(false);
+  const previewPending = ref(false);
+  let inspectRequestId = 0;
+
+  const options = ref<SnapshotImportOptionsFor<"ofx">>(defaultOptions());
+
+  const sourceInput = computed<SnapshotImportSourceInput | null>(() => {
+    if (!sourceFileName.value || !sourceFileContent.value) return null;
+
+    return {
+      kind: "ofx",
+      file_name: sourceFileName.value,
+      content: sourceFileContent.value
+    };
+  });
+
+  const busy = computed(() => inspectPending.value || previewPending.value || target.isPending.value);
+
+  const hasPendingOverwrites = computed(() => (preview.value?.summary.overwrite_count ?? 0) > 0);
+
+  const hasImportableRows = computed(() => {
+    const summary = preview.value?.summary;
+    return summary != null && (summary.create_count + summary.overwrite_count) > 0;
+  });
+
+  const hasOnlySkippedRows = computed(() => {
+    const summary = preview.value?.summary;
+    return summary != null && summary.skip_count === summary.total_rows;
+  });
+
+  // A new account is still created when every row is skipped.
+  const isCompleteNoop = computed(() => hasOnlySkippedRows.value && !target.isNewAccount.value);
+
+  watch(selectedFile, async (file) => {
+    if (!file) {
+      clearFile();
+      return;
+    }
+
+    await inspectFile(file);
+  });
+
+  async function inspectFile(file: File) {
+    const requestId = ++inspectRequestId;
+    const fileName = file.name;
+
+    inspectPending.value = true;
+    params.setErrorMessage(null);
+    sourceFileName.value = "";
+    sourceFileContent.value = "";
+    inspection.value = null;
+    preview.value = null;
+
+    try {
+      const fileContent = await file.text();
+      if (!isCurrentInspectRequest(requestId, file)) return;
+
+      const inspected = await api.accountSnapshotImportInspect(params.accountId.value, {
+        kind: "ofx",
+        file_name: fileName,
+        content: fileContent
+      });
+      if (!isCurrentInspectRequest(requestId, file)) return;
+
+      if (inspected.kind !== "ofx") {
+        throw new Error("Import source returned an unexpected inspection result");
+      }
+
+      sourceFileName.value = fileName;
+      sourceFileContent.value = fileContent;
+      inspection.value = inspected;
+      options.value = {
+        ...defaultOptions(),
+        source: {
+          kind: "ofx",
+          balance_kind: inspected.guesses.balance_kind ?? "ledger"
+        }
+      };
+    } catch (error) {
+      if (!isCurrentInspectRequest(requestId, file)) return;
+
+      if (!(error instanceof ApiCommandError)) {
+        reportHandledError(error, { source: "ofx_import_inspect" });
+      }
+
+      inspection.value = null;
+      params.setErrorMessage(error instanceof Error ? error.message : "Failed to inspect import source");
+    } finally {
+      if (isCurrentInspectRequest(requestId, file)) {
+        inspectPending.value = false;
+      }
+    }
+  }
+
+  function isCurrentInspectRequest(requestId: number, file: File) {
+    return requestId === inspectRequestId && selectedFile.value === file;
+  }
+
+  async function loadPreview() {
+    if (!target.isReady.value || sourceInput.value == null) return false;
+    const startedAt = performance.now();
+
+    previewPending.value = true;
+    params.setErrorMessage(null);
+    preview.value = null;
+    options.value = {
+      ...options.value,
+      overwrite_existing_confirmed: false
+    };
+
+    try {
+      preview.value = await target.preview(sourceInput.value, options.value);
+      captureAnalyticsEvent("snapshot_import:ofx_preview_generate", ofxImportSummaryProperties(), {
+        operationStartedAt: startedAt
+      });
+      return true;
+    } catch (error) {
+      captureAnalyticsEvent("snapshot_import:ofx_preview_fail", {
+        import_row_count: inspection.value?.balances.length ?? 0,
+        ...getAnalyticsErrorProperties(error)
+      }, {
+        operationStartedAt: startedAt
+      });
+
+      if (!(error instanceof ApiCommandError)) {
+        reportHandledError(error, { source: "ofx_import_preview" });
+      }
+
+      params.setErrorMessage(error instanceof Error ? error.message : "Failed to preview import");
+      return false;
+    } finally {
+      previewPending.value = false;
+    }
+  }
+
+  async function complete() {
+    if (!target.isReady.value || sourceInput.value == null || preview.value == null) return false;
+    const startedAt = performance.now();
+    const analyticsProperties = ofxImportSummaryProperties();
+
+    if (isCompleteNoop.value && params.accountId.value != null) {
+      params.setErrorMessage(null);
+      captureAnalyticsEvent("snapshot_import:ofx_import_end", analyticsProperties, {
+        operationStartedAt: startedAt
+      });
+      params.onComplete(params.accountId.value);
+      return true;
+    }
+
+    if (hasPendingOverwrites.value && !options.value.overwrite_existing_confirmed) {
+      params.setErrorMessage("Confirm overwrite to continue");
+      return false;
+    }
+
+    params.setErrorMessage(null);
+
+    try {
+      const { accountId, result } = await target.commit(sourceInput.value, options.value);
+      captureAnalyticsEvent("snapshot_import:ofx_import_end", {
+        ...analyticsProperties,
+        snapshot_create_count: result.created_count,
+        snapshot_overwrite_count: result.overwritten_count,
+        snapshot_skip_count: result.skipped_count
+      }, {
+        operationStartedAt: startedAt
+      });
+      params.onComplete(accountId);
+      return true;
+    } catch (error) {
+      captureAnalyticsEvent("snapshot_import:ofx_import_fail", {
+        ...analyticsProperties,
+        ...getAnalyticsErrorProperties(error)
+      }, {
+        operationStartedAt: startedAt
+      });
+
+      if (!(error instanceof ApiCommandError)) {
+        reportHandledError(error, { source: "ofx_import_commit" });
+      }
+
+      params.setErrorMessage(error instanceof Error ? error.message : "Failed to import snapshots");
+      return false;
+    }
+  }
+
+  function clearFile() {
+    inspectRequestId += 1;
+    selectedFile.value = null;
+    sourceFileName.value = "";
+    sourceFileContent.value = "";
+    inspection.value = null;
+    preview.value = null;
+    inspectPending.value = false;
+  }
+
+  function reset() {
+    clearFile();
+    previewPending.value = false;
+    options.value = defaultOptions();
+  }
+
+  function ofxImportSummaryProperties() {
+    const summary = preview.value?.summary;
+
+    if (!summary) {
+      return {
+        import_row_count: inspection.value?.balances.length ?? 0,
+        has_overwrite_confirmation: options.value.overwrite_existing_confirmed
+      };
+    }
+
+    return {
+      import_row_count: summary.total_rows,
+      snapshot_create_count: summary.create_count,
+      snapshot_overwrite_count: summary.overwrite_count,
+      snapshot_skip_count: summary.skip_count,
+      snapshot_invalid_count: summary.invalid_count,
+      has_overwrites: summary.overwrite_count > 0,
+      has_invalid_rows: summary.invalid_count > 0,
+      has_overwrite_confirmation: options.value.overwrite_existing_confirmed,
+      is_new_account: target.isNewAccount.value,
+      is_noop: summary.total_rows > 0 && summary.skip_count === summary.total_rows
+    };
+  }
+
+  return {
+    id: "ofx",
+    label: "OFX or QFX file",
+    description: "Import statement balances from a bank or card download.",
+    icon: "i-lucide-landmark",
+    completeLabel: "Import snapshots",
+    isBusy: () => busy.value,
+    reset,
+    canComplete: () => {
+      return target.isReady.value
+        && sourceInput.value != null
+        && preview.value != null
+        && preview.value.summary.invalid_count === 0
+        && (hasImportableRows.value || hasOnlySkippedRows.value)
+        && (!hasPendingOverwrites.value || options.value.overwrite_existing_confirmed);
+    },
+    isCompleteNoop: () => isCompleteNoop.value,
+    complete,
+    steps: () => [
+      {
+        id: "ofx-file",
+        title: "File",
+        icon: "i-lucide-file-up",
+        component: ofxFileStepComponent,
+        canContinue: () => (inspection.value?.balances.length ?? 0) > 0,
+        onBack: clearFile,
+        props: () => ({
+          selectedFile: selectedFile.value,
+          inspection: inspection.value,
+          busy: busy.value,
+          "onUpdate:selectedFile": (file: File | null) => {
+            selectedFile.value = file;
+          }
+        })
+      },
+      {
+        id: "ofx-options",
+        title: "Options",
+        icon: "i-lucide-sliders-horizontal",
+        component: ofxOptionsStepComponent,
+        canContinue: () => target.isReady.value && sourceInput.value != null,
+        beforeNext: loadPreview,
+        props: () => ({
+          modelValue: options.value,
+          busy: busy.value,
+          "onUpdate:modelValue": (value: SnapshotImportOptionsFor<"ofx">) => {
+            options.value = value;
+          }
+        })
+      },
+      {
+        id: "ofx-review",
+        title: "Review",
+        icon: "i-lucide-list-checks",
+        component: reviewStepComponent,
+        canContinue: () => false,
+        props: () => ({
+          preview: preview.value,
+          currencyCode: params.currencyCode.value,
+          overwriteExistingConfirmed: options.value.overwrite_existing_confirmed,
+          invalidPreviewDescription: "Go back and choose another balance or fix the source file before importing.",
+          emptyPreviewDescription: "Preview will appear here after the import options are checked.",
+          "onUpdate:overwriteExistingConfirmed": (value: boolean) => {
+            options.value = {
+              ...options.value,
+              overwrite_existing_confirmed: value
+            };
+          }
+        })
+      }
+    ]
+  };
+}
+
+function defaultOptions(): SnapshotImportOptionsFor<"ofx"> {
+  return {
+    source: {
+      kind: "ofx",
+      balance_kind: "ledger"
+    },
+    existing_date_policy: "overwrite",
+    unchanged_value_policy: "exclude",
+    duplicate_date_policy: "keep_last",
+    overwrite_existing_confirmed: false
+  };
+}
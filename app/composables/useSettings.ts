@@ -46,9 +46,14 @@ export function useSettingsManager() {
     mutationFn: async (input: AppSettingsUpdateInput) => api.settingsUpdate(input),
     onSuccess: async (updated) => {
       const previousCurrency = settings.value.default_display_currency_code;
+      const previousBackupFolder = settings.value.backup_folder;
       settings.value = updated;
       queryClient.setQueryData(queryKeys.settings.get(), updated);
 
+      if (previousBackupFolder !== updated.backup_folder) {
+        await queryClient.invalidateQueries({ queryKey: queryKeys.backups.list() });
+      }
+
       if (previousCurrency !== updated.default_display_currency_code) {
         await Promise.all([
           queryClient.invalidateQueries({ queryKey: queryKeys.dashboard.prefixes.root() }),
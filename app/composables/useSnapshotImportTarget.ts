@@ -0,0 +1,65 @@
+import type {
+  AccountUpsertInput,
+  SnapshotImportCommitDto,
+  SnapshotImportOptionsInput,
+  SnapshotImportSourceInput
+} from "~/generated/bindings";
+
+interface UseSnapshotImportTargetParams {
+  accountId: Ref<number | null>
+  newAccount: Ref<AccountUpsertInput | null>
+}
+
+/**
+ * Previews and commits an import into either an existing account or a new one
+ * that is created together with its snapshots.
+ */
+export function useSnapshotImportTarget(params: UseSnapshotImportTargetParams) {
+  const api = useApi();
+  const { importSnapshots } = useAccountSnapshotMutations();
+  const { createAccountFromImport } = useAccountMutations();
+
+  const isReady = computed(() => params.accountId.value != null || params.newAccount.value != null);
+  const isNewAccount = computed(() => params.accountId.value == null && params.newAccount.value != null);
+  const isPending = computed(() => importSnapshots.isPending || createAccountFromImport.isPending);
+
+  async function preview(input: SnapshotImportSourceInput, options: SnapshotImportOptionsInput) {
+    if (params.accountId.value != null) {
+      return api.accountSnapshotImportPreview(params.accountId.value, input, options);
+    }
+    if (params.newAccount.value != null) {
+      return api.accountCreateImportPreview(params.newAccount.value, input, options);
+    }
+
+    throw new Error("Import has no target account");
+  }
+
+  async function commit(
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput
+  ): Promise<{ accountId: number, result: SnapshotImportCommitDto }> {
+    if (params.accountId.value != null) {
+      const accountId = params.accountId.value;
+      const result = await importSnapshots.mutateAsync({ accountId, input, options });
+      return { accountId, result };
+    }
+    if (params.newAccount.value != null) {
+      const { account_id: accountId, result } = await createAccountFromImport.mutateAsync({
+        account: params.newAccount.value,
+        input,
+        options
+      });
+      return { accountId, result };
+    }
+
+    throw new Error("Import has no target account");
+  }
+
+  return {
+    isReady,
+    isNewAccount,
+    isPending,
+    preview,
+    commit
+  };
+}
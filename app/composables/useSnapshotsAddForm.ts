@@ -1,4 +1,4 @@
-import type { AccountBalanceSnapshotDto, AccountSnapshotsCreateInput } from "~/generated/bindings";
+import type { AccountBalanceSnapshotDto, AccountSnapshotsCreateInput, CurrencyCode } from "~/generated/bindings";
 
 type NumericField = "amount" | "change";
 
@@ -11,6 +11,7 @@ interface SnapshotDraftRow {
   liveAmount: number | undefined
   editingField: NumericField | null
   amountTouched: boolean
+  note: string
 }
 
 interface SnapshotContext {
@@ -38,6 +39,7 @@ interface UseSnapshotsAddFormParams {
   accountId: Ref<number | null>
   openedDate: Ref<string | null | undefined>
   closedDate: Ref<string | null | undefined>
+  currencyCode: Ref<CurrencyCode>
   snapshots: Ref<AccountBalanceSnapshotDto[]>
 }
 
@@ -85,7 +87,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     const activeRows = rows.value.slice(0, activeRowCount.value);
     const stagedDates = new Set(
       activeRows
-        .filter((row) => row.date !== "" && convertCurrencyMajorAmountToMinorUnits(effectiveAmount(row)) != null)
+        .filter((row) => row.date !== "" && convertCurrencyMajorAmountToMinorUnits(effectiveAmount(row), params.currencyCode.value) != null)
         .map((row) => row.date)
     );
     const existingSnapshots = snapshotsAsc.value.filter((snapshot) => !stagedDates.has(snapshot.date));
@@ -115,7 +117,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
       }
 
       const amountValue = effectiveAmount(row);
-      const amountMinor = convertCurrencyMajorAmountToMinorUnits(amountValue);
+      const amountMinor = convertCurrencyMajorAmountToMinorUnits(amountValue, params.currencyCode.value);
       const conflictExisting = row.date === "" ? null : (existingByDate.value.get(row.date) || null);
 
       while (
@@ -157,7 +159,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
         previous,
         conflictExisting,
         changeMinor,
-        changeValue: changeMinor == null ? undefined : convertCurrencyMinorUnitsToMajorAmount(changeMinor),
+        changeValue: changeMinor == null ? undefined : convertCurrencyMinorUnitsToMajorAmount(changeMinor, params.currencyCode.value),
         sameBalanceWarning: previous != null && amountMinor != null && amountMinor === previous.balance_minor,
         amountError,
         changeError: previous != null ? amountError : null
@@ -229,6 +231,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
       snapshots: rows.value.slice(0, activeRowCount.value).map((row, index) => ({
         date: row.date,
         balance_minor: rowStates.value[index]!.amountMinor!,
+        note: row.note.trim() === "" ? null : row.note,
         overwrite_existing: rowStates.value[index]!.conflictExisting != null && overwriteExistingConfirmed.value
       }))
     };
@@ -315,7 +318,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     if (row == null) return;
 
     row.editingField = "amount";
-    row.liveAmount = normalizeOptionalCurrencyMajorAmount(parseCurrencyInputNumberEventValue(event));
+    row.liveAmount = normalizeOptionalCurrencyMajorAmount(parseCurrencyInputNumberEventValue(event, params.currencyCode.value), params.currencyCode.value);
   }
 
   function onRowChangeInput(index: number, event: Event) {
@@ -324,7 +327,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     if (row == null) return;
 
     row.editingField = "change";
-    const changeValue = normalizeOptionalCurrencyMajorAmount(parseCurrencyInputNumberEventValue(event));
+    const changeValue = normalizeOptionalCurrencyMajorAmount(parseCurrencyInputNumberEventValue(event, params.currencyCode.value), params.currencyCode.value);
 
     if (changeValue == null) {
       row.liveAmount = undefined;
@@ -332,7 +335,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     }
 
     if (previous != null) {
-      row.liveAmount = normalizeCurrencyMajorAmount(convertCurrencyMinorUnitsToMajorAmount(previous.balance_minor) + changeValue);
+      row.liveAmount = normalizeCurrencyMajorAmount(convertCurrencyMinorUnitsToMajorAmount(previous.balance_minor, params.currencyCode.value) + changeValue, params.currencyCode.value);
     }
   }
 
@@ -340,7 +343,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     const row = rows.value[index];
     if (row == null) return;
 
-    const normalized = normalizeOptionalCurrencyMajorAmount(value);
+    const normalized = normalizeOptionalCurrencyMajorAmount(value, params.currencyCode.value);
     row.amount = normalized;
     row.liveAmount = normalized;
   }
@@ -357,7 +360,7 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
     }
 
     if (previous != null) {
-      const normalized = normalizeCurrencyMajorAmount(convertCurrencyMinorUnitsToMajorAmount(previous.balance_minor) + value);
+      const normalized = normalizeCurrencyMajorAmount(convertCurrencyMinorUnitsToMajorAmount(previous.balance_minor, params.currencyCode.value) + value, params.currencyCode.value);
       row.amount = normalized;
       row.liveAmount = normalized;
     }
@@ -380,9 +383,9 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
   function changeModelValue(row: SnapshotDraftRow, index: number) {
     if (row.editingField === "change") {
       const previous = rowStates.value[index]!.previous;
-      const amountMinor = convertCurrencyMajorAmountToMinorUnits(row.amount);
+      const amountMinor = convertCurrencyMajorAmountToMinorUnits(row.amount, params.currencyCode.value);
       if (previous == null || amountMinor == null) return undefined;
-      return convertCurrencyMinorUnitsToMajorAmount(amountMinor - previous.balance_minor);
+      return convertCurrencyMinorUnitsToMajorAmount(amountMinor - previous.balance_minor, params.currencyCode.value);
     }
 
     return rowStates.value[index]!.changeValue;
@@ -428,7 +431,8 @@ export function useSnapshotsAddForm(params: UseSnapshotsAddFormParams) {
       amount: undefined,
       liveAmount: undefined,
       editingField: null,
-      amountTouched: false
+      amountTouched: false,
+      note: ""
     };
     nextRowKey += 1;
     return row;
@@ -9,6 +9,25 @@ export const commands = {
 	settingsGet: () => typedError<AppSettingsDto, ApiError>(__TAURI_INVOKE("settings_get")),
 	settingsUpdate: (input: AppSettingsUpdateInput) => typedError<AppSettingsDto, ApiError>(__TAURI_INVOKE("settings_update", { input })),
 	dataFolderOpen: () => typedError<null, ApiError>(__TAURI_INVOKE("data_folder_open")),
+	databaseStatus: () => typedError<DatabaseStatusDto, ApiError>(__TAURI_INVOKE("database_status")),
+	databaseUnlock: (passphrase: string) => typedError<null, ApiError>(__TAURI_INVOKE("database_unlock", { passphrase })),
+	databaseEncryptionEnable: (passphrase: string) => typedError<null, ApiError>(__TAURI_INVOKE("database_encryption_enable", { passphrase })),
+	databasePassphraseChange: (currentPassphrase: string, newPassphrase: string) => typedError<null, ApiError>(__TAURI_INVOKE("database_passphrase_change", { currentPassphrase, newPassphrase })),
+	databaseEncryptionDisable: (currentPassphrase: string) => typedError<null, ApiError>(__TAURI_INVOKE("database_encryption_disable", { currentPassphrase })),
+	databaseDoctor: () => typedError<DoctorReportDto, ApiError>(__TAURI_INVOKE("database_doctor")),
+	databaseDoctorRepair: (repair: DoctorRepair) => typedError<DoctorReportDto, ApiError>(__TAURI_INVOKE("database_doctor_repair", { repair })),
+	dataExport: (options: DataExportOptionsInput) => typedError<DataExportDto, ApiError>(__TAURI_INVOKE("data_export", { options })),
+	dataExportsFolderOpen: () => typedError<null, ApiError>(__TAURI_INVOKE("data_exports_folder_open")),
+	dataImportPreview: (input: DataImportSourceInput, options: DataImportOptionsInput) => typedError<DataImportPreviewDto, ApiError>(__TAURI_INVOKE("data_import_preview", { input, options })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,exported_at:new Date(v.data.exported_at)}) } : v) as typeof v)),
+	dataImportCommit: (input: DataImportSourceInput, options: DataImportOptionsInput) => typedError<DataImportCommitDto, ApiError>(__TAURI_INVOKE("data_import_commit", { input, options })),
+	backupsList: () => typedError<BackupListDto, ApiError>(__TAURI_INVOKE("backups_list")).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,backups:v.data.backups.map(i=>({...i,created_at:new Date(i.created_at)}))}) } : v) as typeof v)),
+	backupCreate: () => typedError<BackupDto, ApiError>(__TAURI_INVOKE("backup_create")).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,created_at:new Date(v.data.created_at)}) } : v) as typeof v)),
+	backupsPrune: () => typedError<BackupsPruneDto, ApiError>(__TAURI_INVOKE("backups_prune")),
+	backupRestore: (fileName: string) => typedError<BackupRestoreDto, ApiError>(__TAURI_INVOKE("backup_restore", { fileName })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,safety_backup:({...v.data.safety_backup,created_at:new Date(v.data.safety_backup.created_at)})}) } : v) as typeof v)),
+	backupsFolderOpen: () => typedError<null, ApiError>(__TAURI_INVOKE("backups_folder_open")),
+	backupArchiveCreate: (passphrase: string) => typedError<BackupArchiveDto, ApiError>(__TAURI_INVOKE("backup_archive_create", { passphrase })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,created_at:new Date(v.data.created_at)}) } : v) as typeof v)),
+	backupArchiveInspect: (source: BackupArchiveSourceInput, passphrase: string) => typedError<BackupArchiveInspectionDto, ApiError>(__TAURI_INVOKE("backup_archive_inspect", { source, passphrase })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,created_at:new Date(v.data.created_at)}) } : v) as typeof v)),
+	backupArchiveRestore: (source: BackupArchiveSourceInput, passphrase: string) => typedError<BackupRestoreDto, ApiError>(__TAURI_INVOKE("backup_archive_restore", { source, passphrase })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,safety_backup:({...v.data.safety_backup,created_at:new Date(v.data.safety_backup.created_at)})}) } : v) as typeof v)),
 	appUpdatesStateGet: () => typedError<AppUpdateStateDto, ApiError>(__TAURI_INVOKE("app_updates_state_get")),
 	appUpdatesCheck: () => typedError<AppUpdateStateDto, ApiError>(__TAURI_INVOKE("app_updates_check")),
 	appUpdatesInstallPendingAndRestart: () => typedError<AppUpdateStateDto, ApiError>(__TAURI_INVOKE("app_updates_install_pending_and_restart")),
@@ -22,18 +41,37 @@ export const commands = {
 	institutionsUpdate: (institutionId: number, input: InstitutionUpsertInput) => typedError<null, ApiError>(__TAURI_INVOKE("institutions_update", { institutionId, input })),
 	institutionsDeletePreview: (institutionId: number) => typedError<InstitutionDeletePreviewDto, ApiError>(__TAURI_INVOKE("institutions_delete_preview", { institutionId })),
 	institutionsDelete: (institutionId: number) => typedError<null, ApiError>(__TAURI_INVOKE("institutions_delete", { institutionId })),
+	accountTypesList: () => typedError<AccountTypeSummaryDto[], ApiError>(__TAURI_INVOKE("account_types_list")),
+	accountTypesCreate: (input: AccountTypeUpsertInput) => typedError<CreatedIdDto, ApiError>(__TAURI_INVOKE("account_types_create", { input })),
+	accountTypesUpdate: (typeId: number, input: AccountTypeUpsertInput) => typedError<null, ApiError>(__TAURI_INVOKE("account_types_update", { typeId, input })),
+	accountTypesDelete: (typeId: number, input: AccountTypeDeleteInput) => typedError<null, ApiError>(__TAURI_INVOKE("account_types_delete", { typeId, input })),
+	tagsList: () => typedError<TagSummaryDto[], ApiError>(__TAURI_INVOKE("tags_list")),
 	institutionsGet: (institutionId: number) => typedError<InstitutionDetailDto, ApiError>(__TAURI_INVOKE("institutions_get", { institutionId })),
 	accountsGet: (accountId: number) => typedError<AccountDto, ApiError>(__TAURI_INVOKE("accounts_get", { accountId })),
 	accountSnapshotsList: (accountId: number) => typedError<AccountBalanceSnapshotDto[], ApiError>(__TAURI_INVOKE("account_snapshots_list", { accountId })).then((v) => ((v.status === "ok" ? { ...v, data: v.data.map(i=>({...i,created_at:new Date(i.created_at)})) } : v) as typeof v)),
+	accountHistoryList: (accountId: number) => typedError<AuditLogEntryDto[], ApiError>(__TAURI_INVOKE("account_history_list", { accountId })).then((v) => ((v.status === "ok" ? { ...v, data: v.data.map(i=>({...i,changed_at:new Date(i.changed_at)})) } : v) as typeof v)),
 	accountSnapshotsCreate: (accountId: number, input: AccountSnapshotsCreateInput) => typedError<null, ApiError>(__TAURI_INVOKE("account_snapshots_create", { accountId, input })),
 	accountSnapshotUpdate: (accountId: number, snapshotId: number, input: AccountSnapshotUpdateInput) => typedError<null, ApiError>(__TAURI_INVOKE("account_snapshot_update", { accountId, snapshotId, input })),
 	accountSnapshotsDelete: (accountId: number, input: AccountSnapshotsDeleteInput) => typedError<null, ApiError>(__TAURI_INVOKE("account_snapshots_delete", { accountId, input })),
-	accountSnapshotImportInspect: (input: SnapshotImportSourceInput) => typedError<SnapshotImportInspectionDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_inspect", { input })),
+	accountSnapshotImportInspect: (accountId: number | null, input: SnapshotImportSourceInput) => typedError<SnapshotImportInspectionDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_inspect", { accountId, input })).then((v) => ((v.status === "ok" ? { ...v, data: v.data.kind === "csv" ? ({...v.data,profile:v.data.profile==null?v.data.profile:({...v.data.profile,created_at:new Date(v.data.profile.created_at),updated_at:new Date(v.data.profile.updated_at)})}) : v.data.kind === "spreadsheet" ? ({...v.data,profile:v.data.profile==null?v.data.profile:({...v.data.profile,created_at:new Date(v.data.profile.created_at),updated_at:new Date(v.data.profile.updated_at)})}) : v.data } : v) as typeof v)),
 	accountSnapshotImportPreview: (accountId: number, input: SnapshotImportSourceInput, options: SnapshotImportOptionsInput) => typedError<SnapshotImportPreviewDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_preview", { accountId, input, options })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,rows:v.data.rows.map(i=>({...i,existing_snapshot:i.existing_snapshot==null?i.existing_snapshot:({...i.existing_snapshot,created_at:new Date(i.existing_snapshot.created_at)})}))}) } : v) as typeof v)),
 	accountSnapshotImportCommit: (accountId: number, input: SnapshotImportSourceInput, options: SnapshotImportOptionsInput) => typedError<SnapshotImportCommitDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_commit", { accountId, input, options })),
+	accountSnapshotImportBatchesList: (accountId: number) => typedError<SnapshotImportBatchDto[], ApiError>(__TAURI_INVOKE("account_snapshot_import_batches_list", { accountId })).then((v) => ((v.status === "ok" ? { ...v, data: v.data.map(i=>({...i,created_at:new Date(i.created_at),rolled_back_at:i.rolled_back_at==null?i.rolled_back_at:new Date(i.rolled_back_at)})) } : v) as typeof v)),
+	accountSnapshotImportProfilesList: (accountId: number) => typedError<SnapshotImportProfileDto[], ApiError>(__TAURI_INVOKE("account_snapshot_import_profiles_list", { accountId })).then((v) => ((v.status === "ok" ? { ...v, data: v.data.map(i=>({...i,created_at:new Date(i.created_at),updated_at:new Date(i.updated_at)})) } : v) as typeof v)),
+	accountSnapshotImportProfileSave: (accountId: number, input: SnapshotImportProfileSaveInput) => typedError<SnapshotImportProfileDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_profile_save", { accountId, input })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,created_at:new Date(v.data.created_at),updated_at:new Date(v.data.updated_at)}) } : v) as typeof v)),
+	accountSnapshotImportProfileDelete: (accountId: number, profileId: number) => typedError<null, ApiError>(__TAURI_INVOKE("account_snapshot_import_profile_delete", { accountId, profileId })),
+	accountSnapshotImportBatchRollback: (accountId: number, batchId: number) => typedError<SnapshotImportRollbackDto, ApiError>(__TAURI_INVOKE("account_snapshot_import_batch_rollback", { accountId, batchId })),
+	accountCreateImportPreview: (account: AccountUpsertInput, input: SnapshotImportSourceInput, options: SnapshotImportOptionsInput) => typedError<SnapshotImportPreviewDto, ApiError>(__TAURI_INVOKE("account_create_import_preview", { account, input, options })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,rows:v.data.rows.map(i=>({...i,existing_snapshot:i.existing_snapshot==null?i.existing_snapshot:({...i.existing_snapshot,created_at:new Date(i.existing_snapshot.created_at)})}))}) } : v) as typeof v)),
+	accountCreateImportCommit: (account: AccountUpsertInput, input: SnapshotImportSourceInput, options: SnapshotImportOptionsInput) => typedError<AccountCreateImportCommitDto, ApiError>(__TAURI_INVOKE("account_create_import_commit", { account, input, options })),
+	snapshotWideImportPreview: (input: CsvSnapshotImportSourceInput, options: WideSnapshotImportOptionsInput) => typedError<WideSnapshotImportPreviewDto, ApiError>(__TAURI_INVOKE("snapshot_wide_import_preview", { input, options })).then((v) => ((v.status === "ok" ? { ...v, data: ({...v.data,accounts:v.data.accounts.map(i=>({...i,preview:({...i.preview,rows:i.preview.rows.map(i=>({...i,existing_snapshot:i.existing_snapshot==null?i.existing_snapshot:({...i.existing_snapshot,created_at:new Date(i.existing_snapshot.created_at)})}))})}))}) } : v) as typeof v)),
+	snapshotWideImportCommit: (input: CsvSnapshotImportSourceInput, options: WideSnapshotImportOptionsInput) => typedError<WideSnapshotImportCommitDto, ApiError>(__TAURI_INVOKE("snapshot_wide_import_commit", { input, options })),
 	accountBalanceOverTime: (accountId: number, period: BalanceOverTimePeriod) => typedError<BalancePointDto[], ApiError>(__TAURI_INVOKE("account_balance_over_time", { accountId, period })),
-	dashboardGet: () => typedError<DashboardDto, ApiError>(__TAURI_INVOKE("dashboard_get")),
-	dashboardBalanceOverTime: (period: BalanceOverTimePeriod) => typedError<DashboardBalancePointDto[], ApiError>(__TAURI_INVOKE("dashboard_balance_over_time", { period })),
+	dashboardGet: (tagId: number | null) => typedError<DashboardDto, ApiError>(__TAURI_INVOKE("dashboard_get", { tagId })),
+	dashboardBalanceOverTime: (period: BalanceOverTimePeriod, tagId: number | null) => typedError<DashboardBalancePointDto[], ApiError>(__TAURI_INVOKE("dashboard_balance_over_time", { period, tagId })),
+	fxRatesList: () => typedError<FxRateDto[], ApiError>(__TAURI_INVOKE("fx_rates_list")),
+	fxRatesUpsert: (input: FxRateWriteInput) => typedError<null, ApiError>(__TAURI_INVOKE("fx_rates_upsert", { input })),
+	fxRatesDelete: (input: FxRatesDeleteInput) => typedError<null, ApiError>(__TAURI_INVOKE("fx_rates_delete", { input })),
+	fxRatesImportCsv: (input: FxRateCsvImportInput) => typedError<FxRateImportCommitDto, ApiError>(__TAURI_INVOKE("fx_rates_import_csv", { input })),
 	search: (query: string) => typedError<SearchResultDto[], ApiError>(__TAURI_INVOKE("search", { query })),
 };
 
@@ -42,11 +80,19 @@ export type AccountBalanceSnapshotDto = {
 	id: number,
 	date: string,
 	balance_minor: number,
+	note: string | null,
+	/**  `None` for snapshots recorded before sources were tracked. */
+	source: SnapshotSource | null,
 	created_at: Date,
 };
 
 export type AccountClassification = "asset" | "liability";
 
+export type AccountCreateImportCommitDto = {
+	account_id: number,
+	result: SnapshotImportCommitDto,
+};
+
 export type AccountDeletePreviewDto = {
 	id: number,
 	name: string,
@@ -59,6 +105,7 @@ export type AccountDto = {
 	name: string,
 	institution: InstitutionDto,
 	account_type: AccountTypeDto,
+	tags: TagDto[],
 	currency_code: CurrencyCode,
 	account_classification: AccountClassification,
 	opened_date: string | null,
@@ -73,12 +120,15 @@ export type AccountDto = {
 export type AccountSnapshotUpdateInput = {
 	date: string,
 	balance_minor: number,
+	note?: string | null,
 	overwrite_existing: boolean,
 };
 
 export type AccountSnapshotWriteInput = {
 	date: string,
 	balance_minor: number,
+	note?: string | null,
+	source?: SnapshotSource | null,
 	overwrite_existing: boolean,
 };
 
@@ -90,21 +140,38 @@ export type AccountSnapshotsDeleteInput = {
 	snapshot_ids: number[],
 };
 
+export type AccountTypeDeleteInput = {
+	replacement_type_id?: number | null,
+};
+
 export type AccountTypeDto = {
 	id: number,
-	name: AccountTypeName,
+	name: string,
+	/**  Hex colour in `#rrggbb` form. */
+	color: string,
 };
 
-export type AccountTypeName = "current" | "savings" | "credit_card" | "isa" | "investment" | "pension" | "cash" | "loan";
+export type AccountTypeSummaryDto = {
+	id: number,
+	name: string,
+	color: string,
+	account_count: number,
+};
+
+export type AccountTypeUpsertInput = {
+	name: string,
+	color: string,
+};
 
 export type AccountUpsertInput = {
 	institution: InstitutionRef,
 	name: string,
-	account_type: AccountTypeName,
+	account_type_id: number,
 	currency_code: CurrencyCode,
 	account_classification: AccountClassification,
 	opened_date?: string | null,
 	closed_date?: string | null,
+	tags: string[],
 };
 
 export type ActivityDataDto = {
@@ -123,6 +190,10 @@ export type AppSettingsDto = {
 	default_display_currency_code: CurrencyCode,
 	display_locale: AppLocaleCode,
 	theme: ThemePreference,
+	backup_schedule: BackupSchedule,
+	backup_retention_count: number,
+	/**  `None` while backups go to the default folder. */
+	backup_folder: string | null,
 };
 
 export type AppSettingsUpdateInput = {
@@ -130,6 +201,9 @@ export type AppSettingsUpdateInput = {
 	default_display_currency_code?: CurrencyCode | null,
 	display_locale?: AppLocaleCode | null,
 	theme?: ThemePreference | null,
+	backup_schedule?: BackupSchedule | null,
+	backup_retention_count?: number | null,
+	backup_folder?: string | null,
 };
 
 export type AppUpdateCheckModeDto = "startup" | "user";
@@ -204,6 +278,97 @@ export type AppUpdateStatusDto =
 /**  A check, download, or install step failed. */
 { kind: "error"; check_mode: AppUpdateCheckModeDto | null; phase: AppUpdatePhaseDto; code: AppUpdateErrorCodeDto; message: string; update: AppUpdateMetadataDto | null };
 
+export type AuditAction = "create" | "update" | "delete";
+
+export type AuditEntityKind = "institution" | "account" | "snapshot";
+
+export type AuditFieldChangeDto = {
+	field: string,
+	/**  Value before the change as stored text; `None` when the field was empty or absent. */
+	before: string | null,
+	after: string | null,
+};
+
+export type AuditLogEntryDto = {
+	id: number,
+	entity_kind: AuditEntityKind,
+	entity_id: number,
+	action: AuditAction,
+	/**  Every recorded field for creates and deletes; only the fields that changed for updates. */
+	changes: AuditFieldChangeDto[],
+	changed_at: Date,
+};
+
+export type BackupArchiveDto = {
+	file_name: string,
+	path: string,
+	created_at: Date,
+	size_bytes: number,
+};
+
+export type BackupArchiveInspectionDto = {
+	created_at: Date,
+	/**  Version of the app that wrote the archive. */
+	app_version: string,
+	/**  Newest migration applied to the archived database. */
+	migration_version: number,
+};
+
+export type BackupArchiveSourceInput = {
+	file_name: string,
+	/**  The whole archive file. */
+	content: number[],
+};
+
+export type BackupDto = {
+	file_name: string,
+	kind: BackupKind,
+	created_at: Date,
+	size_bytes: number,
+	/**  Set for `PreMigration` backups. */
+	migration: BackupMigrationDto | null,
+};
+
+export type BackupKind = 
+/**  Taken by the backup schedule. */
+"scheduled" | 
+/**  Requested from Settings. */
+"manual" | 
+/**  The database as it was just before a restore replaced it. */
+"pre_restore" | 
+/**  The database as it was just before startup applied new migrations. */
+"pre_migration";
+
+export type BackupListDto = {
+	/**  Folder backups are read from and written to. */
+	folder: string,
+	/**  Newest first. */
+	backups: BackupDto[],
+	/**  Why the last scheduled backup check failed, until a later one succeeds. */
+	last_scheduled_error: string | null,
+};
+
+/**
+ *  The update a `PreMigration` backup was taken before, recorded in its file name.
+ */
+export type BackupMigrationDto = {
+	/**  Version of Worth that went on to apply new migrations. */
+	app_version: string,
+	/**  Newest migration applied to the database in the backup. */
+	migration_version: number,
+};
+
+export type BackupRestoreDto = {
+	/**  Copy of the database taken just before it was replaced. */
+	safety_backup: BackupDto,
+};
+
+export type BackupSchedule = "off" | "startup" | "daily";
+
+export type BackupsPruneDto = {
+	deleted_count: number,
+};
+
 export type BalanceOverTimePeriod = "1M" | "6M" | "1Y" | "MAX";
 
 export type BalancePointDto = {
@@ -215,7 +380,34 @@ export type CreatedIdDto = {
 	id: number,
 };
 
-export type CsvSnapshotImportBalanceFormat = "thousands_comma_decimal_dot" | "thousands_dot_decimal_comma";
+/**  Whether the guessed amount column holds running balances or transaction amounts. */
+export type CsvSnapshotImportAmountKind = "balances" | "transactions";
+
+export type CsvSnapshotImportBalanceFormat = 
+"thousands_comma_decimal_dot" | 
+"thousands_dot_decimal_comma" | 
+/**  Plain, no-break, or narrow no-break spaces between thousands. */
+"thousands_space_decimal_comma" | 
+"thousands_space_decimal_dot" | 
+/**  Swiss style, with a straight or typographic apostrophe between thousands. */
+"thousands_apostrophe_decimal_dot" | 
+/**  Whole numbers already in the currency's minor unit, such as pence. */
+"minor_units" | 
+/**
+ *  Like `ThousandsCommaDecimalDot`, but negative amounts may end in `-`, as in
+ *  "12.50-".
+ */
+"thousands_comma_decimal_dot_trailing_minus" | 
+/**
+ *  Like `ThousandsDotDecimalComma`, but negative amounts may end in `-`, as in
+ *  "12,50-".
+ */
+"thousands_dot_decimal_comma_trailing_minus" | 
+/**
+ *  Like `ThousandsCommaDecimalDot`, but amounts may end in `CR` for credits or `DR`
+ *  for debits, which are negative.
+ */
+"thousands_comma_decimal_dot_credit_debit";
 
 export type CsvSnapshotImportBlankAmountPolicy = "skip" | "zero" | "error";
 
@@ -225,7 +417,41 @@ export type CsvSnapshotImportColumnDto = {
 	sample_values: string[],
 };
 
-export type CsvSnapshotImportDateFormat = "yyyy_mm_dd" | "dd_mm_yyyy_slash" | "dd_mm_yy_slash" | "mm_dd_yyyy_slash" | "mm_dd_yy_slash" | "dd_mm_yyyy_dash" | "yyyy_mm_dd_slash" | "iso_8601_date_time";
+export type CsvSnapshotImportDateFormat = 
+"yyyy_mm_dd" | 
+"dd_mm_yyyy_slash" | 
+"dd_mm_yy_slash" | 
+"mm_dd_yyyy_slash" | 
+"mm_dd_yy_slash" | 
+"dd_mm_yyyy_dash" | 
+"yyyy_mm_dd_slash" | 
+"iso_8601_date_time" | 
+/**  Day, month name and year, such as "03 Jan 2024" or "3-January-24". */
+"dd_mon_yyyy" | 
+/**  Month name, day and year, such as "January 3, 2024". */
+"mon_dd_yyyy" | 
+/**  Year, month name and day, such as "2024-Jan-03". */
+"yyyy_mon_dd" | 
+/**  Eight digits with no separators, such as "20240103". */
+"yyyy_mm_dd_compact" | 
+/**
+ *  Spreadsheet day numbers counted from 1899-12-30, such as "45294". Any time of
+ *  day after the decimal point is ignored.
+ */
+"excel_serial";
+
+export type CsvSnapshotImportDelimiter = "comma" | "semicolon" | "tab" | "pipe";
+
+export type CsvSnapshotImportEncoding = "utf8" | "utf16_le" | "utf16_be" | "windows1252";
+
+/**  How a CSV file was read, whether detected or chosen by the user. */
+export type CsvSnapshotImportFormatDto = {
+	encoding: CsvSnapshotImportEncoding,
+	/**  Whether the file started with a byte order mark, which is never part of the data. */
+	byte_order_mark: boolean,
+	delimiter: CsvSnapshotImportDelimiter,
+	quote: CsvSnapshotImportQuote,
+};
 
 export type CsvSnapshotImportGuessesDto = {
 	date_column: string | null,
@@ -242,8 +468,36 @@ export type CsvSnapshotImportInspectionDto = {
 	sample_rows: CsvSnapshotImportSampleRowDto[],
 	guesses: CsvSnapshotImportGuessesDto,
 	total_rows: number,
+	format: CsvSnapshotImportFormatDto,
+	/**
+	 *  Built-in bank or platform layout recognised from the headers; `guesses` follow it
+	 *  when it is a confident match.
+	 */
+	known_profile: CsvSnapshotImportKnownProfileDto | null,
+	/**
+	 *  Saved profile whose columns match this file; its options take the place of
+	 *  `guesses` when set.
+	 */
+	profile: SnapshotImportProfileDto | null,
+};
+
+/**  Built-in export layout recognised from a CSV's headers. */
+export type CsvSnapshotImportKnownProfileDto = {
+	id: string,
+	name: string,
+	amount_kind: CsvSnapshotImportAmountKind,
+	confidence: CsvSnapshotImportKnownProfileConfidence,
 };
 
+/**
+ *  How sure a header match is. `Low` matches are named to the user, but the columns and
+ *  formats are guessed from the values rather than taken from the profile.
+ */
+export type CsvSnapshotImportKnownProfileConfidence = 
+/**  The signature is generic enough that other exports share it. */
+"low" | 
+"high";
+
 export type CsvSnapshotImportMissingTimezonePolicy = "local" | "utc" | "timezone";
 
 export type CsvSnapshotImportOptionsInput = {
@@ -257,6 +511,12 @@ export type CsvSnapshotImportOptionsInput = {
 	blank_amount_policy: CsvSnapshotImportBlankAmountPolicy,
 };
 
+export type CsvSnapshotImportQuote = 
+"double" | 
+"single" | 
+/**  Quote characters are read as part of the value. */
+"none";
+
 export type CsvSnapshotImportSampleRowDto = {
 	source_row_number: number,
 	values: string[],
@@ -264,25 +524,48 @@ export type CsvSnapshotImportSampleRowDto = {
 
 export type CsvSnapshotImportSourceInput = {
 	file_name: string,
-	content: string,
+	/**  Raw file bytes; the encoding is detected unless `encoding` is set. */
+	content: number[],
 	has_header_row: boolean,
+	/**  Overrides for the detected format; `None` detects from the content. */
+	encoding: CsvSnapshotImportEncoding | null,
+	delimiter: CsvSnapshotImportDelimiter | null,
+	quote: CsvSnapshotImportQuote | null,
 };
 
 export type CsvSnapshotImportTimestampDatePolicy = "date_as_written" | "convert_to_local" | "convert_to_utc";
 
-export type CurrencyCode = "GBP";
+/**  The known end-of-day balance that derived balances are summed from. */
+export type CsvTransactionsSnapshotImportAnchor = { kind: "latest"; balance_minor: number } | { kind: "on_date"; date: string; balance_minor: number };
+
+/**
+ *  Options for a CSV or spreadsheet of transactions with no running-balance column.
+ *  Balances are derived by summing the amount column away from one known balance.
+ */
+export type CsvTransactionsSnapshotImportOptionsInput = {
+	/**  Column and format options; `amount_column` holds signed transaction amounts. */
+	csv: CsvSnapshotImportOptionsInput,
+	anchor: CsvTransactionsSnapshotImportAnchor,
+};
+
+export type CurrencyCode = "AED" | "AFN" | "ALL" | "AMD" | "AOA" | "ARS" | "AUD" | "AWG" | "AZN" | "BAM" | "BBD" | "BDT" | "BGN" | "BHD" | "BIF" | "BMD" | "BND" | "BOB" | "BOV" | "BRL" | "BSD" | "BTN" | "BWP" | "BYN" | "BZD" | "CAD" | "CDF" | "CHE" | "CHF" | "CHW" | "CLF" | "CLP" | "CNY" | "COP" | "COU" | "CRC" | "CUP" | "CVE" | "CZK" | "DJF" | "DKK" | "DOP" | "DZD" | "EGP" | "ERN" | "ETB" | "EUR" | "FJD" | "FKP" | "GBP" | "GEL" | "GHS" | "GIP" | "GMD" | "GNF" | "GTQ" | "GYD" | "HKD" | "HNL" | "HTG" | "HUF" | "IDR" | "ILS" | "INR" | "IQD" | "IRR" | "ISK" | "JMD" | "JOD" | "JPY" | "KES" | "KGS" | "KHR" | "KMF" | "KPW" | "KRW" | "KWD" | "KYD" | "KZT" | "LAK" | "LBP" | "LKR" | "LRD" | "LSL" | "LYD" | "MAD" | "MDL" | "MGA" | "MKD" | "MMK" | "MNT" | "MOP" | "MRU" | "MUR" | "MVR" | "MWK" | "MXN" | "MXV" | "MYR" | "MZN" | "NAD" | "NGN" | "NIO" | "NOK" | "NPR" | "NZD" | "OMR" | "PAB" | "PEN" | "PGK" | "PHP" | "PKR" | "PLN" | "PYG" | "QAR" | "RON" | "RSD" | "RUB" | "RWF" | "SAR" | "SBD" | "SCR" | "SDG" | "SEK" | "SGD" | "SHP" | "SLE" | "SOS" | "SRD" | "SSP" | "STN" | "SVC" | "SYP" | "SZL" | "THB" | "TJS" | "TMT" | "TND" | "TOP" | "TRY" | "TTD" | "TWD" | "TZS" | "UAH" | "UGX" | "USD" | "USN" | "UYI" | "UYU" | "UYW" | "UZS" | "VED" | "VES" | "VND" | "VUV" | "WST" | "XAF" | "XCD" | "XCG" | "XOF" | "XPF" | "YER" | "ZAR" | "ZMW" | "ZWG";
 
 export type DashboardAllocationDto = {
-	account_type: AccountTypeName,
+	account_type: AccountTypeDto,
 	balance_minor: number,
 };
 
 export type DashboardBalancePointDto = {
 	date: string,
+	/**  Total in the display currency. */
 	balance_minor: number,
+	/**  Whether an account balance on this date was left out for lack of an exchange rate. */
+	has_missing_fx_rate: boolean,
 };
 
 export type DashboardDto = {
+	/**  Display currency that every dashboard amount has been converted into. */
+	currency_code: CurrencyCode,
 	total_balance_minor: number,
 	change_vs_last_month_pct: number | null,
 	monthly_yield_minor: number,
@@ -290,6 +573,171 @@ export type DashboardDto = {
 	active_accounts: number,
 	active_institutions: number,
 	allocation_by_type: DashboardAllocationDto[],
+	/**
+	 *  Account currencies left out of the dashboard amounts because no exchange rate
+	 *  into the display currency was in effect.
+	 */
+	missing_fx_rate_currency_codes: CurrencyCode[],
+};
+
+export type DataExportDto = {
+	/**  The JSON file, or the folder holding the CSV files. */
+	path: string,
+	file_names: string[],
+	account_count: number,
+	snapshot_count: number,
+};
+
+export type DataExportFormat = "json" | "csv";
+
+export type DataExportOptionsInput = {
+	format: DataExportFormat,
+	/**  Earliest snapshot date to include, inclusive. */
+	from_date: string | null,
+	/**  Latest snapshot date to include, inclusive. */
+	to_date: string | null,
+	/**  `None` exports every account. */
+	account_ids: number[] | null,
+};
+
+export type DataImportAccountPreviewDto = {
+	institution_name: string,
+	name: string,
+	currency_code: CurrencyCode,
+	action: DataImportMatchAction,
+	/**  `None` for accounts the import will create. */
+	account_id: number | null,
+	summary: SnapshotImportPreviewSummaryDto,
+	/**  Problems that stop this account being imported. */
+	issues: string[],
+};
+
+export type DataImportCommitDto = {
+	created_account_type_count: number,
+	created_institution_count: number,
+	created_account_count: number,
+	merged_account_count: number,
+	created_count: number,
+	overwritten_count: number,
+	skipped_count: number,
+};
+
+export type DataImportEntityPreviewDto = {
+	name: string,
+	action: DataImportMatchAction,
+};
+
+export type DataImportMatchAction = "create" | 
+/**  Reuses the existing row with the same name. */
+"merge";
+
+export type DataImportOptionsInput = {
+	/**  What to do when a matched account already has a snapshot on an imported date. */
+	existing_date_policy: SnapshotImportExistingDatePolicy,
+	overwrite_existing_confirmed: boolean,
+};
+
+export type DataImportPreviewDto = {
+	exported_at: Date,
+	app_version: string,
+	account_types: DataImportEntityPreviewDto[],
+	institutions: DataImportEntityPreviewDto[],
+	accounts: DataImportAccountPreviewDto[],
+	summary: SnapshotImportPreviewSummaryDto,
+};
+
+export type DataImportSourceInput = {
+	file_name: string,
+	content: string,
+};
+
+export type DatabaseIncompatibilityDto = {
+	/**  Applied migrations this version does not know, or knows with different contents. */
+	unknown_migrations: number[],
+	/**  Newest migration this version knows. */
+	latest_known_migration: number,
+};
+
+export type DatabaseStatusDto = {
+	/**  Whether the database file is encrypted with a passphrase. */
+	encrypted: boolean,
+	/**
+	 *  Whether the database is waiting for `database_unlock`. Commands that read or write
+	 *  data fail until it is unlocked.
+	 */
+	locked: boolean,
+	/**
+	 *  Set when the database was migrated by a newer version of Worth. It is then open
+	 *  read-only, and writes fail until a compatible backup is restored.
+	 */
+	incompatible: DatabaseIncompatibilityDto | null,
+};
+
+export type DoctorCheck = 
+/**  `PRAGMA integrity_check`. */
+"integrity" | 
+/**  `PRAGMA foreign_key_check`. */
+"foreign_keys" | 
+/**  `search_fts` against institutions and accounts. */
+"search_index" | 
+/**
+ *  Snapshot balances within `BALANCE_MINOR_ABS_MAX`. Out-of-range balances also fail the
+ *  table's `CHECK` constraint, so they are usually reported by [`Self::Integrity`] too.
+ */
+"snapshot_balances" | 
+/**  Snapshot dates that parse as `YYYY-MM-DD`. */
+"snapshot_dates";
+
+export type DoctorIssueDto = {
+	check: DoctorCheck,
+	message: string,
+	/**  Repair that fixes this issue, when there is a safe one. */
+	repair: DoctorRepair | null,
+};
+
+export type DoctorRepair = 
+/**
+ *  Rebuilds `search_fts` from institutions and accounts. It only holds derived data,
+ *  so nothing can be lost.
+ */
+"rebuild_search_index";
+
+export type DoctorReportDto = {
+	/**  Every problem found. Empty when the database is healthy. */
+	issues: DoctorIssueDto[],
+};
+
+export type FxRateCsvImportInput = {
+	source: CsvSnapshotImportSourceInput,
+	base_currency_code: CurrencyCode,
+	quote_currency_code: CurrencyCode,
+	date_column: string,
+	rate_column: string,
+	date_format: CsvSnapshotImportDateFormat,
+};
+
+export type FxRateDto = {
+	id: number,
+	base_currency_code: CurrencyCode,
+	quote_currency_code: CurrencyCode,
+	date: string,
+	rate: number,
+};
+
+export type FxRateImportCommitDto = {
+	created_count: number,
+	updated_count: number,
+};
+
+export type FxRateWriteInput = {
+	base_currency_code: CurrencyCode,
+	quote_currency_code: CurrencyCode,
+	date: string,
+	rate: number,
+};
+
+export type FxRatesDeleteInput = {
+	rate_ids: number[],
 };
 
 export type InstitutionDeletePreviewAccountDto = {
@@ -322,7 +770,7 @@ export type InstitutionSummaryDto = {
 	name: string,
 	account_count: number,
 	empty_account_count: number,
-	account_types: AccountTypeName[],
+	account_types: AccountTypeDto[],
 	total_balance_minor: number,
 };
 
@@ -330,12 +778,57 @@ export type InstitutionUpsertInput = {
 	name: string,
 };
 
-export type SearchResultDto = { kind: "account"; id: number; name: string; account_type: AccountTypeName; institution_name: string } | { kind: "institution"; id: number; name: string };
+export type OfxSnapshotImportBalanceDto = {
+	source_row_number: number,
+	kind: OfxSnapshotImportBalanceKind,
+	raw_date: string,
+	raw_amount: string,
+	date: string | null,
+	currency_code: string | null,
+};
+
+/**  Which statement balance aggregate becomes the snapshot balance. */
+export type OfxSnapshotImportBalanceKind = "ledger" | "available";
+
+export type OfxSnapshotImportGuessesDto = {
+	balance_kind: OfxSnapshotImportBalanceKind | null,
+};
+
+export type OfxSnapshotImportInspectionDto = {
+	file_name: string,
+	statement_count: number,
+	balances: OfxSnapshotImportBalanceDto[],
+	guesses: OfxSnapshotImportGuessesDto,
+};
+
+export type OfxSnapshotImportOptionsInput = {
+	balance_kind: OfxSnapshotImportBalanceKind,
+};
+
+export type OfxSnapshotImportSourceInput = {
+	file_name: string,
+	content: string,
+};
+
+export type SearchResultDto = { kind: "account"; id: number; name: string; account_type: AccountTypeDto; institution_name: string } | { kind: "institution"; id: number; name: string };
+
+export type SnapshotImportBatchDto = {
+	id: number,
+	file_name: string,
+	options: SnapshotImportOptionsInput,
+	created_count: number,
+	overwritten_count: number,
+	skipped_count: number,
+	created_at: Date,
+	rolled_back_at: Date | null,
+};
 
 export type SnapshotImportCommitDto = {
 	created_count: number,
 	overwritten_count: number,
 	skipped_count: number,
+	/**  Batch to roll back to undo this import; `None` when every row was skipped. */
+	batch_id: number | null,
 };
 
 export type SnapshotImportDuplicateDatePolicy = "keep_first" | "keep_last" | "error";
@@ -349,9 +842,13 @@ export type SnapshotImportExistingSnapshotDto = {
 	created_at: Date,
 };
 
-export type SnapshotImportInspectionDto = {
+export type SnapshotImportInspectionDto = ({
 	kind: "csv",
-} & CsvSnapshotImportInspectionDto;
+} & CsvSnapshotImportInspectionDto) | ({
+	kind: "ofx",
+} & OfxSnapshotImportInspectionDto) | ({
+	kind: "spreadsheet",
+} & SpreadsheetSnapshotImportInspectionDto);
 
 export type SnapshotImportOptionsInput = {
 	source: SnapshotImportSourceOptionsInput,
@@ -389,16 +886,92 @@ export type SnapshotImportPreviewSummaryDto = {
 	invalid_count: number,
 };
 
-export type SnapshotImportSourceInput = {
+/**
+ *  Import options saved for an account, reapplied when a CSV with the same columns
+ *  is inspected again.
+ */
+export type SnapshotImportProfileDto = {
+	id: number,
+	name: string,
+	/**
+	 *  CSV column names the profile was saved against, in file order.
+	 */
+	columns: string[],
+	options: SnapshotImportOptionsInput,
+	created_at: Date,
+	updated_at: Date,
+};
+
+export type SnapshotImportProfileSaveInput = {
+	name: string,
+	columns: string[],
+	options: SnapshotImportOptionsInput,
+};
+
+export type SnapshotImportRollbackDto = {
+	deleted_count: number,
+	restored_count: number,
+};
+
+export type SnapshotImportSourceInput = ({
 	kind: "csv",
-} & CsvSnapshotImportSourceInput;
+} & CsvSnapshotImportSourceInput) | ({
+	kind: "ofx",
+} & OfxSnapshotImportSourceInput) | ({
+	kind: "spreadsheet",
+} & SpreadsheetSnapshotImportSourceInput);
 
-export type SnapshotImportSourceOptionsInput = {
+export type SnapshotImportSourceOptionsInput = ({
 	kind: "csv",
-} & CsvSnapshotImportOptionsInput;
+} & CsvSnapshotImportOptionsInput) | ({
+	kind: "csv_transactions",
+} & CsvTransactionsSnapshotImportOptionsInput) | ({
+	kind: "ofx",
+} & OfxSnapshotImportOptionsInput);
 
 export type SnapshotImportUnchangedValuePolicy = "exclude" | "include";
 
+/**  Where a snapshot balance came from. */
+export type SnapshotSource = { kind: "manual" } | { kind: "csv_import"; file_name: string } | { kind: "ofx_import"; file_name: string } | { kind: "spreadsheet_import"; file_name: string } | { kind: "api"; provider: string };
+
+export type SpreadsheetSnapshotImportInspectionDto = {
+	file_name: string,
+	/**  Worksheet names in workbook order. */
+	sheets: string[],
+	/**  The worksheet that was read. */
+	sheet: string,
+	columns: CsvSnapshotImportColumnDto[],
+	sample_rows: CsvSnapshotImportSampleRowDto[],
+	guesses: CsvSnapshotImportGuessesDto,
+	total_rows: number,
+	known_profile: CsvSnapshotImportKnownProfileDto | null,
+	profile: SnapshotImportProfileDto | null,
+};
+
+/**
+ *  One worksheet of a workbook, imported with the CSV options. Those options only
+ *  apply to cells stored as text; date and number cells are read as they are.
+ */
+export type SpreadsheetSnapshotImportSourceInput = {
+	file_name: string,
+	/**  Raw workbook bytes; .xlsx, .xls and .ods files are told apart by their content. */
+	content: number[],
+	/**  Worksheet to read; `None` reads the first one. */
+	sheet: string | null,
+	has_header_row: boolean,
+};
+
+export type TagDto = {
+	id: number,
+	name: string,
+};
+
+export type TagSummaryDto = {
+	id: number,
+	name: string,
+	account_count: number,
+};
+
 export type ThemePreference = "system" | "light" | "dark";
 
 export type ValidationIssue = {
@@ -421,6 +994,55 @@ export type ValidationIssue = {
 	telemetry_message: string | null,
 };
 
+export type WideSnapshotImportAccountCommitDto = {
+	column: string,
+	account_id: number,
+	created_account: boolean,
+	result: SnapshotImportCommitDto,
+};
+
+export type WideSnapshotImportAccountPreviewDto = {
+	column: string,
+	/**  `None` for accounts the commit will create. */
+	account_id: number | null,
+	account_name: string,
+	currency_code: CurrencyCode,
+	preview: SnapshotImportPreviewDto,
+};
+
+export type WideSnapshotImportColumnInput = {
+	column: string,
+	target: WideSnapshotImportTarget,
+};
+
+export type WideSnapshotImportCommitDto = {
+	accounts: WideSnapshotImportAccountCommitDto[],
+};
+
+/**
+ *  Options for a "dates down the side, one column per account" CSV. Every mapped
+ *  column is planned as its own single-account import with the shared policies.
+ */
+export type WideSnapshotImportOptionsInput = {
+	date_column: string,
+	date_format: CsvSnapshotImportDateFormat,
+	balance_format: CsvSnapshotImportBalanceFormat,
+	blank_amount_policy: CsvSnapshotImportBlankAmountPolicy,
+	/**  Columns to import; unmapped columns are ignored. */
+	columns: WideSnapshotImportColumnInput[],
+	existing_date_policy: SnapshotImportExistingDatePolicy,
+	unchanged_value_policy: SnapshotImportUnchangedValuePolicy,
+	duplicate_date_policy: SnapshotImportDuplicateDatePolicy,
+	overwrite_existing_confirmed: boolean,
+};
+
+export type WideSnapshotImportPreviewDto = {
+	summary: SnapshotImportPreviewSummaryDto,
+	accounts: WideSnapshotImportAccountPreviewDto[],
+};
+
+export type WideSnapshotImportTarget = { kind: "existing"; account_id: number } | { kind: "new"; input: AccountUpsertInput };
+
 /* Tauri Specta runtime */
 async function typedError<T, E>(result: Promise<T>): Promise<{ status: "ok"; data: T } | { status: "error"; error: E }> {
     try {
@@ -1,5 +1,5 @@
 // Generated file, update with `bun run contracts:gen`.
 import { z } from "zod";
 
-export const accountSnapshotUpdateInputGeneratedSchema = z.object({ "balance_minor": z.number({ error: "Enter a balance" }).int().gte(-99999999999999, { error: "Balance is too large" }).lte(99999999999999, { error: "Balance is too large" }), "date": z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function").transform((value) => value.toString()).pipe(z.string().date()), "overwrite_existing": z.boolean() });
+export const accountSnapshotUpdateInputGeneratedSchema = z.object({ "balance_minor": z.number({ error: "Enter a balance" }).int().gte(-99999999999999, { error: "Balance is too large" }).lte(99999999999999, { error: "Balance is too large" }), "date": z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function").transform((value) => value.toString()).pipe(z.string().date()), "note": z.union([z.string().refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 500) return false; } return true; }, { error: "Note must be 500 characters or fewer" }), z.null()]).optional(), "overwrite_existing": z.boolean() });
 export type AccountSnapshotUpdateInputFromSchema = z.infer<typeof accountSnapshotUpdateInputGeneratedSchema>;
@@ -0,0 +1,5 @@
+// Generated file, update with `bun run contracts:gen`.
+import { z } from "zod";
+
+export const accountTypeDeleteInputGeneratedSchema = z.object({ "replacement_type_id": z.union([z.number().int(), z.null()]).optional() });
+export type AccountTypeDeleteInputFromSchema = z.infer<typeof accountTypeDeleteInputGeneratedSchema>;
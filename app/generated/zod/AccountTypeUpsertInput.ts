@@ -0,0 +1,5 @@
+// Generated file, update with `bun run contracts:gen`.
+import { z } from "zod";
+
+export const accountTypeUpsertInputGeneratedSchema = z.object({ "color": z.string({ error: "Choose a colour" }).regex(new RegExp("^#[0-9A-Fa-f]{6}$"), { error: "Choose a colour" }), "name": z.string({ error: "Enter an account type name" }).regex(new RegExp(".*\\S.*"), { error: "Enter an account type name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length >= 1) return true; } return false; }, { error: "Enter an account type name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 80) return false; } return true; }, { error: "Account type name must be 80 characters or fewer" }) });
+export type AccountTypeUpsertInputFromSchema = z.infer<typeof accountTypeUpsertInputGeneratedSchema>;
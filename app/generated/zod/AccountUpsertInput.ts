@@ -1,5 +1,5 @@
 // Generated file, update with `bun run contracts:gen`.
 import { z } from "zod";
 
-export const accountUpsertInputGeneratedSchema = z.object({ "account_classification": z.enum(["asset","liability"], { error: "Select a balance type" }), "account_type": z.enum(["current","savings","credit_card","isa","investment","pension","cash","loan"], { error: "Select an account type" }), "closed_date": z.union([z.null(), z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function")]).transform((value) => value == null ? null : value.toString()).pipe(z.union([z.string().date(), z.null()])).transform((value) => value ?? undefined).optional(), "currency_code": z.literal("GBP", { error: "Select a currency" }), "institution": z.discriminatedUnion("kind", [z.object({ "id": z.number({ error: "Select or create an institution" }).int().gte(1, { error: "Select or create an institution" }), "kind": z.literal("existing") }), z.object({ "input": z.object({ "name": z.string({ error: "Enter an institution name" }).regex(new RegExp(".*\\S.*"), { error: "Enter an institution name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length >= 1) return true; } return false; }, { error: "Enter an institution name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 80) return false; } return true; }, { error: "Institution name must be 80 characters or fewer" }) }), "kind": z.literal("new") })], { error: "Select or create an institution" }), "name": z.string({ error: "Enter an account name" }).regex(new RegExp(".*\\S.*"), { error: "Enter an account name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length >= 1) return true; } return false; }, { error: "Enter an account name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 80) return false; } return true; }, { error: "Account name must be 80 characters or fewer" }), "opened_date": z.union([z.null(), z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function")]).transform((value) => value == null ? null : value.toString()).pipe(z.union([z.string().date(), z.null()])).transform((value) => value ?? undefined).optional() });
+export const accountUpsertInputGeneratedSchema = z.object({ "account_classification": z.enum(["asset","liability"], { error: "Select a balance type" }), "account_type_id": z.number({ error: "Select an account type" }).int().gte(1, { error: "Select an account type" }), "closed_date": z.union([z.null(), z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function")]).transform((value) => value == null ? null : value.toString()).pipe(z.union([z.string().date(), z.null()])).transform((value) => value ?? undefined).optional(), "currency_code": z.enum(["AED","AFN","ALL","AMD","AOA","ARS","AUD","AWG","AZN","BAM","BBD","BDT","BGN","BHD","BIF","BMD","BND","BOB","BOV","BRL","BSD","BTN","BWP","BYN","BZD","CAD","CDF","CHE","CHF","CHW","CLF","CLP","CNY","COP","COU","CRC","CUP","CVE","CZK","DJF","DKK","DOP","DZD","EGP","ERN","ETB","EUR","FJD","FKP","GBP","GEL","GHS","GIP","GMD","GNF","GTQ","GYD","HKD","HNL","HTG","HUF","IDR","ILS","INR","IQD","IRR","ISK","JMD","JOD","JPY","KES","KGS","KHR","KMF","KPW","KRW","KWD","KYD","KZT","LAK","LBP","LKR","LRD","LSL","LYD","MAD","MDL","MGA","MKD","MMK","MNT","MOP","MRU","MUR","MVR","MWK","MXN","MXV","MYR","MZN","NAD","NGN","NIO","NOK","NPR","NZD","OMR","PAB","PEN","PGK","PHP","PKR","PLN","PYG","QAR","RON","RSD","RUB","RWF","SAR","SBD","SCR","SDG","SEK","SGD","SHP","SLE","SOS","SRD","SSP","STN","SVC","SYP","SZL","THB","TJS","TMT","TND","TOP","TRY","TTD","TWD","TZS","UAH","UGX","USD","USN","UYI","UYU","UYW","UZS","VED","VES","VND","VUV","WST","XAF","XCD","XCG","XOF","XPF","YER","ZAR","ZMW","ZWG"], { error: "Select a currency" }), "institution": z.discriminatedUnion("kind", [z.object({ "id": z.number({ error: "Select or create an institution" }).int().gte(1, { error: "Select or create an institution" }), "kind": z.literal("existing") }), z.object({ "input": z.object({ "name": z.string({ error: "Enter an institution name" }).regex(new RegExp(".*\\S.*"), { error: "Enter an institution name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length >= 1) return true; } return false; }, { error: "Enter an institution name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 80) return false; } return true; }, { error: "Institution name must be 80 characters or fewer" }) }), "kind": z.literal("new") })], { error: "Select or create an institution" }), "name": z.string({ error: "Enter an account name" }).regex(new RegExp(".*\\S.*"), { error: "Enter an account name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length >= 1) return true; } return false; }, { error: "Enter an account name" }).refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 80) return false; } return true; }, { error: "Account name must be 80 characters or fewer" }), "opened_date": z.union([z.null(), z.custom<import("@internationalized/date").CalendarDate>((value) => value != null && typeof value === "object" && typeof value.toString === "function")]).transform((value) => value == null ? null : value.toString()).pipe(z.union([z.string().date(), z.null()])).transform((value) => value ?? undefined).optional(), "tags": z.array(z.string(), { error: "Enter a tag name" }) });
 export type AccountUpsertInputFromSchema = z.infer<typeof accountUpsertInputGeneratedSchema>;
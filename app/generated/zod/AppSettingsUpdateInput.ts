@@ -1,5 +1,5 @@
 // Generated file, update with `bun run contracts:gen`.
 import { z } from "zod";
 
-export const appSettingsUpdateInputGeneratedSchema = z.object({ "analytics_enabled": z.union([z.boolean(), z.null()]).optional(), "default_display_currency_code": z.union([z.literal("GBP"), z.null()]).optional(), "display_locale": z.union([z.enum(["system","en-GB"]), z.null()]).optional(), "theme": z.union([z.enum(["system","light","dark"]), z.null()]).optional() });
+export const appSettingsUpdateInputGeneratedSchema = z.object({ "analytics_enabled": z.union([z.boolean(), z.null()]).optional(), "backup_folder": z.union([z.string().refine((value) => { let length = 0; for (const _character of value) { length += 1; if (length > 1024) return false; } return true; }, { error: "Folder path must be 1024 characters or fewer" }), z.null()]).optional(), "backup_retention_count": z.union([z.number({ error: "Keep between 1 and 100 backups" }).int().gte(1, { error: "Keep between 1 and 100 backups" }).lte(100, { error: "Keep between 1 and 100 backups" }), z.null()]).optional(), "backup_schedule": z.union([z.enum(["off","startup","daily"]), z.null()]).optional(), "default_display_currency_code": z.union([z.enum(["AED","AFN","ALL","AMD","AOA","ARS","AUD","AWG","AZN","BAM","BBD","BDT","BGN","BHD","BIF","BMD","BND","BOB","BOV","BRL","BSD","BTN","BWP","BYN","BZD","CAD","CDF","CHE","CHF","CHW","CLF","CLP","CNY","COP","COU","CRC","CUP","CVE","CZK","DJF","DKK","DOP","DZD","EGP","ERN","ETB","EUR","FJD","FKP","GBP","GEL","GHS","GIP","GMD","GNF","GTQ","GYD","HKD","HNL","HTG","HUF","IDR","ILS","INR","IQD","IRR","ISK","JMD","JOD","JPY","KES","KGS","KHR","KMF","KPW","KRW","KWD","KYD","KZT","LAK","LBP","LKR","LRD","LSL","LYD","MAD","MDL","MGA","MKD","MMK","MNT","MOP","MRU","MUR","MVR","MWK","MXN","MXV","MYR","MZN","NAD","NGN","NIO","NOK","NPR","NZD","OMR","PAB","PEN","PGK","PHP","PKR","PLN","PYG","QAR","RON","RSD","RUB","RWF","SAR","SBD","SCR","SDG","SEK","SGD","SHP","SLE","SOS","SRD","SSP","STN","SVC","SYP","SZL","THB","TJS","TMT","TND","TOP","TRY","TTD","TWD","TZS","UAH","UGX","USD","USN","UYI","UYU","UYW","UZS","VED","VES","VND","VUV","WST","XAF","XCD","XCG","XOF","XPF","YER","ZAR","ZMW","ZWG"]), z.null()]).optional(), "display_locale": z.union([z.enum(["system","en-GB"]), z.null()]).optional(), "theme": z.union([z.enum(["system","light","dark"]), z.null()]).optional() });
 export type AppSettingsUpdateInputFromSchema = z.infer<typeof appSettingsUpdateInputGeneratedSchema>;
@@ -0,0 +1,5 @@
+// Generated file, update with `bun run contracts:gen`.
+import { z } from "zod";
+
+export const backupScheduleGeneratedSchema = z.enum(["off","startup","daily"]);
+export type BackupScheduleFromSchema = z.infer<typeof backupScheduleGeneratedSchema>;
@@ -0,0 +1,5 @@
+// Generated file, update with `bun run contracts:gen`.
+import { z } from "zod";
+
+export const fxRatesDeleteInputGeneratedSchema = z.object({ "rate_ids": z.array(z.number().int(), { error: "Select at least one rate" }).min(1, { error: "Select at least one rate" }) });
+export type FxRatesDeleteInputFromSchema = z.infer<typeof fxRatesDeleteInputGeneratedSchema>;
@@ -10,14 +10,24 @@ export { accountSnapshotsCreateInputGeneratedSchema } from "./AccountSnapshotsCr
 export type { AccountSnapshotsCreateInputFromSchema } from "./AccountSnapshotsCreateInput";
 export { accountSnapshotsDeleteInputGeneratedSchema } from "./AccountSnapshotsDeleteInput";
 export type { AccountSnapshotsDeleteInputFromSchema } from "./AccountSnapshotsDeleteInput";
+export { accountTypeDeleteInputGeneratedSchema } from "./AccountTypeDeleteInput";
+export type { AccountTypeDeleteInputFromSchema } from "./AccountTypeDeleteInput";
+export { accountTypeUpsertInputGeneratedSchema } from "./AccountTypeUpsertInput";
+export type { AccountTypeUpsertInputFromSchema } from "./AccountTypeUpsertInput";
 export { accountUpsertInputGeneratedSchema } from "./AccountUpsertInput";
 export type { AccountUpsertInputFromSchema } from "./AccountUpsertInput";
 export { appLocaleCodeGeneratedSchema } from "./AppLocaleCode";
 export type { AppLocaleCodeFromSchema } from "./AppLocaleCode";
 export { appSettingsUpdateInputGeneratedSchema } from "./AppSettingsUpdateInput";
 export type { AppSettingsUpdateInputFromSchema } from "./AppSettingsUpdateInput";
+export { backupScheduleGeneratedSchema } from "./BackupSchedule";
+export type { BackupScheduleFromSchema } from "./BackupSchedule";
 export { currencyCodeGeneratedSchema } from "./CurrencyCode";
 export type { CurrencyCodeFromSchema } from "./CurrencyCode";
+export { fxRateWriteInputGeneratedSchema } from "./FxRateWriteInput";
+export type { FxRateWriteInputFromSchema } from "./FxRateWriteInput";
+export { fxRatesDeleteInputGeneratedSchema } from "./FxRatesDeleteInput";
+export type { FxRatesDeleteInputFromSchema } from "./FxRatesDeleteInput";
 export { institutionUpsertInputGeneratedSchema } from "./InstitutionUpsertInput";
 export type { InstitutionUpsertInputFromSchema } from "./InstitutionUpsertInput";
 export { themePreferenceGeneratedSchema } from "./ThemePreference";
@@ -10,11 +10,16 @@ export default defineNuxtPlugin({
     const queryClient = useQueryClient();
     const settings = useNullableSettings();
     const colorMode = useColorMode();
+    const databaseStatus = useDatabaseStatus();
     const i18n = nuxtApp.$i18n as { locale: Ref<string> };
     const systemDisplayLocale = i18n.locale.value;
 
-    settings.value = await api.settingsGet();
-    queryClient.setQueryData(queryKeys.settings.get(), settings.value);
+    databaseStatus.value = await api.databaseStatus();
+    // A locked database is unlocked, and its settings loaded, by the unlock screen.
+    if (!databaseStatus.value.locked) {
+      settings.value = await api.settingsGet();
+      queryClient.setQueryData(queryKeys.settings.get(), settings.value);
+    }
 
     function applySettings(value: AppSettingsDto | null) {
       if (value == null) return;
@@ -16,28 +16,50 @@ function supportedCurrencyValuesFromSchema(): CurrencyCode[] {
 export const supportedCurrencyCodes = supportedCurrencyValuesFromSchema();
 export const CURRENCY_MINOR_ABS_MAX = 99_999_999_999_999;
 
-export function convertCurrencyMajorAmountToMinorUnits(amount: number | undefined) {
+const DEFAULT_MINOR_UNIT_EXPONENT = 2;
+const minorUnitExponentCache = new Map<CurrencyCode, number>();
+
+// Intl reports ISO 4217 minor units (JPY 0, BHD 3), matching `CurrencyCode::minor_unit_exponent`.
+export function currencyMinorUnitExponent(currencyCode: CurrencyCode | undefined) {
+  if (currencyCode == null) return DEFAULT_MINOR_UNIT_EXPONENT;
+
+  const cached = minorUnitExponentCache.get(currencyCode);
+  if (cached != null) return cached;
+
+  const exponent = new Intl.NumberFormat("en", { style: "currency", currency: currencyCode })
+    .resolvedOptions()
+    .maximumFractionDigits ?? DEFAULT_MINOR_UNIT_EXPONENT;
+  minorUnitExponentCache.set(currencyCode, exponent);
+  return exponent;
+}
+
+function minorUnitScale(currencyCode: CurrencyCode | undefined) {
+  return 10 ** currencyMinorUnitExponent(currencyCode);
+}
+
+export function convertCurrencyMajorAmountToMinorUnits(amount: number | undefined, currencyCode?: CurrencyCode) {
   if (amount == null || !Number.isFinite(amount)) return null;
 
-  const minor = Math.round(amount * 100);
+  const minor = Math.round(amount * minorUnitScale(currencyCode));
   if (Math.abs(minor) > CURRENCY_MINOR_ABS_MAX) return null;
 
   return minor;
 }
 
-export function convertCurrencyMinorUnitsToMajorAmount(minor: number) {
-  return minor / 100;
+export function convertCurrencyMinorUnitsToMajorAmount(minor: number, currencyCode?: CurrencyCode) {
+  return minor / minorUnitScale(currencyCode);
 }
 
-export function normalizeCurrencyMajorAmount(amount: number) {
-  return Math.round(amount * 100) / 100;
+export function normalizeCurrencyMajorAmount(amount: number, currencyCode?: CurrencyCode) {
+  const scale = minorUnitScale(currencyCode);
+  return Math.round(amount * scale) / scale;
 }
 
-export function normalizeOptionalCurrencyMajorAmount(amount: number | null | undefined) {
-  return amount == null ? undefined : normalizeCurrencyMajorAmount(amount);
+export function normalizeOptionalCurrencyMajorAmount(amount: number | null | undefined, currencyCode?: CurrencyCode) {
+  return amount == null ? undefined : normalizeCurrencyMajorAmount(amount, currencyCode);
 }
 
-export function parseCurrencyInputNumberEventValue(event: Event) {
+export function parseCurrencyInputNumberEventValue(event: Event, currencyCode?: CurrencyCode) {
   const target = event.target;
   if (!(target instanceof HTMLInputElement)) return undefined;
 
@@ -48,5 +70,5 @@ export function parseCurrencyInputNumberEventValue(event: Event) {
 
   const parsed = Number(normalized);
   if (!Number.isFinite(parsed)) return undefined;
-  return normalizeCurrencyMajorAmount(parsed);
+  return normalizeCurrencyMajorAmount(parsed, currencyCode);
 }
@@ -26,6 +26,9 @@ export const queryKeys = {
   settings: {
     get: () => ["settings", "get"] as const
   },
+  backups: {
+    list: () => ["backups", "list"] as const
+  },
   accounts: {
     prefixes: {
       root: () => ["accounts"] as const,
@@ -35,8 +38,28 @@ export const queryKeys = {
     get: (accountId: number) => ["accounts", param("accountId", accountId), "get"] as const,
     deletePreview: (accountId: number) => ["accounts", param("accountId", accountId), "deletePreview"] as const,
     snapshots: (accountId: number) => ["accounts", param("accountId", accountId), "snapshots"] as const,
+    history: (accountId: number) => ["accounts", param("accountId", accountId), "history"] as const,
+    importBatches: (accountId: number) => ["accounts", param("accountId", accountId), "importBatches"] as const,
     balanceOverTime: (accountId: number, period: BalanceOverTimePeriod) => ["accounts", param("accountId", accountId), "balanceOverTime", param("period", period)] as const
   },
+  accountTypes: {
+    prefixes: {
+      root: () => ["accountTypes"] as const
+    },
+    list: () => ["accountTypes", "list"] as const
+  },
+  fxRates: {
+    prefixes: {
+      root: () => ["fxRates"] as const
+    },
+    list: () => ["fxRates", "list"] as const
+  },
+  tags: {
+    prefixes: {
+      root: () => ["tags"] as const
+    },
+    list: () => ["tags", "list"] as const
+  },
   institutions: {
     prefixes: {
       root: () => ["institutions"] as const,
@@ -50,8 +73,8 @@ export const queryKeys = {
     prefixes: {
       root: () => ["dashboard"] as const
     },
-    summary: () => ["dashboard", "summary"] as const,
-    balanceOverTime: (period: BalanceOverTimePeriod) => ["dashboard", "balanceOverTime", param("period", period)] as const
+    summary: (tagId: number | null) => ["dashboard", "summary", param("tagId", tagId)] as const,
+    balanceOverTime: (period: BalanceOverTimePeriod, tagId: number | null) => ["dashboard", "balanceOverTime", param("period", period), param("tagId", tagId)] as const
   },
   search: {
     prefixes: {
@@ -1,5 +1,18 @@
 import type { StepperItem } from "@nuxt/ui";
 import type { Component } from "vue";
+import type {
+  CsvSnapshotImportBalanceFormat,
+  CsvSnapshotImportBlankAmountPolicy,
+  CsvSnapshotImportDateFormat,
+  CsvSnapshotImportDelimiter,
+  CsvSnapshotImportEncoding,
+  CsvSnapshotImportQuote,
+  CsvSnapshotImportSourceInput,
+  CurrencyCode,
+  SnapshotImportOptionsInput,
+  SnapshotImportSourceOptionsInput,
+  WideSnapshotImportOptionsInput
+} from "~/generated/bindings";
 
 export interface SnapshotImportFlowStep {
   id: string
@@ -32,3 +45,96 @@ export function stepperItemFromImportStep(step: SnapshotImportFlowStep): Stepper
     icon: step.icon
   };
 }
+
+export type SnapshotImportPolicyOptions = Pick<
+  SnapshotImportOptionsInput,
+  "duplicate_date_policy" | "existing_date_policy" | "unchanged_value_policy"
+>;
+
+export type SnapshotImportOptionsFor<K extends SnapshotImportSourceOptionsInput["kind"]> = Omit<SnapshotImportOptionsInput, "source"> & {
+  source: Extract<SnapshotImportSourceOptionsInput, { kind: K }>
+};
+
+export const csvDateFormatItems: Array<{ label: string, value: CsvSnapshotImportDateFormat }> = [
+  { label: "YYYY-MM-DD", value: "yyyy_mm_dd" },
+  { label: "DD/MM/YYYY", value: "dd_mm_yyyy_slash" },
+  { label: "DD/MM/YY", value: "dd_mm_yy_slash" },
+  { label: "MM/DD/YYYY", value: "mm_dd_yyyy_slash" },
+  { label: "MM/DD/YY", value: "mm_dd_yy_slash" },
+  { label: "DD-MM-YYYY", value: "dd_mm_yyyy_dash" },
+  { label: "YYYY/MM/DD", value: "yyyy_mm_dd_slash" },
+  { label: "YYYY-MM-DDThh:mm:ss (ISO 8601)", value: "iso_8601_date_time" },
+  { label: "DD Mon YYYY (e.g. 03 Jan 2024)", value: "dd_mon_yyyy" },
+  { label: "Mon DD, YYYY (e.g. January 3, 2024)", value: "mon_dd_yyyy" },
+  { label: "YYYY-Mon-DD (e.g. 2024-Jan-03)", value: "yyyy_mon_dd" },
+  { label: "YYYYMMDD", value: "yyyy_mm_dd_compact" },
+  { label: "Spreadsheet day number (e.g. 45294)", value: "excel_serial" }
+];
+
+export const csvBalanceFormatItems: Array<{ label: string, value: CsvSnapshotImportBalanceFormat }> = [
+  { label: "1,234.56", value: "thousands_comma_decimal_dot" },
+  { label: "1.234,56", value: "thousands_dot_decimal_comma" },
+  { label: "1 234,56", value: "thousands_space_decimal_comma" },
+  { label: "1 234.56", value: "thousands_space_decimal_dot" },
+  { label: "1'234.56", value: "thousands_apostrophe_decimal_dot" },
+  { label: "123456 (minor units, e.g. pence)", value: "minor_units" },
+  { label: "1,234.56- (trailing minus)", value: "thousands_comma_decimal_dot_trailing_minus" },
+  { label: "1.234,56- (trailing minus)", value: "thousands_dot_decimal_comma_trailing_minus" },
+  { label: "1,234.56 CR / DR", value: "thousands_comma_decimal_dot_credit_debit" }
+];
+
+export const csvBlankAmountPolicyItems: Array<{ label: string, value: CsvSnapshotImportBlankAmountPolicy }> = [
+  { label: "Skip row", value: "skip" },
+  { label: "Treat as zero", value: "zero" },
+  { label: "Treat as error", value: "error" }
+];
+
+export const csvEncodingItems: Array<{ label: string, value: CsvSnapshotImportEncoding }> = [
+  { label: "UTF-8", value: "utf8" },
+  { label: "UTF-16 (little endian)", value: "utf16_le" },
+  { label: "UTF-16 (big endian)", value: "utf16_be" },
+  { label: "Windows-1252", value: "windows1252" }
+];
+
+export const csvDelimiterItems: Array<{ label: string, value: CsvSnapshotImportDelimiter }> = [
+  { label: "Comma", value: "comma" },
+  { label: "Semicolon", value: "semicolon" },
+  { label: "Tab", value: "tab" },
+  { label: "Pipe", value: "pipe" }
+];
+
+export const csvQuoteItems: Array<{ label: string, value: CsvSnapshotImportQuote }> = [
+  { label: "Double quotes", value: "double" },
+  { label: "Single quotes", value: "single" },
+  { label: "None", value: "none" }
+];
+
+/** Overrides for how a CSV is read; `null` leaves that setting to detection. */
+export type CsvSnapshotImportFormatOverrides = Pick<CsvSnapshotImportSourceInput, "encoding" | "delimiter" | "quote">;
+
+/** Raw file bytes for a CSV or spreadsheet source, so the backend can detect the format. */
+export async function readCsvFileContent(file: File): Promise<number[]> {
+  return Array.from(new Uint8Array(await file.arrayBuffer()));
+}
+
+/** Whether a file should be read as a workbook rather than as a CSV. */
+export function isSpreadsheetFileName(fileName: string) {
+  return /\.(?:xlsx|xlsm|xls|ods)$/i.test(fileName);
+}
+
+/**
+ * Form state for one spreadsheet column in a wide import, before it becomes a
+ * `WideSnapshotImportColumnInput`. `target` is an account id, a new account or skipped.
+ */
+export interface WideSnapshotImportColumnMapping {
+  column: string
+  target: number | "new" | "skip"
+  name: string
+  institutionName: string
+  accountTypeId: number | undefined
+  currencyCode: CurrencyCode
+}
+
+export type WideSnapshotImportFormState = Omit<WideSnapshotImportOptionsInput, "columns" | "overwrite_existing_confirmed"> & {
+  mappings: WideSnapshotImportColumnMapping[]
+};
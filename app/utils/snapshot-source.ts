@@ -0,0 +1,16 @@
+import type { SnapshotSource } from "~/generated/bindings";
+
+export function snapshotSourceMeta(source: SnapshotSource) {
+  switch (source.kind) {
+    case "manual":
+      return { icon: "i-lucide-pencil-line", label: "Entered manually" };
+    case "csv_import":
+      return { icon: "i-lucide-file-spreadsheet", label: `Imported from ${source.file_name}` };
+    case "ofx_import":
+      return { icon: "i-lucide-landmark", label: `Imported from ${source.file_name}` };
+    case "spreadsheet_import":
+      return { icon: "i-lucide-sheet", label: `Imported from ${source.file_name}` };
+    case "api":
+      return { icon: "i-lucide-plug", label: `Synced from ${source.provider}` };
+  }
+}
@@ -0,0 +1,20 @@
+-- One row per currency pair and calendar date; a rate stays in effect until the
+-- next dated rate for the same pair.
+CREATE TABLE fx_rates (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  base_currency_code TEXT NOT NULL,
+  quote_currency_code TEXT NOT NULL,
+  rate_date TEXT NOT NULL,
+  -- Units of the quote currency per one unit of the base currency.
+  rate REAL NOT NULL CHECK (rate > 0),
+  created_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  updated_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  CHECK (base_currency_code <> quote_currency_code),
+  UNIQUE (base_currency_code, quote_currency_code, rate_date)
+);
+
+CREATE INDEX idx_fx_rates_quote_base_date ON fx_rates (
+  quote_currency_code,
+  base_currency_code,
+  rate_date
+);
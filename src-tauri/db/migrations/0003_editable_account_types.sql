@@ -0,0 +1,47 @@
+-- Account types are user-editable: `name` now holds the display label shown in the app,
+-- and each type carries the lowercase `#rrggbb` colour used for badges and charts.
+ALTER TABLE account_types
+ADD COLUMN color TEXT NOT NULL DEFAULT '#64748b' CHECK (
+  color GLOB '#[0-9a-f][0-9a-f][0-9a-f][0-9a-f][0-9a-f][0-9a-f]'
+);
+
+-- Renaming fires account_types_au, which refreshes the account rows in search_fts.
+UPDATE account_types
+SET
+  name = CASE name
+    WHEN 'current' THEN 'Current'
+    WHEN 'savings' THEN 'Savings'
+    WHEN 'credit_card' THEN 'Credit card'
+    WHEN 'isa' THEN 'ISA'
+    WHEN 'investment' THEN 'Investment'
+    WHEN 'pension' THEN 'Pension'
+    WHEN 'cash' THEN 'Cash'
+    WHEN 'loan' THEN 'Loan'
+    ELSE name
+  END,
+  color = CASE name
+    WHEN 'current' THEN '#3b82f6'
+    WHEN 'savings' THEN '#22c55e'
+    WHEN 'credit_card' THEN '#ef4444'
+    WHEN 'isa' THEN '#f97316'
+    WHEN 'investment' THEN '#a855f7'
+    WHEN 'pension' THEN '#db2777'
+    WHEN 'cash' THEN '#eab308'
+    WHEN 'loan' THEN '#14b8a6'
+    ELSE color
+  END;
+
+-- accounts.type_id cascades on delete, so a type must have no accounts before it is
+-- deleted; callers reassign accounts to another type first.
+CREATE TRIGGER account_types_bd BEFORE DELETE ON account_types WHEN EXISTS (
+  SELECT
+    1
+  FROM
+    accounts
+  WHERE
+    type_id = old.id
+) BEGIN
+SELECT
+  RAISE(ABORT, 'account type is in use');
+
+END;
@@ -0,0 +1,354 @@
+-- Tags are free-form account groupings; names compare case-insensitively so "Joint" and
+-- "joint" are the same tag.
+CREATE TABLE tags (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE CHECK (LENGTH(name) <= 40)
+);
+
+CREATE TABLE account_tags (
+  account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+  tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+  PRIMARY KEY (account_id, tag_id)
+);
+
+CREATE INDEX idx_account_tags_tag ON account_tags (tag_id);
+
+-- search_fts gains a tags column. FTS5 tables cannot add columns, so the table and the
+-- triggers that write to it are recreated. Account rows are read from search_fts_accounts
+-- so every trigger builds the same denormalized text.
+DROP TRIGGER institutions_ai;
+
+DROP TRIGGER institutions_au;
+
+DROP TRIGGER institutions_ad;
+
+DROP TRIGGER accounts_ai;
+
+DROP TRIGGER accounts_au;
+
+DROP TRIGGER accounts_ad;
+
+DROP TRIGGER account_types_au;
+
+DROP TABLE search_fts;
+
+CREATE VIRTUAL TABLE search_fts USING fts5 (
+  kind unindexed,
+  entity_id unindexed,
+  name,
+  institution_name,
+  account_type,
+  tags,
+  tokenize = 'unicode61 remove_diacritics 2',
+  prefix = '2 3 4 5 6 7 8'
+);
+
+CREATE VIEW search_fts_accounts AS
+SELECT
+  a.id AS entity_id,
+  a.name,
+  i.name AS institution_name,
+  t.name AS account_type,
+  COALESCE(
+    (
+      SELECT
+        GROUP_CONCAT(tg.name, ' ')
+      FROM
+        account_tags AS at
+        INNER JOIN tags AS tg ON tg.id = at.tag_id
+      WHERE
+        at.account_id = a.id
+    ),
+    ''
+  ) AS tags
+FROM
+  accounts AS a
+  INNER JOIN institutions AS i ON i.id = a.institution_id
+  INNER JOIN account_types AS t ON t.id = a.type_id;
+
+CREATE TRIGGER institutions_ai AFTER INSERT ON institutions BEGIN
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+VALUES
+  ('institution', new.id, new.name, '', '', '');
+
+END;
+
+CREATE TRIGGER institutions_au AFTER
+UPDATE ON institutions BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'institution'
+  AND entity_id = old.id;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+VALUES
+  ('institution', new.id, new.name, '', '', '');
+
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id IN (
+    SELECT
+      id
+    FROM
+      accounts
+    WHERE
+      institution_id = new.id
+  );
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  s.entity_id,
+  s.name,
+  s.institution_name,
+  s.account_type,
+  s.tags
+FROM
+  search_fts_accounts AS s
+  INNER JOIN accounts AS a ON a.id = s.entity_id
+WHERE
+  a.institution_id = new.id;
+
+END;
+
+CREATE TRIGGER institutions_ad AFTER DELETE ON institutions BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'institution'
+  AND entity_id = old.id;
+
+END;
+
+CREATE TRIGGER accounts_ai AFTER INSERT ON accounts BEGIN
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  entity_id,
+  name,
+  institution_name,
+  account_type,
+  tags
+FROM
+  search_fts_accounts
+WHERE
+  entity_id = new.id;
+
+END;
+
+CREATE TRIGGER accounts_au AFTER
+UPDATE ON accounts BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id = old.id;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  entity_id,
+  name,
+  institution_name,
+  account_type,
+  tags
+FROM
+  search_fts_accounts
+WHERE
+  entity_id = new.id;
+
+END;
+
+CREATE TRIGGER accounts_ad AFTER DELETE ON accounts BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id = old.id;
+
+END;
+
+CREATE TRIGGER account_types_au AFTER
+UPDATE ON account_types BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id IN (
+    SELECT
+      id
+    FROM
+      accounts
+    WHERE
+      type_id = new.id
+  );
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  s.entity_id,
+  s.name,
+  s.institution_name,
+  s.account_type,
+  s.tags
+FROM
+  search_fts_accounts AS s
+  INNER JOIN accounts AS a ON a.id = s.entity_id
+WHERE
+  a.type_id = new.id;
+
+END;
+
+CREATE TRIGGER account_tags_ai AFTER INSERT ON account_tags BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id = new.account_id;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  entity_id,
+  name,
+  institution_name,
+  account_type,
+  tags
+FROM
+  search_fts_accounts
+WHERE
+  entity_id = new.account_id;
+
+END;
+
+-- A tag exists only while an account uses it, so removing its last link deletes it.
+CREATE TRIGGER account_tags_ad AFTER DELETE ON account_tags BEGIN
+DELETE FROM search_fts
+WHERE
+  kind = 'account'
+  AND entity_id = old.account_id;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  entity_id,
+  name,
+  institution_name,
+  account_type,
+  tags
+FROM
+  search_fts_accounts
+WHERE
+  entity_id = old.account_id;
+
+DELETE FROM tags
+WHERE
+  id = old.tag_id
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      account_tags
+    WHERE
+      tag_id = old.tag_id
+  );
+
+END;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'institution',
+  i.id,
+  i.name,
+  '',
+  '',
+  ''
+FROM
+  institutions AS i;
+
+INSERT INTO
+  search_fts (
+    kind,
+    entity_id,
+    name,
+    institution_name,
+    account_type,
+    tags
+  )
+SELECT
+  'account',
+  entity_id,
+  name,
+  institution_name,
+  account_type,
+  tags
+FROM
+  search_fts_accounts;
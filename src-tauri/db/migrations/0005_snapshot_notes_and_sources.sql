@@ -0,0 +1,20 @@
+-- Snapshots record an optional note and where the balance came from. Snapshots written
+-- before this migration keep a NULL source because their origin was not recorded.
+ALTER TABLE account_balance_snapshots
+ADD COLUMN note TEXT CHECK (
+  note IS NULL
+  OR LENGTH(note) <= 500
+);
+
+ALTER TABLE account_balance_snapshots
+ADD COLUMN source_kind TEXT CHECK (
+  source_kind IS NULL
+  OR source_kind IN ('manual', 'csv_import', 'api')
+);
+
+-- CSV file name for csv_import, provider name for api, NULL for manual entry.
+ALTER TABLE account_balance_snapshots
+ADD COLUMN source_detail TEXT CHECK (
+  source_detail IS NULL
+  OR LENGTH(source_detail) <= 255
+);
@@ -0,0 +1,291 @@
+-- Append-only history of every create, update, and delete of institutions, accounts, and
+-- snapshots. Triggers write the rows, so cascaded deletes and imports are recorded without
+-- any application code remembering to do it. before_json and after_json hold the row's
+-- values as a JSON object; before_json is NULL for creates and after_json for deletes.
+CREATE TABLE audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  entity_kind TEXT NOT NULL CHECK (entity_kind IN ('institution', 'account', 'snapshot')),
+  entity_id INTEGER NOT NULL,
+  -- Owning account for account and snapshot rows; kept after the account is deleted.
+  account_id INTEGER,
+  action TEXT NOT NULL CHECK (action IN ('create', 'update', 'delete')),
+  before_json TEXT CHECK (
+    before_json IS NULL
+    OR JSON_VALID(before_json)
+  ),
+  after_json TEXT CHECK (
+    after_json IS NULL
+    OR JSON_VALID(after_json)
+  ),
+  changed_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX idx_audit_log_account ON audit_log (account_id, id);
+
+CREATE INDEX idx_audit_log_entity ON audit_log (entity_kind, entity_id, id);
+
+CREATE TRIGGER audit_log_bu BEFORE
+UPDATE ON audit_log BEGIN
+SELECT
+  RAISE(ABORT, 'audit_log is append-only');
+
+END;
+
+CREATE TRIGGER audit_log_bd BEFORE DELETE ON audit_log BEGIN
+SELECT
+  RAISE(ABORT, 'audit_log is append-only');
+
+END;
+
+CREATE TRIGGER institutions_audit_ai AFTER INSERT ON institutions BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'institution',
+    new.id,
+    NULL,
+    'create',
+    NULL,
+    JSON_OBJECT('name', new.name)
+  );
+
+END;
+
+CREATE TRIGGER institutions_audit_au AFTER
+UPDATE ON institutions WHEN old.name IS NOT new.name BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'institution',
+    new.id,
+    NULL,
+    'update',
+    JSON_OBJECT('name', old.name),
+    JSON_OBJECT('name', new.name)
+  );
+
+END;
+
+CREATE TRIGGER institutions_audit_ad AFTER DELETE ON institutions BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'institution',
+    old.id,
+    NULL,
+    'delete',
+    JSON_OBJECT('name', old.name),
+    NULL
+  );
+
+END;
+
+CREATE TRIGGER accounts_audit_ai AFTER INSERT ON accounts BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'account',
+    new.id,
+    new.id,
+    'create',
+    NULL,
+    JSON_OBJECT(
+      'name',
+      new.name,
+      'institution_id',
+      new.institution_id,
+      'type_id',
+      new.type_id,
+      'currency_code',
+      new.currency_code,
+      'account_classification',
+      new.account_classification,
+      'opened_date',
+      new.opened_date,
+      'closed_date',
+      new.closed_date
+    )
+  );
+
+END;
+
+-- updated_at alone is not a change worth recording.
+CREATE TRIGGER accounts_audit_au AFTER
+UPDATE ON accounts WHEN old.name IS NOT new.name
+OR old.institution_id IS NOT new.institution_id
+OR old.type_id IS NOT new.type_id
+OR old.currency_code IS NOT new.currency_code
+OR old.account_classification IS NOT new.account_classification
+OR old.opened_date IS NOT new.opened_date
+OR old.closed_date IS NOT new.closed_date BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'account',
+    new.id,
+    new.id,
+    'update',
+    JSON_OBJECT(
+      'name',
+      old.name,
+      'institution_id',
+      old.institution_id,
+      'type_id',
+      old.type_id,
+      'currency_code',
+      old.currency_code,
+      'account_classification',
+      old.account_classification,
+      'opened_date',
+      old.opened_date,
+      'closed_date',
+      old.closed_date
+    ),
+    JSON_OBJECT(
+      'name',
+      new.name,
+      'institution_id',
+      new.institution_id,
+      'type_id',
+      new.type_id,
+      'currency_code',
+      new.currency_code,
+      'account_classification',
+      new.account_classification,
+      'opened_date',
+      new.opened_date,
+      'closed_date',
+      new.closed_date
+    )
+  );
+
+END;
+
+CREATE TRIGGER accounts_audit_ad AFTER DELETE ON accounts BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'account',
+    old.id,
+    old.id,
+    'delete',
+    JSON_OBJECT(
+      'name',
+      old.name,
+      'institution_id',
+      old.institution_id,
+      'type_id',
+      old.type_id,
+      'currency_code',
+      old.currency_code,
+      'account_classification',
+      old.account_classification,
+      'opened_date',
+      old.opened_date,
+      'closed_date',
+      old.closed_date
+    ),
+    NULL
+  );
+
+END;
+
+CREATE TRIGGER account_balance_snapshots_audit_ai AFTER INSERT ON account_balance_snapshots BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    new.id,
+    new.account_id,
+    'create',
+    NULL,
+    JSON_OBJECT(
+      'balance_date',
+      new.balance_date,
+      'balance_minor',
+      new.balance_minor,
+      'note',
+      new.note,
+      'source_kind',
+      new.source_kind,
+      'source_detail',
+      new.source_detail
+    )
+  );
+
+END;
+
+CREATE TRIGGER account_balance_snapshots_audit_au AFTER
+UPDATE ON account_balance_snapshots WHEN old.balance_date IS NOT new.balance_date
+OR old.balance_minor IS NOT new.balance_minor
+OR old.note IS NOT new.note
+OR old.source_kind IS NOT new.source_kind
+OR old.source_detail IS NOT new.source_detail BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    new.id,
+    new.account_id,
+    'update',
+    JSON_OBJECT(
+      'balance_date',
+      old.balance_date,
+      'balance_minor',
+      old.balance_minor,
+      'note',
+      old.note,
+      'source_kind',
+      old.source_kind,
+      'source_detail',
+      old.source_detail
+    ),
+    JSON_OBJECT(
+      'balance_date',
+      new.balance_date,
+      'balance_minor',
+      new.balance_minor,
+      'note',
+      new.note,
+      'source_kind',
+      new.source_kind,
+      'source_detail',
+      new.source_detail
+    )
+  );
+
+END;
+
+CREATE TRIGGER account_balance_snapshots_audit_ad AFTER DELETE ON account_balance_snapshots BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    old.id,
+    old.account_id,
+    'delete',
+    JSON_OBJECT(
+      'balance_date',
+      old.balance_date,
+      'balance_minor',
+      old.balance_minor,
+      'note',
+      old.note,
+      'source_kind',
+      old.source_kind,
+      'source_detail',
+      old.source_detail
+    ),
+    NULL
+  );
+
+END;
@@ -0,0 +1,41 @@
+-- Each committed snapshot import is a batch so it can be rolled back later. options_json holds
+-- the SnapshotImportOptionsInput the commit was planned with.
+CREATE TABLE snapshot_import_batches (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+  file_name TEXT NOT NULL CHECK (LENGTH(file_name) <= 255),
+  options_json TEXT NOT NULL CHECK (JSON_VALID(options_json)),
+  created_count INTEGER NOT NULL CHECK (created_count >= 0),
+  overwritten_count INTEGER NOT NULL CHECK (overwritten_count >= 0),
+  skipped_count INTEGER NOT NULL CHECK (skipped_count >= 0),
+  created_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  rolled_back_at TEXT
+);
+
+CREATE INDEX idx_snapshot_import_batches_account ON snapshot_import_batches (account_id, id);
+
+-- One row per snapshot the batch wrote. snapshot_id is not a foreign key: the snapshot may be
+-- deleted later, and rollback must notice that rather than lose the record. previous_* hold
+-- the overwritten snapshot's values and are NULL for created snapshots.
+CREATE TABLE snapshot_import_batch_writes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  batch_id INTEGER NOT NULL REFERENCES snapshot_import_batches (id) ON DELETE CASCADE,
+  snapshot_id INTEGER NOT NULL,
+  action TEXT NOT NULL CHECK (action IN ('create', 'overwrite')),
+  balance_date TEXT NOT NULL,
+  balance_minor INTEGER NOT NULL,
+  previous_balance_minor INTEGER,
+  previous_note TEXT,
+  previous_source_kind TEXT,
+  previous_source_detail TEXT,
+  CHECK (
+    action = 'overwrite'
+    OR previous_balance_minor IS NULL
+  ),
+  CHECK (
+    action = 'create'
+    OR previous_balance_minor IS NOT NULL
+  )
+);
+
+CREATE INDEX idx_snapshot_import_batch_writes_batch ON snapshot_import_batch_writes (batch_id);
@@ -0,0 +1,13 @@
+-- Named import options saved per account. options_json holds a SnapshotImportOptionsInput and
+-- columns_json the CSV column names it was saved against, so inspecting the next file from
+-- the same export can recognise it and reapply the options.
+CREATE TABLE snapshot_import_profiles (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+  name TEXT NOT NULL COLLATE NOCASE CHECK (LENGTH(name) <= 80),
+  columns_json TEXT NOT NULL CHECK (JSON_VALID(columns_json)),
+  options_json TEXT NOT NULL CHECK (JSON_VALID(options_json)),
+  created_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  updated_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  UNIQUE (account_id, name)
+);
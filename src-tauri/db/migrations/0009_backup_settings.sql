@@ -0,0 +1,17 @@
+-- Automatic backups. backup_folder is NULL while backups go to the default folder under the
+-- app's local data directory.
+ALTER TABLE app_settings
+ADD COLUMN backup_schedule TEXT NOT NULL DEFAULT 'daily' CHECK (
+  backup_schedule IN ('off', 'startup', 'daily')
+);
+
+ALTER TABLE app_settings
+ADD COLUMN backup_retention_count INTEGER NOT NULL DEFAULT 10 CHECK (
+  backup_retention_count BETWEEN 1 AND 100
+);
+
+ALTER TABLE app_settings
+ADD COLUMN backup_folder TEXT CHECK (
+  backup_folder IS NULL
+  OR LENGTH(backup_folder) BETWEEN 1 AND 1024
+);
@@ -0,0 +1,175 @@
+-- Snapshots imported from OFX files and spreadsheets record their own source kinds. SQLite cannot alter a
+-- CHECK constraint, so the table is rebuilt with the wider list. Nothing references snapshot
+-- ids through a foreign key, and dropping the old table removes its indexes and audit
+-- triggers, which are recreated below.
+CREATE TABLE account_balance_snapshots_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts (id) ON DELETE CASCADE,
+  balance_date TEXT NOT NULL,
+  balance_minor INTEGER NOT NULL CHECK (
+    balance_minor BETWEEN -99999999999999 AND 99999999999999
+  ),
+  created_at TEXT NOT NULL DEFAULT (STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')),
+  note TEXT CHECK (
+    note IS NULL
+    OR LENGTH(note) <= 500
+  ),
+  source_kind TEXT CHECK (
+    source_kind IS NULL
+    OR source_kind IN (
+      'manual',
+      'csv_import',
+      'ofx_import',
+      'spreadsheet_import',
+      'api'
+    )
+  ),
+  -- File name for imports, provider name for api, NULL for manual entry.
+  source_detail TEXT CHECK (
+    source_detail IS NULL
+    OR LENGTH(source_detail) <= 255
+  ),
+  UNIQUE (account_id, balance_date)
+);
+
+INSERT INTO
+  account_balance_snapshots_new (
+    id,
+    account_id,
+    balance_date,
+    balance_minor,
+    created_at,
+    note,
+    source_kind,
+    source_detail
+  )
+SELECT
+  id,
+  account_id,
+  balance_date,
+  balance_minor,
+  created_at,
+  note,
+  source_kind,
+  source_detail
+FROM
+  account_balance_snapshots;
+
+-- Keep the AUTOINCREMENT high-water mark so ids of deleted snapshots, which the audit log and
+-- import batches still mention, are never reused.
+DELETE FROM sqlite_sequence
+WHERE
+  name = 'account_balance_snapshots_new';
+
+INSERT INTO
+  sqlite_sequence (name, seq)
+SELECT
+  'account_balance_snapshots_new',
+  seq
+FROM
+  sqlite_sequence
+WHERE
+  name = 'account_balance_snapshots';
+
+DROP TABLE account_balance_snapshots;
+
+ALTER TABLE account_balance_snapshots_new
+RENAME TO account_balance_snapshots;
+
+CREATE INDEX idx_balance_account_date ON account_balance_snapshots (account_id, balance_date);
+
+CREATE INDEX idx_balance_date_account ON account_balance_snapshots (balance_date, account_id);
+
+CREATE TRIGGER account_balance_snapshots_audit_ai AFTER INSERT ON account_balance_snapshots BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    new.id,
+    new.account_id,
+    'create',
+    NULL,
+    JSON_OBJECT(
+      'balance_date',
+      new.balance_date,
+      'balance_minor',
+      new.balance_minor,
+      'note',
+      new.note,
+      'source_kind',
+      new.source_kind,
+      'source_detail',
+      new.source_detail
+    )
+  );
+
+END;
+
+CREATE TRIGGER account_balance_snapshots_audit_au AFTER
+UPDATE ON account_balance_snapshots WHEN old.balance_date IS NOT new.balance_date
+OR old.balance_minor IS NOT new.balance_minor
+OR old.note IS NOT new.note
+OR old.source_kind IS NOT new.source_kind
+OR old.source_detail IS NOT new.source_detail BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    new.id,
+    new.account_id,
+    'update',
+    JSON_OBJECT(
+      'balance_date',
+      old.balance_date,
+      'balance_minor',
+      old.balance_minor,
+      'note',
+      old.note,
+      'source_kind',
+      old.source_kind,
+      'source_detail',
+      old.source_detail
+    ),
+    JSON_OBJECT(
+      'balance_date',
+      new.balance_date,
+      'balance_minor',
+      new.balance_minor,
+      'note',
+      new.note,
+      'source_kind',
+      new.source_kind,
+      'source_detail',
+      new.source_detail
+    )
+  );
+
+END;
+
+CREATE TRIGGER account_balance_snapshots_audit_ad AFTER DELETE ON account_balance_snapshots BEGIN
+INSERT INTO
+  audit_log (entity_kind, entity_id, account_id, action, before_json, after_json)
+VALUES
+  (
+    'snapshot',
+    old.id,
+    old.account_id,
+    'delete',
+    JSON_OBJECT(
+      'balance_date',
+      old.balance_date,
+      'balance_minor',
+      old.balance_minor,
+      'note',
+      old.note,
+      'source_kind',
+      old.source_kind,
+      'source_detail',
+      old.source_detail
+    ),
+    NULL
+  );
+
+END;
@@ -31,7 +31,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'current'
+        name = 'Current'
     ),
     'GBP',
     'asset',
@@ -48,7 +48,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'current'
+        name = 'Current'
     ),
     'GBP',
     'asset',
@@ -65,7 +65,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'savings'
+        name = 'Savings'
     ),
     'GBP',
     'asset',
@@ -82,7 +82,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'savings'
+        name = 'Savings'
     ),
     'GBP',
     'asset',
@@ -99,7 +99,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'isa'
+        name = 'ISA'
     ),
     'GBP',
     'asset',
@@ -116,7 +116,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'investment'
+        name = 'Investment'
     ),
     'GBP',
     'asset',
@@ -133,7 +133,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'pension'
+        name = 'Pension'
     ),
     'GBP',
     'asset',
@@ -150,7 +150,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'savings'
+        name = 'Savings'
     ),
     'GBP',
     'asset',
@@ -167,7 +167,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'isa'
+        name = 'ISA'
     ),
     'GBP',
     'asset',
@@ -184,7 +184,7 @@ VALUES
       FROM
         account_types
       WHERE
-        name = 'credit_card'
+        name = 'Credit card'
     ),
     'GBP',
     'liability',
@@ -348,3 +348,18 @@ WHERE
     -- Plus a sparse set of other days to create gaps.
     OR m.pick_score = m.pick_win_max
   );
+
+INSERT INTO
+  tags (id, name)
+VALUES
+  (1, 'Emergency fund'),
+  (2, 'Long term');
+
+INSERT INTO
+  account_tags (account_id, tag_id)
+VALUES
+  (3, 1),
+  (4, 1),
+  (5, 2),
+  (6, 2),
+  (7, 2);
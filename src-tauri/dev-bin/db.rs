@@ -5,10 +5,11 @@ use sqlx::{
     sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
 };
 use std::collections::HashSet;
-use std::ffi::OsString;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use worth_lib::db;
+use worth_lib::doctor::{self, DoctorRepair};
 
 const DB_FILENAME: &str = "worth.sqlite";
 
@@ -35,6 +36,8 @@ enum Command {
     Clear(ClearArgs),
     /// Delete database backup files
     Clean(ConfirmArgs),
+    /// Check the database for corruption and inconsistent data
+    Doctor(DoctorArgs),
 }
 
 #[derive(Debug, Args)]
@@ -106,6 +109,17 @@ struct ClearArgs {
     backup_overwrite: bool,
 }
 
+#[derive(Debug, Args)]
+struct DoctorArgs {
+    /// Apply the safe repairs the checks offer, then check again
+    #[arg(long)]
+    repair: bool,
+
+    /// Passphrase for an encrypted database. If omitted, you'll be prompted for it (input is shown).
+    #[arg(long, value_name = "PASSPHRASE")]
+    passphrase: Option<String>,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -116,6 +130,81 @@ async fn main() -> Result<()> {
         Command::Clear(args) => clear_db(args),
         Command::Clean(args) => clean_db_backups(args.yes),
         Command::Restore(args) => restore_db(args),
+        Command::Doctor(args) => doctor_command(args).await,
+    }
+}
+
+async fn doctor_command(args: DoctorArgs) -> Result<()> {
+    let db_path = resolve_db_path()?;
+    if !db_path.is_file() {
+        bail!("no database found at {}", db_path.display());
+    }
+
+    let passphrase = if db::encryption::is_encrypted(&db_path).context("read database header")? {
+        let passphrase = match args.passphrase {
+            Some(passphrase) => passphrase,
+            None => prompt_passphrase()?,
+        };
+        if !db::encryption::passphrase_matches(&db_path, &passphrase)
+            .await
+            .context("check passphrase")?
+        {
+            bail!("incorrect passphrase for {}", db_path.display());
+        }
+        Some(passphrase)
+    } else {
+        None
+    };
+
+    let pool = db::open_pool(&db_path, passphrase.as_deref())
+        .await
+        .context("open database")?;
+    let mut report = doctor::check(&pool).await.context("check database")?;
+
+    if args.repair {
+        let mut repairs = Vec::<DoctorRepair>::new();
+        for repair in report.issues.iter().filter_map(|issue| issue.repair) {
+            if !repairs.contains(&repair) {
+                repairs.push(repair);
+            }
+        }
+        for repair in &repairs {
+            doctor::repair(&pool, *repair)
+                .await
+                .with_context(|| format!("repair {}", doctor_repair_label(*repair)))?;
+            println!("Repaired: {}", doctor_repair_label(*repair));
+        }
+        if !repairs.is_empty() {
+            report = doctor::check(&pool).await.context("check database")?;
+        }
+    }
+
+    println!("Database: {}", db_path.display());
+    if report.issues.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    for issue in &report.issues {
+        match issue.repair {
+            Some(repair) => println!(
+                "  - {:?}: {} (repair: {})",
+                issue.check,
+                issue.message,
+                doctor_repair_label(repair)
+            ),
+            None => println!("  - {:?}: {}", issue.check, issue.message),
+        }
+    }
+    if !args.repair && report.issues.iter().any(|issue| issue.repair.is_some()) {
+        println!("Run `db doctor --repair` to apply the safe repairs.");
+    }
+    bail!("{} problem(s) found", report.issues.len())
+}
+
+fn doctor_repair_label(repair: DoctorRepair) -> &'static str {
+    match repair {
+        DoctorRepair::RebuildSearchIndex => "rebuild search index",
     }
 }
 
@@ -617,7 +706,7 @@ fn backup_path_for(path: &Path, backup_name: &str) -> Result<PathBuf> {
 fn restore_db_files(db_path: &Path, backup_name: &str) -> Result<()> {
     let mut restored_any = false;
 
-    for path in db_file_paths(db_path) {
+    for path in db::file_set(db_path) {
         let backup_path = backup_path_for(&path, backup_name)?;
         if !backup_path.exists() {
             continue;
@@ -656,18 +745,9 @@ fn timestamp_ms() -> String {
     now.as_millis().to_string()
 }
 
-fn db_file_paths(db_path: &Path) -> [PathBuf; 4] {
-    [
-        db_path.to_path_buf(),
-        with_os_suffix(db_path, "-wal"),
-        with_os_suffix(db_path, "-shm"),
-        with_os_suffix(db_path, "-journal"),
-    ]
-}
-
 fn plan_backup_pairs(db_path: &Path, backup_name: &str) -> Result<Vec<(PathBuf, PathBuf)>> {
     let mut planned = Vec::<(PathBuf, PathBuf)>::new();
-    for path in db_file_paths(db_path) {
+    for path in db::file_set(db_path) {
         if !path.exists() {
             continue;
         }
@@ -762,10 +842,15 @@ fn copy_db_files_to_backup(db_path: &Path, backup_name: &str, overwrite: bool) -
     Ok(())
 }
 
-fn with_os_suffix(path: &Path, suffix: &str) -> PathBuf {
-    let mut s: OsString = path.as_os_str().to_os_string();
-    s.push(suffix);
-    PathBuf::from(s)
+fn prompt_passphrase() -> Result<String> {
+    print!("The database is encrypted. Passphrase (input is shown): ");
+    io::stdout().flush().context("flush stdout")?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("read passphrase from stdin")?;
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
 }
 
 fn confirm_default_yes(question: &str) -> Result<bool> {
@@ -5,21 +5,45 @@ use thiserror::Error;
 use chrono::{Duration, Local, NaiveDate, Utc};
 use garde::Validate;
 use sqlx::SqlitePool;
-use std::collections::{BTreeMap, HashMap, HashSet};
-use tauri::{AppHandle, State};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use tauri::{AppHandle, Manager, State};
 
+use crate::backups::archive::{
+    BackupArchiveDto, BackupArchiveInspectionDto, BackupArchiveSourceInput,
+};
+use crate::backups::{
+    BackupDto, BackupError, BackupKind, BackupListDto, BackupRestoreDto, BackupsPruneDto,
+    ScheduledBackupStatus,
+};
 use crate::contracts::{
-    AccountClassification, AccountSnapshotUpdateInput, AccountSnapshotsCreateInput,
-    AccountSnapshotsDeleteInput, AccountTypeName, AccountUpsertInput, AppLocaleCode,
-    AppSettingsUpdateInput, CurrencyCode, InstitutionRef, InstitutionUpsertInput, ThemePreference,
+    AccountClassification, AccountSnapshotUpdateInput, AccountSnapshotWriteInput,
+    AccountSnapshotsCreateInput, AccountSnapshotsDeleteInput, AccountTypeDeleteInput,
+    AccountTypeUpsertInput, AccountUpsertInput, AppLocaleCode, AppSettingsUpdateInput,
+    BackupSchedule, CurrencyCode, FxRateWriteInput, FxRatesDeleteInput, InstitutionRef,
+    InstitutionUpsertInput, SnapshotSource, ThemePreference,
+};
+use crate::doctor::{DoctorRepair, DoctorReportDto};
+use crate::exports::{
+    DataExportDocument, DataExportDto, DataExportFormat, DataExportOptionsInput, DataExportRows,
+};
+use crate::fx::FxRates;
+use crate::imports::data::{
+    DataImportCommitDto, DataImportOptionsInput, DataImportPlan, DataImportPreviewDto,
+    DataImportSourceInput,
 };
+use crate::imports::fx_rates::{FxRateCsvImportInput, FxRateImportCommitDto};
 use crate::imports::snapshots::{
-    SnapshotImportCommitDto, SnapshotImportInspectionDto, SnapshotImportOptionsInput,
+    CsvSnapshotImportSourceInput, SnapshotImportBatchDto, SnapshotImportCommitDto,
+    SnapshotImportInspectionDto, SnapshotImportOptionsInput, SnapshotImportPlan,
     SnapshotImportPlanningContext, SnapshotImportPreviewAction, SnapshotImportPreviewDto,
-    SnapshotImportSourceInput, SnapshotImportValidationIssue,
+    SnapshotImportProfileDto, SnapshotImportProfileSaveInput, SnapshotImportRollbackDto,
+    SnapshotImportSourceInput, SnapshotImportValidationIssue, WideSnapshotImportAccountCommitDto,
+    WideSnapshotImportAccountPreviewDto, WideSnapshotImportCommitDto,
+    WideSnapshotImportOptionsInput, WideSnapshotImportPreviewDto, WideSnapshotImportTarget,
 };
-use crate::state::AppState;
-use crate::updates::AppUpdateStateDto;
+use crate::state::{AppState, DatabaseLock};
+use crate::updates::{AppUpdateManager, AppUpdateStateDto};
 use crate::{db, db::AccountListRow};
 
 #[derive(Debug, Error, Serialize, Deserialize, Type)]
@@ -55,6 +79,30 @@ pub struct AppSettingsDto {
     pub default_display_currency_code: CurrencyCode,
     pub display_locale: AppLocaleCode,
     pub theme: ThemePreference,
+    pub backup_schedule: BackupSchedule,
+    pub backup_retention_count: u32,
+    /// `None` while backups go to the default folder.
+    pub backup_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DatabaseStatusDto {
+    /// Whether the database file is encrypted with a passphrase.
+    pub encrypted: bool,
+    /// Whether the database is waiting for `database_unlock`. Commands that read or write
+    /// data fail until it is unlocked.
+    pub locked: bool,
+    /// Set when the database was migrated by a newer version of Worth. It is then open
+    /// read-only, and writes fail until a compatible backup is restored.
+    pub incompatible: Option<DatabaseIncompatibilityDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DatabaseIncompatibilityDto {
+    /// Applied migrations this version does not know, or knows with different contents.
+    pub unknown_migrations: Vec<i64>,
+    /// Newest migration this version knows.
+    pub latest_known_migration: i64,
 }
 
 #[derive(
@@ -108,14 +156,37 @@ pub struct InstitutionSummaryDto {
     pub name: String,
     pub account_count: u32,
     pub empty_account_count: u32,
-    pub account_types: Vec<AccountTypeName>,
+    pub account_types: Vec<AccountTypeDto>,
     pub total_balance_minor: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct AccountTypeDto {
     pub id: i64,
-    pub name: AccountTypeName,
+    pub name: String,
+    /// Hex colour in `#rrggbb` form.
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AccountTypeSummaryDto {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    pub account_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TagDto {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TagSummaryDto {
+    pub id: i64,
+    pub name: String,
+    pub account_count: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -124,6 +195,7 @@ pub struct AccountDto {
     pub name: String,
     pub institution: InstitutionDto,
     pub account_type: AccountTypeDto,
+    pub tags: Vec<TagDto>,
     pub currency_code: CurrencyCode,
     pub account_classification: AccountClassification,
     pub opened_date: Option<NaiveDate>,
@@ -162,16 +234,25 @@ pub struct CreatedIdDto {
     pub id: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AccountCreateImportCommitDto {
+    pub account_id: i64,
+    pub result: SnapshotImportCommitDto,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DashboardAllocationDto {
-    pub account_type: AccountTypeName,
+    pub account_type: AccountTypeDto,
     pub balance_minor: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DashboardBalancePointDto {
     pub date: NaiveDate,
+    /// Total in the display currency.
     pub balance_minor: i64,
+    /// Whether an account balance on this date was left out for lack of an exchange rate.
+    pub has_missing_fx_rate: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -179,9 +260,96 @@ pub struct AccountBalanceSnapshotDto {
     pub id: i64,
     pub date: NaiveDate,
     pub balance_minor: i64,
+    pub note: Option<String>,
+    /// `None` for snapshots recorded before sources were tracked.
+    pub source: Option<SnapshotSource>,
     pub created_at: chrono::DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityKind {
+    Institution,
+    Account,
+    Snapshot,
+}
+
+impl AuditEntityKind {
+    /// Recorded fields in display order; matches the JSON objects the audit triggers write.
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            AuditEntityKind::Institution => &["name"],
+            AuditEntityKind::Account => &[
+                "name",
+                "institution_id",
+                "type_id",
+                "currency_code",
+                "account_classification",
+                "opened_date",
+                "closed_date",
+            ],
+            AuditEntityKind::Snapshot => &[
+                "balance_date",
+                "balance_minor",
+                "note",
+                "source_kind",
+                "source_detail",
+            ],
+        }
+    }
+}
+
+impl FromStr for AuditEntityKind {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "institution" => Ok(AuditEntityKind::Institution),
+            "account" => Ok(AuditEntityKind::Account),
+            "snapshot" => Ok(AuditEntityKind::Snapshot),
+            _ => Err("Invalid audit entity kind"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl FromStr for AuditAction {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            _ => Err("Invalid audit action"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct AuditFieldChangeDto {
+    pub field: String,
+    /// Value before the change as stored text; `None` when the field was empty or absent.
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AuditLogEntryDto {
+    pub id: i64,
+    pub entity_kind: AuditEntityKind,
+    pub entity_id: i64,
+    pub action: AuditAction,
+    /// Every recorded field for creates and deletes; only the fields that changed for updates.
+    pub changes: Vec<AuditFieldChangeDto>,
+    pub changed_at: chrono::DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct BalancePointDto {
     pub date: NaiveDate,
@@ -190,6 +358,8 @@ pub struct BalancePointDto {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DashboardDto {
+    /// Display currency that every dashboard amount has been converted into.
+    pub currency_code: CurrencyCode,
     pub total_balance_minor: i64,
     pub change_vs_last_month_pct: f64,
     pub monthly_yield_minor: i64,
@@ -197,6 +367,18 @@ pub struct DashboardDto {
     pub active_accounts: u32,
     pub active_institutions: u32,
     pub allocation_by_type: Vec<DashboardAllocationDto>,
+    /// Account currencies left out of the dashboard amounts because no exchange rate
+    /// into the display currency was in effect.
+    pub missing_fx_rate_currency_codes: Vec<CurrencyCode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FxRateDto {
+    pub id: i64,
+    pub base_currency_code: CurrencyCode,
+    pub quote_currency_code: CurrencyCode,
+    pub date: NaiveDate,
+    pub rate: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -205,7 +387,7 @@ pub enum SearchResultDto {
     Account {
         id: i64,
         name: String,
-        account_type: AccountTypeName,
+        account_type: AccountTypeDto,
         institution_name: String,
     },
     Institution {
@@ -228,6 +410,11 @@ pub async fn settings_update(
     state: State<'_, AppState>,
     input: AppSettingsUpdateInput,
 ) -> Result<AppSettingsDto, ApiError> {
+    let issues = validation_issues_from_garde_report(input.validate().err());
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
     let pool = &state.pool;
     let mutation = db::AppSettingsMutationInput {
         analytics_enabled: input.analytics_enabled,
@@ -238,6 +425,14 @@ pub async fn settings_update(
             .display_locale
             .map(|locale| locale.as_str().to_owned()),
         theme: input.theme.map(|theme| theme.as_str().to_owned()),
+        backup_schedule: input
+            .backup_schedule
+            .map(|schedule| schedule.as_str().to_owned()),
+        backup_retention_count: input.backup_retention_count.map(i64::from),
+        backup_folder: input.backup_folder.map(|folder| {
+            let folder = folder.trim();
+            (!folder.is_empty()).then(|| folder.to_owned())
+        }),
     };
 
     let row = db::app_settings_update(pool, &mutation)
@@ -256,747 +451,1131 @@ pub async fn data_folder_open(app: AppHandle) -> Result<(), ApiError> {
 
 #[tauri::command]
 #[specta::specta]
-pub async fn app_updates_state_get(
-    state: State<'_, AppState>,
-) -> Result<AppUpdateStateDto, ApiError> {
-    Ok(state.updates.state())
+pub async fn database_status(app: AppHandle) -> Result<DatabaseStatusDto, ApiError> {
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    let state = app.try_state::<AppState>();
+    Ok(DatabaseStatusDto {
+        encrypted: db::encryption::is_encrypted(&db_path).map_err(|_| ApiError::Db)?,
+        locked: state.is_none(),
+        incompatible: state.filter(|state| state.is_read_only()).map(|state| {
+            DatabaseIncompatibilityDto {
+                unknown_migrations: state.incompatible_migrations.clone(),
+                latest_known_migration: db::latest_migration_version(),
+            }
+        }),
+    })
 }
 
+// Opens an encrypted database with its passphrase and starts everything that was waiting
+// for it. Every other database command fails until this succeeds.
 #[tauri::command]
 #[specta::specta]
-pub async fn app_updates_check(
+pub async fn database_unlock(
     app: AppHandle,
-    state: State<'_, AppState>,
-) -> Result<AppUpdateStateDto, ApiError> {
-    Ok(state.updates.check_for_updates(app).await)
+    lock: State<'_, DatabaseLock>,
+    passphrase: String,
+) -> Result<(), ApiError> {
+    let _unlocking = lock.unlocking.lock().await;
+    if app.try_state::<AppState>().is_some() {
+        return Ok(());
+    }
+
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    if !db::encryption::passphrase_matches(&db_path, &passphrase)
+        .await
+        .map_err(|_| ApiError::Db)?
+    {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "passphrase",
+            INCORRECT_PASSPHRASE,
+        )]));
+    }
+
+    let pool = db::open_pool(&db_path, Some(&passphrase))
+        .await
+        .map_err(|_| ApiError::Db)?;
+    AppState::start(&app, pool, &db_path)
+        .await
+        .map_err(|_| ApiError::Db)
 }
 
+// The commands below rewrite the database file and close the connection on success, so
+// the frontend must relaunch the app straight away.
 #[tauri::command]
 #[specta::specta]
-pub async fn app_updates_install_pending_and_restart(
+pub async fn database_encryption_enable(
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<AppUpdateStateDto, ApiError> {
-    Ok(state.updates.install_pending_and_restart(app).await)
+    passphrase: String,
+) -> Result<(), ApiError> {
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    if db::encryption::is_encrypted(&db_path).map_err(|_| ApiError::Db)? {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "passphrase",
+            "The database is already encrypted",
+        )]));
+    }
+    let issues = new_passphrase_issues("passphrase", &passphrase);
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    db::encryption::rewrite(&state.pool, &db_path, Some(&passphrase))
+        .await
+        .map_err(|_| ApiError::Db)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn accounts_list(state: State<'_, AppState>) -> Result<Vec<AccountDto>, ApiError> {
-    let pool = &state.pool;
+pub async fn database_passphrase_change(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), ApiError> {
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    let mut issues = current_passphrase_issues(&db_path, &current_passphrase).await?;
+    issues.extend(new_passphrase_issues("new_passphrase", &new_passphrase));
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
 
-    let accounts = db::accounts_list_full(pool)
+    db::encryption::rewrite(&state.pool, &db_path, Some(&new_passphrase))
         .await
-        .map_err(|_| ApiError::Db)?;
-    build_account_dtos(pool, accounts).await
+        .map_err(|_| ApiError::Db)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_list(
+pub async fn database_encryption_disable(
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<Vec<InstitutionSummaryDto>, ApiError> {
-    let pool = &state.pool;
+    current_passphrase: String,
+) -> Result<(), ApiError> {
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    let issues = current_passphrase_issues(&db_path, &current_passphrase).await?;
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
 
-    let summary_rows = db::institutions_list_summary(pool)
+    db::encryption::rewrite(&state.pool, &db_path, None)
         .await
-        .map_err(|_| ApiError::Db)?;
-    let type_rows = db::institutions_account_types(pool)
+        .map_err(|_| ApiError::Db)
+}
+
+const INCORRECT_PASSPHRASE: &str = "Incorrect passphrase";
+
+async fn current_passphrase_issues(
+    db_path: &std::path::Path,
+    current_passphrase: &str,
+) -> Result<Vec<ValidationIssue>, ApiError> {
+    if !db::encryption::is_encrypted(db_path).map_err(|_| ApiError::Db)? {
+        return Ok(vec![validation_issue(
+            "current_passphrase",
+            "The database is not encrypted",
+        )]);
+    }
+    let matches = db::encryption::passphrase_matches(db_path, current_passphrase)
         .await
         .map_err(|_| ApiError::Db)?;
+    Ok(if matches {
+        Vec::new()
+    } else {
+        vec![validation_issue("current_passphrase", INCORRECT_PASSPHRASE)]
+    })
+}
 
-    let mut types_by_institution: HashMap<i64, Vec<AccountTypeName>> =
-        HashMap::with_capacity(summary_rows.len());
-    for row in type_rows {
-        let account_type_name = row.type_name.parse().map_err(|_| ApiError::Db)?;
-        types_by_institution
-            .entry(row.institution_id)
-            .or_default()
-            .push(account_type_name);
+fn new_passphrase_issues(field: &str, passphrase: &str) -> Vec<ValidationIssue> {
+    if passphrase.chars().count() < db::encryption::PASSPHRASE_MIN_LENGTH {
+        vec![validation_issue(
+            field,
+            &format!(
+                "Passphrase must be at least {} characters",
+                db::encryption::PASSPHRASE_MIN_LENGTH
+            ),
+        )]
+    } else {
+        Vec::new()
     }
+}
 
-    let mut out = Vec::with_capacity(summary_rows.len());
-    for row in summary_rows {
-        out.push(InstitutionSummaryDto {
-            id: row.id,
-            name: row.name,
-            account_count: u32::try_from(row.account_count)
-                .expect("account count should fit in u32"),
-            empty_account_count: u32::try_from(row.empty_account_count)
-                .expect("empty account count should fit in u32"),
-            account_types: types_by_institution.remove(&row.id).unwrap_or_default(),
-            total_balance_minor: row.total_balance_minor,
-        });
-    }
+#[tauri::command]
+#[specta::specta]
+pub async fn database_doctor(state: State<'_, AppState>) -> Result<DoctorReportDto, ApiError> {
+    crate::doctor::check(&state.pool)
+        .await
+        .map_err(|_| ApiError::Db)
+}
 
-    Ok(out)
+// Runs a repair the last report offered, then checks again so the frontend can show what
+// is left.
+#[tauri::command]
+#[specta::specta]
+pub async fn database_doctor_repair(
+    state: State<'_, AppState>,
+    repair: DoctorRepair,
+) -> Result<DoctorReportDto, ApiError> {
+    crate::doctor::repair(&state.pool, repair)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    crate::doctor::check(&state.pool)
+        .await
+        .map_err(|_| ApiError::Db)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn search(
+pub async fn data_export(
+    app: AppHandle,
     state: State<'_, AppState>,
-    query: String,
-) -> Result<Vec<SearchResultDto>, ApiError> {
-    let query = query.trim();
-    if query.is_empty() {
-        return Ok(Vec::new());
+    options: DataExportOptionsInput,
+) -> Result<DataExportDto, ApiError> {
+    let exports_dir = crate::exports::exports_dir(&app).map_err(|_| ApiError::Db)?;
+    data_export_into(
+        &state.pool,
+        &options,
+        &exports_dir,
+        &app.package_info().version.to_string(),
+        Utc::now(),
+    )
+    .await
+}
+
+async fn data_export_into(
+    pool: &SqlitePool,
+    options: &DataExportOptionsInput,
+    exports_dir: &std::path::Path,
+    app_version: &str,
+    exported_at: chrono::DateTime<Utc>,
+) -> Result<DataExportDto, ApiError> {
+    let mut issues = Vec::new();
+    if let (Some(from_date), Some(to_date)) = (options.from_date, options.to_date)
+        && to_date < from_date
+    {
+        issues.push(validation_issue(
+            "to_date",
+            "End date cannot be before start date",
+        ));
     }
 
-    let pool = &state.pool;
-    let rows = db::search_global(pool, query)
+    let mut accounts = db::accounts_list_full(pool)
         .await
         .map_err(|_| ApiError::Db)?;
+    if let Some(account_ids) = &options.account_ids {
+        if account_ids.is_empty() {
+            issues.push(validation_issue(
+                "account_ids",
+                "Choose at least one account",
+            ));
+        } else if account_ids
+            .iter()
+            .any(|id| !accounts.iter().any(|account| account.id == *id))
+        {
+            issues.push(validation_issue("account_ids", "Account does not exist"));
+        }
+        accounts.retain(|account| account_ids.contains(&account.id));
+    }
 
-    rows.into_iter()
-        .map(|row| match row {
-            db::GlobalSearchRow::Account {
-                id,
-                name,
-                type_name,
-                institution_name,
-            } => Ok(SearchResultDto::Account {
-                id,
-                name,
-                account_type: type_name.parse().map_err(|_| ApiError::Db)?,
-                institution_name,
-            }),
-            db::GlobalSearchRow::Institution { id, name } => {
-                Ok(SearchResultDto::Institution { id, name })
-            }
-        })
-        .collect()
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    let account_ids = accounts
+        .iter()
+        .map(|account| account.id)
+        .collect::<Vec<_>>();
+    let rows = DataExportRows {
+        account_types: db::account_types_list_summary(pool)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        institutions: db::institutions_list_summary(pool)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        tags: db::account_tags_for_accounts(pool, &account_ids)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        snapshots: db::snapshots_for_export(pool, &account_ids, options.from_date, options.to_date)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        accounts,
+    };
+    let document = crate::exports::document(rows, options, exported_at, app_version)
+        .map_err(|_| ApiError::Db)?;
+    let base_name = format!(
+        "worth-export-{}",
+        exported_at.with_timezone(&Local).format("%Y-%m-%d-%H%M%S")
+    );
+    let files =
+        crate::exports::files(&document, options.format, &base_name).map_err(|_| ApiError::Db)?;
+
+    // A JSON export is one file; CSVs get a folder of their own.
+    let output_dir = match options.format {
+        DataExportFormat::Json => exports_dir.to_path_buf(),
+        DataExportFormat::Csv => exports_dir.join(&base_name),
+    };
+    std::fs::create_dir_all(&output_dir).map_err(|_| ApiError::Db)?;
+    for file in &files {
+        std::fs::write(output_dir.join(&file.name), &file.content).map_err(|_| ApiError::Db)?;
+    }
+    let path = match options.format {
+        DataExportFormat::Json => output_dir.join(&files[0].name),
+        DataExportFormat::Csv => output_dir,
+    };
+
+    Ok(DataExportDto {
+        path: path.to_string_lossy().into_owned(),
+        file_names: files.into_iter().map(|file| file.name).collect(),
+        account_count: u32::try_from(document.accounts.len())
+            .expect("account count should fit in u32"),
+        snapshot_count: u32::try_from(document.snapshots.len())
+            .expect("snapshot count should fit in u32"),
+    })
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_get(
-    state: State<'_, AppState>,
-    institution_id: i64,
-) -> Result<InstitutionDetailDto, ApiError> {
-    let pool = &state.pool;
-    institution_detail_by_id(pool, institution_id).await
+pub async fn data_exports_folder_open(app: AppHandle) -> Result<(), ApiError> {
+    let exports_dir = crate::exports::exports_dir(&app).map_err(|_| ApiError::Db)?;
+    std::fs::create_dir_all(&exports_dir).map_err(|_| ApiError::Db)?;
+    tauri_plugin_opener::open_path(&exports_dir, None::<&str>).map_err(|_| ApiError::Db)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_create(
+pub async fn data_import_preview(
     state: State<'_, AppState>,
-    input: InstitutionUpsertInput,
-) -> Result<CreatedIdDto, ApiError> {
-    let pool = &state.pool;
-    let validated = validate_institution_upsert(pool, &input, None).await?;
-
-    let created = db::institution_create(pool, &validated.name)
-        .await
-        .map_err(map_institution_write_error)?;
-
-    Ok(CreatedIdDto { id: created.id })
+    input: DataImportSourceInput,
+    options: DataImportOptionsInput,
+) -> Result<DataImportPreviewDto, ApiError> {
+    Ok(data_import_plan(&state.pool, &input, &options)
+        .await?
+        .preview)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_update(
+pub async fn data_import_commit(
     state: State<'_, AppState>,
-    institution_id: i64,
-    input: InstitutionUpsertInput,
-) -> Result<(), ApiError> {
-    let pool = &state.pool;
-    let validated = validate_institution_upsert(pool, &input, Some(institution_id)).await?;
-
-    let updated = db::institution_update(pool, institution_id, &validated.name)
-        .await
-        .map_err(map_institution_write_error)?;
+    input: DataImportSourceInput,
+    options: DataImportOptionsInput,
+) -> Result<DataImportCommitDto, ApiError> {
+    data_import_commit_into(&state.pool, &input, &options).await
+}
 
-    if updated.is_none() {
-        return Err(ApiError::NotFound);
-    }
+async fn data_import_commit_into(
+    pool: &SqlitePool,
+    input: &DataImportSourceInput,
+    options: &DataImportOptionsInput,
+) -> Result<DataImportCommitDto, ApiError> {
+    let plan = data_import_plan(pool, input, options).await?;
+    let summary = &plan.preview.summary;
 
-    Ok(())
-}
-
-#[tauri::command]
-#[specta::specta]
-pub async fn accounts_get(
-    state: State<'_, AppState>,
-    account_id: i64,
-) -> Result<AccountDto, ApiError> {
-    let pool = &state.pool;
-    account_dto_by_id(pool, account_id).await
-}
-
-#[tauri::command]
-#[specta::specta]
-pub async fn accounts_delete_preview(
-    state: State<'_, AppState>,
-    account_id: i64,
-) -> Result<AccountDeletePreviewDto, ApiError> {
-    let pool = &state.pool;
-    let Some(row) = db::account_delete_preview(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?
-    else {
-        return Err(ApiError::NotFound);
-    };
+    if summary.invalid_count > 0 {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Fix the problems shown in the preview before importing",
+        )]));
+    }
 
-    Ok(AccountDeletePreviewDto {
-        id: row.id,
-        name: row.name,
-        institution_name: row.institution_name,
-        snapshot_count: u32::try_from(row.snapshot_count)
-            .expect("snapshot count should fit in u32"),
-    })
-}
+    if summary.overwrite_count > 0 && !options.overwrite_existing_confirmed {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Confirm overwrite to continue",
+        )]));
+    }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn accounts_delete(state: State<'_, AppState>, account_id: i64) -> Result<(), ApiError> {
-    let pool = &state.pool;
-    let deleted = db::account_delete(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?;
+    let skipped_count = summary.skip_count;
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let mut created_account_type_count = 0;
+    let mut created_institution_count = 0;
+    let mut created_account_count = 0;
+    let mut merged_account_count = 0;
+    let mut created_count = 0;
+    let mut overwritten_count = 0;
 
-    if !deleted {
-        return Err(ApiError::NotFound);
+    // Document ids only tie rows together within the file; map them to database ids.
+    let mut type_ids = HashMap::with_capacity(plan.account_types.len());
+    for account_type in plan.account_types {
+        let id = match account_type.existing_id {
+            Some(id) => id,
+            None => {
+                created_account_type_count += 1;
+                db::account_type_create_tx(
+                    &mut tx,
+                    &account_type.item.name,
+                    &account_type.item.color.to_ascii_lowercase(),
+                )
+                .await
+                .map_err(map_account_type_write_error)?
+            }
+        };
+        type_ids.insert(account_type.item.id, id);
     }
 
-    Ok(())
-}
+    let mut institution_ids = HashMap::with_capacity(plan.institutions.len());
+    for institution in plan.institutions {
+        let id = match institution.existing_id {
+            Some(id) => id,
+            None => {
+                created_institution_count += 1;
+                db::institution_create_tx(&mut tx, &institution.item.name)
+                    .await
+                    .map_err(map_institution_write_error)?
+            }
+        };
+        institution_ids.insert(institution.item.id, id);
+    }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn accounts_create(
-    state: State<'_, AppState>,
-    input: AccountUpsertInput,
-) -> Result<CreatedIdDto, ApiError> {
-    let pool = &state.pool;
-    let validated = validate_account_upsert(pool, &input, None).await?;
+    for account_plan in plan.accounts {
+        let account = &account_plan.account.item;
+        let account_id = match account_plan.account.existing_id {
+            Some(id) => {
+                merged_account_count += 1;
+                id
+            }
+            None => {
+                created_account_count += 1;
+                let mutation = db::AccountMutationInput {
+                    institution_id: institution_ids[&account.institution_id],
+                    name: account.name.clone(),
+                    type_id: type_ids[&account.type_id],
+                    currency_code: account.currency_code.as_str().to_owned(),
+                    account_classification: account.account_classification.as_str().to_owned(),
+                    opened_date: account.opened_date,
+                    closed_date: account.closed_date,
+                };
+                db::account_create_tx(&mut tx, &mutation)
+                    .await
+                    .map_err(map_account_write_error)?
+            }
+        };
+        if let Some(tags) = &account_plan.tags {
+            db::account_tags_set_tx(&mut tx, account_id, tags)
+                .await
+                .map_err(|_| ApiError::Db)?;
+        }
 
-    let account_id = match &validated.institution {
-        ValidatedInstitutionRef::Existing { id } => {
-            let mutation = db::AccountMutationInput {
-                institution_id: *id,
-                name: validated.name.clone(),
-                type_id: validated.type_id,
-                currency_code: validated.currency_code.clone(),
-                account_classification: validated.account_classification.as_str().to_owned(),
-                opened_date: validated.opened_date,
-                closed_date: validated.closed_date,
+        for write in account_plan.writes {
+            // Restoring keeps the exported note and source, including on overwrites.
+            let mutation = db::AccountSnapshotMutationInput {
+                balance_date: write.snapshot.date,
+                balance_minor: write.snapshot.balance_minor,
+                note: write.snapshot.note,
+                source_kind: write
+                    .snapshot
+                    .source
+                    .as_ref()
+                    .map(|source| source.kind_str().to_owned()),
+                source_detail: write
+                    .snapshot
+                    .source
+                    .as_ref()
+                    .and_then(SnapshotSource::detail)
+                    .map(str::to_owned),
             };
-
-            db::account_create(pool, &mutation)
-                .await
-                .map_err(map_account_write_error)?
-                .id
+            match (write.action, write.existing_snapshot_id) {
+                (SnapshotImportPreviewAction::Overwrite, Some(snapshot_id)) => {
+                    let updated =
+                        db::account_snapshot_update_tx(&mut tx, account_id, snapshot_id, &mutation)
+                            .await
+                            .map_err(map_account_snapshot_write_error)?;
+                    if !updated {
+                        return Err(ApiError::NotFound);
+                    }
+                    overwritten_count += 1;
+                }
+                (SnapshotImportPreviewAction::Create, None) => {
+                    db::account_snapshot_create_tx(&mut tx, account_id, &mutation)
+                        .await
+                        .map_err(map_account_snapshot_write_error)?;
+                    created_count += 1;
+                }
+                _ => return Err(ApiError::Db),
+            }
         }
-        ValidatedInstitutionRef::New { name } => {
-            let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    }
 
-            let institution_id = db::institution_create_tx(&mut tx, name)
-                .await
-                .map_err(map_institution_write_error)?;
+    tx.commit().await.map_err(|_| ApiError::Db)?;
 
-            let mutation = db::AccountMutationInput {
-                institution_id,
-                name: validated.name.clone(),
-                type_id: validated.type_id,
-                currency_code: validated.currency_code.clone(),
-                account_classification: validated.account_classification.as_str().to_owned(),
-                opened_date: validated.opened_date,
-                closed_date: validated.closed_date,
-            };
+    Ok(DataImportCommitDto {
+        created_account_type_count,
+        created_institution_count,
+        created_account_count,
+        merged_account_count,
+        created_count,
+        overwritten_count,
+        skipped_count,
+    })
+}
 
-            let account_id = db::account_create_tx(&mut tx, &mutation)
-                .await
-                .map_err(map_account_write_error)?;
+/// Reads and validates the export, then plans it against everything already in the
+/// database. Field issues are reported against their place in the document, such as
+/// `document.accounts.0.name`.
+async fn data_import_plan(
+    pool: &SqlitePool,
+    input: &DataImportSourceInput,
+    options: &DataImportOptionsInput,
+) -> Result<DataImportPlan, ApiError> {
+    let document =
+        crate::imports::data::parse_document(input).map_err(map_snapshot_import_validation)?;
+    let issues = validate_data_import_document(&document);
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
 
-            tx.commit().await.map_err(|_| ApiError::Db)?;
-            account_id
-        }
+    let accounts = db::accounts_list_full(pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let account_ids = accounts
+        .iter()
+        .map(|account| account.id)
+        .collect::<Vec<_>>();
+    let existing = DataExportRows {
+        account_types: db::account_types_list_summary(pool)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        institutions: db::institutions_list_summary(pool)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        tags: db::account_tags_for_accounts(pool, &account_ids)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        snapshots: db::snapshots_for_export(pool, &account_ids, None, None)
+            .await
+            .map_err(|_| ApiError::Db)?,
+        accounts,
     };
 
-    Ok(CreatedIdDto { id: account_id })
+    Ok(crate::imports::data::plan(document, &existing, options))
 }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn accounts_update(
-    state: State<'_, AppState>,
-    account_id: i64,
-    input: AccountUpsertInput,
-) -> Result<(), ApiError> {
-    let pool = &state.pool;
-
-    // Keep behavior explicit before we potentially create a new institution.
-    let exists = db::account_get_full(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?
-        .is_some();
-    if !exists {
-        return Err(ApiError::NotFound);
+fn validate_data_import_document(document: &DataExportDocument) -> Vec<ValidationIssue> {
+    fn nested(prefix: String, issues: Vec<ValidationIssue>) -> Vec<ValidationIssue> {
+        issues
+            .into_iter()
+            .map(|issue| ValidationIssue {
+                field: format!("{prefix}.{}", issue.field),
+                ..issue
+            })
+            .collect()
     }
 
-    let validated = validate_account_upsert(pool, &input, Some(account_id)).await?;
+    let mut issues = Vec::new();
+    for (index, account_type) in document.account_types.iter().enumerate() {
+        let input = AccountTypeUpsertInput {
+            name: account_type.name.clone(),
+            color: account_type.color.clone(),
+        };
+        issues.extend(nested(
+            format!("document.account_types.{index}"),
+            validation_issues_from_garde_report(input.validate().err()),
+        ));
+    }
 
-    match &validated.institution {
-        ValidatedInstitutionRef::Existing { id } => {
-            let mutation = db::AccountMutationInput {
-                institution_id: *id,
-                name: validated.name.clone(),
-                type_id: validated.type_id,
-                currency_code: validated.currency_code.clone(),
-                account_classification: validated.account_classification.as_str().to_owned(),
-                opened_date: validated.opened_date,
-                closed_date: validated.closed_date,
-            };
+    for (index, institution) in document.institutions.iter().enumerate() {
+        let input = InstitutionUpsertInput {
+            name: institution.name.clone(),
+        };
+        issues.extend(nested(
+            format!("document.institutions.{index}"),
+            validation_issues_from_garde_report(input.validate().err()),
+        ));
+    }
 
-            let updated = db::account_update(pool, account_id, &mutation)
-                .await
-                .map_err(map_account_write_error)?;
-            if updated.is_none() {
-                return Err(ApiError::NotFound);
-            }
+    let mut currency_codes = HashMap::with_capacity(document.accounts.len());
+    for (index, account) in document.accounts.iter().enumerate() {
+        currency_codes.insert(account.id, account.currency_code);
+        let input = AccountUpsertInput {
+            institution: InstitutionRef::Existing {
+                id: account.institution_id,
+            },
+            name: account.name.clone(),
+            account_type_id: account.type_id,
+            currency_code: account.currency_code,
+            account_classification: account.account_classification,
+            opened_date: account.opened_date,
+            closed_date: account.closed_date,
+            tags: account.tags.clone(),
+        };
+        let mut account_issues = validation_issues_from_garde_report(input.validate().err());
+        if let (Some(opened_date), Some(closed_date)) = (account.opened_date, account.closed_date)
+            && closed_date < opened_date
+        {
+            account_issues.push(validation_issue(
+                "closed_date",
+                "Closed date cannot be before opened date",
+            ));
         }
-        ValidatedInstitutionRef::New { name } => {
-            let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
-            let institution_id = db::institution_create_tx(&mut tx, name)
-                .await
-                .map_err(map_institution_write_error)?;
-            let mutation = db::AccountMutationInput {
-                institution_id,
-                name: validated.name.clone(),
-                type_id: validated.type_id,
-                currency_code: validated.currency_code.clone(),
-                account_classification: validated.account_classification.as_str().to_owned(),
-                opened_date: validated.opened_date,
-                closed_date: validated.closed_date,
-            };
-
-            let updated = db::account_update_tx(&mut tx, account_id, &mutation)
-                .await
-                .map_err(map_account_write_error)?;
-            if !updated {
-                return Err(ApiError::NotFound);
-            }
+        issues.extend(nested(format!("document.accounts.{index}"), account_issues));
+    }
 
-            tx.commit().await.map_err(|_| ApiError::Db)?;
-        }
+    for (index, snapshot) in document.snapshots.iter().enumerate() {
+        let input = AccountSnapshotWriteInput {
+            date: snapshot.date,
+            balance_minor: snapshot.balance_minor,
+            note: snapshot.note.clone(),
+            source: snapshot.source.clone(),
+            overwrite_existing: false,
+        };
+        issues.extend(nested(
+            format!("document.snapshots.{index}"),
+            validation_issues_from_garde_report(
+                input
+                    .validate_with(&currency_codes[&snapshot.account_id])
+                    .err(),
+            ),
+        ));
     }
 
-    Ok(())
+    issues
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshots_list(
+pub async fn backups_list(
+    app: AppHandle,
     state: State<'_, AppState>,
-    account_id: i64,
-) -> Result<Vec<AccountBalanceSnapshotDto>, ApiError> {
-    let pool = &state.pool;
-
-    // Ensure account exists for consistent NotFound behavior.
-    let exists = db::account_get_full(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?
-        .is_some();
-    if !exists {
-        return Err(ApiError::NotFound);
-    }
+    status: State<'_, ScheduledBackupStatus>,
+) -> Result<BackupListDto, ApiError> {
+    let (dir, _) = backup_settings(&app, &state.pool).await?;
+    let backups = crate::backups::list(&dir).map_err(map_backup_error)?;
+    Ok(BackupListDto {
+        folder: dir.to_string_lossy().into_owned(),
+        backups,
+        last_scheduled_error: status.last_error(),
+    })
+}
 
-    let rows = db::snapshots_for_account(pool, account_id)
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_create(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BackupDto, ApiError> {
+    let (dir, retention_count) = backup_settings(&app, &state.pool).await?;
+    let backup = crate::backups::create(&state.pool, &dir, BackupKind::Manual, Utc::now())
         .await
-        .map_err(|_| ApiError::Db)?;
-
-    Ok(rows
-        .into_iter()
-        .map(|r| AccountBalanceSnapshotDto {
-            id: r.id,
-            date: r.balance_date,
-            balance_minor: r.balance_minor,
-            created_at: r.created_at,
-        })
-        .collect())
+        .map_err(map_backup_error)?;
+    crate::backups::prune(&dir, retention_count).map_err(map_backup_error)?;
+    Ok(backup)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshots_create(
+pub async fn backups_prune(
+    app: AppHandle,
     state: State<'_, AppState>,
-    account_id: i64,
-    input: AccountSnapshotsCreateInput,
-) -> Result<(), ApiError> {
-    let pool = &state.pool;
+) -> Result<BackupsPruneDto, ApiError> {
+    let (dir, retention_count) = backup_settings(&app, &state.pool).await?;
+    let deleted_count = crate::backups::prune(&dir, retention_count).map_err(map_backup_error)?;
+    Ok(BackupsPruneDto { deleted_count })
+}
 
-    let exists = db::account_get_full(pool, account_id)
+// Restores a backup after backing up the current database. The database connection is
+// closed on success, so the frontend must relaunch the app straight away.
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_restore(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    file_name: String,
+) -> Result<BackupRestoreDto, ApiError> {
+    let (dir, _) = backup_settings(&app, &state.pool).await?;
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    let pool = restore_pool(
+        &state,
+        crate::backups::verify(&state.pool, &dir, &file_name),
+    )
+    .await?;
+    let safety_backup = crate::backups::restore(&pool, &db_path, &dir, &file_name, Utc::now())
         .await
-        .map_err(|_| ApiError::Db)?
-        .is_some();
-    if !exists {
-        return Err(ApiError::NotFound);
-    }
+        .map_err(map_backup_error)?;
+    Ok(BackupRestoreDto { safety_backup })
+}
 
-    validate_account_snapshots_create(pool, account_id, &input).await?;
-    let snapshot_dates = input
-        .snapshots
-        .iter()
-        .map(|snapshot| snapshot.date)
-        .collect::<Vec<_>>();
-    let existing_by_date = db::snapshots_for_account_dates(pool, account_id, &snapshot_dates)
+// A read-only database cannot write the copy a restore swaps in, so once `verify` has
+// accepted the backup it is reopened for writing. That closes `state.pool`, so the app
+// must relaunch even if the restore then fails.
+async fn restore_pool<T>(
+    state: &AppState,
+    verify: impl Future<Output = Result<T, BackupError>>,
+) -> Result<SqlitePool, ApiError> {
+    if !state.is_read_only() {
+        return Ok(state.pool.clone());
+    }
+    verify.await.map_err(map_backup_error)?;
+    db::reopen_pool(&state.pool, false)
         .await
-        .map_err(|_| ApiError::Db)?
-        .into_iter()
-        .map(|row| (row.balance_date, row.id))
-        .collect::<HashMap<_, _>>();
+        .map_err(|_| ApiError::Db)
+}
 
-    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
-    for snapshot in input.snapshots {
-        if let Some(existing_id) = existing_by_date.get(&snapshot.date) {
-            db::account_snapshot_update_tx(
-                &mut tx,
-                account_id,
-                *existing_id,
-                snapshot.date,
-                snapshot.balance_minor,
-            )
-            .await
-            .map_err(map_account_snapshot_write_error)?;
-            continue;
-        }
+#[tauri::command]
+#[specta::specta]
+pub async fn backups_folder_open(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let (dir, _) = backup_settings(&app, &state.pool).await?;
+    std::fs::create_dir_all(&dir).map_err(|_| ApiError::Db)?;
+    tauri_plugin_opener::open_path(&dir, None::<&str>).map_err(|_| ApiError::Db)
+}
 
-        db::account_snapshot_create_tx(&mut tx, account_id, snapshot.date, snapshot.balance_minor)
-            .await
-            .map_err(map_account_snapshot_write_error)?;
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_archive_create(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<BackupArchiveDto, ApiError> {
+    let issues = new_passphrase_issues("passphrase", &passphrase);
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
     }
 
-    tx.commit().await.map_err(|_| ApiError::Db)?;
-    Ok(())
+    let (dir, _) = backup_settings(&app, &state.pool).await?;
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    crate::backups::archive::create(
+        &state.pool,
+        &db_path,
+        &dir,
+        &passphrase,
+        &app.package_info().version.to_string(),
+        Utc::now(),
+    )
+    .await
+    .map_err(map_backup_error)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshot_update(
+pub async fn backup_archive_inspect(
+    app: AppHandle,
     state: State<'_, AppState>,
-    account_id: i64,
-    snapshot_id: i64,
-    input: AccountSnapshotUpdateInput,
-) -> Result<(), ApiError> {
-    let pool = &state.pool;
-
-    let exists = db::account_get_full(pool, account_id)
+    source: BackupArchiveSourceInput,
+    passphrase: String,
+) -> Result<BackupArchiveInspectionDto, ApiError> {
+    check_backup_archive_source(&source)?;
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    crate::backups::archive::inspect(&state.pool, &db_path, &source.content, &passphrase)
         .await
-        .map_err(|_| ApiError::Db)?
-        .is_some();
-    if !exists {
-        return Err(ApiError::NotFound);
+        .map_err(map_backup_error)
+}
+
+// Like `backup_restore`, the database connection is closed on success, so the frontend
+// must relaunch the app straight away.
+#[tauri::command]
+#[specta::specta]
+pub async fn backup_archive_restore(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    source: BackupArchiveSourceInput,
+    passphrase: String,
+) -> Result<BackupRestoreDto, ApiError> {
+    check_backup_archive_source(&source)?;
+    let (dir, _) = backup_settings(&app, &state.pool).await?;
+    let db_path = db::database_path(&app).map_err(|_| ApiError::Db)?;
+    let pool = restore_pool(
+        &state,
+        crate::backups::archive::inspect(&state.pool, &db_path, &source.content, &passphrase),
+    )
+    .await?;
+    let safety_backup = crate::backups::archive::restore(
+        &pool,
+        &db_path,
+        &dir,
+        &source.content,
+        &passphrase,
+        Utc::now(),
+    )
+    .await
+    .map_err(map_backup_error)?;
+    Ok(BackupRestoreDto { safety_backup })
+}
+
+fn check_backup_archive_source(source: &BackupArchiveSourceInput) -> Result<(), ApiError> {
+    if source.has_archive_extension() {
+        return Ok(());
     }
+    Err(ApiError::Validation(vec![validation_issue(
+        "file_name",
+        "Choose a .worthbackup archive",
+    )]))
+}
 
-    let current = db::account_snapshot_get(pool, account_id, snapshot_id)
-        .await
+// The backup folder and retention count currently configured in app settings.
+async fn backup_settings(
+    app: &AppHandle,
+    pool: &SqlitePool,
+) -> Result<(std::path::PathBuf, usize), ApiError> {
+    let settings = db::app_settings_get(pool).await.map_err(|_| ApiError::Db)?;
+    let dir = crate::backups::backups_dir(app, settings.backup_folder.as_deref())
         .map_err(|_| ApiError::Db)?;
-    if current.is_none() {
-        return Err(ApiError::NotFound);
+    let retention_count =
+        usize::try_from(settings.backup_retention_count).map_err(|_| ApiError::Db)?;
+    Ok((dir, retention_count))
+}
+
+fn map_backup_error(error: BackupError) -> ApiError {
+    match error {
+        BackupError::NotFound => ApiError::NotFound,
+        BackupError::Unreadable => ApiError::Validation(vec![validation_issue(
+            "file_name",
+            "This backup is damaged or is not a Worth backup",
+        )]),
+        BackupError::Incompatible => ApiError::Validation(vec![validation_issue(
+            "file_name",
+            "This backup needs a newer version of Worth",
+        )]),
+        BackupError::WrongPassphrase => ApiError::Validation(vec![validation_issue(
+            "passphrase",
+            "Incorrect passphrase, or the archive is damaged",
+        )]),
+        BackupError::Io(_) | BackupError::Db(_) => ApiError::Db,
     }
+}
 
-    validate_account_snapshot_update(pool, account_id, snapshot_id, &input).await?;
-    let conflicting = db::snapshots_for_account_dates(pool, account_id, &[input.date])
+#[tauri::command]
+#[specta::specta]
+pub async fn app_updates_state_get(
+    updates: State<'_, AppUpdateManager>,
+) -> Result<AppUpdateStateDto, ApiError> {
+    Ok(updates.state())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn app_updates_check(
+    app: AppHandle,
+    updates: State<'_, AppUpdateManager>,
+) -> Result<AppUpdateStateDto, ApiError> {
+    Ok(updates.check_for_updates(app).await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn app_updates_install_pending_and_restart(
+    app: AppHandle,
+    updates: State<'_, AppUpdateManager>,
+) -> Result<AppUpdateStateDto, ApiError> {
+    Ok(updates.install_pending_and_restart(app).await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn accounts_list(state: State<'_, AppState>) -> Result<Vec<AccountDto>, ApiError> {
+    let pool = &state.pool;
+
+    let accounts = db::accounts_list_full(pool)
         .await
-        .map_err(|_| ApiError::Db)?
-        .into_iter()
-        .find(|snapshot| snapshot.id != snapshot_id);
+        .map_err(|_| ApiError::Db)?;
+    build_account_dtos(pool, accounts).await
+}
 
-    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
-    if let Some(conflicting) = conflicting {
-        db::account_snapshot_delete_many_tx(&mut tx, account_id, &[conflicting.id])
-            .await
-            .map_err(|_| ApiError::Db)?;
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<InstitutionSummaryDto>, ApiError> {
+    let pool = &state.pool;
+
+    let summary_rows = db::institutions_list_summary(pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let type_rows = db::institutions_account_types(pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    let mut types_by_institution: HashMap<i64, Vec<AccountTypeDto>> =
+        HashMap::with_capacity(summary_rows.len());
+    for row in type_rows {
+        types_by_institution
+            .entry(row.institution_id)
+            .or_default()
+            .push(AccountTypeDto {
+                id: row.type_id,
+                name: row.type_name,
+                color: row.type_color,
+            });
     }
 
-    let updated = db::account_snapshot_update_tx(
-        &mut tx,
-        account_id,
-        snapshot_id,
-        input.date,
-        input.balance_minor,
-    )
-    .await
-    .map_err(map_account_snapshot_write_error)?;
-    if !updated {
-        return Err(ApiError::NotFound);
+    let mut out = Vec::with_capacity(summary_rows.len());
+    for row in summary_rows {
+        out.push(InstitutionSummaryDto {
+            id: row.id,
+            name: row.name,
+            account_count: u32::try_from(row.account_count)
+                .expect("account count should fit in u32"),
+            empty_account_count: u32::try_from(row.empty_account_count)
+                .expect("empty account count should fit in u32"),
+            account_types: types_by_institution.remove(&row.id).unwrap_or_default(),
+            total_balance_minor: row.total_balance_minor,
+        });
     }
 
-    tx.commit().await.map_err(|_| ApiError::Db)?;
-    Ok(())
+    Ok(out)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshots_delete(
+pub async fn search(
     state: State<'_, AppState>,
-    account_id: i64,
-    input: AccountSnapshotsDeleteInput,
-) -> Result<(), ApiError> {
+    query: String,
+) -> Result<Vec<SearchResultDto>, ApiError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
     let pool = &state.pool;
+    let rows = db::search_global(pool, query)
+        .await
+        .map_err(|_| ApiError::Db)?;
 
-    let exists = db::account_get_full(pool, account_id)
+    rows.into_iter()
+        .map(|row| match row {
+            db::GlobalSearchRow::Account {
+                id,
+                name,
+                type_id,
+                type_name,
+                type_color,
+                institution_name,
+            } => Ok(SearchResultDto::Account {
+                id,
+                name,
+                account_type: AccountTypeDto {
+                    id: type_id,
+                    name: type_name,
+                    color: type_color,
+                },
+                institution_name,
+            }),
+            db::GlobalSearchRow::Institution { id, name } => {
+                Ok(SearchResultDto::Institution { id, name })
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_get(
+    state: State<'_, AppState>,
+    institution_id: i64,
+) -> Result<InstitutionDetailDto, ApiError> {
+    let pool = &state.pool;
+    institution_detail_by_id(pool, institution_id).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_create(
+    state: State<'_, AppState>,
+    input: InstitutionUpsertInput,
+) -> Result<CreatedIdDto, ApiError> {
+    let pool = &state.pool;
+    let validated = validate_institution_upsert(pool, &input, None).await?;
+
+    let created = db::institution_create(pool, &validated.name)
         .await
-        .map_err(|_| ApiError::Db)?
-        .is_some();
-    if !exists {
-        return Err(ApiError::NotFound);
-    }
+        .map_err(map_institution_write_error)?;
 
-    let snapshot_ids = validate_account_snapshots_delete(&input)?;
+    Ok(CreatedIdDto { id: created.id })
+}
 
-    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
-    let deleted = db::account_snapshot_delete_many_tx(&mut tx, account_id, &snapshot_ids)
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_update(
+    state: State<'_, AppState>,
+    institution_id: i64,
+    input: InstitutionUpsertInput,
+) -> Result<(), ApiError> {
+    let pool = &state.pool;
+    let validated = validate_institution_upsert(pool, &input, Some(institution_id)).await?;
+
+    let updated = db::institution_update(pool, institution_id, &validated.name)
         .await
-        .map_err(|_| ApiError::Db)?;
-    if deleted != snapshot_ids.len() as u64 {
+        .map_err(map_institution_write_error)?;
+
+    if updated.is_none() {
         return Err(ApiError::NotFound);
     }
 
-    tx.commit().await.map_err(|_| ApiError::Db)?;
     Ok(())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshot_import_inspect(
-    input: SnapshotImportSourceInput,
-) -> Result<SnapshotImportInspectionDto, ApiError> {
-    crate::imports::snapshots::inspect_source(&input).map_err(map_snapshot_import_validation)
+pub async fn accounts_get(
+    state: State<'_, AppState>,
+    account_id: i64,
+) -> Result<AccountDto, ApiError> {
+    let pool = &state.pool;
+    account_dto_by_id(pool, account_id).await
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshot_import_preview(
+pub async fn accounts_delete_preview(
     state: State<'_, AppState>,
     account_id: i64,
-    input: SnapshotImportSourceInput,
-    options: SnapshotImportOptionsInput,
-) -> Result<SnapshotImportPreviewDto, ApiError> {
-    account_snapshot_import_preview_with_today(
-        &state.pool,
-        account_id,
-        input,
-        options,
-        Local::now().date_naive(),
-    )
-    .await
-}
-
-async fn account_snapshot_import_preview_with_today(
-    pool: &SqlitePool,
-    account_id: i64,
-    input: SnapshotImportSourceInput,
-    options: SnapshotImportOptionsInput,
-    today: NaiveDate,
-) -> Result<SnapshotImportPreviewDto, ApiError> {
-    let Some(account) = db::account_get_full(pool, account_id)
+) -> Result<AccountDeletePreviewDto, ApiError> {
+    let pool = &state.pool;
+    let Some(row) = db::account_delete_preview(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?
     else {
         return Err(ApiError::NotFound);
     };
 
-    let existing_snapshots = db::snapshots_for_account(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?;
-    let plan = crate::imports::snapshots::plan_import(
-        &input,
-        &options,
-        &existing_snapshots,
-        SnapshotImportPlanningContext {
-            account_opened_date: account.opened_date,
-            account_closed_date: account.closed_date,
-            today,
-        },
-    )
-    .map_err(map_snapshot_import_validation)?;
-
-    Ok(plan.preview)
+    Ok(AccountDeletePreviewDto {
+        id: row.id,
+        name: row.name,
+        institution_name: row.institution_name,
+        snapshot_count: u32::try_from(row.snapshot_count)
+            .expect("snapshot count should fit in u32"),
+    })
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_snapshot_import_commit(
-    state: State<'_, AppState>,
-    account_id: i64,
-    input: SnapshotImportSourceInput,
-    options: SnapshotImportOptionsInput,
-) -> Result<SnapshotImportCommitDto, ApiError> {
-    account_snapshot_import_commit_with_today(
-        &state.pool,
-        account_id,
-        input,
-        options,
-        Local::now().date_naive(),
-    )
-    .await
-}
-
-async fn account_snapshot_import_commit_with_today(
-    pool: &SqlitePool,
-    account_id: i64,
-    input: SnapshotImportSourceInput,
-    options: SnapshotImportOptionsInput,
-    today: NaiveDate,
-) -> Result<SnapshotImportCommitDto, ApiError> {
-    let Some(account) = db::account_get_full(pool, account_id)
-        .await
-        .map_err(|_| ApiError::Db)?
-    else {
-        return Err(ApiError::NotFound);
-    };
-
-    let existing_snapshots = db::snapshots_for_account(pool, account_id)
+pub async fn accounts_delete(state: State<'_, AppState>, account_id: i64) -> Result<(), ApiError> {
+    let pool = &state.pool;
+    let deleted = db::account_delete(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?;
-    let plan = crate::imports::snapshots::plan_import(
-        &input,
-        &options,
-        &existing_snapshots,
-        SnapshotImportPlanningContext {
-            account_opened_date: account.opened_date,
-            account_closed_date: account.closed_date,
-            today,
-        },
-    )
-    .map_err(map_snapshot_import_validation)?;
 
-    if plan.preview.summary.invalid_count > 0 {
-        return Err(ApiError::Validation(vec![validation_issue(
-            "import",
-            "Fix invalid rows before importing snapshots",
-        )]));
+    if !deleted {
+        return Err(ApiError::NotFound);
     }
 
-    if plan.preview.summary.overwrite_count > 0 && !options.overwrite_existing_confirmed {
-        return Err(ApiError::Validation(vec![validation_issue(
-            "import",
-            "Confirm overwrite to continue",
-        )]));
-    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn accounts_create(
+    state: State<'_, AppState>,
+    input: AccountUpsertInput,
+) -> Result<CreatedIdDto, ApiError> {
+    let pool = &state.pool;
+    let validated = validate_account_upsert(pool, &input, None).await?;
 
     let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
-    let mut created_count = 0;
-    let mut overwritten_count = 0;
+    let institution_id = match &validated.institution {
+        ValidatedInstitutionRef::Existing { id } => *id,
+        ValidatedInstitutionRef::New { name } => db::institution_create_tx(&mut tx, name)
+            .await
+            .map_err(map_institution_write_error)?,
+    };
 
-    for write in plan.writes {
-        match write.action {
-            SnapshotImportPreviewAction::Create => {
-                db::account_snapshot_create_tx(
-                    &mut tx,
-                    account_id,
-                    write.date,
-                    write.balance_minor,
-                )
-                .await
-                .map_err(map_account_snapshot_write_error)?;
-                created_count += 1;
-            }
-            SnapshotImportPreviewAction::Overwrite => {
-                let Some(existing_snapshot_id) = write.existing_snapshot_id else {
-                    return Err(ApiError::Db);
-                };
-                let updated = db::account_snapshot_update_tx(
-                    &mut tx,
-                    account_id,
-                    existing_snapshot_id,
-                    write.date,
-                    write.balance_minor,
-                )
-                .await
-                .map_err(map_account_snapshot_write_error)?;
-                if !updated {
-                    return Err(ApiError::NotFound);
-                }
-                overwritten_count += 1;
-            }
-            SnapshotImportPreviewAction::SkipExisting
-            | SnapshotImportPreviewAction::SkipUnchanged
-            | SnapshotImportPreviewAction::SkipDuplicate
-            | SnapshotImportPreviewAction::SkipBlankAmount
-            | SnapshotImportPreviewAction::Invalid => {}
-        }
-    }
+    let mutation = account_mutation_input(&validated, institution_id);
+    let account_id = db::account_create_tx(&mut tx, &mutation)
+        .await
+        .map_err(map_account_write_error)?;
+    db::account_tags_set_tx(&mut tx, account_id, &validated.tags)
+        .await
+        .map_err(|_| ApiError::Db)?;
 
     tx.commit().await.map_err(|_| ApiError::Db)?;
 
-    Ok(SnapshotImportCommitDto {
-        created_count,
-        overwritten_count,
-        skipped_count: plan.preview.summary.skip_count,
-    })
+    Ok(CreatedIdDto { id: account_id })
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_delete_preview(
+pub async fn accounts_update(
     state: State<'_, AppState>,
-    institution_id: i64,
-) -> Result<InstitutionDeletePreviewDto, ApiError> {
+    account_id: i64,
+    input: AccountUpsertInput,
+) -> Result<(), ApiError> {
     let pool = &state.pool;
-    let Some(institution) = db::institution_get(pool, institution_id)
+
+    // Keep behavior explicit before we potentially create a new institution.
+    let exists = db::account_get_full(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?
-    else {
+        .is_some();
+    if !exists {
         return Err(ApiError::NotFound);
+    }
+
+    let validated = validate_account_upsert(pool, &input, Some(account_id)).await?;
+
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let institution_id = match &validated.institution {
+        ValidatedInstitutionRef::Existing { id } => *id,
+        ValidatedInstitutionRef::New { name } => db::institution_create_tx(&mut tx, name)
+            .await
+            .map_err(map_institution_write_error)?,
     };
 
-    let accounts = db::institution_accounts_delete_preview(pool, institution_id)
+    let mutation = account_mutation_input(&validated, institution_id);
+    let updated = db::account_update_tx(&mut tx, account_id, &mutation)
         .await
-        .map_err(|_| ApiError::Db)?
-        .into_iter()
-        .map(|row| InstitutionDeletePreviewAccountDto {
-            id: row.id,
-            name: row.name,
-            snapshot_count: u32::try_from(row.snapshot_count)
-                .expect("snapshot count should fit in u32"),
-        })
-        .collect::<Vec<_>>();
+        .map_err(map_account_write_error)?;
+    if !updated {
+        return Err(ApiError::NotFound);
+    }
+    db::account_tags_set_tx(&mut tx, account_id, &validated.tags)
+        .await
+        .map_err(|_| ApiError::Db)?;
 
-    let total_snapshots = accounts.iter().map(|account| account.snapshot_count).sum();
+    tx.commit().await.map_err(|_| ApiError::Db)?;
 
-    Ok(InstitutionDeletePreviewDto {
-        institution: InstitutionDto {
-            id: institution.id,
-            name: institution.name,
-        },
-        accounts,
-        total_snapshots,
-    })
+    Ok(())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn institutions_delete(
+pub async fn account_snapshots_list(
     state: State<'_, AppState>,
-    institution_id: i64,
-) -> Result<(), ApiError> {
+    account_id: i64,
+) -> Result<Vec<AccountBalanceSnapshotDto>, ApiError> {
     let pool = &state.pool;
-    let deleted = db::institution_delete(pool, institution_id)
-        .await
-        .map_err(|_| ApiError::Db)?;
 
-    if !deleted {
+    // Ensure account exists for consistent NotFound behavior.
+    let exists = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
         return Err(ApiError::NotFound);
     }
 
-    Ok(())
+    let rows = db::snapshots_for_account(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| AccountBalanceSnapshotDto {
+            id: r.id,
+            date: r.balance_date,
+            balance_minor: r.balance_minor,
+            source: SnapshotSource::from_columns(
+                r.source_kind.as_deref(),
+                r.source_detail.as_deref(),
+            ),
+            note: r.note,
+            created_at: r.created_at,
+        })
+        .collect())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn account_balance_over_time(
+pub async fn account_history_list(
     state: State<'_, AppState>,
     account_id: i64,
-    period: BalanceOverTimePeriod,
-) -> Result<Vec<BalancePointDto>, ApiError> {
-    let today = Local::now().date_naive();
-    let pool = &state.pool;
+) -> Result<Vec<AuditLogEntryDto>, ApiError> {
+    account_history(&state.pool, account_id).await
+}
 
-    // Ensure account exists.
+async fn account_history(
+    pool: &SqlitePool,
+    account_id: i64,
+) -> Result<Vec<AuditLogEntryDto>, ApiError> {
     let exists = db::account_get_full(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?
@@ -1005,1010 +1584,3885 @@ pub async fn account_balance_over_time(
         return Err(ApiError::NotFound);
     }
 
-    let Some(earliest) = db::earliest_snapshot_date_for_account(pool, account_id)
+    let rows = db::audit_log_for_account(pool, account_id)
         .await
-        .map_err(|_| ApiError::Db)?
-    else {
-        return Ok(Vec::new());
-    };
+        .map_err(|_| ApiError::Db)?;
 
-    let computed_start = match period {
-        BalanceOverTimePeriod::P1M => today - Duration::days(30 - 1),
-        BalanceOverTimePeriod::P6M => today - Duration::days(183 - 1),
-        BalanceOverTimePeriod::P1Y => today - Duration::days(365 - 1),
-        BalanceOverTimePeriod::Max => earliest,
-    };
+    rows.into_iter().map(audit_log_entry_dto_from_row).collect()
+}
 
-    let start = std::cmp::max(computed_start, earliest);
-    if today < start {
-        return Ok(Vec::new());
-    }
+fn audit_log_entry_dto_from_row(row: db::rows::AuditLogRow) -> Result<AuditLogEntryDto, ApiError> {
+    let entity_kind: AuditEntityKind = row.entity_kind.parse().map_err(|_| ApiError::Db)?;
+    let action: AuditAction = row.action.parse().map_err(|_| ApiError::Db)?;
+    let before = audit_values(row.before_json.as_deref())?;
+    let after = audit_values(row.after_json.as_deref())?;
 
-    let points = (today - start).num_days() as usize + 1;
-    let account_ids = vec![account_id];
+    let changes = entity_kind
+        .fields()
+        .iter()
+        .filter_map(|field| {
+            let before = before.get(*field).and_then(audit_value_text);
+            let after = after.get(*field).and_then(audit_value_text);
+            if action == AuditAction::Update && before == after {
+                return None;
+            }
+            Some(AuditFieldChangeDto {
+                field: (*field).to_owned(),
+                before,
+                after,
+            })
+        })
+        .collect();
 
-    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, start, today)
-        .await
-        .map_err(|_| ApiError::Db)?;
-    let last_before = db::last_snapshots_before(pool, &account_ids, start)
-        .await
-        .map_err(|_| ApiError::Db)?;
+    Ok(AuditLogEntryDto {
+        id: row.id,
+        entity_kind,
+        entity_id: row.entity_id,
+        action,
+        changes,
+        changed_at: row.changed_at,
+    })
+}
 
-    let mut date_map: HashMap<NaiveDate, i64> = HashMap::new();
-    for s in snapshots {
-        date_map.insert(s.balance_date, s.balance_minor);
+fn audit_values(
+    json: Option<&str>,
+) -> Result<serde_json::Map<String, serde_json::Value>, ApiError> {
+    match json {
+        Some(json) => serde_json::from_str(json).map_err(|_| ApiError::Db),
+        None => Ok(serde_json::Map::new()),
     }
+}
 
-    let seed_before = last_before.first().map(|s| s.balance_minor);
-    let series = filled_values_for_period(&date_map, seed_before, start, points);
-
-    Ok(series
-        .into_iter()
-        .enumerate()
-        .map(|(i, v)| {
-            let date = start + Duration::days(i as i64);
-            BalancePointDto {
-                date,
-                balance_minor: v.unwrap_or(0),
-            }
-        })
-        .collect())
+fn audit_value_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn dashboard_get(state: State<'_, AppState>) -> Result<DashboardDto, ApiError> {
+pub async fn account_snapshots_create(
+    state: State<'_, AppState>,
+    account_id: i64,
+    input: AccountSnapshotsCreateInput,
+) -> Result<(), ApiError> {
     let pool = &state.pool;
-    let today = Local::now().date_naive();
 
-    let accounts = db::accounts_list_full(pool)
+    let Some(account) = db::account_get_full(pool, account_id)
         .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+    let currency_code = account
+        .currency_code
+        .parse::<CurrencyCode>()
         .map_err(|_| ApiError::Db)?;
 
-    let mut total_balance_minor: i64 = 0;
-    let mut active_accounts: u32 = 0;
-    let mut active_institution_ids: HashSet<i64> = HashSet::new();
-    let mut allocation: BTreeMap<AccountTypeName, i64> = BTreeMap::new();
+    validate_account_snapshots_create(pool, account_id, currency_code, &input).await?;
+    let snapshot_dates = input
+        .snapshots
+        .iter()
+        .map(|snapshot| snapshot.date)
+        .collect::<Vec<_>>();
+    let existing_by_date = db::snapshots_for_account_dates(pool, account_id, &snapshot_dates)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .map(|row| (row.balance_date, row.id))
+        .collect::<HashMap<_, _>>();
 
-    for a in &accounts {
-        let account_type_name = a.type_name.parse().map_err(|_| ApiError::Db)?;
-        let latest_minor = a.latest_balance_minor.unwrap_or(0);
-        total_balance_minor += latest_minor;
-        if latest_minor != 0 {
-            active_accounts += 1;
-            active_institution_ids.insert(a.institution_id);
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    for snapshot in input.snapshots {
+        let source = snapshot.source.unwrap_or(SnapshotSource::Manual);
+        let mutation = db::AccountSnapshotMutationInput {
+            balance_date: snapshot.date,
+            balance_minor: snapshot.balance_minor,
+            note: normalize_snapshot_note(snapshot.note.as_deref()),
+            source_kind: Some(source.kind_str().to_owned()),
+            source_detail: source.detail().map(|detail| detail.trim().to_owned()),
+        };
+        if let Some(existing_id) = existing_by_date.get(&snapshot.date) {
+            db::account_snapshot_update_tx(&mut tx, account_id, *existing_id, &mutation)
+                .await
+                .map_err(map_account_snapshot_write_error)?;
+            continue;
         }
-        *allocation.entry(account_type_name).or_insert(0) += latest_minor;
+
+        db::account_snapshot_create_tx(&mut tx, account_id, &mutation)
+            .await
+            .map_err(map_account_snapshot_write_error)?;
     }
 
-    // Pie chart allocations should be non-negative; exclude net-negative/zero groups (e.g. credit cards).
-    let allocation_by_type = allocation
-        .into_iter()
-        .filter(|(_kind, balance_minor)| *balance_minor > 0)
-        .map(|(account_type, balance_minor)| DashboardAllocationDto {
-            account_type,
-            balance_minor,
-        })
-        .collect::<Vec<_>>();
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(())
+}
 
-    // Compute monthly metrics using a minimal 31-point (30-day) series.
-    let start_30d = today - Duration::days(31 - 1);
-    let balance_30d = total_balance_over_time(pool, &accounts, start_30d, today).await?;
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_update(
+    state: State<'_, AppState>,
+    account_id: i64,
+    snapshot_id: i64,
+    input: AccountSnapshotUpdateInput,
+) -> Result<(), ApiError> {
+    let pool = &state.pool;
 
-    let last_minor = balance_30d
-        .last()
-        .map_or(total_balance_minor, |p| p.balance_minor);
+    let Some(account) = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+    let currency_code = account
+        .currency_code
+        .parse::<CurrencyCode>()
+        .map_err(|_| ApiError::Db)?;
 
-    let month_ago_minor = balance_30d
-        .get(balance_30d.len().saturating_sub(31))
-        .map_or(last_minor, |p| p.balance_minor);
+    let Some(current) = db::account_snapshot_get(pool, account_id, snapshot_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
 
-    let monthly_yield_minor = last_minor - month_ago_minor;
-    let change_vs_last_month_pct = if month_ago_minor != 0 {
-        (monthly_yield_minor as f64) / (month_ago_minor as f64) * 100.0
-    } else {
-        0.0
+    validate_account_snapshot_update(pool, account_id, snapshot_id, currency_code, &input).await?;
+    let conflicting = db::snapshots_for_account_dates(pool, account_id, &[input.date])
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .find(|snapshot| snapshot.id != snapshot_id);
+
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    if let Some(conflicting) = conflicting {
+        db::account_snapshot_delete_many_tx(&mut tx, account_id, &[conflicting.id])
+            .await
+            .map_err(|_| ApiError::Db)?;
+    }
+
+    let mutation = db::AccountSnapshotMutationInput {
+        balance_date: input.date,
+        balance_minor: input.balance_minor,
+        note: normalize_snapshot_note(input.note.as_deref()),
+        source_kind: current.source_kind,
+        source_detail: current.source_detail,
     };
+    let updated = db::account_snapshot_update_tx(&mut tx, account_id, snapshot_id, &mutation)
+        .await
+        .map_err(map_account_snapshot_write_error)?;
+    if !updated {
+        return Err(ApiError::NotFound);
+    }
 
-    Ok(DashboardDto {
-        total_balance_minor,
-        change_vs_last_month_pct,
-        monthly_yield_minor,
-        total_accounts: u32::try_from(accounts.len()).expect("account count should fit in u32"),
-        active_accounts,
-        active_institutions: u32::try_from(active_institution_ids.len())
-            .expect("active institution count should fit in u32"),
-        allocation_by_type,
-    })
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn dashboard_balance_over_time(
+pub async fn account_snapshots_delete(
     state: State<'_, AppState>,
-    period: BalanceOverTimePeriod,
-) -> Result<Vec<DashboardBalancePointDto>, ApiError> {
-    let today = Local::now().date_naive();
+    account_id: i64,
+    input: AccountSnapshotsDeleteInput,
+) -> Result<(), ApiError> {
     let pool = &state.pool;
-    let accounts = db::accounts_list_full(pool)
+
+    let exists = db::account_get_full(pool, account_id)
         .await
-        .map_err(|_| ApiError::Db)?;
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound);
+    }
 
-    let start = match period {
-        BalanceOverTimePeriod::P1M => today - Duration::days(30 - 1),
-        BalanceOverTimePeriod::P6M => today - Duration::days(183 - 1),
-        BalanceOverTimePeriod::P1Y => today - Duration::days(365 - 1),
-        BalanceOverTimePeriod::Max => match db::earliest_snapshot_date(pool)
-            .await
-            .map_err(|_| ApiError::Db)?
-        {
-            Some(s) => s,
-            None => today,
-        },
-    };
+    let snapshot_ids = validate_account_snapshots_delete(&input)?;
 
-    total_balance_over_time(pool, &accounts, start, today).await
-}
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let deleted = db::account_snapshot_delete_many_tx(&mut tx, account_id, &snapshot_ids)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    if deleted != snapshot_ids.len() as u64 {
+        return Err(ApiError::NotFound);
+    }
 
-#[derive(Debug, Clone)]
-struct ValidatedInstitutionUpsert {
-    name: String,
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
-enum ValidatedInstitutionRef {
-    Existing { id: i64 },
-    New { name: String },
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_inspect(
+    state: State<'_, AppState>,
+    account_id: Option<i64>,
+    input: SnapshotImportSourceInput,
+) -> Result<SnapshotImportInspectionDto, ApiError> {
+    account_snapshot_import_inspect_in(&state.pool, account_id, input).await
 }
 
-#[derive(Debug, Clone)]
-struct ValidatedAccountUpsert {
-    institution: ValidatedInstitutionRef,
-    name: String,
-    type_id: i64,
-    currency_code: String,
-    account_classification: AccountClassification,
-    opened_date: Option<NaiveDate>,
-    closed_date: Option<NaiveDate>,
+/// Inspects the file and, for an account, attaches the saved profile whose columns match.
+async fn account_snapshot_import_inspect_in(
+    pool: &SqlitePool,
+    account_id: Option<i64>,
+    input: SnapshotImportSourceInput,
+) -> Result<SnapshotImportInspectionDto, ApiError> {
+    let locale = display_locale(pool).await?;
+    let mut inspection = crate::imports::snapshots::inspect_source(&input, locale)
+        .map_err(map_snapshot_import_validation)?;
+
+    let (columns, profile) = match &mut inspection {
+        SnapshotImportInspectionDto::Csv(csv) => (&csv.columns, &mut csv.profile),
+        SnapshotImportInspectionDto::Spreadsheet(spreadsheet) => {
+            (&spreadsheet.columns, &mut spreadsheet.profile)
+        }
+        SnapshotImportInspectionDto::Ofx(_) => return Ok(inspection),
+    };
+    if let Some(account_id) = account_id {
+        // A profile saved by an older version may no longer deserialize; it is skipped
+        // rather than blocking the import.
+        let profiles = db::snapshot_import_profiles_for_account(pool, account_id)
+            .await
+            .map_err(|_| ApiError::Db)?
+            .into_iter()
+            .filter_map(|row| snapshot_import_profile_dto_from_row(row).ok())
+            .collect::<Vec<_>>();
+        let columns = columns
+            .iter()
+            .map(|column| column.name.clone())
+            .collect::<Vec<_>>();
+        *profile = crate::imports::snapshots::matching_profile(profiles, &columns).map(Box::new);
+    }
+
+    Ok(inspection)
 }
 
-fn app_settings_dto_from_row(row: db::rows::AppSettingsRow) -> Result<AppSettingsDto, ApiError> {
-    Ok(AppSettingsDto {
-        analytics_enabled: row.analytics_enabled,
-        default_display_currency_code: row
-            .default_display_currency_code
-            .parse()
-            .map_err(|_| ApiError::Db)?,
-        display_locale: row.display_locale.parse().map_err(|_| ApiError::Db)?,
-        theme: row.theme.parse().map_err(|_| ApiError::Db)?,
-    })
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_preview(
+    state: State<'_, AppState>,
+    account_id: i64,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+) -> Result<SnapshotImportPreviewDto, ApiError> {
+    account_snapshot_import_preview_with_today(
+        &state.pool,
+        account_id,
+        input,
+        options,
+        Local::now().date_naive(),
+    )
+    .await
 }
 
-async fn institution_detail_by_id(
+async fn account_snapshot_import_preview_with_today(
     pool: &SqlitePool,
-    institution_id: i64,
-) -> Result<InstitutionDetailDto, ApiError> {
-    let Some(institution) = db::institution_get(pool, institution_id)
+    account_id: i64,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<SnapshotImportPreviewDto, ApiError> {
+    let Some(account) = db::account_get_full(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?
     else {
         return Err(ApiError::NotFound);
     };
 
-    let accounts = db::accounts_list_full_for_institution(pool, institution_id)
+    let existing_snapshots = db::snapshots_for_account(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?;
-    let account_dtos = build_account_dtos(pool, accounts).await?;
+    let plan = crate::imports::snapshots::plan_import(
+        &input,
+        &options,
+        &existing_snapshots,
+        SnapshotImportPlanningContext {
+            account_opened_date: account.opened_date,
+            account_closed_date: account.closed_date,
+            currency_code: account.currency_code.parse().map_err(|_| ApiError::Db)?,
+            locale: display_locale(pool).await?,
+            today,
+        },
+    )
+    .map_err(map_snapshot_import_validation)?;
 
-    Ok(InstitutionDetailDto {
-        id: institution.id,
-        name: institution.name,
-        accounts: account_dtos,
-    })
+    Ok(plan.preview)
 }
 
-async fn account_dto_by_id(pool: &SqlitePool, account_id: i64) -> Result<AccountDto, ApiError> {
-    let Some(account_row) = db::account_get_full(pool, account_id)
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_commit(
+    state: State<'_, AppState>,
+    account_id: i64,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+) -> Result<SnapshotImportCommitDto, ApiError> {
+    account_snapshot_import_commit_with_today(
+        &state.pool,
+        account_id,
+        input,
+        options,
+        Local::now().date_naive(),
+    )
+    .await
+}
+
+async fn account_snapshot_import_commit_with_today(
+    pool: &SqlitePool,
+    account_id: i64,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<SnapshotImportCommitDto, ApiError> {
+    let Some(account) = db::account_get_full(pool, account_id)
         .await
         .map_err(|_| ApiError::Db)?
     else {
         return Err(ApiError::NotFound);
     };
 
-    let mut dtos = build_account_dtos(pool, vec![account_row]).await?;
-    dtos.pop().ok_or(ApiError::NotFound)
-}
-
-async fn validate_institution_upsert(
-    pool: &SqlitePool,
-    input: &InstitutionUpsertInput,
-    exclude_institution_id: Option<i64>,
-) -> Result<ValidatedInstitutionUpsert, ApiError> {
-    let normalized = normalize_institution_upsert(input);
-    let mut issues = validation_issues_from_garde_report(normalized.validate().err());
+    let existing_snapshots = db::snapshots_for_account(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let plan = crate::imports::snapshots::plan_import(
+        &input,
+        &options,
+        &existing_snapshots,
+        SnapshotImportPlanningContext {
+            account_opened_date: account.opened_date,
+            account_closed_date: account.closed_date,
+            currency_code: account.currency_code.parse().map_err(|_| ApiError::Db)?,
+            locale: display_locale(pool).await?,
+            today,
+        },
+    )
+    .map_err(map_snapshot_import_validation)?;
 
-    if issues.is_empty() {
-        let exists = db::institution_name_exists(pool, &normalized.name, exclude_institution_id)
-            .await
-            .map_err(|_| ApiError::Db)?;
-        if exists {
-            issues.push(validation_issue(
-                "name",
-                "An institution with this name already exists",
-            ));
-        }
+    if plan.preview.summary.invalid_count > 0 {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Fix invalid rows before importing snapshots",
+        )]));
     }
 
-    if !issues.is_empty() {
-        return Err(ApiError::Validation(issues));
+    if plan.preview.summary.overwrite_count > 0 && !options.overwrite_existing_confirmed {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Confirm overwrite to continue",
+        )]));
     }
 
-    Ok(ValidatedInstitutionUpsert {
-        name: normalized.name,
-    })
+    let options_json = serde_json::to_string(&options).map_err(|_| ApiError::Db)?;
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let result = snapshot_import_plan_apply_tx(
+        &mut tx,
+        account_id,
+        &input.snapshot_source(),
+        options_json,
+        existing_snapshots,
+        plan,
+    )
+    .await?;
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+
+    Ok(result)
 }
 
-async fn validate_account_upsert(
+#[tauri::command]
+#[specta::specta]
+pub async fn account_create_import_preview(
+    state: State<'_, AppState>,
+    account: AccountUpsertInput,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+) -> Result<SnapshotImportPreviewDto, ApiError> {
+    let (_, plan) = account_create_import_plan(
+        &state.pool,
+        &account,
+        &input,
+        &options,
+        Local::now().date_naive(),
+    )
+    .await?;
+
+    Ok(plan.preview)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn account_create_import_commit(
+    state: State<'_, AppState>,
+    account: AccountUpsertInput,
+    input: SnapshotImportSourceInput,
+    options: SnapshotImportOptionsInput,
+) -> Result<AccountCreateImportCommitDto, ApiError> {
+    account_create_import_commit_with_today(
+        &state.pool,
+        &account,
+        &input,
+        &options,
+        Local::now().date_naive(),
+    )
+    .await
+}
+
+async fn account_create_import_commit_with_today(
     pool: &SqlitePool,
-    input: &AccountUpsertInput,
-    exclude_account_id: Option<i64>,
-) -> Result<ValidatedAccountUpsert, ApiError> {
-    let normalized = normalize_account_upsert(input);
-    let mut issues = validation_issues_from_garde_report(normalized.validate().err());
+    account: &AccountUpsertInput,
+    input: &SnapshotImportSourceInput,
+    options: &SnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<AccountCreateImportCommitDto, ApiError> {
+    let (validated, plan) =
+        account_create_import_plan(pool, account, input, options, today).await?;
+
+    if plan.preview.summary.invalid_count > 0 {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Fix invalid rows before importing snapshots",
+        )]));
+    }
 
-    let account_type_db = normalized.account_type.as_str();
-    let type_id = db::account_type_id_by_name(pool, account_type_db)
+    let options_json = serde_json::to_string(options).map_err(|_| ApiError::Db)?;
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let institution_id = match &validated.institution {
+        ValidatedInstitutionRef::Existing { id } => *id,
+        ValidatedInstitutionRef::New { name } => db::institution_create_tx(&mut tx, name)
+            .await
+            .map_err(map_institution_write_error)?,
+    };
+    let mutation = account_mutation_input(&validated, institution_id);
+    let account_id = db::account_create_tx(&mut tx, &mutation)
+        .await
+        .map_err(map_account_write_error)?;
+    db::account_tags_set_tx(&mut tx, account_id, &validated.tags)
         .await
         .map_err(|_| ApiError::Db)?;
-    if type_id.is_none() {
-        issues.push(validation_issue("account_type", "Invalid account type"));
-    }
+    let result = snapshot_import_plan_apply_tx(
+        &mut tx,
+        account_id,
+        &input.snapshot_source(),
+        options_json,
+        Vec::new(),
+        plan,
+    )
+    .await?;
+    tx.commit().await.map_err(|_| ApiError::Db)?;
 
-    let institution = match &normalized.institution {
-        InstitutionRef::Existing { id } => {
-            let exists = db::institution_exists(pool, *id)
-                .await
-                .map_err(|_| ApiError::Db)?;
-            if !exists {
-                issues.push(validation_issue(
-                    "institution.id",
-                    "Institution does not exist",
-                ));
-            }
-            ValidatedInstitutionRef::Existing { id: *id }
+    Ok(AccountCreateImportCommitDto { account_id, result })
+}
+
+/// Validates the new account and plans the import against it as an account with no
+/// snapshots yet. Account issues are nested under `account` and reported together with
+/// any issues in the import options.
+async fn account_create_import_plan(
+    pool: &SqlitePool,
+    account: &AccountUpsertInput,
+    input: &SnapshotImportSourceInput,
+    options: &SnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<(ValidatedAccountUpsert, SnapshotImportPlan), ApiError> {
+    let mut issues = Vec::new();
+    let validated = match validate_account_upsert(pool, account, None).await {
+        Ok(validated) => Some(validated),
+        Err(ApiError::Validation(account_issues)) => {
+            issues.extend(account_issues.into_iter().map(|issue| ValidationIssue {
+                field: format!("account.{}", issue.field),
+                ..issue
+            }));
+            None
         }
-        InstitutionRef::New { input } => {
-            if issues.is_empty() {
-                let exists = db::institution_name_exists(pool, &input.name, None)
+        Err(error) => return Err(error),
+    };
+
+    let plan = crate::imports::snapshots::plan_import(
+        input,
+        options,
+        &[],
+        SnapshotImportPlanningContext {
+            account_opened_date: account.opened_date,
+            account_closed_date: account.closed_date,
+            currency_code: account.currency_code,
+            locale: display_locale(pool).await?,
+            today,
+        },
+    );
+    let plan = match plan {
+        Ok(plan) => Some(plan),
+        Err(import_issues) => {
+            issues.extend(import_issues.into_iter().map(|issue| {
+                validation_issue_with_telemetry_message(
+                    &issue.field,
+                    &issue.message,
+                    issue.telemetry_message.as_deref(),
+                )
+            }));
+            None
+        }
+    };
+
+    match (validated, plan) {
+        (Some(validated), Some(plan)) if issues.is_empty() => Ok((validated, plan)),
+        _ => Err(ApiError::Validation(issues)),
+    }
+}
+
+/// Writes a checked plan and records its import batch in the caller's transaction.
+async fn snapshot_import_plan_apply_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    account_id: i64,
+    source: &SnapshotSource,
+    options_json: String,
+    existing_snapshots: Vec<db::rows::AccountBalanceSnapshotRow>,
+    plan: SnapshotImportPlan,
+) -> Result<SnapshotImportCommitDto, ApiError> {
+    let existing_by_id = existing_snapshots
+        .into_iter()
+        .map(|snapshot| (snapshot.id, snapshot))
+        .collect::<HashMap<_, _>>();
+    let mut created_count = 0;
+    let mut overwritten_count = 0;
+    let mut batch_writes = Vec::new();
+
+    for write in plan.writes {
+        let mut mutation = db::AccountSnapshotMutationInput {
+            balance_date: write.date,
+            balance_minor: write.balance_minor,
+            note: None,
+            source_kind: Some(source.kind_str().to_owned()),
+            source_detail: source.detail().map(str::to_owned),
+        };
+        match write.action {
+            SnapshotImportPreviewAction::Create => {
+                let snapshot_id = db::account_snapshot_create_tx(tx, account_id, &mutation)
                     .await
-                    .map_err(|_| ApiError::Db)?;
-                if exists {
-                    issues.push(validation_issue(
-                        "institution.input.name",
-                        "An institution with this name already exists",
-                    ));
-                }
+                    .map_err(map_account_snapshot_write_error)?;
+                created_count += 1;
+                batch_writes.push(db::SnapshotImportBatchWriteInput {
+                    snapshot_id,
+                    balance_date: write.date,
+                    balance_minor: write.balance_minor,
+                    previous: None,
+                });
             }
-
-            ValidatedInstitutionRef::New {
-                name: input.name.clone(),
+            SnapshotImportPreviewAction::Overwrite => {
+                let Some(existing) = write
+                    .existing_snapshot_id
+                    .and_then(|id| existing_by_id.get(&id))
+                else {
+                    return Err(ApiError::Db);
+                };
+                let existing_snapshot_id = existing.id;
+                // Imported rows carry no note, so overwrites keep the note already on the snapshot.
+                mutation.note = existing.note.clone();
+                let updated =
+                    db::account_snapshot_update_tx(tx, account_id, existing_snapshot_id, &mutation)
+                        .await
+                        .map_err(map_account_snapshot_write_error)?;
+                if !updated {
+                    return Err(ApiError::NotFound);
+                }
+                overwritten_count += 1;
+                batch_writes.push(db::SnapshotImportBatchWriteInput {
+                    snapshot_id: existing_snapshot_id,
+                    balance_date: write.date,
+                    balance_minor: write.balance_minor,
+                    previous: Some(db::AccountSnapshotMutationInput {
+                        balance_date: existing.balance_date,
+                        balance_minor: existing.balance_minor,
+                        note: existing.note.clone(),
+                        source_kind: existing.source_kind.clone(),
+                        source_detail: existing.source_detail.clone(),
+                    }),
+                });
             }
+            SnapshotImportPreviewAction::SkipExisting
+            | SnapshotImportPreviewAction::SkipUnchanged
+            | SnapshotImportPreviewAction::SkipDuplicate
+            | SnapshotImportPreviewAction::SkipBlankAmount
+            | SnapshotImportPreviewAction::Invalid => {}
         }
-    };
+    }
 
-    if issues.is_empty()
-        && let ValidatedInstitutionRef::Existing { id } = &institution
-    {
-        let duplicate =
-            db::account_name_exists_in_institution(pool, *id, &normalized.name, exclude_account_id)
+    let skipped_count = plan.preview.summary.skip_count;
+    let batch_id = if batch_writes.is_empty() {
+        None
+    } else {
+        let batch_id = db::snapshot_import_batch_create_tx(
+            tx,
+            &db::SnapshotImportBatchMutationInput {
+                account_id,
+                file_name: source.detail().unwrap_or_default().to_owned(),
+                options_json,
+                created_count,
+                overwritten_count,
+                skipped_count,
+            },
+        )
+        .await
+        .map_err(|_| ApiError::Db)?;
+        for batch_write in &batch_writes {
+            db::snapshot_import_batch_write_create_tx(tx, batch_id, batch_write)
                 .await
                 .map_err(|_| ApiError::Db)?;
-        if duplicate {
-            issues.push(validation_issue(
-                "name",
-                "An account with this name already exists for this institution",
-            ));
         }
-    }
+        Some(batch_id)
+    };
 
-    if let (Some(opened_date), Some(closed_date)) = (normalized.opened_date, normalized.closed_date)
-        && closed_date < opened_date
-    {
-        issues.push(validation_issue(
-            "closed_date",
-            "Closed date cannot be before opened date",
-        ));
-    }
+    Ok(SnapshotImportCommitDto {
+        created_count,
+        overwritten_count,
+        skipped_count,
+        batch_id,
+    })
+}
 
-    if !issues.is_empty() {
-        return Err(ApiError::Validation(issues));
-    }
+#[tauri::command]
+#[specta::specta]
+pub async fn snapshot_wide_import_preview(
+    state: State<'_, AppState>,
+    input: CsvSnapshotImportSourceInput,
+    options: WideSnapshotImportOptionsInput,
+) -> Result<WideSnapshotImportPreviewDto, ApiError> {
+    snapshot_wide_import_preview_with_today(
+        &state.pool,
+        &input,
+        &options,
+        Local::now().date_naive(),
+    )
+    .await
+}
 
-    Ok(ValidatedAccountUpsert {
-        institution,
-        name: normalized.name,
-        type_id: type_id.expect("validated above"),
-        currency_code: normalized.currency_code.as_str().to_owned(),
-        account_classification: normalized.account_classification,
-        opened_date: normalized.opened_date,
-        closed_date: normalized.closed_date,
+async fn snapshot_wide_import_preview_with_today(
+    pool: &SqlitePool,
+    input: &CsvSnapshotImportSourceInput,
+    options: &WideSnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<WideSnapshotImportPreviewDto, ApiError> {
+    let plans = snapshot_wide_import_plan(pool, input, options, today).await?;
+    let accounts = plans
+        .into_iter()
+        .map(|column_plan| WideSnapshotImportAccountPreviewDto {
+            column: column_plan.column,
+            account_id: match column_plan.target {
+                WideSnapshotImportColumnTarget::Existing { account_id } => Some(account_id),
+                WideSnapshotImportColumnTarget::New(_) => None,
+            },
+            account_name: column_plan.account_name,
+            currency_code: column_plan.currency_code,
+            preview: column_plan.plan.preview,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(WideSnapshotImportPreviewDto {
+        summary: crate::imports::snapshots::wide_preview_summary(
+            accounts.iter().map(|account| &account.preview),
+        ),
+        accounts,
     })
 }
 
-async fn validate_account_snapshots_create(
+#[tauri::command]
+#[specta::specta]
+pub async fn snapshot_wide_import_commit(
+    state: State<'_, AppState>,
+    input: CsvSnapshotImportSourceInput,
+    options: WideSnapshotImportOptionsInput,
+) -> Result<WideSnapshotImportCommitDto, ApiError> {
+    snapshot_wide_import_commit_with_today(&state.pool, &input, &options, Local::now().date_naive())
+        .await
+}
+
+async fn snapshot_wide_import_commit_with_today(
     pool: &SqlitePool,
-    account_id: i64,
-    input: &AccountSnapshotsCreateInput,
-) -> Result<(), ApiError> {
-    let mut issues = validation_issues_from_garde_report(input.validate().err());
-    let mut seen_dates = HashMap::<NaiveDate, usize>::new();
-    let mut unique_dates = Vec::<NaiveDate>::new();
-    let mut previous_date = None;
+    input: &CsvSnapshotImportSourceInput,
+    options: &WideSnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<WideSnapshotImportCommitDto, ApiError> {
+    let plans = snapshot_wide_import_plan(pool, input, options, today).await?;
+    let summary = crate::imports::snapshots::wide_preview_summary(
+        plans.iter().map(|column_plan| &column_plan.plan.preview),
+    );
 
-    for (index, snapshot) in input.snapshots.iter().enumerate() {
-        if let Some(last_date) = previous_date
-            && snapshot.date <= last_date
-        {
-            issues.push(validation_issue(
-                &format!("snapshots.{index}.date"),
-                "Snapshots must be in ascending date order",
-            ));
-        }
-        previous_date = Some(snapshot.date);
-
-        if let Some(existing_index) = seen_dates.insert(snapshot.date, index) {
-            issues.push(validation_issue(
-                &format!("snapshots.{existing_index}.date"),
-                "Duplicate snapshot date",
-            ));
-            issues.push(validation_issue(
-                &format!("snapshots.{index}.date"),
-                "Duplicate snapshot date",
-            ));
-        } else {
-            unique_dates.push(snapshot.date);
-        }
+    if summary.invalid_count > 0 {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Fix invalid rows before importing snapshots",
+        )]));
     }
 
-    if !unique_dates.is_empty() {
-        let existing_by_date = db::snapshots_for_account_dates(pool, account_id, &unique_dates)
-            .await
-            .map_err(|_| ApiError::Db)?
-            .into_iter()
-            .map(|row| (row.balance_date, row.id))
-            .collect::<HashMap<_, _>>();
+    if summary.overwrite_count > 0 && !options.overwrite_existing_confirmed {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Confirm overwrite to continue",
+        )]));
+    }
 
-        for (index, snapshot) in input.snapshots.iter().enumerate() {
-            if existing_by_date.contains_key(&snapshot.date) && !snapshot.overwrite_existing {
-                issues.push(validation_issue(
-                    &format!("snapshots.{index}.overwrite_existing"),
-                    "This date already exists. Confirm overwrite to continue",
-                ));
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    // Columns may share a new institution; create it once and reuse it.
+    let mut created_institution_ids = HashMap::<String, i64>::new();
+    let mut accounts = Vec::with_capacity(plans.len());
+
+    for column_plan in plans {
+        let (account_id, created_account) = match &column_plan.target {
+            WideSnapshotImportColumnTarget::Existing { account_id } => (*account_id, false),
+            WideSnapshotImportColumnTarget::New(validated) => {
+                let institution_id = match &validated.institution {
+                    ValidatedInstitutionRef::Existing { id } => *id,
+                    ValidatedInstitutionRef::New { name } => {
+                        if let Some(id) = created_institution_ids.get(name) {
+                            *id
+                        } else {
+                            let id = db::institution_create_tx(&mut tx, name)
+                                .await
+                                .map_err(map_institution_write_error)?;
+                            created_institution_ids.insert(name.clone(), id);
+                            id
+                        }
+                    }
+                };
+                let mutation = account_mutation_input(validated, institution_id);
+                let account_id = db::account_create_tx(&mut tx, &mutation)
+                    .await
+                    .map_err(map_account_write_error)?;
+                db::account_tags_set_tx(&mut tx, account_id, &validated.tags)
+                    .await
+                    .map_err(|_| ApiError::Db)?;
+                (account_id, true)
             }
-        }
-    }
+        };
 
-    if !issues.is_empty() {
-        return Err(ApiError::Validation(issues));
+        let options_json = serde_json::to_string(&column_plan.options).map_err(|_| ApiError::Db)?;
+        let result = snapshot_import_plan_apply_tx(
+            &mut tx,
+            account_id,
+            &SnapshotSource::CsvImport {
+                file_name: input.file_name.trim().to_owned(),
+            },
+            options_json,
+            column_plan.existing_snapshots,
+            column_plan.plan,
+        )
+        .await?;
+        accounts.push(WideSnapshotImportAccountCommitDto {
+            column: column_plan.column,
+            account_id,
+            created_account,
+            result,
+        });
     }
 
-    Ok(())
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+
+    Ok(WideSnapshotImportCommitDto { accounts })
 }
 
-async fn validate_account_snapshot_update(
+#[derive(Debug)]
+enum WideSnapshotImportColumnTarget {
+    Existing { account_id: i64 },
+    New(ValidatedAccountUpsert),
+}
+
+#[derive(Debug)]
+struct WideSnapshotImportColumnPlan {
+    column: String,
+    target: WideSnapshotImportColumnTarget,
+    account_name: String,
+    currency_code: CurrencyCode,
+    options: SnapshotImportOptionsInput,
+    existing_snapshots: Vec<db::rows::AccountBalanceSnapshotRow>,
+    plan: SnapshotImportPlan,
+}
+
+/// Resolves every mapped column to an account and plans it as a single-account import.
+/// Validation issues from all columns are reported together, with new-account issues
+/// nested under the column's `target.input`.
+async fn snapshot_wide_import_plan(
     pool: &SqlitePool,
-    account_id: i64,
-    snapshot_id: i64,
-    input: &AccountSnapshotUpdateInput,
-) -> Result<(), ApiError> {
-    let mut issues = validation_issues_from_garde_report(input.validate().err());
+    input: &CsvSnapshotImportSourceInput,
+    options: &WideSnapshotImportOptionsInput,
+    today: NaiveDate,
+) -> Result<Vec<WideSnapshotImportColumnPlan>, ApiError> {
+    crate::imports::snapshots::validate_wide_columns(input, options)
+        .map_err(map_snapshot_import_validation)?;
+
+    let source = SnapshotImportSourceInput::Csv(input.clone());
+    let locale = display_locale(pool).await?;
+    let mut issues = Vec::new();
+    let mut new_account_keys = HashSet::new();
+    let mut plans = Vec::with_capacity(options.columns.len());
+
+    for (index, mapping) in options.columns.iter().enumerate() {
+        let (target, account_name, currency_code, opened_date, closed_date, existing_snapshots) =
+            match &mapping.target {
+                WideSnapshotImportTarget::Existing { account_id } => {
+                    let Some(account) = db::account_get_full(pool, *account_id)
+                        .await
+                        .map_err(|_| ApiError::Db)?
+                    else {
+                        issues.push(validation_issue(
+                            &format!("options.columns.{index}.target.account_id"),
+                            "Account does not exist",
+                        ));
+                        continue;
+                    };
+                    let existing_snapshots = db::snapshots_for_account(pool, account.id)
+                        .await
+                        .map_err(|_| ApiError::Db)?;
+                    (
+                        WideSnapshotImportColumnTarget::Existing {
+                            account_id: account.id,
+                        },
+                        account.name,
+                        account
+                            .currency_code
+                            .parse::<CurrencyCode>()
+                            .map_err(|_| ApiError::Db)?,
+                        account.opened_date,
+                        account.closed_date,
+                        existing_snapshots,
+                    )
+                }
+                WideSnapshotImportTarget::New {
+                    input: account_input,
+                } => {
+                    let validated = match validate_account_upsert(pool, account_input, None).await {
+                        Ok(validated) => validated,
+                        Err(ApiError::Validation(account_issues)) => {
+                            issues.extend(account_issues.into_iter().map(|issue| {
+                                ValidationIssue {
+                                    field: format!(
+                                        "options.columns.{index}.target.input.{}",
+                                        issue.field
+                                    ),
+                                    ..issue
+                                }
+                            }));
+                            continue;
+                        }
+                        Err(error) => return Err(error),
+                    };
+                    let institution_key = match &validated.institution {
+                        ValidatedInstitutionRef::Existing { id } => id.to_string(),
+                        ValidatedInstitutionRef::New { name } => format!("new:{name}"),
+                    };
+                    if !new_account_keys.insert((institution_key, validated.name.clone())) {
+                        issues.push(validation_issue(
+                            &format!("options.columns.{index}.target.input.name"),
+                            "Another column creates an account with this name for this institution",
+                        ));
+                        continue;
+                    }
+                    (
+                        WideSnapshotImportColumnTarget::New(validated.clone()),
+                        validated.name,
+                        validated
+                            .currency_code
+                            .parse::<CurrencyCode>()
+                            .map_err(|_| ApiError::Db)?,
+                        validated.opened_date,
+                        validated.closed_date,
+                        Vec::new(),
+                    )
+                }
+            };
 
-    let conflicting = db::snapshots_for_account_dates(pool, account_id, &[input.date])
-        .await
-        .map_err(|_| ApiError::Db)?
-        .into_iter()
-        .find(|snapshot| snapshot.id != snapshot_id);
-    if conflicting.is_some() && !input.overwrite_existing {
-        issues.push(validation_issue(
-            "overwrite_existing",
-            "This date already exists. Confirm overwrite to continue",
-        ));
+        let column_options =
+            crate::imports::snapshots::wide_column_options(options, &mapping.column);
+        let plan = crate::imports::snapshots::plan_import(
+            &source,
+            &column_options,
+            &existing_snapshots,
+            SnapshotImportPlanningContext {
+                account_opened_date: opened_date,
+                account_closed_date: closed_date,
+                currency_code,
+                locale,
+                today,
+            },
+        )
+        .map_err(map_snapshot_import_validation)?;
+        plans.push(WideSnapshotImportColumnPlan {
+            column: mapping.column.clone(),
+            target,
+            account_name,
+            currency_code,
+            options: column_options,
+            existing_snapshots,
+            plan,
+        });
     }
 
     if !issues.is_empty() {
         return Err(ApiError::Validation(issues));
     }
 
-    Ok(())
+    Ok(plans)
 }
 
-fn validate_account_snapshots_delete(
-    input: &AccountSnapshotsDeleteInput,
-) -> Result<Vec<i64>, ApiError> {
-    let mut issues = validation_issues_from_garde_report(input.validate().err());
-    let mut snapshot_ids = Vec::with_capacity(input.snapshot_ids.len());
-    let mut seen = HashSet::new();
+const SNAPSHOT_IMPORT_BATCHES_LIST_LIMIT: i64 = 20;
 
-    for (index, snapshot_id) in input.snapshot_ids.iter().enumerate() {
-        if *snapshot_id < 1 {
-            issues.push(validation_issue(
-                &format!("snapshot_ids.{index}"),
-                "Snapshot id must be greater than 0",
-            ));
-            continue;
-        }
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_batches_list(
+    state: State<'_, AppState>,
+    account_id: i64,
+) -> Result<Vec<SnapshotImportBatchDto>, ApiError> {
+    let pool = &state.pool;
 
-        if seen.insert(*snapshot_id) {
-            snapshot_ids.push(*snapshot_id);
-        }
+    let exists = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound);
     }
 
-    if !issues.is_empty() {
-        return Err(ApiError::Validation(issues));
-    }
+    let rows = db::snapshot_import_batches_for_account(
+        pool,
+        account_id,
+        SNAPSHOT_IMPORT_BATCHES_LIST_LIMIT,
+    )
+    .await
+    .map_err(|_| ApiError::Db)?;
 
-    Ok(snapshot_ids)
+    rows.into_iter()
+        .map(snapshot_import_batch_dto_from_row)
+        .collect()
 }
 
-/// Creates a validation issue whose UI message is also safe to send as telemetry.
-///
-/// Use this only when `message` does not include user-entered values, raw imported
-/// data, or other sensitive details.
-fn validation_issue(field: &str, message: &str) -> ValidationIssue {
-    ValidationIssue {
-        field: field.to_string(),
-        message: message.to_string(),
-        telemetry_message: Some(message.to_string()),
-    }
-}
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_profiles_list(
+    state: State<'_, AppState>,
+    account_id: i64,
+) -> Result<Vec<SnapshotImportProfileDto>, ApiError> {
+    let pool = &state.pool;
 
-/// Creates a validation issue with a separately controlled telemetry message.
-///
-/// Use this when the UI message is useful locally but may contain sensitive details.
-/// Pass `None` to suppress the message from telemetry, or pass a redacted/generalized
-/// alternative that is safe to send.
-fn validation_issue_with_telemetry_message(
-    field: &str,
-    message: &str,
-    telemetry_message: Option<&str>,
-) -> ValidationIssue {
-    ValidationIssue {
-        field: field.to_string(),
-        message: message.to_string(),
-        telemetry_message: telemetry_message.map(str::to_string),
+    let exists = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound);
     }
-}
 
-fn validation_issues_from_garde_report(
-    report: Option<garde::error::Report>,
-) -> Vec<ValidationIssue> {
-    let Some(report) = report else {
-        return Vec::new();
-    };
-
-    report
-        .iter()
-        .map(|(path, error)| {
-            let field = garde_path_to_field(path);
-            validation_issue(&field, &error.to_string())
-        })
+    db::snapshot_import_profiles_for_account(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .map(snapshot_import_profile_dto_from_row)
         .collect()
 }
 
-fn map_snapshot_import_validation(issues: Vec<SnapshotImportValidationIssue>) -> ApiError {
-    ApiError::Validation(
-        issues
-            .into_iter()
-            .map(|issue| {
-                validation_issue_with_telemetry_message(
-                    &issue.field,
-                    &issue.message,
-                    issue.telemetry_message.as_deref(),
-                )
-            })
-            .collect(),
-    )
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_profile_save(
+    state: State<'_, AppState>,
+    account_id: i64,
+    input: SnapshotImportProfileSaveInput,
+) -> Result<SnapshotImportProfileDto, ApiError> {
+    account_snapshot_import_profile_save_in(&state.pool, account_id, input).await
 }
 
-fn garde_path_to_field(path: &garde::error::Path) -> String {
-    let raw = path.to_string();
-    if raw == "$" {
-        return String::new();
-    }
-    if let Some(stripped) = raw.strip_prefix("$.") {
-        return stripped.to_string();
-    }
-    raw
-}
+/// Saves under the given name, replacing the account's existing profile of that name.
+async fn account_snapshot_import_profile_save_in(
+    pool: &SqlitePool,
+    account_id: i64,
+    input: SnapshotImportProfileSaveInput,
+) -> Result<SnapshotImportProfileDto, ApiError> {
+    let profile = crate::imports::snapshots::validate_profile(&input)
+        .map_err(map_snapshot_import_validation)?;
 
-fn normalize_institution_upsert(input: &InstitutionUpsertInput) -> InstitutionUpsertInput {
-    InstitutionUpsertInput {
-        name: input.name.trim().to_string(),
+    let exists = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound);
     }
-}
 
-fn normalize_account_upsert(input: &AccountUpsertInput) -> AccountUpsertInput {
-    AccountUpsertInput {
-        institution: match &input.institution {
-            InstitutionRef::Existing { id } => InstitutionRef::Existing { id: *id },
-            InstitutionRef::New { input } => InstitutionRef::New {
-                input: normalize_institution_upsert(input),
-            },
+    let row = db::snapshot_import_profile_upsert(
+        pool,
+        &db::SnapshotImportProfileMutationInput {
+            account_id,
+            name: profile.name,
+            columns_json: serde_json::to_string(&profile.columns).map_err(|_| ApiError::Db)?,
+            options_json: serde_json::to_string(&profile.options).map_err(|_| ApiError::Db)?,
         },
-        name: input.name.trim().to_string(),
-        account_type: input.account_type,
-        currency_code: input.currency_code,
-        account_classification: input.account_classification,
-        opened_date: input.opened_date,
-        closed_date: input.closed_date,
-    }
+    )
+    .await
+    .map_err(|_| ApiError::Db)?;
+
+    snapshot_import_profile_dto_from_row(row)
 }
 
-fn map_institution_write_error(error: sqlx::Error) -> ApiError {
-    if is_unique_constraint(&error, "institutions.name") {
-        return ApiError::Validation(vec![validation_issue(
-            "name",
-            "An institution with this name already exists",
-        )]);
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_profile_delete(
+    state: State<'_, AppState>,
+    account_id: i64,
+    profile_id: i64,
+) -> Result<(), ApiError> {
+    let deleted = db::snapshot_import_profile_delete(&state.pool, account_id, profile_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    if deleted == 0 {
+        return Err(ApiError::NotFound);
     }
 
-    ApiError::Db
+    Ok(())
 }
 
-fn map_account_write_error(error: sqlx::Error) -> ApiError {
-    if is_unique_constraint(&error, "accounts.institution_id, accounts.name") {
-        return ApiError::Validation(vec![validation_issue(
-            "name",
-            "An account with this name already exists for this institution",
-        )]);
-    }
-
-    ApiError::Db
-}
-
-fn map_account_snapshot_write_error(error: sqlx::Error) -> ApiError {
-    if is_unique_constraint(
-        &error,
-        "account_balance_snapshots.account_id, account_balance_snapshots.balance_date",
-    ) {
-        return ApiError::Validation(vec![validation_issue(
-            "date",
-            "A snapshot already exists for this date",
-        )]);
-    }
-
-    ApiError::Db
+fn snapshot_import_profile_dto_from_row(
+    row: db::rows::SnapshotImportProfileRow,
+) -> Result<SnapshotImportProfileDto, ApiError> {
+    Ok(SnapshotImportProfileDto {
+        id: row.id,
+        columns: serde_json::from_str(&row.columns_json).map_err(|_| ApiError::Db)?,
+        options: serde_json::from_str(&row.options_json).map_err(|_| ApiError::Db)?,
+        name: row.name,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+    })
 }
 
-fn is_unique_constraint(error: &sqlx::Error, needle: &str) -> bool {
-    let sqlx::Error::Database(db_error) = error else {
-        return false;
-    };
-    db_error.message().contains("UNIQUE constraint failed") && db_error.message().contains(needle)
+#[tauri::command]
+#[specta::specta]
+pub async fn account_snapshot_import_batch_rollback(
+    state: State<'_, AppState>,
+    account_id: i64,
+    batch_id: i64,
+) -> Result<SnapshotImportRollbackDto, ApiError> {
+    account_snapshot_import_batch_rollback_in(&state.pool, account_id, batch_id).await
 }
 
-async fn build_account_dtos(
+async fn account_snapshot_import_batch_rollback_in(
     pool: &SqlitePool,
-    accounts: Vec<AccountListRow>,
-) -> Result<Vec<AccountDto>, ApiError> {
-    let today = Local::now().date_naive();
-    let account_ids = accounts.iter().map(|a| a.id).collect::<Vec<_>>();
+    account_id: i64,
+    batch_id: i64,
+) -> Result<SnapshotImportRollbackDto, ApiError> {
+    let Some(batch) = db::snapshot_import_batch_get(pool, account_id, batch_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+    if batch.rolled_back_at.is_some() {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "This import has already been rolled back",
+        )]));
+    }
 
-    // Longest period shown in the UI is 6M (180 points). We always build that once and slice.
-    let full_points: usize = 180;
-    let full_start = today - Duration::days(full_points as i64 - 1);
+    // Marking the batch first takes the write lock, so the checks below see the same
+    // snapshots the rollback then changes.
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    if !db::snapshot_import_batch_mark_rolled_back_tx(&mut tx, batch_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "This import has already been rolled back",
+        )]));
+    }
 
-    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, full_start, today)
+    let later_overlaps = db::snapshot_import_batch_later_overlaps_tx(&mut tx, account_id, batch_id)
         .await
         .map_err(|_| ApiError::Db)?;
-    let last_before = db::last_snapshots_before(pool, &account_ids, full_start)
+    if !later_overlaps.is_empty() {
+        return Err(ApiError::Validation(vec![validation_issue(
+            "import",
+            "Roll back the later import of these dates first",
+        )]));
+    }
+
+    let writes = db::snapshot_import_batch_writes_tx(&mut tx, batch_id)
         .await
         .map_err(|_| ApiError::Db)?;
+    let current_by_id = db::snapshots_for_account_tx(&mut tx, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .map(|snapshot| (snapshot.id, snapshot))
+        .collect::<HashMap<_, _>>();
 
-    let mut snapshots_by_account: HashMap<i64, HashMap<NaiveDate, i64>> = HashMap::new();
-    for s in snapshots {
-        snapshots_by_account
-            .entry(s.account_id)
-            .or_default()
-            .insert(s.balance_date, s.balance_minor);
+    // Snapshots edited or deleted since the import are left alone; rolling back around them
+    // would silently discard the user's later changes.
+    let changed_issues = writes
+        .iter()
+        .filter(|write| {
+            current_by_id.get(&write.snapshot_id).is_none_or(|current| {
+                current.balance_date != write.balance_date
+                    || current.balance_minor != write.balance_minor
+            })
+        })
+        .map(|write| {
+            validation_issue_with_telemetry_message(
+                "import",
+                &format!(
+                    "The snapshot for {} has changed since this import",
+                    write.balance_date
+                ),
+                Some("A snapshot has changed since this import"),
+            )
+        })
+        .collect::<Vec<_>>();
+    if !changed_issues.is_empty() {
+        return Err(ApiError::Validation(changed_issues));
     }
 
-    let initial_before: HashMap<i64, i64> = last_before
-        .into_iter()
-        .map(|s| (s.account_id, s.balance_minor))
-        .collect();
-
-    let mut out = Vec::with_capacity(accounts.len());
-    let empty_dates: HashMap<NaiveDate, i64> = HashMap::new();
-    for a in accounts {
-        let account_type_name = a.type_name.parse().map_err(|_| ApiError::Db)?;
+    let created_ids = writes
+        .iter()
+        .filter(|write| write.previous_balance_minor.is_none())
+        .map(|write| write.snapshot_id)
+        .collect::<Vec<_>>();
+    let deleted_count = db::account_snapshot_delete_many_tx(&mut tx, account_id, &created_ids)
+        .await
+        .map_err(|_| ApiError::Db)?;
 
-        let institution = InstitutionDto {
-            id: a.institution_id,
-            name: a.institution_name,
-        };
-        let account_type = AccountTypeDto {
-            id: a.type_id,
-            name: account_type_name,
+    let mut restored_count = 0;
+    for write in &writes {
+        let Some(previous_balance_minor) = write.previous_balance_minor else {
+            continue;
         };
+        let restored = db::account_snapshot_update_tx(
+            &mut tx,
+            account_id,
+            write.snapshot_id,
+            &db::AccountSnapshotMutationInput {
+                balance_date: write.balance_date,
+                balance_minor: previous_balance_minor,
+                note: write.previous_note.clone(),
+                source_kind: write.previous_source_kind.clone(),
+                source_detail: write.previous_source_detail.clone(),
+            },
+        )
+        .await
+        .map_err(|_| ApiError::Db)?;
+        if restored {
+            restored_count += 1;
+        }
+    }
 
-        let latest_balance_minor = a.latest_balance_minor.unwrap_or(0);
+    tx.commit().await.map_err(|_| ApiError::Db)?;
 
-        let date_map = snapshots_by_account.get(&a.id).unwrap_or(&empty_dates);
-        let seed_before = initial_before.get(&a.id).copied();
-        let series_180 = filled_values_for_period(date_map, seed_before, full_start, full_points);
-        let monthly_change_minor = series_180.last().copied().flatten().unwrap_or(0)
-            - series_180
-                .get(series_180.len().saturating_sub(31))
-                .copied()
-                .flatten()
-                .unwrap_or(0);
+    Ok(SnapshotImportRollbackDto {
+        deleted_count: u32::try_from(deleted_count).map_err(|_| ApiError::Db)?,
+        restored_count,
+    })
+}
 
-        let activity_by_period: BTreeMap<_, _> = [
-            (ActivityPeriod::P1W, 7),
-            (ActivityPeriod::P1M, 30),
-            (ActivityPeriod::P3M, 90),
-            (ActivityPeriod::P6M, 180),
-        ]
-        .into_iter()
-        .map(|(period, points)| {
-            let values = series_180[series_180.len().saturating_sub(points)..].to_vec();
-            let delta_minor = match (
-                values.iter().find_map(|&v| v),
-                values.iter().rev().find_map(|&v| v),
-            ) {
-                (Some(first), Some(last)) => last - first,
-                _ => 0,
-            };
+fn snapshot_import_batch_dto_from_row(
+    row: db::rows::SnapshotImportBatchRow,
+) -> Result<SnapshotImportBatchDto, ApiError> {
+    Ok(SnapshotImportBatchDto {
+        id: row.id,
+        options: serde_json::from_str(&row.options_json).map_err(|_| ApiError::Db)?,
+        file_name: row.file_name,
+        created_count: u32::try_from(row.created_count).map_err(|_| ApiError::Db)?,
+        overwritten_count: u32::try_from(row.overwritten_count).map_err(|_| ApiError::Db)?,
+        skipped_count: u32::try_from(row.skipped_count).map_err(|_| ApiError::Db)?,
+        created_at: row.created_at,
+        rolled_back_at: row.rolled_back_at,
+    })
+}
 
-            (
-                period,
-                ActivityDataDto {
-                    values,
-                    delta_minor,
-                },
-            )
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_delete_preview(
+    state: State<'_, AppState>,
+    institution_id: i64,
+) -> Result<InstitutionDeletePreviewDto, ApiError> {
+    let pool = &state.pool;
+    let Some(institution) = db::institution_get(pool, institution_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+
+    let accounts = db::institution_accounts_delete_preview(pool, institution_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .map(|row| InstitutionDeletePreviewAccountDto {
+            id: row.id,
+            name: row.name,
+            snapshot_count: u32::try_from(row.snapshot_count)
+                .expect("snapshot count should fit in u32"),
         })
-        .collect();
+        .collect::<Vec<_>>();
 
-        out.push(AccountDto {
-            id: a.id,
-            name: a.name,
-            institution,
-            account_type,
-            currency_code: a.currency_code.parse().map_err(|_| ApiError::Db)?,
-            account_classification: a.account_classification.parse().map_err(|_| ApiError::Db)?,
-            opened_date: a.opened_date,
-            closed_date: a.closed_date,
-            first_snapshot_date: a.first_snapshot_date,
-            latest_snapshot_date: a.latest_snapshot_date,
-            latest_balance_minor,
-            monthly_change_minor,
-            activity_by_period,
-        });
-    }
+    let total_snapshots = accounts.iter().map(|account| account.snapshot_count).sum();
 
-    Ok(out)
+    Ok(InstitutionDeletePreviewDto {
+        institution: InstitutionDto {
+            id: institution.id,
+            name: institution.name,
+        },
+        accounts,
+        total_snapshots,
+    })
 }
 
-// A missing snapshot date means "unchanged since the previous snapshot"; values
-// stay None before the first known balance so callers can choose how to handle absence.
-fn filled_values_for_period(
-    snapshots_by_date: &HashMap<NaiveDate, i64>,
-    initial_before: Option<i64>,
-    period_start: NaiveDate,
-    points: usize,
-) -> Vec<Option<i64>> {
-    let mut last = initial_before;
-    (0..points)
-        .map(|i| {
-            let date = period_start + Duration::days(i as i64);
-            last = snapshots_by_date.get(&date).copied().or(last);
-            last
-        })
-        .collect()
-}
+#[tauri::command]
+#[specta::specta]
+pub async fn institutions_delete(
+    state: State<'_, AppState>,
+    institution_id: i64,
+) -> Result<(), ApiError> {
+    let pool = &state.pool;
+    let deleted = db::institution_delete(pool, institution_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
 
-async fn total_balance_over_time(
-    pool: &SqlitePool,
-    accounts: &[AccountListRow],
-    start: NaiveDate,
-    end: NaiveDate,
-) -> Result<Vec<DashboardBalancePointDto>, ApiError> {
-    if end < start {
-        return Ok(Vec::new());
+    if !deleted {
+        return Err(ApiError::NotFound);
     }
 
-    let points = (end - start).num_days() as usize + 1;
-    let account_ids = accounts.iter().map(|a| a.id).collect::<Vec<_>>();
+    Ok(())
+}
 
-    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, start, end)
-        .await
-        .map_err(|_| ApiError::Db)?;
-    let last_before = db::last_snapshots_before(pool, &account_ids, start)
+#[tauri::command]
+#[specta::specta]
+pub async fn account_types_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountTypeSummaryDto>, ApiError> {
+    let rows = db::account_types_list_summary(&state.pool)
         .await
         .map_err(|_| ApiError::Db)?;
 
-    let mut snapshots_by_account: HashMap<i64, HashMap<NaiveDate, i64>> = HashMap::new();
-    for s in snapshots {
-        snapshots_by_account
-            .entry(s.account_id)
-            .or_default()
-            .insert(s.balance_date, s.balance_minor);
-    }
-
-    let initial_before: HashMap<i64, i64> = last_before
+    Ok(rows
         .into_iter()
-        .map(|s| (s.account_id, s.balance_minor))
-        .collect();
+        .map(|row| AccountTypeSummaryDto {
+            id: row.id,
+            name: row.name,
+            color: row.color,
+            account_count: u32::try_from(row.account_count)
+                .expect("account count should fit in u32"),
+        })
+        .collect())
+}
 
-    // Forward-fill each account, then sum per day.
-    let mut totals = vec![0i64; points];
-    let empty_dates: HashMap<NaiveDate, i64> = HashMap::new();
-    for a in accounts {
-        let date_map = snapshots_by_account.get(&a.id).unwrap_or(&empty_dates);
-        let seed_before = initial_before.get(&a.id).copied();
-        let series = filled_values_for_period(date_map, seed_before, start, points);
-        for (i, v) in series.into_iter().enumerate() {
-            if let Some(minor) = v {
-                totals[i] += minor;
+#[tauri::command]
+#[specta::specta]
+pub async fn account_types_create(
+    state: State<'_, AppState>,
+    input: AccountTypeUpsertInput,
+) -> Result<CreatedIdDto, ApiError> {
+    let pool = &state.pool;
+    let validated = validate_account_type_upsert(pool, &input, None).await?;
+
+    let id = db::account_type_create(pool, &validated.name, &validated.color)
+        .await
+        .map_err(map_account_type_write_error)?;
+
+    Ok(CreatedIdDto { id })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn account_types_update(
+    state: State<'_, AppState>,
+    type_id: i64,
+    input: AccountTypeUpsertInput,
+) -> Result<(), ApiError> {
+    let pool = &state.pool;
+    let validated = validate_account_type_upsert(pool, &input, Some(type_id)).await?;
+
+    let updated = db::account_type_update(pool, type_id, &validated.name, &validated.color)
+        .await
+        .map_err(map_account_type_write_error)?;
+    if !updated {
+        return Err(ApiError::NotFound);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn account_types_delete(
+    state: State<'_, AppState>,
+    type_id: i64,
+    input: AccountTypeDeleteInput,
+) -> Result<(), ApiError> {
+    account_type_delete_with_replacement(&state.pool, type_id, &input).await
+}
+
+async fn account_type_delete_with_replacement(
+    pool: &SqlitePool,
+    type_id: i64,
+    input: &AccountTypeDeleteInput,
+) -> Result<(), ApiError> {
+    if db::account_type_get(pool, type_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_none()
+    {
+        return Err(ApiError::NotFound);
+    }
+
+    let account_count = db::account_type_account_count(pool, type_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let replacement_type_id = match input.replacement_type_id {
+        Some(replacement_type_id) if replacement_type_id == type_id => {
+            return Err(ApiError::Validation(vec![validation_issue(
+                "replacement_type_id",
+                "Choose a different account type",
+            )]));
+        }
+        Some(replacement_type_id) => {
+            let exists = db::account_type_get(pool, replacement_type_id)
+                .await
+                .map_err(|_| ApiError::Db)?
+                .is_some();
+            if !exists {
+                return Err(ApiError::Validation(vec![validation_issue(
+                    "replacement_type_id",
+                    "Account type does not exist",
+                )]));
             }
+            Some(replacement_type_id)
         }
+        None if account_count > 0 => {
+            return Err(ApiError::Validation(vec![validation_issue(
+                "replacement_type_id",
+                "Choose a type for the accounts that use this type",
+            )]));
+        }
+        None => None,
+    };
+
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    if let Some(replacement_type_id) = replacement_type_id {
+        db::accounts_reassign_type_tx(&mut tx, type_id, replacement_type_id)
+            .await
+            .map_err(|_| ApiError::Db)?;
+    }
+    let deleted = db::account_type_delete_tx(&mut tx, type_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    if !deleted {
+        return Err(ApiError::NotFound);
+    }
+
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn tags_list(state: State<'_, AppState>) -> Result<Vec<TagSummaryDto>, ApiError> {
+    let rows = db::tags_list_summary(&state.pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TagSummaryDto {
+            id: row.id,
+            name: row.name,
+            account_count: u32::try_from(row.account_count)
+                .expect("account count should fit in u32"),
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn account_balance_over_time(
+    state: State<'_, AppState>,
+    account_id: i64,
+    period: BalanceOverTimePeriod,
+) -> Result<Vec<BalancePointDto>, ApiError> {
+    let today = Local::now().date_naive();
+    let pool = &state.pool;
+
+    // Ensure account exists.
+    let exists = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::NotFound);
+    }
+
+    let Some(earliest) = db::earliest_snapshot_date_for_account(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let computed_start = match period {
+        BalanceOverTimePeriod::P1M => today - Duration::days(30 - 1),
+        BalanceOverTimePeriod::P6M => today - Duration::days(183 - 1),
+        BalanceOverTimePeriod::P1Y => today - Duration::days(365 - 1),
+        BalanceOverTimePeriod::Max => earliest,
+    };
+
+    let start = std::cmp::max(computed_start, earliest);
+    if today < start {
+        return Ok(Vec::new());
+    }
+
+    let points = (today - start).num_days() as usize + 1;
+    let account_ids = vec![account_id];
+
+    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, start, today)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let last_before = db::last_snapshots_before(pool, &account_ids, start)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    let mut date_map: HashMap<NaiveDate, i64> = HashMap::new();
+    for s in snapshots {
+        date_map.insert(s.balance_date, s.balance_minor);
     }
 
-    let out: Vec<_> = totals
+    let seed_before = last_before.first().map(|s| s.balance_minor);
+    let series = filled_values_for_period(&date_map, seed_before, start, points);
+
+    Ok(series
         .into_iter()
         .enumerate()
-        .map(|(i, balance_minor)| {
+        .map(|(i, v)| {
             let date = start + Duration::days(i as i64);
-            DashboardBalancePointDto {
+            BalancePointDto {
                 date,
-                balance_minor,
+                balance_minor: v.unwrap_or(0),
             }
         })
-        .collect();
-
-    Ok(out)
+        .collect())
 }
 
-pub(crate) fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
-    use tauri_specta::{Builder, collect_commands};
+#[tauri::command]
+#[specta::specta]
+pub async fn dashboard_get(
+    state: State<'_, AppState>,
+    tag_id: Option<i64>,
+) -> Result<DashboardDto, ApiError> {
+    dashboard_get_with_today(&state.pool, tag_id, Local::now().date_naive()).await
+}
 
-    let mut semantic_types = specta_typescript::semantic::Configuration::default();
-    semantic_types
-        .rules_mut()
-        .retain(|rule| !(rule.module_path == "chrono" && rule.name == "NaiveDate"));
+async fn dashboard_get_with_today(
+    pool: &SqlitePool,
+    tag_id: Option<i64>,
+    today: NaiveDate,
+) -> Result<DashboardDto, ApiError> {
+    let accounts = dashboard_accounts(pool, tag_id).await?;
+    let (display_currency_code, fx_rates) = display_currency_fx_rates(pool).await?;
 
-    Builder::<tauri::Wry>::new()
-        .commands(collect_commands![
-            settings_get,
-            settings_update,
-            data_folder_open,
-            app_updates_state_get,
-            app_updates_check,
-            app_updates_install_pending_and_restart,
-            accounts_list,
-            accounts_create,
-            accounts_update,
-            accounts_delete_preview,
-            accounts_delete,
-            institutions_list,
-            institutions_create,
-            institutions_update,
-            institutions_delete_preview,
-            institutions_delete,
-            institutions_get,
-            accounts_get,
-            account_snapshots_list,
-            account_snapshots_create,
-            account_snapshot_update,
-            account_snapshots_delete,
-            account_snapshot_import_inspect,
-            account_snapshot_import_preview,
-            account_snapshot_import_commit,
-            account_balance_over_time,
-            dashboard_get,
-            dashboard_balance_over_time,
-            search,
-        ])
-        .dangerously_cast_bigints_to_number()
-        .semantic_types(semantic_types)
-}
+    let mut total_balance_minor: i64 = 0;
+    let mut active_accounts: u32 = 0;
+    let mut active_institution_ids: HashSet<i64> = HashSet::new();
+    let mut allocation: BTreeMap<i64, (AccountTypeDto, i64)> = BTreeMap::new();
+    let mut missing_fx_rate_currency_codes: BTreeSet<CurrencyCode> = BTreeSet::new();
 
-pub fn export_bindings_to_app_generated() -> anyhow::Result<()> {
-    use specta_typescript::Typescript;
-    use std::path::PathBuf;
+    for a in &accounts {
+        let currency_code: CurrencyCode = a.currency_code.parse().map_err(|_| ApiError::Db)?;
+        let latest_minor = a.latest_balance_minor.unwrap_or(0);
+        if latest_minor != 0 {
+            active_accounts += 1;
+            active_institution_ids.insert(a.institution_id);
+        }
 
-    let bindings_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("..")
-        .join("app")
-        .join("generated")
-        .join("bindings.ts");
+        let Some(converted_minor) =
+            fx_rates.convert_minor(latest_minor, currency_code, display_currency_code, today)
+        else {
+            missing_fx_rate_currency_codes.insert(currency_code);
+            continue;
+        };
+        total_balance_minor += converted_minor;
+        allocation
+            .entry(a.type_id)
+            .or_insert_with(|| {
+                (
+                    AccountTypeDto {
+                        id: a.type_id,
+                        name: a.type_name.clone(),
+                        color: a.type_color.clone(),
+                    },
+                    0,
+                )
+            })
+            .1 += converted_minor;
+    }
 
-    specta_builder()
-        .export(
-            Typescript::default().header(
-                "// Generated file, update with `bun run contracts:gen`.\nexport type Result<T, E> = { status: \"ok\"; data: T } | { status: \"error\"; error: E };",
-            ),
-            bindings_path,
-        )
-        .map_err(|error| anyhow::anyhow!("Failed to export typescript bindings: {error}"))
-}
+    // Pie chart allocations should be non-negative; exclude net-negative/zero groups (e.g. credit cards).
+    let allocation_by_type = allocation
+        .into_values()
+        .filter(|(_account_type, balance_minor)| *balance_minor > 0)
+        .map(|(account_type, balance_minor)| DashboardAllocationDto {
+            account_type,
+            balance_minor,
+        })
+        .collect::<Vec<_>>();
 
-#[cfg(test)]
-mod tests {
-    use chrono::{Duration, Local, NaiveDate};
-    use serde_json::json;
-    use sqlx::{
-        SqlitePool,
-        sqlite::{SqliteConnectOptions, SqlitePoolOptions},
-    };
-    use std::str::FromStr;
+    // Compute monthly metrics using a minimal 31-point (30-day) series.
+    let start_30d = today - Duration::days(31 - 1);
+    let balance_30d = total_balance_over_time(
+        pool,
+        &accounts,
+        start_30d,
+        today,
+        display_currency_code,
+        &fx_rates,
+    )
+    .await?;
+    missing_fx_rate_currency_codes.extend(balance_30d.missing_fx_rate_currency_codes);
+    let balance_30d = balance_30d.points;
 
-    use super::{
-        ApiError, SnapshotImportOptionsInput, SnapshotImportSourceInput,
-        account_snapshot_import_commit_with_today, build_account_dtos,
+    let last_minor = balance_30d
+        .last()
+        .map_or(total_balance_minor, |p| p.balance_minor);
+
+    let month_ago_minor = balance_30d
+        .get(balance_30d.len().saturating_sub(31))
+        .map_or(last_minor, |p| p.balance_minor);
+
+    let monthly_yield_minor = last_minor - month_ago_minor;
+    let change_vs_last_month_pct = if month_ago_minor != 0 {
+        (monthly_yield_minor as f64) / (month_ago_minor as f64) * 100.0
+    } else {
+        0.0
     };
-    use crate::db;
+
+    Ok(DashboardDto {
+        currency_code: display_currency_code,
+        total_balance_minor,
+        change_vs_last_month_pct,
+        monthly_yield_minor,
+        total_accounts: u32::try_from(accounts.len()).expect("account count should fit in u32"),
+        active_accounts,
+        active_institutions: u32::try_from(active_institution_ids.len())
+            .expect("active institution count should fit in u32"),
+        allocation_by_type,
+        missing_fx_rate_currency_codes: missing_fx_rate_currency_codes.into_iter().collect(),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn dashboard_balance_over_time(
+    state: State<'_, AppState>,
+    period: BalanceOverTimePeriod,
+    tag_id: Option<i64>,
+) -> Result<Vec<DashboardBalancePointDto>, ApiError> {
+    dashboard_balance_over_time_with_today(&state.pool, period, tag_id, Local::now().date_naive())
+        .await
+}
+
+async fn dashboard_balance_over_time_with_today(
+    pool: &SqlitePool,
+    period: BalanceOverTimePeriod,
+    tag_id: Option<i64>,
+    today: NaiveDate,
+) -> Result<Vec<DashboardBalancePointDto>, ApiError> {
+    let accounts = dashboard_accounts(pool, tag_id).await?;
+    let (display_currency_code, fx_rates) = display_currency_fx_rates(pool).await?;
+
+    let start = match period {
+        BalanceOverTimePeriod::P1M => today - Duration::days(30 - 1),
+        BalanceOverTimePeriod::P6M => today - Duration::days(183 - 1),
+        BalanceOverTimePeriod::P1Y => today - Duration::days(365 - 1),
+        BalanceOverTimePeriod::Max => {
+            let earliest = match tag_id {
+                Some(_) => accounts.iter().filter_map(|a| a.first_snapshot_date).min(),
+                None => db::earliest_snapshot_date(pool)
+                    .await
+                    .map_err(|_| ApiError::Db)?,
+            };
+            earliest.unwrap_or(today)
+        }
+    };
+
+    let series = total_balance_over_time(
+        pool,
+        &accounts,
+        start,
+        today,
+        display_currency_code,
+        &fx_rates,
+    )
+    .await?;
+    Ok(series.points)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fx_rates_list(state: State<'_, AppState>) -> Result<Vec<FxRateDto>, ApiError> {
+    let rows = db::fx_rates_list(&state.pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    rows.into_iter().map(fx_rate_dto_from_row).collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fx_rates_upsert(
+    state: State<'_, AppState>,
+    input: FxRateWriteInput,
+) -> Result<(), ApiError> {
+    let pool = &state.pool;
+    validate_fx_rate_write(&input)?;
+
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    db::fx_rate_upsert_tx(
+        &mut tx,
+        input.base_currency_code.as_str(),
+        input.quote_currency_code.as_str(),
+        input.date,
+        input.rate,
+    )
+    .await
+    .map_err(|_| ApiError::Db)?;
+
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fx_rates_delete(
+    state: State<'_, AppState>,
+    input: FxRatesDeleteInput,
+) -> Result<(), ApiError> {
+    let issues = validation_issues_from_garde_report(input.validate().err());
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    let rate_ids = input
+        .rate_ids
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let deleted = db::fx_rates_delete_many(&state.pool, &rate_ids)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    if deleted != rate_ids.len() as u64 {
+        return Err(ApiError::NotFound);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fx_rates_import_csv(
+    state: State<'_, AppState>,
+    input: FxRateCsvImportInput,
+) -> Result<FxRateImportCommitDto, ApiError> {
+    fx_rates_import_csv_into(&state.pool, input).await
+}
+
+async fn fx_rates_import_csv_into(
+    pool: &SqlitePool,
+    input: FxRateCsvImportInput,
+) -> Result<FxRateImportCommitDto, ApiError> {
+    let locale = display_locale(pool).await?;
+    let rows = crate::imports::fx_rates::parse_csv_rates(&input, locale)
+        .map_err(map_snapshot_import_validation)?;
+
+    let mut tx = pool.begin().await.map_err(|_| ApiError::Db)?;
+    let mut created_count = 0;
+    let mut updated_count = 0;
+    for row in rows {
+        let created = db::fx_rate_upsert_tx(
+            &mut tx,
+            input.base_currency_code.as_str(),
+            input.quote_currency_code.as_str(),
+            row.date,
+            row.rate,
+        )
+        .await
+        .map_err(|_| ApiError::Db)?;
+        if created {
+            created_count += 1;
+        } else {
+            updated_count += 1;
+        }
+    }
+
+    tx.commit().await.map_err(|_| ApiError::Db)?;
+    Ok(FxRateImportCommitDto {
+        created_count,
+        updated_count,
+    })
+}
+
+#[derive(Debug, Clone)]
+struct ValidatedInstitutionUpsert {
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct ValidatedAccountTypeUpsert {
+    name: String,
+    color: String,
+}
+
+#[derive(Debug, Clone)]
+enum ValidatedInstitutionRef {
+    Existing { id: i64 },
+    New { name: String },
+}
+
+#[derive(Debug, Clone)]
+struct ValidatedAccountUpsert {
+    institution: ValidatedInstitutionRef,
+    name: String,
+    type_id: i64,
+    currency_code: String,
+    account_classification: AccountClassification,
+    opened_date: Option<NaiveDate>,
+    closed_date: Option<NaiveDate>,
+    tags: Vec<String>,
+}
+
+fn account_mutation_input(
+    validated: &ValidatedAccountUpsert,
+    institution_id: i64,
+) -> db::AccountMutationInput {
+    db::AccountMutationInput {
+        institution_id,
+        name: validated.name.clone(),
+        type_id: validated.type_id,
+        currency_code: validated.currency_code.clone(),
+        account_classification: validated.account_classification.as_str().to_owned(),
+        opened_date: validated.opened_date,
+        closed_date: validated.closed_date,
+    }
+}
+
+fn app_settings_dto_from_row(row: db::rows::AppSettingsRow) -> Result<AppSettingsDto, ApiError> {
+    Ok(AppSettingsDto {
+        analytics_enabled: row.analytics_enabled,
+        default_display_currency_code: row
+            .default_display_currency_code
+            .parse()
+            .map_err(|_| ApiError::Db)?,
+        display_locale: row.display_locale.parse().map_err(|_| ApiError::Db)?,
+        theme: row.theme.parse().map_err(|_| ApiError::Db)?,
+        backup_schedule: row.backup_schedule.parse().map_err(|_| ApiError::Db)?,
+        backup_retention_count: u32::try_from(row.backup_retention_count)
+            .map_err(|_| ApiError::Db)?,
+        backup_folder: row.backup_folder,
+    })
+}
+
+fn fx_rate_dto_from_row(row: db::rows::FxRateRow) -> Result<FxRateDto, ApiError> {
+    Ok(FxRateDto {
+        id: row.id,
+        base_currency_code: row.base_currency_code.parse().map_err(|_| ApiError::Db)?,
+        quote_currency_code: row.quote_currency_code.parse().map_err(|_| ApiError::Db)?,
+        date: row.rate_date,
+        rate: row.rate,
+    })
+}
+
+// Dashboard figures cover every account, or only the accounts carrying `tag_id`.
+async fn dashboard_accounts(
+    pool: &SqlitePool,
+    tag_id: Option<i64>,
+) -> Result<Vec<AccountListRow>, ApiError> {
+    let mut accounts = db::accounts_list_full(pool)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let Some(tag_id) = tag_id else {
+        return Ok(accounts);
+    };
+
+    if db::tag_get(pool, tag_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .is_none()
+    {
+        return Err(ApiError::NotFound);
+    }
+    let tagged_ids: HashSet<i64> = db::account_ids_with_tag(pool, tag_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .collect();
+    accounts.retain(|a| tagged_ids.contains(&a.id));
+    Ok(accounts)
+}
+
+async fn display_locale(pool: &SqlitePool) -> Result<AppLocaleCode, ApiError> {
+    let settings = db::app_settings_get(pool).await.map_err(|_| ApiError::Db)?;
+    settings.display_locale.parse().map_err(|_| ApiError::Db)
+}
+
+async fn display_currency_fx_rates(pool: &SqlitePool) -> Result<(CurrencyCode, FxRates), ApiError> {
+    let settings = db::app_settings_get(pool).await.map_err(|_| ApiError::Db)?;
+    let display_currency_code: CurrencyCode = settings
+        .default_display_currency_code
+        .parse()
+        .map_err(|_| ApiError::Db)?;
+    let rows = db::fx_rates_for_currency(pool, display_currency_code.as_str())
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let fx_rates = FxRates::from_rows(&rows).map_err(|_| ApiError::Db)?;
+
+    Ok((display_currency_code, fx_rates))
+}
+
+async fn institution_detail_by_id(
+    pool: &SqlitePool,
+    institution_id: i64,
+) -> Result<InstitutionDetailDto, ApiError> {
+    let Some(institution) = db::institution_get(pool, institution_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+
+    let accounts = db::accounts_list_full_for_institution(pool, institution_id)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let account_dtos = build_account_dtos(pool, accounts).await?;
+
+    Ok(InstitutionDetailDto {
+        id: institution.id,
+        name: institution.name,
+        accounts: account_dtos,
+    })
+}
+
+async fn account_dto_by_id(pool: &SqlitePool, account_id: i64) -> Result<AccountDto, ApiError> {
+    let Some(account_row) = db::account_get_full(pool, account_id)
+        .await
+        .map_err(|_| ApiError::Db)?
+    else {
+        return Err(ApiError::NotFound);
+    };
+
+    let mut dtos = build_account_dtos(pool, vec![account_row]).await?;
+    dtos.pop().ok_or(ApiError::NotFound)
+}
+
+async fn validate_institution_upsert(
+    pool: &SqlitePool,
+    input: &InstitutionUpsertInput,
+    exclude_institution_id: Option<i64>,
+) -> Result<ValidatedInstitutionUpsert, ApiError> {
+    let normalized = normalize_institution_upsert(input);
+    let mut issues = validation_issues_from_garde_report(normalized.validate().err());
+
+    if issues.is_empty() {
+        let exists = db::institution_name_exists(pool, &normalized.name, exclude_institution_id)
+            .await
+            .map_err(|_| ApiError::Db)?;
+        if exists {
+            issues.push(validation_issue(
+                "name",
+                "An institution with this name already exists",
+            ));
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(ValidatedInstitutionUpsert {
+        name: normalized.name,
+    })
+}
+
+async fn validate_account_type_upsert(
+    pool: &SqlitePool,
+    input: &AccountTypeUpsertInput,
+    exclude_type_id: Option<i64>,
+) -> Result<ValidatedAccountTypeUpsert, ApiError> {
+    let normalized = normalize_account_type_upsert(input);
+    let mut issues = validation_issues_from_garde_report(normalized.validate().err());
+
+    if issues.is_empty() {
+        let exists = db::account_type_name_exists(pool, &normalized.name, exclude_type_id)
+            .await
+            .map_err(|_| ApiError::Db)?;
+        if exists {
+            issues.push(validation_issue(
+                "name",
+                "An account type with this name already exists",
+            ));
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(ValidatedAccountTypeUpsert {
+        name: normalized.name,
+        color: normalized.color,
+    })
+}
+
+async fn validate_account_upsert(
+    pool: &SqlitePool,
+    input: &AccountUpsertInput,
+    exclude_account_id: Option<i64>,
+) -> Result<ValidatedAccountUpsert, ApiError> {
+    let normalized = normalize_account_upsert(input);
+    let mut issues = validation_issues_from_garde_report(normalized.validate().err());
+
+    if normalized.account_type_id >= 1 {
+        let exists = db::account_type_get(pool, normalized.account_type_id)
+            .await
+            .map_err(|_| ApiError::Db)?
+            .is_some();
+        if !exists {
+            issues.push(validation_issue(
+                "account_type_id",
+                "Account type does not exist",
+            ));
+        }
+    }
+
+    let institution = match &normalized.institution {
+        InstitutionRef::Existing { id } => {
+            let exists = db::institution_exists(pool, *id)
+                .await
+                .map_err(|_| ApiError::Db)?;
+            if !exists {
+                issues.push(validation_issue(
+                    "institution.id",
+                    "Institution does not exist",
+                ));
+            }
+            ValidatedInstitutionRef::Existing { id: *id }
+        }
+        InstitutionRef::New { input } => {
+            if issues.is_empty() {
+                let exists = db::institution_name_exists(pool, &input.name, None)
+                    .await
+                    .map_err(|_| ApiError::Db)?;
+                if exists {
+                    issues.push(validation_issue(
+                        "institution.input.name",
+                        "An institution with this name already exists",
+                    ));
+                }
+            }
+
+            ValidatedInstitutionRef::New {
+                name: input.name.clone(),
+            }
+        }
+    };
+
+    if issues.is_empty()
+        && let ValidatedInstitutionRef::Existing { id } = &institution
+    {
+        let duplicate =
+            db::account_name_exists_in_institution(pool, *id, &normalized.name, exclude_account_id)
+                .await
+                .map_err(|_| ApiError::Db)?;
+        if duplicate {
+            issues.push(validation_issue(
+                "name",
+                "An account with this name already exists for this institution",
+            ));
+        }
+    }
+
+    if let (Some(opened_date), Some(closed_date)) = (normalized.opened_date, normalized.closed_date)
+        && closed_date < opened_date
+    {
+        issues.push(validation_issue(
+            "closed_date",
+            "Closed date cannot be before opened date",
+        ));
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(ValidatedAccountUpsert {
+        institution,
+        name: normalized.name,
+        type_id: normalized.account_type_id,
+        currency_code: normalized.currency_code.as_str().to_owned(),
+        account_classification: normalized.account_classification,
+        opened_date: normalized.opened_date,
+        closed_date: normalized.closed_date,
+        tags: normalized.tags,
+    })
+}
+
+async fn validate_account_snapshots_create(
+    pool: &SqlitePool,
+    account_id: i64,
+    currency_code: CurrencyCode,
+    input: &AccountSnapshotsCreateInput,
+) -> Result<(), ApiError> {
+    let mut issues = validation_issues_from_garde_report(input.validate_with(&currency_code).err());
+    let mut seen_dates = HashMap::<NaiveDate, usize>::new();
+    let mut unique_dates = Vec::<NaiveDate>::new();
+    let mut previous_date = None;
+
+    for (index, snapshot) in input.snapshots.iter().enumerate() {
+        if let Some(last_date) = previous_date
+            && snapshot.date <= last_date
+        {
+            issues.push(validation_issue(
+                &format!("snapshots.{index}.date"),
+                "Snapshots must be in ascending date order",
+            ));
+        }
+        previous_date = Some(snapshot.date);
+
+        if let Some(existing_index) = seen_dates.insert(snapshot.date, index) {
+            issues.push(validation_issue(
+                &format!("snapshots.{existing_index}.date"),
+                "Duplicate snapshot date",
+            ));
+            issues.push(validation_issue(
+                &format!("snapshots.{index}.date"),
+                "Duplicate snapshot date",
+            ));
+        } else {
+            unique_dates.push(snapshot.date);
+        }
+    }
+
+    if !unique_dates.is_empty() {
+        let existing_by_date = db::snapshots_for_account_dates(pool, account_id, &unique_dates)
+            .await
+            .map_err(|_| ApiError::Db)?
+            .into_iter()
+            .map(|row| (row.balance_date, row.id))
+            .collect::<HashMap<_, _>>();
+
+        for (index, snapshot) in input.snapshots.iter().enumerate() {
+            if existing_by_date.contains_key(&snapshot.date) && !snapshot.overwrite_existing {
+                issues.push(validation_issue(
+                    &format!("snapshots.{index}.overwrite_existing"),
+                    "This date already exists. Confirm overwrite to continue",
+                ));
+            }
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(())
+}
+
+async fn validate_account_snapshot_update(
+    pool: &SqlitePool,
+    account_id: i64,
+    snapshot_id: i64,
+    currency_code: CurrencyCode,
+    input: &AccountSnapshotUpdateInput,
+) -> Result<(), ApiError> {
+    let mut issues = validation_issues_from_garde_report(input.validate_with(&currency_code).err());
+
+    let conflicting = db::snapshots_for_account_dates(pool, account_id, &[input.date])
+        .await
+        .map_err(|_| ApiError::Db)?
+        .into_iter()
+        .find(|snapshot| snapshot.id != snapshot_id);
+    if conflicting.is_some() && !input.overwrite_existing {
+        issues.push(validation_issue(
+            "overwrite_existing",
+            "This date already exists. Confirm overwrite to continue",
+        ));
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(())
+}
+
+fn validate_fx_rate_write(input: &FxRateWriteInput) -> Result<(), ApiError> {
+    let mut issues = validation_issues_from_garde_report(input.validate().err());
+    if input.base_currency_code == input.quote_currency_code {
+        issues.push(validation_issue(
+            "quote_currency_code",
+            "Choose two different currencies",
+        ));
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(())
+}
+
+fn validate_account_snapshots_delete(
+    input: &AccountSnapshotsDeleteInput,
+) -> Result<Vec<i64>, ApiError> {
+    let mut issues = validation_issues_from_garde_report(input.validate().err());
+    let mut snapshot_ids = Vec::with_capacity(input.snapshot_ids.len());
+    let mut seen = HashSet::new();
+
+    for (index, snapshot_id) in input.snapshot_ids.iter().enumerate() {
+        if *snapshot_id < 1 {
+            issues.push(validation_issue(
+                &format!("snapshot_ids.{index}"),
+                "Snapshot id must be greater than 0",
+            ));
+            continue;
+        }
+
+        if seen.insert(*snapshot_id) {
+            snapshot_ids.push(*snapshot_id);
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(ApiError::Validation(issues));
+    }
+
+    Ok(snapshot_ids)
+}
+
+/// Creates a validation issue whose UI message is also safe to send as telemetry.
+///
+/// Use this only when `message` does not include user-entered values, raw imported
+/// data, or other sensitive details.
+fn validation_issue(field: &str, message: &str) -> ValidationIssue {
+    ValidationIssue {
+        field: field.to_string(),
+        message: message.to_string(),
+        telemetry_message: Some(message.to_string()),
+    }
+}
+
+/// Creates a validation issue with a separately controlled telemetry message.
+///
+/// Use this when the UI message is useful locally but may contain sensitive details.
+/// Pass `None` to suppress the message from telemetry, or pass a redacted/generalized
+/// alternative that is safe to send.
+fn validation_issue_with_telemetry_message(
+    field: &str,
+    message: &str,
+    telemetry_message: Option<&str>,
+) -> ValidationIssue {
+    ValidationIssue {
+        field: field.to_string(),
+        message: message.to_string(),
+        telemetry_message: telemetry_message.map(str::to_string),
+    }
+}
+
+fn validation_issues_from_garde_report(
+    report: Option<garde::error::Report>,
+) -> Vec<ValidationIssue> {
+    let Some(report) = report else {
+        return Vec::new();
+    };
+
+    report
+        .iter()
+        .map(|(path, error)| {
+            let field = garde_path_to_field(path);
+            validation_issue(&field, &error.to_string())
+        })
+        .collect()
+}
+
+fn map_snapshot_import_validation(issues: Vec<SnapshotImportValidationIssue>) -> ApiError {
+    ApiError::Validation(
+        issues
+            .into_iter()
+            .map(|issue| {
+                validation_issue_with_telemetry_message(
+                    &issue.field,
+                    &issue.message,
+                    issue.telemetry_message.as_deref(),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn garde_path_to_field(path: &garde::error::Path) -> String {
+    let raw = path.to_string();
+    if raw == "$" {
+        return String::new();
+    }
+    if let Some(stripped) = raw.strip_prefix("$.") {
+        return stripped.to_string();
+    }
+    raw
+}
+
+fn normalize_institution_upsert(input: &InstitutionUpsertInput) -> InstitutionUpsertInput {
+    InstitutionUpsertInput {
+        name: input.name.trim().to_string(),
+    }
+}
+
+fn normalize_account_type_upsert(input: &AccountTypeUpsertInput) -> AccountTypeUpsertInput {
+    AccountTypeUpsertInput {
+        name: input.name.trim().to_string(),
+        color: input.color.trim().to_ascii_lowercase(),
+    }
+}
+
+fn normalize_account_upsert(input: &AccountUpsertInput) -> AccountUpsertInput {
+    AccountUpsertInput {
+        institution: match &input.institution {
+            InstitutionRef::Existing { id } => InstitutionRef::Existing { id: *id },
+            InstitutionRef::New { input } => InstitutionRef::New {
+                input: normalize_institution_upsert(input),
+            },
+        },
+        name: input.name.trim().to_string(),
+        account_type_id: input.account_type_id,
+        currency_code: input.currency_code,
+        account_classification: input.account_classification,
+        opened_date: input.opened_date,
+        closed_date: input.closed_date,
+        tags: normalize_account_tags(&input.tags),
+    }
+}
+
+// Trims tag names and drops case-insensitive repeats, keeping the first spelling.
+fn normalize_account_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| seen.insert(tag.to_lowercase()))
+        .collect()
+}
+
+fn normalize_snapshot_note(note: Option<&str>) -> Option<String> {
+    note.map(str::trim)
+        .filter(|note| !note.is_empty())
+        .map(str::to_owned)
+}
+
+fn map_institution_write_error(error: sqlx::Error) -> ApiError {
+    if is_unique_constraint(&error, "institutions.name") {
+        return ApiError::Validation(vec![validation_issue(
+            "name",
+            "An institution with this name already exists",
+        )]);
+    }
+
+    ApiError::Db
+}
+
+fn map_account_type_write_error(error: sqlx::Error) -> ApiError {
+    if is_unique_constraint(&error, "account_types.name") {
+        return ApiError::Validation(vec![validation_issue(
+            "name",
+            "An account type with this name already exists",
+        )]);
+    }
+
+    ApiError::Db
+}
+
+fn map_account_write_error(error: sqlx::Error) -> ApiError {
+    if is_unique_constraint(&error, "accounts.institution_id, accounts.name") {
+        return ApiError::Validation(vec![validation_issue(
+            "name",
+            "An account with this name already exists for this institution",
+        )]);
+    }
+
+    ApiError::Db
+}
+
+fn map_account_snapshot_write_error(error: sqlx::Error) -> ApiError {
+    if is_unique_constraint(
+        &error,
+        "account_balance_snapshots.account_id, account_balance_snapshots.balance_date",
+    ) {
+        return ApiError::Validation(vec![validation_issue(
+            "date",
+            "A snapshot already exists for this date",
+        )]);
+    }
+
+    ApiError::Db
+}
+
+fn is_unique_constraint(error: &sqlx::Error, needle: &str) -> bool {
+    let sqlx::Error::Database(db_error) = error else {
+        return false;
+    };
+    db_error.message().contains("UNIQUE constraint failed") && db_error.message().contains(needle)
+}
+
+async fn build_account_dtos(
+    pool: &SqlitePool,
+    accounts: Vec<AccountListRow>,
+) -> Result<Vec<AccountDto>, ApiError> {
+    let today = Local::now().date_naive();
+    let account_ids = accounts.iter().map(|a| a.id).collect::<Vec<_>>();
+
+    // Longest period shown in the UI is 6M (180 points). We always build that once and slice.
+    let full_points: usize = 180;
+    let full_start = today - Duration::days(full_points as i64 - 1);
+
+    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, full_start, today)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let last_before = db::last_snapshots_before(pool, &account_ids, full_start)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let account_tags = db::account_tags_for_accounts(pool, &account_ids)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    let mut tags_by_account: HashMap<i64, Vec<TagDto>> = HashMap::new();
+    for t in account_tags {
+        tags_by_account
+            .entry(t.account_id)
+            .or_default()
+            .push(TagDto {
+                id: t.tag_id,
+                name: t.tag_name,
+            });
+    }
+
+    let mut snapshots_by_account: HashMap<i64, HashMap<NaiveDate, i64>> = HashMap::new();
+    for s in snapshots {
+        snapshots_by_account
+            .entry(s.account_id)
+            .or_default()
+            .insert(s.balance_date, s.balance_minor);
+    }
+
+    let initial_before: HashMap<i64, i64> = last_before
+        .into_iter()
+        .map(|s| (s.account_id, s.balance_minor))
+        .collect();
+
+    let mut out = Vec::with_capacity(accounts.len());
+    let empty_dates: HashMap<NaiveDate, i64> = HashMap::new();
+    for a in accounts {
+        let institution = InstitutionDto {
+            id: a.institution_id,
+            name: a.institution_name,
+        };
+        let account_type = AccountTypeDto {
+            id: a.type_id,
+            name: a.type_name,
+            color: a.type_color,
+        };
+
+        let latest_balance_minor = a.latest_balance_minor.unwrap_or(0);
+
+        let date_map = snapshots_by_account.get(&a.id).unwrap_or(&empty_dates);
+        let seed_before = initial_before.get(&a.id).copied();
+        let series_180 = filled_values_for_period(date_map, seed_before, full_start, full_points);
+        let monthly_change_minor = series_180.last().copied().flatten().unwrap_or(0)
+            - series_180
+                .get(series_180.len().saturating_sub(31))
+                .copied()
+                .flatten()
+                .unwrap_or(0);
+
+        let activity_by_period: BTreeMap<_, _> = [
+            (ActivityPeriod::P1W, 7),
+            (ActivityPeriod::P1M, 30),
+            (ActivityPeriod::P3M, 90),
+            (ActivityPeriod::P6M, 180),
+        ]
+        .into_iter()
+        .map(|(period, points)| {
+            let values = series_180[series_180.len().saturating_sub(points)..].to_vec();
+            let delta_minor = match (
+                values.iter().find_map(|&v| v),
+                values.iter().rev().find_map(|&v| v),
+            ) {
+                (Some(first), Some(last)) => last - first,
+                _ => 0,
+            };
+
+            (
+                period,
+                ActivityDataDto {
+                    values,
+                    delta_minor,
+                },
+            )
+        })
+        .collect();
+
+        out.push(AccountDto {
+            id: a.id,
+            name: a.name,
+            institution,
+            account_type,
+            tags: tags_by_account.remove(&a.id).unwrap_or_default(),
+            currency_code: a.currency_code.parse().map_err(|_| ApiError::Db)?,
+            account_classification: a.account_classification.parse().map_err(|_| ApiError::Db)?,
+            opened_date: a.opened_date,
+            closed_date: a.closed_date,
+            first_snapshot_date: a.first_snapshot_date,
+            latest_snapshot_date: a.latest_snapshot_date,
+            latest_balance_minor,
+            monthly_change_minor,
+            activity_by_period,
+        });
+    }
+
+    Ok(out)
+}
+
+// A missing snapshot date means "unchanged since the previous snapshot"; values
+// stay None before the first known balance so callers can choose how to handle absence.
+fn filled_values_for_period(
+    snapshots_by_date: &HashMap<NaiveDate, i64>,
+    initial_before: Option<i64>,
+    period_start: NaiveDate,
+    points: usize,
+) -> Vec<Option<i64>> {
+    let mut last = initial_before;
+    (0..points)
+        .map(|i| {
+            let date = period_start + Duration::days(i as i64);
+            last = snapshots_by_date.get(&date).copied().or(last);
+            last
+        })
+        .collect()
+}
+
+struct TotalBalanceSeries {
+    points: Vec<DashboardBalancePointDto>,
+    missing_fx_rate_currency_codes: BTreeSet<CurrencyCode>,
+}
+
+async fn total_balance_over_time(
+    pool: &SqlitePool,
+    accounts: &[AccountListRow],
+    start: NaiveDate,
+    end: NaiveDate,
+    display_currency_code: CurrencyCode,
+    fx_rates: &FxRates,
+) -> Result<TotalBalanceSeries, ApiError> {
+    let mut missing_fx_rate_currency_codes = BTreeSet::new();
+    if end < start {
+        return Ok(TotalBalanceSeries {
+            points: Vec::new(),
+            missing_fx_rate_currency_codes,
+        });
+    }
+
+    let points = (end - start).num_days() as usize + 1;
+    let account_ids = accounts.iter().map(|a| a.id).collect::<Vec<_>>();
+
+    let snapshots = db::snapshots_for_accounts_between(pool, &account_ids, start, end)
+        .await
+        .map_err(|_| ApiError::Db)?;
+    let last_before = db::last_snapshots_before(pool, &account_ids, start)
+        .await
+        .map_err(|_| ApiError::Db)?;
+
+    let mut snapshots_by_account: HashMap<i64, HashMap<NaiveDate, i64>> = HashMap::new();
+    for s in snapshots {
+        snapshots_by_account
+            .entry(s.account_id)
+            .or_default()
+            .insert(s.balance_date, s.balance_minor);
+    }
+
+    let initial_before: HashMap<i64, i64> = last_before
+        .into_iter()
+        .map(|s| (s.account_id, s.balance_minor))
+        .collect();
+
+    // Forward-fill each account, convert each day at the rate in effect, then sum per day.
+    let mut totals = vec![0i64; points];
+    let mut missing_fx_rate = vec![false; points];
+    let empty_dates: HashMap<NaiveDate, i64> = HashMap::new();
+    for a in accounts {
+        let currency_code: CurrencyCode = a.currency_code.parse().map_err(|_| ApiError::Db)?;
+        let date_map = snapshots_by_account.get(&a.id).unwrap_or(&empty_dates);
+        let seed_before = initial_before.get(&a.id).copied();
+        let series = filled_values_for_period(date_map, seed_before, start, points);
+        for (i, v) in series.into_iter().enumerate() {
+            let Some(minor) = v else {
+                continue;
+            };
+            let date = start + Duration::days(i as i64);
+            match fx_rates.convert_minor(minor, currency_code, display_currency_code, date) {
+                Some(converted_minor) => totals[i] += converted_minor,
+                None => {
+                    missing_fx_rate[i] = true;
+                    missing_fx_rate_currency_codes.insert(currency_code);
+                }
+            }
+        }
+    }
+
+    let points: Vec<_> = totals
+        .into_iter()
+        .zip(missing_fx_rate)
+        .enumerate()
+        .map(|(i, (balance_minor, has_missing_fx_rate))| {
+            let date = start + Duration::days(i as i64);
+            DashboardBalancePointDto {
+                date,
+                balance_minor,
+                has_missing_fx_rate,
+            }
+        })
+        .collect();
+
+    Ok(TotalBalanceSeries {
+        points,
+        missing_fx_rate_currency_codes,
+    })
+}
+
+pub(crate) fn specta_builder() -> tauri_specta::Builder<tauri::Wry> {
+    use tauri_specta::{Builder, collect_commands};
+
+    let mut semantic_types = specta_typescript::semantic::Configuration::default();
+    semantic_types
+        .rules_mut()
+        .retain(|rule| !(rule.module_path == "chrono" && rule.name == "NaiveDate"));
+
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            settings_get,
+            settings_update,
+            data_folder_open,
+            database_status,
+            database_unlock,
+            database_encryption_enable,
+            database_passphrase_change,
+            database_encryption_disable,
+            database_doctor,
+            database_doctor_repair,
+            data_export,
+            data_exports_folder_open,
+            data_import_preview,
+            data_import_commit,
+            backups_list,
+            backup_create,
+            backups_prune,
+            backup_restore,
+            backups_folder_open,
+            backup_archive_create,
+            backup_archive_inspect,
+            backup_archive_restore,
+            app_updates_state_get,
+            app_updates_check,
+            app_updates_install_pending_and_restart,
+            accounts_list,
+            accounts_create,
+            accounts_update,
+            accounts_delete_preview,
+            accounts_delete,
+            institutions_list,
+            institutions_create,
+            institutions_update,
+            institutions_delete_preview,
+            institutions_delete,
+            account_types_list,
+            account_types_create,
+            account_types_update,
+            account_types_delete,
+            tags_list,
+            institutions_get,
+            accounts_get,
+            account_snapshots_list,
+            account_history_list,
+            account_snapshots_create,
+            account_snapshot_update,
+            account_snapshots_delete,
+            account_snapshot_import_inspect,
+            account_snapshot_import_preview,
+            account_snapshot_import_commit,
+            account_snapshot_import_batches_list,
+            account_snapshot_import_profiles_list,
+            account_snapshot_import_profile_save,
+            account_snapshot_import_profile_delete,
+            account_snapshot_import_batch_rollback,
+            account_create_import_preview,
+            account_create_import_commit,
+            snapshot_wide_import_preview,
+            snapshot_wide_import_commit,
+            account_balance_over_time,
+            dashboard_get,
+            dashboard_balance_over_time,
+            fx_rates_list,
+            fx_rates_upsert,
+            fx_rates_delete,
+            fx_rates_import_csv,
+            search,
+        ])
+        .dangerously_cast_bigints_to_number()
+        .semantic_types(semantic_types)
+}
+
+pub fn export_bindings_to_app_generated() -> anyhow::Result<()> {
+    use specta_typescript::Typescript;
+    use std::path::PathBuf;
+
+    let bindings_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("app")
+        .join("generated")
+        .join("bindings.ts");
+
+    specta_builder()
+        .export(
+            Typescript::default().header(
+                "// Generated file, update with `bun run contracts:gen`.\nexport type Result<T, E> = { status: \"ok\"; data: T } | { status: \"error\"; error: E };",
+            ),
+            bindings_path,
+        )
+        .map_err(|error| anyhow::anyhow!("Failed to export typescript bindings: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
+    use serde_json::json;
+    use sqlx::{
+        SqlitePool,
+        sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    };
+    use std::str::FromStr;
+
+    use super::{
+        AccountTypeDeleteInput, AccountUpsertInput, ApiError, AuditAction, AuditEntityKind,
+        AuditFieldChangeDto, BalanceOverTimePeriod, CsvSnapshotImportSourceInput, DataExportRows,
+        DataImportOptionsInput, DataImportSourceInput, SnapshotImportInspectionDto,
+        SnapshotImportOptionsInput, SnapshotImportProfileSaveInput, SnapshotImportSourceInput,
+        WideSnapshotImportOptionsInput, account_create_import_commit_with_today, account_history,
+        account_snapshot_import_batch_rollback_in, account_snapshot_import_commit_with_today,
+        account_snapshot_import_inspect_in, account_snapshot_import_profile_save_in,
+        account_type_delete_with_replacement, build_account_dtos,
+        dashboard_balance_over_time_with_today, dashboard_get_with_today, data_export_into,
+        data_import_commit_into, data_import_plan, fx_rates_import_csv_into,
+        snapshot_wide_import_commit_with_today, snapshot_wide_import_preview_with_today,
+    };
+    use crate::contracts::{CurrencyCode, SnapshotSource};
+    use crate::db;
+    use crate::exports::{DataExportFormat, DataExportOptionsInput};
+    use crate::imports::data::DataImportMatchAction;
+    use crate::imports::snapshots::{SnapshotImportSourceOptionsInput, test_xlsx};
+
+    #[tokio::test]
+    async fn account_monthly_change_uses_zero_when_no_balance_thirty_days_ago() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let today = Local::now().date_naive();
+        insert_snapshot_on(&pool, account_id, today - Duration::days(10), 1000).await;
+
+        let account = db::account_get_full(&pool, account_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let dto = build_account_dtos(&pool, vec![account])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(dto.monthly_change_minor, 1000);
+    }
+
+    #[tokio::test]
+    async fn account_monthly_change_ignores_future_dated_snapshots() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let today = Local::now().date_naive();
+        insert_snapshot_on(&pool, account_id, today + Duration::days(1), 1000).await;
+
+        let account = db::account_get_full(&pool, account_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let dto = build_account_dtos(&pool, vec![account])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(dto.monthly_change_minor, 0);
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_returns_not_found_for_missing_account() {
+        let pool = test_pool().await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            999,
+            csv_input("date,balance\n2026-01-09,1.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ApiError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_blocks_invalid_preview_without_writes() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\nnot-a-date,1.00\n2026-01-09,2.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await;
+
+        assert_validation_error(
+            result,
+            "import",
+            "Fix invalid rows before importing snapshots",
+        );
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            Vec::<(NaiveDate, i64)>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_requires_overwrite_confirmation() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 9, 100).await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-09,2.00\n"),
+            import_options("overwrite", "include", "keep_last", false),
+            date(2026, 1, 9),
+        )
+        .await;
+
+        assert_validation_error(result, "import", "Confirm overwrite to continue");
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            vec![(date(2026, 1, 9), 100)]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_persists_creates_overwrites_and_skips() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 8, 100).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 10, 500).await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input(
+                "date,balance\n2026-01-09,1.00\n2026-01-10,6.00\n2026-01-11,7.00\n2026-01-11,8.00\n",
+            ),
+            import_options("overwrite", "exclude", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.created_count, 1);
+        assert_eq!(result.overwritten_count, 1);
+        assert_eq!(result.skipped_count, 2);
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            vec![
+                (date(2026, 1, 8), 100),
+                (date(2026, 1, 10), 600),
+                (date(2026, 1, 11), 800),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_does_not_write_skip_existing_rows() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 9, 100).await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-09,2.00\n2026-01-10,2.00\n"),
+            import_options("skip", "include", "keep_last", false),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.created_count, 1);
+        assert_eq!(result.overwritten_count, 0);
+        assert_eq!(result.skipped_count, 1);
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            vec![(date(2026, 1, 9), 100), (date(2026, 1, 10), 200)]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_uses_the_account_currency_minor_unit() {
+        let pool = test_pool().await;
+        let account_id = create_account_in_currency(&pool, "JPY").await;
+
+        let result = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-09,\"12,345\"\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.created_count, 1);
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            vec![(date(2026, 1, 9), 12345)]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_records_the_file_and_keeps_notes() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 8, 100).await;
+        sqlx::query(
+            "UPDATE account_balance_snapshots SET note = 'Bonus paid' WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-08,2.00\n2026-01-09,3.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+
+        let summary = db::snapshots_for_account(&pool, account_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| {
+                (
+                    snapshot.balance_date,
+                    snapshot.note,
+                    SnapshotSource::from_columns(
+                        snapshot.source_kind.as_deref(),
+                        snapshot.source_detail.as_deref(),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        let source = Some(SnapshotSource::CsvImport {
+            file_name: "snapshots.csv".to_owned(),
+        });
+        let mut expected = vec![
+            (
+                date(2026, 1, 8),
+                Some("Bonus paid".to_owned()),
+                source.clone(),
+            ),
+            (date(2026, 1, 9), None, source),
+        ];
+        expected.sort_by_key(|(date, _, _)| *date);
+        let mut summary = summary;
+        summary.sort_by_key(|(date, _, _)| *date);
+        assert_eq!(summary, expected);
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_records_ofx_imports_as_ofx() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let input = serde_json::from_value(json!({
+            "kind": "ofx",
+            "file_name": " statement.ofx ",
+            "content": "<OFX><STMTRS><CURDEF>GBP<LEDGERBAL><BALAMT>1234.56<DTASOF>20260131</LEDGERBAL></STMTRS></OFX>",
+        }))
+        .unwrap();
+        let options = serde_json::from_value(json!({
+            "source": { "kind": "ofx", "balance_kind": "ledger" },
+            "existing_date_policy": "overwrite",
+            "unchanged_value_policy": "include",
+            "duplicate_date_policy": "keep_last",
+            "overwrite_existing_confirmed": false,
+        }))
+        .unwrap();
+
+        account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            input,
+            options,
+            date(2026, 2, 1),
+        )
+        .await
+        .unwrap();
+
+        let snapshots = db::snapshots_for_account(&pool, account_id).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].balance_minor, 123_456);
+        assert_eq!(
+            SnapshotSource::from_columns(
+                snapshots[0].source_kind.as_deref(),
+                snapshots[0].source_detail.as_deref(),
+            ),
+            Some(SnapshotSource::OfxImport {
+                file_name: "statement.ofx".to_owned(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_commit_records_spreadsheet_imports_as_spreadsheets() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let content = test_xlsx(&[(
+            "Balances",
+            r#"
+                <row r="1">
+                    <c r="A1" t="inlineStr"><is><t>date</t></is></c>
+                    <c r="B1" t="inlineStr"><is><t>balance</t></is></c>
+                </row>
+                <row r="2">
+                    <c r="A2" t="inlineStr"><is><t>2026-01-09</t></is></c>
+                    <c r="B2"><v>12.5</v></c>
+                </row>
+            "#,
+        )]);
+        let input = serde_json::from_value(json!({
+            "kind": "spreadsheet",
+            "file_name": "balances.xlsx",
+            "content": content,
+            "sheet": null,
+            "has_header_row": true,
+        }))
+        .unwrap();
+
+        account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            input,
+            import_options("overwrite", "include", "keep_last", false),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+
+        let snapshots = db::snapshots_for_account(&pool, account_id).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].balance_minor, 1_250);
+        assert_eq!(
+            SnapshotSource::from_columns(
+                snapshots[0].source_kind.as_deref(),
+                snapshots[0].source_detail.as_deref(),
+            ),
+            Some(SnapshotSource::SpreadsheetImport {
+                file_name: "balances.xlsx".to_owned(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_batch_rollback_restores_overwrites_and_deletes_creates() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 8, 100).await;
+        sqlx::query(
+            "UPDATE account_balance_snapshots SET note = 'Bonus paid' WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let committed = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-08,2.00\n2026-01-09,3.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap();
+        let batch_id = committed.batch_id.unwrap();
+
+        let rollback = account_snapshot_import_batch_rollback_in(&pool, account_id, batch_id)
+            .await
+            .unwrap();
+
+        assert_eq!(rollback.deleted_count, 1);
+        assert_eq!(rollback.restored_count, 1);
+        let snapshots = db::snapshots_for_account(&pool, account_id).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].balance_minor, 100);
+        assert_eq!(snapshots[0].note.as_deref(), Some("Bonus paid"));
+        assert_eq!(snapshots[0].source_kind, None);
+
+        assert_validation_error(
+            account_snapshot_import_batch_rollback_in(&pool, account_id, batch_id).await,
+            "import",
+            "This import has already been rolled back",
+        );
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_batch_rollback_refuses_changed_or_later_imported_snapshots() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let first = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-08,2.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap()
+        .batch_id
+        .unwrap();
+        let second = account_snapshot_import_commit_with_today(
+            &pool,
+            account_id,
+            csv_input("date,balance\n2026-01-08,4.00\n"),
+            import_options("overwrite", "include", "keep_last", true),
+            date(2026, 1, 9),
+        )
+        .await
+        .unwrap()
+        .batch_id
+        .unwrap();
+
+        assert_validation_error(
+            account_snapshot_import_batch_rollback_in(&pool, account_id, first).await,
+            "import",
+            "Roll back the later import of these dates first",
+        );
+
+        sqlx::query(
+            "UPDATE account_balance_snapshots SET balance_minor = 500 WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_validation_error(
+            account_snapshot_import_batch_rollback_in(&pool, account_id, second).await,
+            "import",
+            "The snapshot for 2026-01-08 has changed since this import",
+        );
+        assert_eq!(
+            snapshot_balances(&pool, account_id).await,
+            vec![(date(2026, 1, 8), 500)]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_history_lists_only_changed_fields_for_updates() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 8, 100).await;
+        sqlx::query(
+            "UPDATE account_balance_snapshots SET balance_minor = 250, note = 'Corrected' WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let history = account_history(&pool, account_id).await.unwrap();
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].entity_kind, AuditEntityKind::Snapshot);
+        assert_eq!(history[0].action, AuditAction::Update);
+        assert_eq!(
+            history[0].changes,
+            vec![
+                AuditFieldChangeDto {
+                    field: "balance_minor".to_owned(),
+                    before: Some("100".to_owned()),
+                    after: Some("250".to_owned()),
+                },
+                AuditFieldChangeDto {
+                    field: "note".to_owned(),
+                    before: None,
+                    after: Some("Corrected".to_owned()),
+                },
+            ]
+        );
+        assert_eq!(history[2].entity_kind, AuditEntityKind::Account);
+        assert_eq!(history[2].action, AuditAction::Create);
+        assert_eq!(history[2].changes.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn account_history_returns_not_found_for_missing_account() {
+        let pool = test_pool().await;
+
+        assert!(matches!(
+            account_history(&pool, 404).await,
+            Err(ApiError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn dashboard_converts_balances_into_the_display_currency() {
+        let pool = test_pool().await;
+        let gbp_account_id = create_account(&pool).await;
+        let eur_account_id = create_account_in_currency(&pool, "EUR").await;
+        let today = date(2026, 1, 20);
+        insert_snapshot_on(&pool, gbp_account_id, today, 10_000).await;
+        insert_snapshot_on(&pool, eur_account_id, today, 12_000).await;
+        insert_fx_rate(&pool, "GBP", "EUR", date(2026, 1, 1), 1.2).await;
+
+        let dashboard = dashboard_get_with_today(&pool, None, today).await.unwrap();
+
+        assert_eq!(dashboard.currency_code, CurrencyCode::GBP);
+        assert_eq!(dashboard.total_balance_minor, 20_000);
+        assert_eq!(dashboard.allocation_by_type[0].balance_minor, 20_000);
+        assert!(dashboard.missing_fx_rate_currency_codes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn dashboard_flags_currencies_without_a_rate() {
+        let pool = test_pool().await;
+        let gbp_account_id = create_account(&pool).await;
+        let usd_account_id = create_account_in_currency(&pool, "USD").await;
+        let today = date(2026, 1, 20);
+        insert_snapshot_on(&pool, gbp_account_id, today, 10_000).await;
+        insert_snapshot_on(&pool, usd_account_id, today, 5_000).await;
+
+        let dashboard = dashboard_get_with_today(&pool, None, today).await.unwrap();
+
+        assert_eq!(dashboard.total_balance_minor, 10_000);
+        assert_eq!(
+            dashboard.missing_fx_rate_currency_codes,
+            vec![CurrencyCode::USD]
+        );
+    }
+
+    #[tokio::test]
+    async fn dashboard_balance_series_uses_the_rate_in_effect_on_each_date() {
+        let pool = test_pool().await;
+        let eur_account_id = create_account_in_currency(&pool, "EUR").await;
+        let today = date(2026, 1, 20);
+        insert_snapshot_on(&pool, eur_account_id, date(2026, 1, 1), 12_000).await;
+        insert_fx_rate(&pool, "GBP", "EUR", date(2026, 1, 10), 1.2).await;
+        insert_fx_rate(&pool, "GBP", "EUR", date(2026, 1, 15), 1.5).await;
+
+        let points =
+            dashboard_balance_over_time_with_today(&pool, BalanceOverTimePeriod::Max, None, today)
+                .await
+                .unwrap();
+
+        let point_on = |day: u32| {
+            let point = points
+                .iter()
+                .find(|point| point.date == date(2026, 1, day))
+                .unwrap();
+            (point.balance_minor, point.has_missing_fx_rate)
+        };
+        assert_eq!(point_on(9), (0, true));
+        assert_eq!(point_on(10), (10_000, false));
+        assert_eq!(point_on(20), (8_000, false));
+    }
+
+    #[tokio::test]
+    async fn fx_rates_import_csv_creates_and_replaces_rates_by_date() {
+        let pool = test_pool().await;
+        insert_fx_rate(&pool, "GBP", "EUR", date(2026, 1, 9), 1.1).await;
+
+        let result = fx_rates_import_csv_into(
+            &pool,
+            serde_json::from_value(json!({
+                "source": {
+                    "file_name": "rates.csv",
+                    "content": "Date,Rate\n2026-01-09,1.16\n2026-01-10,1.17\n".as_bytes(),
+                    "has_header_row": true,
+                },
+                "base_currency_code": "GBP",
+                "quote_currency_code": "EUR",
+                "date_column": "Date",
+                "rate_column": "Rate",
+                "date_format": "yyyy_mm_dd",
+            }))
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!((result.created_count, result.updated_count), (1, 1));
+        let rates = db::fx_rates_list(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.rate_date, row.rate))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rates,
+            vec![(date(2026, 1, 10), 1.17), (date(2026, 1, 9), 1.16)]
+        );
+    }
+
+    #[tokio::test]
+    async fn account_type_delete_requires_a_replacement_while_accounts_use_it() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let type_id = account_type_id(&pool, "Current").await;
+
+        let result = account_type_delete_with_replacement(
+            &pool,
+            type_id,
+            &AccountTypeDeleteInput {
+                replacement_type_id: None,
+            },
+        )
+        .await;
+
+        assert_validation_error(
+            result,
+            "replacement_type_id",
+            "Choose a type for the accounts that use this type",
+        );
+        let account = db::account_get_full(&pool, account_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.type_id, type_id);
+    }
+
+    #[tokio::test]
+    async fn account_type_delete_moves_accounts_to_the_replacement_type() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let type_id = account_type_id(&pool, "Current").await;
+        let replacement_type_id = db::account_type_create(&pool, "Everyday", "#3b82f6")
+            .await
+            .unwrap();
+
+        account_type_delete_with_replacement(
+            &pool,
+            type_id,
+            &AccountTypeDeleteInput {
+                replacement_type_id: Some(replacement_type_id),
+            },
+        )
+        .await
+        .unwrap();
+
+        let account = db::account_get_full(&pool, account_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.type_id, replacement_type_id);
+        assert_eq!(account.type_name, "Everyday");
+        assert!(
+            db::account_type_get(&pool, type_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn dashboard_allocation_groups_custom_account_types() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let type_id = db::account_type_create(&pool, "Crypto", "#f59e0b")
+            .await
+            .unwrap();
+        sqlx::query("UPDATE accounts SET type_id = ? WHERE id = ?")
+            .bind(type_id)
+            .bind(account_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let today = date(2026, 1, 20);
+        insert_snapshot_on(&pool, account_id, today, 2_500).await;
+
+        let dashboard = dashboard_get_with_today(&pool, None, today).await.unwrap();
+
+        assert_eq!(dashboard.allocation_by_type.len(), 1);
+        assert_eq!(dashboard.allocation_by_type[0].account_type.name, "Crypto");
+        assert_eq!(
+            dashboard.allocation_by_type[0].account_type.color,
+            "#f59e0b"
+        );
+        assert_eq!(dashboard.allocation_by_type[0].balance_minor, 2_500);
+    }
+
+    #[tokio::test]
+    async fn dashboard_tag_filter_only_counts_tagged_accounts() {
+        let pool = test_pool().await;
+        let tagged_account_id = create_account_in_currency(&pool, "GBP").await;
+        let other_account_id = create_account_in_currency(&pool, "EUR").await;
+        let today = date(2026, 1, 20);
+        insert_snapshot_on(&pool, tagged_account_id, date(2026, 1, 10), 1_000).await;
+        insert_snapshot_on(&pool, tagged_account_id, today, 1_500).await;
+        insert_snapshot_on(&pool, other_account_id, date(2025, 6, 1), 9_000).await;
+        let mut tx = pool.begin().await.unwrap();
+        db::account_tags_set_tx(&mut tx, tagged_account_id, &["Emergency fund".to_string()])
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        let tag_id = db::tags_list_summary(&pool).await.unwrap()[0].id;
+
+        let dashboard = dashboard_get_with_today(&pool, Some(tag_id), today)
+            .await
+            .unwrap();
+        let series = dashboard_balance_over_time_with_today(
+            &pool,
+            BalanceOverTimePeriod::Max,
+            Some(tag_id),
+            today,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(dashboard.total_accounts, 1);
+        assert_eq!(dashboard.total_balance_minor, 1_500);
+        assert!(dashboard.missing_fx_rate_currency_codes.is_empty());
+        assert_eq!(series.first().map(|p| p.date), Some(date(2026, 1, 10)));
+        assert_eq!(series.last().map(|p| p.balance_minor), Some(1_500));
+        assert!(matches!(
+            dashboard_get_with_today(&pool, Some(tag_id + 1), today).await,
+            Err(ApiError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn snapshot_wide_import_commit_creates_accounts_and_shares_new_institutions() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        insert_snapshot(&pool, account_id, 2026, 1, 1, 100).await;
+        let type_id = account_type_id(&pool, "Current").await;
+        let input = wide_input(
+            "Date,Everyday,ISA,Pension\n2026-01-01,1.00,5.00,\n2026-01-02,2.00,6.00,7.00\n",
+        );
+        let options = wide_options(json!([
+            { "column": "Everyday", "target": { "kind": "existing", "account_id": account_id } },
+            { "column": "ISA", "target": { "kind": "new", "input": new_account_input("ISA", type_id) } },
+            { "column": "Pension", "target": { "kind": "new", "input": new_account_input("Pension", type_id) } },
+        ]));
+
+        let preview =
+            snapshot_wide_import_preview_with_today(&pool, &input, &options, date(2026, 2, 1))
+                .await
+                .unwrap();
+        let result =
+            snapshot_wide_import_commit_with_today(&pool, &input, &options, date(2026, 2, 1))
+                .await
+                .unwrap();
+
+        assert_eq!(preview.accounts[0].account_id, Some(account_id));
+        assert_eq!(preview.accounts[1].account_id, None);
+        assert_eq!(preview.summary.create_count, 4);
+        assert_eq!(preview.summary.skip_count, 2);
+        assert_eq!(result.accounts.len(), 3);
+        assert!(!result.accounts[0].created_account);
+        assert_eq!(result.accounts[0].result.created_count, 1);
+        assert_eq!(result.accounts[0].result.skipped_count, 1);
+        assert!(result.accounts[1].created_account);
+        assert_eq!(
+            snapshot_balances(&pool, result.accounts[1].account_id).await,
+            vec![(date(2026, 1, 1), 500), (date(2026, 1, 2), 600)]
+        );
+        assert_eq!(
+            snapshot_balances(&pool, result.accounts[2].account_id).await,
+            vec![(date(2026, 1, 2), 700)]
+        );
+        let broker_count =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM institutions WHERE name = 'Broker'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(broker_count, 1);
+        assert!(
+            result
+                .accounts
+                .iter()
+                .all(|account| account.result.batch_id.is_some())
+        );
+    }
 
     #[tokio::test]
-    async fn account_monthly_change_uses_zero_when_no_balance_thirty_days_ago() {
+    async fn snapshot_wide_import_commit_writes_nothing_when_any_column_is_invalid() {
         let pool = test_pool().await;
-        let account_id = create_account(&pool).await;
-        let today = Local::now().date_naive();
-        insert_snapshot_on(&pool, account_id, today - Duration::days(10), 1000).await;
+        let type_id = account_type_id(&pool, "Current").await;
+        let input = wide_input("Date,ISA,Pension\n2026-01-01,5.00,oops\n");
+        let options = wide_options(json!([
+            { "column": "ISA", "target": { "kind": "new", "input": new_account_input("ISA", type_id) } },
+            { "column": "Pension", "target": { "kind": "new", "input": new_account_input("Pension", type_id) } },
+        ]));
+        let unnamed = wide_options(json!([
+            { "column": "ISA", "target": { "kind": "new", "input": new_account_input(" ", type_id) } },
+        ]));
+
+        let result =
+            snapshot_wide_import_commit_with_today(&pool, &input, &options, date(2026, 2, 1)).await;
+        let unnamed_result =
+            snapshot_wide_import_preview_with_today(&pool, &input, &unnamed, date(2026, 2, 1))
+                .await;
 
-        let account = db::account_get_full(&pool, account_id)
-            .await
-            .unwrap()
-            .unwrap();
-        let dto = build_account_dtos(&pool, vec![account])
+        assert_validation_error(
+            result,
+            "import",
+            "Fix invalid rows before importing snapshots",
+        );
+        let Err(ApiError::Validation(issues)) = unnamed_result else {
+            panic!("expected validation error");
+        };
+        assert_eq!(issues[0].field, "options.columns.0.target.input.name");
+        let account_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM accounts")
+            .fetch_one(&pool)
             .await
-            .unwrap()
-            .pop()
             .unwrap();
-
-        assert_eq!(dto.monthly_change_minor, 1000);
+        assert_eq!(account_count, 0);
     }
 
     #[tokio::test]
-    async fn account_monthly_change_ignores_future_dated_snapshots() {
+    async fn account_create_import_commit_creates_the_account_with_its_snapshots() {
         let pool = test_pool().await;
-        let account_id = create_account(&pool).await;
-        let today = Local::now().date_naive();
-        insert_snapshot_on(&pool, account_id, today + Duration::days(1), 1000).await;
+        let type_id = account_type_id(&pool, "Current").await;
+        let account: AccountUpsertInput =
+            serde_json::from_value(new_account_input("ISA", type_id)).unwrap();
+        let input = csv_input("date,balance\n2026-01-02,2.00\n2026-01-01,1.00\n");
+        let options = import_options("error", "include", "error", false);
 
-        let account = db::account_get_full(&pool, account_id)
-            .await
-            .unwrap()
-            .unwrap();
-        let dto = build_account_dtos(&pool, vec![account])
+        let result = account_create_import_commit_with_today(
+            &pool,
+            &account,
+            &input,
+            &options,
+            date(2026, 2, 1),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.result.created_count, 2);
+        assert!(result.result.batch_id.is_some());
+        assert_eq!(
+            snapshot_balances(&pool, result.account_id).await,
+            vec![(date(2026, 1, 1), 100), (date(2026, 1, 2), 200)]
+        );
+        let account_row = db::account_get_full(&pool, result.account_id)
             .await
             .unwrap()
-            .pop()
             .unwrap();
-
-        assert_eq!(dto.monthly_change_minor, 0);
+        assert_eq!(account_row.name, "ISA");
+        assert_eq!(account_row.institution_name, "Broker");
     }
 
     #[tokio::test]
-    async fn account_snapshot_import_commit_returns_not_found_for_missing_account() {
+    async fn account_create_import_commit_reports_account_and_import_issues_together() {
         let pool = test_pool().await;
+        let type_id = account_type_id(&pool, "Current").await;
+        let unnamed: AccountUpsertInput =
+            serde_json::from_value(new_account_input(" ", type_id)).unwrap();
+        let account: AccountUpsertInput =
+            serde_json::from_value(new_account_input("ISA", type_id)).unwrap();
+        let mut missing_column = import_options("error", "include", "error", false);
+        if let SnapshotImportSourceOptionsInput::Csv(csv_options) = &mut missing_column.source {
+            csv_options.amount_column = "value".to_string();
+        }
+        let valid_options = import_options("error", "include", "error", false);
+        let today = date(2026, 2, 1);
 
-        let result = account_snapshot_import_commit_with_today(
+        let both = account_create_import_commit_with_today(
             &pool,
-            999,
-            csv_input("date,balance\n2026-01-09,1.00\n"),
-            import_options("overwrite", "include", "keep_last", true),
-            date(2026, 1, 9),
+            &unnamed,
+            &csv_input("date,balance\n2026-01-01,1.00\n"),
+            &missing_column,
+            today,
+        )
+        .await;
+        let invalid_rows = account_create_import_commit_with_today(
+            &pool,
+            &account,
+            &csv_input("date,balance\n2026-01-01,oops\n"),
+            &valid_options,
+            today,
         )
         .await;
 
-        assert!(matches!(result, Err(ApiError::NotFound)));
+        let Err(ApiError::Validation(issues)) = both else {
+            panic!("expected validation error");
+        };
+        assert_eq!(issues[0].field, "account.name");
+        assert!(
+            issues[1..]
+                .iter()
+                .any(|issue| issue.field.starts_with("options."))
+        );
+        assert_validation_error(
+            invalid_rows,
+            "import",
+            "Fix invalid rows before importing snapshots",
+        );
+        let account_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM accounts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(account_count, 0);
     }
 
     #[tokio::test]
-    async fn account_snapshot_import_commit_blocks_invalid_preview_without_writes() {
+    async fn data_export_writes_the_chosen_accounts_and_dates() {
         let pool = test_pool().await;
         let account_id = create_account(&pool).await;
+        let other_account_id = create_account_in_currency(&pool, "EUR").await;
+        insert_snapshot(&pool, account_id, 2026, 1, 1, 100).await;
+        insert_snapshot(&pool, account_id, 2026, 2, 1, 200).await;
+        insert_snapshot(&pool, other_account_id, 2026, 2, 1, 300).await;
+        let exports_dir = tempfile::tempdir().unwrap();
+        let options = |format, account_ids| DataExportOptionsInput {
+            format,
+            from_date: Some(date(2026, 1, 15)),
+            to_date: None,
+            account_ids,
+        };
+        let exported_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
 
-        let result = account_snapshot_import_commit_with_today(
+        let json = data_export_into(
             &pool,
-            account_id,
-            csv_input("date,balance\nnot-a-date,1.00\n2026-01-09,2.00\n"),
-            import_options("overwrite", "include", "keep_last", true),
-            date(2026, 1, 9),
+            &options(DataExportFormat::Json, Some(vec![account_id])),
+            exports_dir.path(),
+            "1.2.3",
+            exported_at,
+        )
+        .await
+        .unwrap();
+        let csv = data_export_into(
+            &pool,
+            &options(DataExportFormat::Csv, None),
+            exports_dir.path(),
+            "1.2.3",
+            exported_at,
+        )
+        .await
+        .unwrap();
+        let empty_subset = data_export_into(
+            &pool,
+            &options(DataExportFormat::Csv, Some(Vec::new())),
+            exports_dir.path(),
+            "1.2.3",
+            exported_at,
         )
         .await;
 
-        assert_validation_error(
-            result,
-            "import",
-            "Fix invalid rows before importing snapshots",
-        );
+        let document = serde_json::from_slice::<crate::exports::DataExportDocument>(
+            &std::fs::read(&json.path).unwrap(),
+        )
+        .unwrap();
+        assert_eq!((json.account_count, json.snapshot_count), (1, 1));
+        assert_eq!(document.accounts[0].id, account_id);
+        assert_eq!(document.snapshots[0].balance_minor, 200);
+        assert_eq!((csv.account_count, csv.snapshot_count), (2, 2));
+        let wide =
+            std::fs::read_to_string(std::path::Path::new(&csv.path).join("snapshots_wide.csv"))
+                .unwrap();
         assert_eq!(
-            snapshot_balances(&pool, account_id).await,
-            Vec::<(NaiveDate, i64)>::new()
+            wide,
+            "Date,Bank / Everyday GBP,Bank / Everyday EUR\n2026-02-01,2.00,3.00\n"
         );
+        assert_validation_error(empty_subset, "account_ids", "Choose at least one account");
     }
 
     #[tokio::test]
-    async fn account_snapshot_import_commit_requires_overwrite_confirmation() {
+    async fn data_import_commit_restores_an_export_into_an_empty_database() {
         let pool = test_pool().await;
         let account_id = create_account(&pool).await;
-        insert_snapshot(&pool, account_id, 2026, 1, 9, 100).await;
+        let other_account_id = create_account_in_currency(&pool, "EUR").await;
+        insert_snapshot(&pool, account_id, 2026, 1, 1, 100).await;
+        insert_snapshot(&pool, other_account_id, 2026, 2, 1, -300).await;
+        sqlx::query(
+            "UPDATE account_balance_snapshots SET note = 'Bonus', source_kind = 'csv_import', \
+             source_detail = 'bank.csv' WHERE account_id = ?",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        db::account_tags_set_tx(&mut tx, account_id, &["Emergency fund".to_string()])
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        let exports_dir = tempfile::tempdir().unwrap();
+        let exports_dir = exports_dir.path();
+        let exported_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let export = |pool| async move {
+            let dto = data_export_into(
+                pool,
+                &DataExportOptionsInput {
+                    format: DataExportFormat::Json,
+                    from_date: None,
+                    to_date: None,
+                    account_ids: None,
+                },
+                exports_dir,
+                "1.2.3",
+                exported_at,
+            )
+            .await
+            .unwrap();
+            std::fs::read_to_string(dto.path).unwrap()
+        };
+        let content = export(&pool).await;
 
-        let result = account_snapshot_import_commit_with_today(
-            &pool,
-            account_id,
-            csv_input("date,balance\n2026-01-09,2.00\n"),
-            import_options("overwrite", "include", "keep_last", false),
-            date(2026, 1, 9),
+        let restored_pool = test_pool().await;
+        let preview = data_import_plan(
+            &restored_pool,
+            &data_import_input(&content),
+            &data_import_options("error", false),
         )
-        .await;
+        .await
+        .unwrap()
+        .preview;
+        let result = data_import_commit_into(
+            &restored_pool,
+            &data_import_input(&content),
+            &data_import_options("error", false),
+        )
+        .await
+        .unwrap();
 
-        assert_validation_error(result, "import", "Confirm overwrite to continue");
         assert_eq!(
-            snapshot_balances(&pool, account_id).await,
-            vec![(date(2026, 1, 9), 100)]
+            preview.institutions[0].action,
+            DataImportMatchAction::Create
+        );
+        assert_eq!(
+            preview.account_types[0].action,
+            DataImportMatchAction::Merge
+        );
+        assert_eq!(
+            (
+                result.created_institution_count,
+                result.created_account_count,
+                result.created_count
+            ),
+            (1, 2, 2)
         );
+        assert_eq!(export(&restored_pool).await, content);
     }
 
     #[tokio::test]
-    async fn account_snapshot_import_commit_persists_creates_overwrites_and_skips() {
+    async fn data_import_merges_matching_accounts_with_the_existing_date_policy() {
         let pool = test_pool().await;
         let account_id = create_account(&pool).await;
-        insert_snapshot(&pool, account_id, 2026, 1, 8, 100).await;
-        insert_snapshot(&pool, account_id, 2026, 1, 10, 500).await;
-
-        let result = account_snapshot_import_commit_with_today(
+        insert_snapshot(&pool, account_id, 2026, 1, 1, 100).await;
+        insert_snapshot(&pool, account_id, 2026, 2, 1, 200).await;
+        let mut document = crate::exports::document(
+            DataExportRows {
+                account_types: db::account_types_list_summary(&pool).await.unwrap(),
+                institutions: db::institutions_list_summary(&pool).await.unwrap(),
+                accounts: db::accounts_list_full(&pool).await.unwrap(),
+                tags: Vec::new(),
+                snapshots: db::snapshots_for_export(&pool, &[account_id], None, None)
+                    .await
+                    .unwrap(),
+            },
+            &DataExportOptionsInput {
+                format: DataExportFormat::Json,
+                from_date: None,
+                to_date: None,
+                account_ids: None,
+            },
+            Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            "1.2.3",
+        )
+        .unwrap();
+        document.snapshots[1].balance_minor = 250;
+        let mut later = document.snapshots[1].clone();
+        later.date = date(2026, 3, 1);
+        later.balance_minor = 300;
+        document.snapshots.push(later);
+        let content = serde_json::to_string(&document).unwrap();
+        document.accounts[0].currency_code = CurrencyCode::EUR;
+        let other_currency = serde_json::to_string(&document).unwrap();
+        let input = data_import_input(&content);
+
+        let conflict = data_import_plan(&pool, &input, &data_import_options("error", false))
+            .await
+            .unwrap()
+            .preview;
+        let skipped = data_import_plan(&pool, &input, &data_import_options("skip", false))
+            .await
+            .unwrap()
+            .preview;
+        let wrong_currency = data_import_plan(
             &pool,
-            account_id,
-            csv_input(
-                "date,balance\n2026-01-09,1.00\n2026-01-10,6.00\n2026-01-11,7.00\n2026-01-11,8.00\n",
-            ),
-            import_options("overwrite", "exclude", "keep_last", true),
-            date(2026, 1, 9),
+            &data_import_input(&other_currency),
+            &data_import_options("overwrite", false),
         )
         .await
-        .unwrap();
+        .unwrap()
+        .preview;
+        let unconfirmed =
+            data_import_commit_into(&pool, &input, &data_import_options("overwrite", false)).await;
+        let result =
+            data_import_commit_into(&pool, &input, &data_import_options("overwrite", true))
+                .await
+                .unwrap();
 
-        assert_eq!(result.created_count, 1);
-        assert_eq!(result.overwritten_count, 1);
-        assert_eq!(result.skipped_count, 2);
+        assert_eq!(conflict.accounts[0].action, DataImportMatchAction::Merge);
+        assert_eq!(conflict.accounts[0].account_id, Some(account_id));
+        assert_eq!(conflict.summary.invalid_count, 1);
+        assert_eq!(
+            conflict.accounts[0].issues,
+            ["Snapshots already exist on some of the imported dates"]
+        );
+        assert_eq!(
+            (skipped.summary.create_count, skipped.summary.skip_count),
+            (1, 2)
+        );
+        assert_eq!(wrong_currency.summary.invalid_count, 3);
+        assert_eq!(
+            wrong_currency.accounts[0].issues,
+            ["The existing account uses GBP, but the export uses EUR"]
+        );
+        assert_validation_error(unconfirmed, "import", "Confirm overwrite to continue");
+        assert_eq!(
+            (
+                result.created_account_count,
+                result.merged_account_count,
+                result.created_count,
+                result.overwritten_count,
+                result.skipped_count
+            ),
+            (0, 1, 1, 1, 1)
+        );
         assert_eq!(
             snapshot_balances(&pool, account_id).await,
-            vec![
-                (date(2026, 1, 8), 100),
-                (date(2026, 1, 10), 600),
-                (date(2026, 1, 11), 800),
+            [
+                (date(2026, 1, 1), 100),
+                (date(2026, 2, 1), 250),
+                (date(2026, 3, 1), 300)
             ]
         );
     }
 
     #[tokio::test]
-    async fn account_snapshot_import_commit_does_not_write_skip_existing_rows() {
+    async fn account_snapshot_import_inspect_applies_the_profile_saved_for_matching_columns() {
         let pool = test_pool().await;
         let account_id = create_account(&pool).await;
-        insert_snapshot(&pool, account_id, 2026, 1, 9, 100).await;
+        let other_account_id = create_account_in_currency(&pool, "USD").await;
+        let save = |name: &str, duplicate_date_policy: &str| SnapshotImportProfileSaveInput {
+            name: name.to_string(),
+            columns: vec!["date".to_string(), "balance".to_string()],
+            options: import_options("skip", "include", duplicate_date_policy, true),
+        };
 
-        let result = account_snapshot_import_commit_with_today(
+        let first =
+            account_snapshot_import_profile_save_in(&pool, account_id, save("Bank", "error"))
+                .await
+                .unwrap();
+        let resaved = account_snapshot_import_profile_save_in(
             &pool,
             account_id,
-            csv_input("date,balance\n2026-01-09,2.00\n2026-01-10,2.00\n"),
-            import_options("skip", "include", "keep_last", false),
-            date(2026, 1, 9),
+            save(" bank ", "keep_first"),
         )
         .await
         .unwrap();
+        let inspect = |account_id, content: &str| {
+            account_snapshot_import_inspect_in(&pool, account_id, csv_input(content))
+        };
+        let matched = inspect(Some(account_id), "date,balance\n2026-01-01,1.00\n")
+            .await
+            .unwrap();
+        let other_columns = inspect(Some(account_id), "date,balance,note\n2026-01-01,1.00,x\n")
+            .await
+            .unwrap();
+        let other_account = inspect(Some(other_account_id), "date,balance\n2026-01-01,1.00\n")
+            .await
+            .unwrap();
 
-        assert_eq!(result.created_count, 1);
-        assert_eq!(result.overwritten_count, 0);
-        assert_eq!(result.skipped_count, 1);
+        assert_eq!(resaved.id, first.id);
+        assert_eq!(resaved.name, "bank");
+        assert!(!resaved.options.overwrite_existing_confirmed);
+        let SnapshotImportInspectionDto::Csv(matched) = matched else {
+            panic!("expected CSV inspection");
+        };
+        let profile = matched.profile.unwrap();
+        assert_eq!(profile.id, first.id);
         assert_eq!(
-            snapshot_balances(&pool, account_id).await,
-            vec![(date(2026, 1, 9), 100), (date(2026, 1, 10), 200)]
+            profile.options.duplicate_date_policy,
+            crate::imports::snapshots::SnapshotImportDuplicateDatePolicy::KeepFirst
         );
+        for inspection in [other_columns, other_account] {
+            let SnapshotImportInspectionDto::Csv(inspection) = inspection else {
+                panic!("expected CSV inspection");
+            };
+            assert!(inspection.profile.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn account_snapshot_import_inspect_skips_profiles_that_no_longer_deserialize() {
+        let pool = test_pool().await;
+        let account_id = create_account(&pool).await;
+        let saved = account_snapshot_import_profile_save_in(
+            &pool,
+            account_id,
+            SnapshotImportProfileSaveInput {
+                name: "Bank".to_string(),
+                columns: vec!["date".to_string(), "balance".to_string()],
+                options: import_options("skip", "include", "error", false),
+            },
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO snapshot_import_profiles (account_id, name, columns_json, options_json) \
+             VALUES (?, 'Old', '[\"date\",\"balance\"]', '{\"source\":{\"kind\":\"retired\"}}')",
+        )
+        .bind(account_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let inspection = account_snapshot_import_inspect_in(
+            &pool,
+            Some(account_id),
+            csv_input("date,balance\n2026-01-01,1.00\n"),
+        )
+        .await
+        .unwrap();
+
+        let SnapshotImportInspectionDto::Csv(inspection) = inspection else {
+            panic!("expected CSV inspection");
+        };
+        assert_eq!(inspection.profile.map(|profile| profile.id), Some(saved.id));
     }
 
     async fn test_pool() -> SqlitePool {
@@ -2026,13 +5480,21 @@ mod tests {
     }
 
     async fn create_account(pool: &SqlitePool) -> i64 {
-        let institution_id = sqlx::query("INSERT INTO institutions (name) VALUES ('Bank')")
+        create_account_in_currency(pool, "GBP").await
+    }
+
+    async fn create_account_in_currency(pool: &SqlitePool, currency_code: &str) -> i64 {
+        sqlx::query("INSERT OR IGNORE INTO institutions (name) VALUES ('Bank')")
             .execute(pool)
             .await
-            .unwrap()
-            .last_insert_rowid();
+            .unwrap();
+        let institution_id =
+            sqlx::query_scalar::<_, i64>("SELECT id FROM institutions WHERE name = 'Bank'")
+                .fetch_one(pool)
+                .await
+                .unwrap();
         let type_id =
-            sqlx::query_scalar::<_, i64>("SELECT id FROM account_types WHERE name = 'current'")
+            sqlx::query_scalar::<_, i64>("SELECT id FROM account_types WHERE name = 'Current'")
                 .fetch_one(pool)
                 .await
                 .unwrap();
@@ -2046,17 +5508,27 @@ mod tests {
                 currency_code,
                 account_classification
             )
-            VALUES ('Everyday', ?, ?, 'GBP', 'asset')
+            VALUES ('Everyday ' || ?, ?, ?, ?, 'asset')
             ",
         )
+        .bind(currency_code)
         .bind(institution_id)
         .bind(type_id)
+        .bind(currency_code)
         .execute(pool)
         .await
         .unwrap()
         .last_insert_rowid()
     }
 
+    async fn account_type_id(pool: &SqlitePool, name: &str) -> i64 {
+        sqlx::query_scalar::<_, i64>("SELECT id FROM account_types WHERE name = ?")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
     async fn insert_snapshot(
         pool: &SqlitePool,
         account_id: i64,
@@ -2088,6 +5560,28 @@ mod tests {
         .unwrap();
     }
 
+    async fn insert_fx_rate(
+        pool: &SqlitePool,
+        base_currency_code: &str,
+        quote_currency_code: &str,
+        rate_date: NaiveDate,
+        rate: f64,
+    ) {
+        sqlx::query(
+            r"
+            INSERT INTO fx_rates (base_currency_code, quote_currency_code, rate_date, rate)
+            VALUES (?, ?, ?, ?)
+            ",
+        )
+        .bind(base_currency_code)
+        .bind(quote_currency_code)
+        .bind(rate_date)
+        .bind(rate)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
     async fn snapshot_balances(pool: &SqlitePool, account_id: i64) -> Vec<(NaiveDate, i64)> {
         db::snapshots_for_account(pool, account_id)
             .await
@@ -2102,7 +5596,7 @@ mod tests {
         serde_json::from_value(json!({
             "kind": "csv",
             "file_name": "snapshots.csv",
-            "content": content,
+            "content": content.as_bytes(),
             "has_header_row": true,
         }))
         .unwrap()
@@ -2134,6 +5628,60 @@ mod tests {
         .unwrap()
     }
 
+    fn wide_input(content: &str) -> CsvSnapshotImportSourceInput {
+        CsvSnapshotImportSourceInput {
+            file_name: "balances.csv".to_string(),
+            content: content.as_bytes().to_vec(),
+            has_header_row: true,
+            encoding: None,
+            delimiter: None,
+            quote: None,
+        }
+    }
+
+    fn wide_options(columns: serde_json::Value) -> WideSnapshotImportOptionsInput {
+        serde_json::from_value(json!({
+            "date_column": "Date",
+            "date_format": "yyyy_mm_dd",
+            "balance_format": "thousands_comma_decimal_dot",
+            "blank_amount_policy": "skip",
+            "columns": columns,
+            "existing_date_policy": "overwrite",
+            "unchanged_value_policy": "exclude",
+            "duplicate_date_policy": "error",
+            "overwrite_existing_confirmed": false,
+        }))
+        .unwrap()
+    }
+
+    fn new_account_input(name: &str, account_type_id: i64) -> serde_json::Value {
+        json!({
+            "institution": { "kind": "new", "input": { "name": "Broker" } },
+            "name": name,
+            "account_type_id": account_type_id,
+            "currency_code": "GBP",
+            "account_classification": "asset",
+            "opened_date": null,
+            "closed_date": null,
+            "tags": [],
+        })
+    }
+
+    fn data_import_input(content: &str) -> DataImportSourceInput {
+        DataImportSourceInput {
+            file_name: "backup.json".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn data_import_options(existing_date_policy: &str, confirmed: bool) -> DataImportOptionsInput {
+        serde_json::from_value(json!({
+            "existing_date_policy": existing_date_policy,
+            "overwrite_existing_confirmed": confirmed,
+        }))
+        .unwrap()
+    }
+
     fn assert_validation_error<T>(result: Result<T, ApiError>, field: &str, message: &str) {
         let Err(ApiError::Validation(issues)) = result else {
             panic!("expected validation error");
@@ -0,0 +1,513 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+use std::path::Path;
+
+use super::{BACKUP_FILE_PREFIX, BACKUP_TIMESTAMP_FORMAT, BackupDto, BackupError};
+use crate::db;
+
+const ARCHIVE_FILE_EXTENSION: &str = "worthbackup";
+const ARCHIVE_MAGIC: &[u8; 8] = b"WRTHBKUP";
+const ARCHIVE_FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// Magic, format version, the three Argon2 parameters, salt, and nonce.
+const HEADER_LEN: usize = ARCHIVE_MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+/// Upper bounds on the Argon2 costs read from a header, so a crafted archive cannot make
+/// key derivation exhaust memory or run for hours.
+const MAX_ARGON2_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ARGON2_TIME_COST: u32 = 16;
+const MAX_ARGON2_LANES: u32 = 16;
+const MANIFEST_FORMAT: &str = "worth-backup";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupArchiveDto {
+    pub file_name: String,
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupArchiveSourceInput {
+    pub file_name: String,
+    /// The whole archive file.
+    pub content: Vec<u8>,
+}
+
+impl BackupArchiveSourceInput {
+    /// Whether the file has the `.worthbackup` extension that archives are written with.
+    pub fn has_archive_extension(&self) -> bool {
+        Path::new(&self.file_name)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(ARCHIVE_FILE_EXTENSION))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupArchiveInspectionDto {
+    pub created_at: DateTime<Utc>,
+    /// Version of the app that wrote the archive.
+    pub app_version: String,
+    /// Newest migration applied to the archived database.
+    pub migration_version: i64,
+}
+
+/// Stored inside the encrypted payload, ahead of the snapshot.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveManifest {
+    format: String,
+    version: u32,
+    created_at: DateTime<Utc>,
+    app_version: String,
+    migration_version: i64,
+    /// Raw SQLCipher key for the snapshot, which is encrypted with it so the database is
+    /// never written to disk in plaintext while the archive is made or read.
+    snapshot_key: String,
+}
+
+/// Writes a `.worthbackup` archive of the live database into `dir`.
+///
+/// The snapshot is a `sqlcipher_export` copy of the database encrypted with a random key.
+/// It is stored after a JSON manifest, and the two are encrypted together with
+/// XChaCha20-Poly1305 under a key derived from `passphrase` with Argon2id. The header is
+/// authenticated as associated data, so any change to the file fails decryption.
+pub async fn create(
+    pool: &SqlitePool,
+    db_path: &Path,
+    dir: &Path,
+    passphrase: &str,
+    app_version: &str,
+    now: DateTime<Utc>,
+) -> Result<BackupArchiveDto, BackupError> {
+    std::fs::create_dir_all(dir)?;
+    let file_name = format!(
+        "{BACKUP_FILE_PREFIX}{}.{ARCHIVE_FILE_EXTENSION}",
+        now.format(BACKUP_TIMESTAMP_FORMAT)
+    );
+    let path = dir.join(&file_name);
+    if path.exists() {
+        return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists).into());
+    }
+
+    let snapshot_key = random_raw_key();
+    let snapshot_path = db::with_suffix(db_path, ".archiving");
+    if snapshot_path.exists() {
+        std::fs::remove_file(&snapshot_path)?;
+    }
+    let mut conn = pool.acquire().await?;
+    let migration_version = db::applied_migration_version(&mut conn, "main")
+        .await?
+        .unwrap_or(0);
+    let exported =
+        db::encryption::export(&mut conn, "main", &snapshot_path, Some(&snapshot_key)).await;
+    drop(conn);
+    let snapshot = match exported {
+        Ok(()) => std::fs::read(&snapshot_path).map_err(BackupError::from),
+        Err(error) => Err(error.into()),
+    };
+    let _ = std::fs::remove_file(&snapshot_path);
+    let snapshot = snapshot?;
+
+    let manifest = ArchiveManifest {
+        format: MANIFEST_FORMAT.to_owned(),
+        version: MANIFEST_VERSION,
+        created_at: now,
+        app_version: app_version.to_owned(),
+        migration_version,
+        snapshot_key,
+    };
+    let manifest = serde_json::to_vec(&manifest).map_err(std::io::Error::other)?;
+    let manifest_len = u32::try_from(manifest.len()).map_err(std::io::Error::other)?;
+    let mut plaintext = Vec::with_capacity(4 + manifest.len() + snapshot.len());
+    plaintext.extend_from_slice(&manifest_len.to_le_bytes());
+    plaintext.extend_from_slice(&manifest);
+    plaintext.extend_from_slice(&snapshot);
+
+    let archive = seal(&plaintext, passphrase)?;
+    std::fs::write(&path, &archive)?;
+
+    Ok(BackupArchiveDto {
+        file_name,
+        path: path.to_string_lossy().into_owned(),
+        created_at: now,
+        size_bytes: archive.len() as u64,
+    })
+}
+
+/// Decrypts an archive and checks its snapshot the same way a restore would, without
+/// changing anything.
+pub async fn inspect(
+    pool: &SqlitePool,
+    db_path: &Path,
+    archive: &[u8],
+    passphrase: &str,
+) -> Result<BackupArchiveInspectionDto, BackupError> {
+    let (manifest, snapshot) = open(archive, passphrase)?;
+    with_snapshot_file(db_path, &snapshot, async |snapshot_path| {
+        let mut conn = pool.acquire().await?;
+        super::attach(&mut conn, snapshot_path, &[Some(&manifest.snapshot_key)]).await?;
+        super::detach(&mut conn).await?;
+        Ok(())
+    })
+    .await?;
+
+    Ok(BackupArchiveInspectionDto {
+        created_at: manifest.created_at,
+        app_version: manifest.app_version,
+        migration_version: manifest.migration_version,
+    })
+}
+
+/// Replaces the database at `db_path` with the snapshot in an archive.
+///
+/// This follows [`super::restore`]: the snapshot is checked and re-encrypted to match the
+/// live database before a `PreRestore` backup is written to `dir` and the files are
+/// swapped, so the app must restart afterwards.
+pub async fn restore(
+    pool: &SqlitePool,
+    db_path: &Path,
+    dir: &Path,
+    archive: &[u8],
+    passphrase: &str,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    let (manifest, snapshot) = open(archive, passphrase)?;
+    let staged_path = with_snapshot_file(db_path, &snapshot, async |snapshot_path| {
+        super::stage(
+            pool,
+            db_path,
+            snapshot_path,
+            &[Some(&manifest.snapshot_key)],
+        )
+        .await
+    })
+    .await?;
+    super::replace_with_staged(pool, db_path, dir, &staged_path, now).await
+}
+
+fn seal(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    let params = Params::default();
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(ARCHIVE_MAGIC);
+    header.push(ARCHIVE_FORMAT_VERSION);
+    header.extend_from_slice(&params.m_cost().to_le_bytes());
+    header.extend_from_slice(&params.t_cost().to_le_bytes());
+    header.extend_from_slice(&params.p_cost().to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let cipher = cipher(passphrase, &salt, params)?;
+    let ciphertext = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| std::io::Error::other("archive encryption failed"))?;
+
+    header.extend_from_slice(&ciphertext);
+    Ok(header)
+}
+
+/// Decrypts an archive and splits it into its manifest and snapshot.
+fn open(archive: &[u8], passphrase: &str) -> Result<(ArchiveManifest, Vec<u8>), BackupError> {
+    if archive.len() < HEADER_LEN || !archive.starts_with(ARCHIVE_MAGIC) {
+        return Err(BackupError::Unreadable);
+    }
+    let (header, ciphertext) = archive.split_at(HEADER_LEN);
+    let mut fields = &header[ARCHIVE_MAGIC.len()..];
+    let format_version = take(&mut fields, 1)[0];
+    if format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(BackupError::Incompatible);
+    }
+    let m_cost = u32::from_le_bytes(take(&mut fields, 4).try_into().unwrap());
+    let t_cost = u32::from_le_bytes(take(&mut fields, 4).try_into().unwrap());
+    let p_cost = u32::from_le_bytes(take(&mut fields, 4).try_into().unwrap());
+    let salt = take(&mut fields, SALT_LEN);
+    let nonce = take(&mut fields, NONCE_LEN);
+    if m_cost > MAX_ARGON2_MEMORY_KIB || t_cost > MAX_ARGON2_TIME_COST || p_cost > MAX_ARGON2_LANES
+    {
+        return Err(BackupError::Unreadable);
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|_| BackupError::Unreadable)?;
+
+    let plaintext = cipher(passphrase, salt, params)?
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| BackupError::WrongPassphrase)?;
+
+    let Some((manifest_len, rest)) = plaintext.split_first_chunk::<4>() else {
+        return Err(BackupError::Unreadable);
+    };
+    let manifest_len = u32::from_le_bytes(*manifest_len) as usize;
+    if rest.len() < manifest_len {
+        return Err(BackupError::Unreadable);
+    }
+    let (manifest, snapshot) = rest.split_at(manifest_len);
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(manifest).map_err(|_| BackupError::Unreadable)?;
+    if manifest.format != MANIFEST_FORMAT {
+        return Err(BackupError::Unreadable);
+    }
+    if manifest.version > MANIFEST_VERSION
+        || manifest.migration_version > db::latest_migration_version()
+    {
+        return Err(BackupError::Incompatible);
+    }
+
+    Ok((manifest, snapshot.to_vec()))
+}
+
+fn cipher(passphrase: &str, salt: &[u8], params: Params) -> Result<XChaCha20Poly1305, BackupError> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| BackupError::Unreadable)?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Writes the still-encrypted snapshot next to the database for `f` to attach, and
+/// removes it again however `f` finishes.
+async fn with_snapshot_file<T>(
+    db_path: &Path,
+    snapshot: &[u8],
+    f: impl AsyncFnOnce(&Path) -> Result<T, BackupError>,
+) -> Result<T, BackupError> {
+    let snapshot_path = db::with_suffix(db_path, ".archive");
+    std::fs::write(&snapshot_path, snapshot)?;
+    let result = f(&snapshot_path).await;
+    let _ = std::fs::remove_file(&snapshot_path);
+    result
+}
+
+/// A random 256-bit key in SQLCipher's raw key syntax, which skips key derivation.
+fn random_raw_key() -> String {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let hex: String = key.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("x'{hex}'")
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> &'a [u8] {
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    taken
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{BackupArchiveSourceInput, create, inspect, open, restore};
+    use crate::backups::{BackupError, BackupKind};
+    use crate::db;
+    use crate::db::test_support::{migrated_pool, set_theme, theme};
+
+    #[test]
+    fn archive_sources_need_the_archive_extension() {
+        let source = |file_name: &str| BackupArchiveSourceInput {
+            file_name: file_name.to_string(),
+            content: Vec::new(),
+        };
+
+        assert!(source("worth-backup-20261018T080000000Z.worthbackup").has_archive_extension());
+        assert!(source("Backup.WorthBackup").has_archive_extension());
+        assert!(!source("worth-backup-20261018T080000000Z-manual.sqlite").has_archive_extension());
+        assert!(!source("worthbackup").has_archive_extension());
+    }
+
+    #[tokio::test]
+    async fn archives_round_trip_and_reject_tampering_and_wrong_passphrases() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let exports_dir = data_dir.path().join("exports");
+        let backups_dir = data_dir.path().join("backups");
+        let db_path = data_dir.path().join("worth.sqlite");
+        let pool = migrated_pool(&db_path, None).await;
+        set_theme(&pool, "light").await;
+        let created_at = Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap();
+
+        let archive = create(
+            &pool,
+            &db_path,
+            &exports_dir,
+            "archive passphrase",
+            "1.2.3",
+            created_at,
+        )
+        .await
+        .unwrap();
+        set_theme(&pool, "dark").await;
+        let bytes = std::fs::read(&archive.path).unwrap();
+        assert!(archive.file_name.ends_with(".worthbackup"));
+        assert!(
+            !bytes
+                .windows(16)
+                .any(|window| window == b"SQLite format 3\0")
+        );
+
+        assert!(matches!(
+            inspect(&pool, &db_path, &bytes, "wrong passphrase").await,
+            Err(BackupError::WrongPassphrase)
+        ));
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            inspect(&pool, &db_path, &tampered, "archive passphrase").await,
+            Err(BackupError::WrongPassphrase)
+        ));
+        assert!(matches!(
+            inspect(&pool, &db_path, b"not an archive", "archive passphrase").await,
+            Err(BackupError::Unreadable)
+        ));
+
+        let inspection = inspect(&pool, &db_path, &bytes, "archive passphrase")
+            .await
+            .unwrap();
+        assert_eq!(inspection.created_at, created_at);
+        assert_eq!(inspection.app_version, "1.2.3");
+        assert_eq!(inspection.migration_version, db::latest_migration_version());
+
+        let safety_backup = restore(
+            &pool,
+            &db_path,
+            &backups_dir,
+            &bytes,
+            "archive passphrase",
+            created_at,
+        )
+        .await
+        .unwrap();
+        assert_eq!(safety_backup.kind, BackupKind::PreRestore);
+        assert!(pool.is_closed());
+        assert_eq!(theme(&db_path).await, "light");
+        assert_eq!(
+            std::fs::read_dir(data_dir.path())
+                .unwrap()
+                .filter_map(|entry| entry.unwrap().file_name().into_string().ok())
+                .filter(|name| name.starts_with("worth.sqlite."))
+                .count(),
+            0
+        );
+    }
+
+    // Archives are copied with `sqlcipher_export` rather than the online backup API, so
+    // this checks that nothing the export skips, such as triggers or the FTS index, is lost.
+    #[tokio::test]
+    async fn restored_archives_keep_the_schema_triggers_and_search_index() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("worth.sqlite");
+        let pool = migrated_pool(&db_path, None).await;
+        sqlx::query(
+            r"
+            INSERT INTO institutions (name) VALUES ('Bank');
+            INSERT INTO accounts (name, institution_id, type_id, currency_code, account_classification)
+            SELECT 'Savings', i.id, t.id, 'GBP', 'asset' FROM institutions AS i, account_types AS t LIMIT 1;
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let created_at = Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap();
+        let live = database_contents(&pool).await;
+
+        let archive = create(
+            &pool,
+            &db_path,
+            &data_dir.path().join("exports"),
+            "archive passphrase",
+            "1.2.3",
+            created_at,
+        )
+        .await
+        .unwrap();
+        restore(
+            &pool,
+            &db_path,
+            &data_dir.path().join("backups"),
+            &std::fs::read(&archive.path).unwrap(),
+            "archive passphrase",
+            created_at,
+        )
+        .await
+        .unwrap();
+
+        let pool = db::open_pool(&db_path, None).await.unwrap();
+        assert_eq!(database_contents(&pool).await, live);
+        sqlx::query("INSERT INTO search_fts (search_fts) VALUES ('integrity-check')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let matches: i64 =
+            sqlx::query_scalar("SELECT count(*) FROM search_fts WHERE search_fts MATCH 'sav*'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(matches, 1);
+        pool.close().await;
+    }
+
+    #[test]
+    fn open_rejects_archives_with_excessive_argon2_costs() {
+        let header = |m_cost: u32, t_cost: u32, p_cost: u32| {
+            let mut header = super::ARCHIVE_MAGIC.to_vec();
+            header.push(super::ARCHIVE_FORMAT_VERSION);
+            header.extend_from_slice(&m_cost.to_le_bytes());
+            header.extend_from_slice(&t_cost.to_le_bytes());
+            header.extend_from_slice(&p_cost.to_le_bytes());
+            header.resize(super::HEADER_LEN, 0);
+            header
+        };
+
+        for costs in [
+            (super::MAX_ARGON2_MEMORY_KIB + 1, 2, 1),
+            (19 * 1024, super::MAX_ARGON2_TIME_COST + 1, 1),
+            (19 * 1024, 2, super::MAX_ARGON2_LANES + 1),
+        ] {
+            assert!(matches!(
+                open(&header(costs.0, costs.1, costs.2), "passphrase"),
+                Err(BackupError::Unreadable)
+            ));
+        }
+    }
+
+    /// Every schema object, including triggers and the FTS shadow tables, and the search
+    /// index rows.
+    async fn database_contents(
+        pool: &sqlx::SqlitePool,
+    ) -> (
+        Vec<(String, String, String, Option<String>)>,
+        Vec<(String, i64, String)>,
+    ) {
+        let schema = sqlx::query_as(
+            "SELECT type, name, tbl_name, sql FROM sqlite_schema ORDER BY type, name",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        let search_index =
+            sqlx::query_as("SELECT kind, entity_id, name FROM search_fts ORDER BY kind, entity_id")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        (schema, search_index)
+    }
+}
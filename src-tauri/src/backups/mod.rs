@@ -0,0 +1,914 @@
+pub mod archive;
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tauri::Manager;
+use tauri::path::BaseDirectory;
+use thiserror::Error;
+
+use crate::contracts::BackupSchedule;
+use crate::db;
+use crate::state::AppState;
+
+const BACKUP_FILE_PREFIX: &str = "worth-backup-";
+const BACKUP_FILE_EXTENSION: &str = ".sqlite";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+/// Keys to attach a backup with. Backups taken while the database was encrypted share its
+/// passphrase, but ones taken before encryption was turned on are plaintext.
+const BACKUP_PASSPHRASES: &[Option<&str>] = &[None, Some("")];
+/// A daily schedule backs up once the newest backup is at least this old.
+const DAILY_BACKUP_INTERVAL: TimeDelta = TimeDelta::hours(24);
+/// Pre-migration backups kept apart from the retention count, so a schedule that backs up
+/// on every launch cannot prune away the copy from before an update.
+const PRE_MIGRATION_BACKUP_RETENTION_COUNT: usize = 3;
+/// How often a running app checks whether a scheduled backup is due.
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    /// Taken by the backup schedule.
+    Scheduled,
+    /// Requested from Settings.
+    Manual,
+    /// The database as it was just before a restore replaced it.
+    PreRestore,
+    /// The database as it was just before startup applied new migrations.
+    PreMigration,
+}
+
+impl BackupKind {
+    fn file_name_part(self) -> &'static str {
+        match self {
+            BackupKind::Scheduled => "scheduled",
+            BackupKind::Manual => "manual",
+            BackupKind::PreRestore => "pre-restore",
+            BackupKind::PreMigration => "pre-migration",
+        }
+    }
+
+    fn from_file_name_part(part: &str) -> Option<Self> {
+        match part {
+            "scheduled" => Some(BackupKind::Scheduled),
+            "manual" => Some(BackupKind::Manual),
+            "pre-restore" => Some(BackupKind::PreRestore),
+            "pre-migration" => Some(BackupKind::PreMigration),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupDto {
+    pub file_name: String,
+    pub kind: BackupKind,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// Set for `PreMigration` backups.
+    pub migration: Option<BackupMigrationDto>,
+}
+
+/// The update a `PreMigration` backup was taken before, recorded in its file name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct BackupMigrationDto {
+    /// Version of Worth that went on to apply new migrations.
+    pub app_version: String,
+    /// Newest migration applied to the database in the backup.
+    pub migration_version: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupListDto {
+    /// Folder backups are read from and written to.
+    pub folder: String,
+    /// Newest first.
+    pub backups: Vec<BackupDto>,
+    /// Why the last scheduled backup check failed, until a later one succeeds.
+    pub last_scheduled_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupsPruneDto {
+    pub deleted_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BackupRestoreDto {
+    /// Copy of the database taken just before it was replaced.
+    pub safety_backup: BackupDto,
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("backup not found")]
+    NotFound,
+    #[error("backup is not a readable Worth database")]
+    Unreadable,
+    #[error("backup needs a newer version of Worth")]
+    Incompatible,
+    #[error("wrong passphrase, or the archive is damaged")]
+    WrongPassphrase,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Db(#[from] sqlx::Error),
+}
+
+pub fn default_backups_dir(app: &tauri::AppHandle) -> tauri::Result<PathBuf> {
+    app.path().resolve("backups", BaseDirectory::AppLocalData)
+}
+
+/// The configured backup folder, or the default one when `folder` is `None`.
+pub fn backups_dir(app: &tauri::AppHandle, folder: Option<&str>) -> tauri::Result<PathBuf> {
+    match folder {
+        Some(folder) => Ok(PathBuf::from(folder)),
+        None => default_backups_dir(app),
+    }
+}
+
+/// Writes a consistent copy of the live database with `VACUUM INTO`, so committed WAL
+/// pages are included without pausing the app or copying the `-wal` and `-shm` files.
+pub async fn create(
+    pool: &SqlitePool,
+    dir: &Path,
+    kind: BackupKind,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    write(pool, dir, kind, None, now).await
+}
+
+/// Writes a `PreMigration` backup whose name records `migration`.
+pub async fn create_pre_migration(
+    pool: &SqlitePool,
+    dir: &Path,
+    migration: BackupMigrationDto,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    write(pool, dir, BackupKind::PreMigration, Some(migration), now).await
+}
+
+async fn write(
+    pool: &SqlitePool,
+    dir: &Path,
+    kind: BackupKind,
+    migration: Option<BackupMigrationDto>,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    std::fs::create_dir_all(dir)?;
+
+    let file_name = backup_file_name(kind, migration.as_ref(), now);
+    let path = dir.join(&file_name);
+    if path.exists() {
+        return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists).into());
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+
+    Ok(BackupDto {
+        file_name,
+        kind,
+        created_at: now,
+        size_bytes: std::fs::metadata(&path)?.len(),
+        migration,
+    })
+}
+
+/// Backups in `dir`, newest first. Files that do not follow the backup naming scheme are
+/// ignored, so a shared folder is never listed or pruned beyond Worth's own files.
+pub fn list(dir: &Path) -> Result<Vec<BackupDto>, BackupError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some(file_name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+        let Some((kind, created_at, migration)) = parse_backup_file_name(&file_name) else {
+            continue;
+        };
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        backups.push(BackupDto {
+            file_name,
+            kind,
+            created_at,
+            size_bytes: metadata.len(),
+            migration,
+        });
+    }
+
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    });
+    Ok(backups)
+}
+
+/// Deletes all but the newest `keep` backups, not counting pre-migration ones, and returns
+/// how many were deleted.
+pub fn prune(dir: &Path, keep: usize) -> Result<u32, BackupError> {
+    prune_where(dir, keep, |kind| kind != BackupKind::PreMigration)
+}
+
+/// Deletes all but the newest [`PRE_MIGRATION_BACKUP_RETENTION_COUNT`] pre-migration
+/// backups and returns how many were deleted.
+pub fn prune_pre_migration(dir: &Path) -> Result<u32, BackupError> {
+    prune_where(dir, PRE_MIGRATION_BACKUP_RETENTION_COUNT, |kind| {
+        kind == BackupKind::PreMigration
+    })
+}
+
+fn prune_where(
+    dir: &Path,
+    keep: usize,
+    include: impl Fn(BackupKind) -> bool,
+) -> Result<u32, BackupError> {
+    let mut deleted_count = 0;
+    for backup in list(dir)?
+        .into_iter()
+        .filter(|backup| include(backup.kind))
+        .skip(keep)
+    {
+        std::fs::remove_file(dir.join(&backup.file_name))?;
+        deleted_count += 1;
+    }
+
+    Ok(deleted_count)
+}
+
+/// Replaces the database at `db_path` with a backup.
+///
+/// The backup is checked and copied before anything changes, then the live database is
+/// backed up as a `PreRestore` backup. The copy is re-encrypted to match the live
+/// database, so restoring never turns encryption on or off. `pool` is closed before the
+/// files are swapped, so the app must restart to open the restored database.
+pub async fn restore(
+    pool: &SqlitePool,
+    db_path: &Path,
+    dir: &Path,
+    file_name: &str,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    let backup_path = backup_path(dir, file_name)?;
+    let staged_path = stage(pool, db_path, &backup_path, BACKUP_PASSPHRASES).await?;
+    replace_with_staged(pool, db_path, dir, &staged_path, now).await
+}
+
+/// Makes the checks [`restore`] makes on a backup, without changing anything. Unlike a
+/// restore, this works on a read-only `pool`.
+pub async fn verify(pool: &SqlitePool, dir: &Path, file_name: &str) -> Result<(), BackupError> {
+    let backup_path = backup_path(dir, file_name)?;
+    let mut conn = pool.acquire().await?;
+    attach(&mut conn, &backup_path, BACKUP_PASSPHRASES).await?;
+    detach(&mut conn).await?;
+    Ok(())
+}
+
+fn backup_path(dir: &Path, file_name: &str) -> Result<PathBuf, BackupError> {
+    if parse_backup_file_name(file_name).is_none() {
+        return Err(BackupError::NotFound);
+    }
+    let backup_path = dir.join(file_name);
+    if !backup_path.is_file() {
+        return Err(BackupError::NotFound);
+    }
+    Ok(backup_path)
+}
+
+/// Checks the database file at `backup_path` and exports it next to the live database,
+/// encrypted with the live database's key, returning the staged copy's path.
+///
+/// The backup is attached with each of `passphrases` in turn until one opens it, where
+/// `None` is the live database's key. Nothing is left behind if the checks fail.
+async fn stage(
+    pool: &SqlitePool,
+    db_path: &Path,
+    backup_path: &Path,
+    passphrases: &[Option<&str>],
+) -> Result<PathBuf, BackupError> {
+    // Stage the copy next to the database so the final rename stays on one filesystem.
+    let staged_path = db::with_suffix(db_path, ".restoring");
+    if staged_path.exists() {
+        std::fs::remove_file(&staged_path)?;
+    }
+
+    let mut conn = pool.acquire().await?;
+    attach(&mut conn, backup_path, passphrases).await?;
+    let exported = db::encryption::export(&mut conn, "backup", &staged_path, None).await;
+    detach(&mut conn).await?;
+    if let Err(error) = exported {
+        let _ = std::fs::remove_file(&staged_path);
+        return Err(error.into());
+    }
+
+    Ok(staged_path)
+}
+
+/// Backs up the live database as a `PreRestore` backup, then swaps `staged_path` in.
+async fn replace_with_staged(
+    pool: &SqlitePool,
+    db_path: &Path,
+    dir: &Path,
+    staged_path: &Path,
+    now: DateTime<Utc>,
+) -> Result<BackupDto, BackupError> {
+    let safety_backup = match create(pool, dir, BackupKind::PreRestore, now).await {
+        Ok(safety_backup) => safety_backup,
+        Err(error) => {
+            let _ = std::fs::remove_file(staged_path);
+            return Err(error);
+        }
+    };
+    db::replace_database(pool, db_path, staged_path).await?;
+
+    Ok(safety_backup)
+}
+
+/// Managed for the life of the app so the backups list can report a scheduled backup
+/// that failed in the background.
+#[derive(Default)]
+pub struct ScheduledBackupStatus {
+    last_error: Mutex<Option<String>>,
+}
+
+impl ScheduledBackupStatus {
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, result: Result<(), BackupError>) {
+        *self
+            .last_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = result.err().map(|error| error.to_string());
+    }
+}
+
+/// Takes a scheduled backup when `schedule` says one is due, then prunes to
+/// `retention_count`. `on_startup` is true only for the check made as the app starts.
+pub async fn create_scheduled_if_due(
+    pool: &SqlitePool,
+    dir: &Path,
+    schedule: BackupSchedule,
+    retention_count: usize,
+    on_startup: bool,
+    now: DateTime<Utc>,
+) -> Result<Option<BackupDto>, BackupError> {
+    let due = match schedule {
+        BackupSchedule::Off => false,
+        BackupSchedule::Startup => on_startup,
+        BackupSchedule::Daily => list(dir)?
+            .first()
+            .is_none_or(|newest| now - newest.created_at >= DAILY_BACKUP_INTERVAL),
+    };
+    if !due {
+        return Ok(None);
+    }
+
+    let backup = create(pool, dir, BackupKind::Scheduled, now).await?;
+    prune(dir, retention_count)?;
+    Ok(Some(backup))
+}
+
+/// Runs the backup schedule for as long as the app is open: once at startup, then every
+/// [`SCHEDULE_CHECK_INTERVAL`]. The pool and settings are read from the managed
+/// [`AppState`] on every check, so a reopened database and changed settings apply
+/// without a restart. The outcome is recorded in [`ScheduledBackupStatus`], and a failed
+/// check is retried at the next one.
+pub fn start_schedule(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut on_startup = true;
+        loop {
+            if let Some(state) = app.try_state::<AppState>()
+                && !state.is_read_only()
+                && !state.pool.is_closed()
+            {
+                let pool = state.pool.clone();
+                let result = run_schedule_check(&app, &pool, on_startup).await;
+                if let Some(status) = app.try_state::<ScheduledBackupStatus>() {
+                    status.record(result);
+                }
+            }
+            on_startup = false;
+            tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+async fn run_schedule_check(
+    app: &tauri::AppHandle,
+    pool: &SqlitePool,
+    on_startup: bool,
+) -> Result<(), BackupError> {
+    let settings = db::app_settings_get(pool).await?;
+    let schedule = settings
+        .backup_schedule
+        .parse()
+        .unwrap_or(BackupSchedule::Off);
+    let dir = backups_dir(app, settings.backup_folder.as_deref())
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    let retention_count = usize::try_from(settings.backup_retention_count).unwrap_or(1);
+
+    create_scheduled_if_due(
+        pool,
+        &dir,
+        schedule,
+        retention_count,
+        on_startup,
+        Utc::now(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Attaches the database file at `backup_path` as `backup` and checks it, detaching it
+/// again if the checks fail.
+async fn attach(
+    conn: &mut SqliteConnection,
+    backup_path: &Path,
+    passphrases: &[Option<&str>],
+) -> Result<(), BackupError> {
+    let backup_path = backup_path.to_string_lossy().into_owned();
+    let mut attached = false;
+    for passphrase in passphrases {
+        let query = match passphrase {
+            Some(passphrase) => sqlx::query("ATTACH DATABASE ? AS backup KEY ?")
+                .bind(&backup_path)
+                .bind(*passphrase),
+            None => sqlx::query("ATTACH DATABASE ? AS backup").bind(&backup_path),
+        };
+        match query.execute(&mut *conn).await {
+            Ok(_) => {
+                attached = true;
+                break;
+            }
+            Err(error) if db::encryption::is_wrong_passphrase(&error) => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+    if !attached {
+        return Err(BackupError::Unreadable);
+    }
+
+    if let Err(error) = check_attached(conn).await {
+        detach(conn).await?;
+        return Err(error);
+    }
+    Ok(())
+}
+
+async fn detach(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    sqlx::query("DETACH DATABASE backup")
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn check_attached(conn: &mut SqliteConnection) -> Result<(), BackupError> {
+    let quick_check = sqlx::query_scalar::<_, String>("PRAGMA backup.quick_check")
+        .fetch_one(&mut *conn)
+        .await;
+    let has_settings = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM backup.sqlite_master WHERE type = 'table' AND name = 'app_settings'",
+    )
+    .fetch_one(&mut *conn)
+    .await;
+    match (quick_check, has_settings) {
+        (Ok(result), Ok(1)) if result == "ok" => {}
+        _ => return Err(BackupError::Unreadable),
+    }
+
+    // A backup from a newer build has migrations this one cannot run past at startup.
+    if !db::incompatible_migrations(conn, "backup")
+        .await?
+        .is_empty()
+    {
+        return Err(BackupError::Incompatible);
+    }
+    Ok(())
+}
+
+fn backup_file_name(
+    kind: BackupKind,
+    migration: Option<&BackupMigrationDto>,
+    created_at: DateTime<Utc>,
+) -> String {
+    let migration = migration
+        .map(|migration| {
+            format!(
+                "-m{:04}-v{}",
+                migration.migration_version, migration.app_version
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "{BACKUP_FILE_PREFIX}{}-{}{migration}{BACKUP_FILE_EXTENSION}",
+        created_at.format(BACKUP_TIMESTAMP_FORMAT),
+        kind.file_name_part()
+    )
+}
+
+fn parse_backup_file_name(
+    file_name: &str,
+) -> Option<(BackupKind, DateTime<Utc>, Option<BackupMigrationDto>)> {
+    let stem = file_name
+        .strip_prefix(BACKUP_FILE_PREFIX)?
+        .strip_suffix(BACKUP_FILE_EXTENSION)?;
+    let (timestamp, kind) = stem.split_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+        .ok()?
+        .and_utc();
+
+    let pre_migration = BackupKind::PreMigration.file_name_part();
+    match kind
+        .strip_prefix(pre_migration)
+        .and_then(|rest| rest.strip_prefix("-m"))
+    {
+        Some(migration) => Some((
+            BackupKind::PreMigration,
+            created_at,
+            Some(parse_backup_migration(migration)?),
+        )),
+        None => Some((BackupKind::from_file_name_part(kind)?, created_at, None)),
+    }
+}
+
+/// Reads the `0009-v0.5.0` that follows `pre-migration-m`. The version may only hold
+/// the characters of a semver version, so a parsed name never leaves the backup folder.
+fn parse_backup_migration(migration: &str) -> Option<BackupMigrationDto> {
+    let (migration_version, app_version) = migration.split_once("-v")?;
+    let valid_version = !app_version.is_empty()
+        && app_version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+    if migration_version.is_empty()
+        || !migration_version.chars().all(|c| c.is_ascii_digit())
+        || !valid_version
+    {
+        return None;
+    }
+
+    Some(BackupMigrationDto {
+        app_version: app_version.to_string(),
+        migration_version: migration_version.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::{
+        BackupError, BackupKind, BackupMigrationDto, ScheduledBackupStatus, create,
+        create_pre_migration, create_scheduled_if_due, list, parse_backup_file_name, prune,
+        prune_pre_migration, restore, verify,
+    };
+    use crate::contracts::BackupSchedule;
+    use crate::db;
+    use crate::db::test_support::{migrated_pool, set_theme, theme};
+
+    #[test]
+    fn scheduled_backup_status_keeps_the_last_error_until_a_check_succeeds() {
+        let status = ScheduledBackupStatus::default();
+
+        status.record(Err(BackupError::Unreadable));
+        assert_eq!(
+            status.last_error().as_deref(),
+            Some("backup is not a readable Worth database")
+        );
+
+        status.record(Ok(()));
+        assert_eq!(status.last_error(), None);
+    }
+
+    #[test]
+    fn backup_file_names_round_trip_and_reject_other_files() {
+        let created_at = at(8);
+        let file_name = super::backup_file_name(BackupKind::PreRestore, None, created_at);
+        let migration = BackupMigrationDto {
+            app_version: "0.5.0-beta.1".to_string(),
+            migration_version: 9,
+        };
+        let pre_migration_file_name =
+            super::backup_file_name(BackupKind::PreMigration, Some(&migration), created_at);
+
+        assert_eq!(
+            file_name,
+            "worth-backup-20261018T080000000Z-pre-restore.sqlite"
+        );
+        assert_eq!(
+            parse_backup_file_name(&file_name),
+            Some((BackupKind::PreRestore, created_at, None))
+        );
+        assert_eq!(
+            pre_migration_file_name,
+            "worth-backup-20261018T080000000Z-pre-migration-m0009-v0.5.0-beta.1.sqlite"
+        );
+        assert_eq!(
+            parse_backup_file_name(&pre_migration_file_name),
+            Some((BackupKind::PreMigration, created_at, Some(migration)))
+        );
+        assert_eq!(
+            parse_backup_file_name(
+                "worth-backup-20261018T080000000Z-pre-migration-m0009-v0.5.0/../x.sqlite"
+            ),
+            None
+        );
+        assert_eq!(parse_backup_file_name("worth.sqlite"), None);
+        assert_eq!(
+            parse_backup_file_name("worth-backup-20261018T080000000Z-other.sqlite"),
+            None
+        );
+        assert_eq!(
+            parse_backup_file_name("worth-backup-../../worth-manual.sqlite"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn scheduled_backups_follow_the_schedule_and_keep_the_newest() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let backups_dir = data_dir.path().join("backups");
+        let pool = migrated_pool(&data_dir.path().join("worth.sqlite"), None).await;
+        std::fs::create_dir_all(&backups_dir).unwrap();
+        std::fs::write(backups_dir.join("notes.txt"), "keep me").unwrap();
+
+        for (schedule, on_startup) in [
+            (BackupSchedule::Off, true),
+            (BackupSchedule::Startup, false),
+        ] {
+            let backup =
+                create_scheduled_if_due(&pool, &backups_dir, schedule, 2, on_startup, at(8))
+                    .await
+                    .unwrap();
+            assert!(backup.is_none());
+        }
+        assert!(
+            create_scheduled_if_due(&pool, &backups_dir, BackupSchedule::Startup, 2, true, at(8))
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            create_scheduled_if_due(&pool, &backups_dir, BackupSchedule::Daily, 2, false, at(20))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        create(&pool, &backups_dir, BackupKind::Manual, at(9))
+            .await
+            .unwrap();
+        let daily =
+            create_scheduled_if_due(&pool, &backups_dir, BackupSchedule::Daily, 2, false, at(33))
+                .await
+                .unwrap()
+                .expect("a day after the newest backup");
+
+        let backups = list(&backups_dir).unwrap();
+        assert_eq!(
+            backups
+                .iter()
+                .map(|backup| (backup.kind, backup.created_at))
+                .collect::<Vec<_>>(),
+            vec![
+                (BackupKind::Scheduled, daily.created_at),
+                (BackupKind::Manual, at(9))
+            ]
+        );
+        assert!(backups.iter().all(|backup| backup.size_bytes > 0));
+        assert!(backups_dir.join("notes.txt").is_file());
+        assert_eq!(prune(&backups_dir, 1).unwrap(), 1);
+        assert_eq!(list(&backups_dir).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pre_migration_backups_have_their_own_retention() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let backups_dir = data_dir.path().join("backups");
+        let pool = migrated_pool(&data_dir.path().join("worth.sqlite"), None).await;
+        for (hour, migration_version) in [(1, 7), (2, 8), (3, 9), (4, 10)] {
+            let migration = BackupMigrationDto {
+                app_version: "0.5.0".to_string(),
+                migration_version,
+            };
+            create_pre_migration(&pool, &backups_dir, migration, at(hour))
+                .await
+                .unwrap();
+        }
+        for hour in [5, 6] {
+            create(&pool, &backups_dir, BackupKind::Scheduled, at(hour))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(prune(&backups_dir, 1).unwrap(), 1);
+        assert_eq!(prune_pre_migration(&backups_dir).unwrap(), 1);
+
+        assert_eq!(
+            list(&backups_dir)
+                .unwrap()
+                .into_iter()
+                .map(|backup| (
+                    backup.kind,
+                    backup
+                        .migration
+                        .map(|migration| migration.migration_version)
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (BackupKind::Scheduled, None),
+                (BackupKind::PreMigration, Some(10)),
+                (BackupKind::PreMigration, Some(9)),
+                (BackupKind::PreMigration, Some(8)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_takes_a_safety_backup_then_replaces_the_database() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let backups_dir = data_dir.path().join("backups");
+        let db_path = data_dir.path().join("worth.sqlite");
+        let pool = migrated_pool(&db_path, None).await;
+        set_theme(&pool, "light").await;
+        let backup = create(&pool, &backups_dir, BackupKind::Manual, at(8))
+            .await
+            .unwrap();
+        set_theme(&pool, "system").await;
+        std::fs::write(
+            backups_dir.join(super::backup_file_name(BackupKind::Manual, None, at(9))),
+            "not a database",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            restore(&pool, &db_path, &backups_dir, "../worth.sqlite", at(10)).await,
+            Err(BackupError::NotFound)
+        ));
+        assert!(matches!(
+            restore(
+                &pool,
+                &db_path,
+                &backups_dir,
+                &super::backup_file_name(BackupKind::Manual, None, at(9)),
+                at(10)
+            )
+            .await,
+            Err(BackupError::Unreadable)
+        ));
+        assert!(!pool.is_closed());
+
+        let safety_backup = restore(&pool, &db_path, &backups_dir, &backup.file_name, at(10))
+            .await
+            .unwrap();
+
+        assert!(pool.is_closed());
+        assert_eq!(safety_backup.kind, BackupKind::PreRestore);
+        assert_eq!(theme(&db_path).await, "light");
+        assert_eq!(
+            theme(&backups_dir.join(&safety_backup.file_name)).await,
+            "system"
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_keeps_the_live_database_encryption() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let backups_dir = data_dir.path().join("backups");
+        let db_path = data_dir.path().join("worth.sqlite");
+        let pool = migrated_pool(&db_path, None).await;
+        set_theme(&pool, "light").await;
+        let plaintext_backup = create(&pool, &backups_dir, BackupKind::Manual, at(8))
+            .await
+            .unwrap();
+        db::encryption::rewrite(&pool, &db_path, Some("passphrase"))
+            .await
+            .unwrap();
+
+        let pool = db::open_pool(&db_path, Some("passphrase")).await.unwrap();
+        set_theme(&pool, "dark").await;
+        let encrypted_backup = create(&pool, &backups_dir, BackupKind::Manual, at(9))
+            .await
+            .unwrap();
+        assert!(
+            db::encryption::is_encrypted(&backups_dir.join(&encrypted_backup.file_name)).unwrap()
+        );
+        restore(
+            &pool,
+            &db_path,
+            &backups_dir,
+            &plaintext_backup.file_name,
+            at(10),
+        )
+        .await
+        .unwrap();
+
+        assert!(db::encryption::is_encrypted(&db_path).unwrap());
+        let pool = db::open_pool(&db_path, Some("passphrase")).await.unwrap();
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "light");
+        restore(
+            &pool,
+            &db_path,
+            &backups_dir,
+            &encrypted_backup.file_name,
+            at(11),
+        )
+        .await
+        .unwrap();
+
+        let pool = db::open_pool(&db_path, Some("passphrase")).await.unwrap();
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "dark");
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn a_database_from_a_newer_build_opens_read_only_and_restores_an_older_backup() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let backups_dir = data_dir.path().join("backups");
+        let db_path = data_dir.path().join("worth.sqlite");
+        let pool = migrated_pool(&db_path, None).await;
+        set_theme(&pool, "light").await;
+        let older_backup = create(&pool, &backups_dir, BackupKind::Manual, at(8))
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES (9999, 'from a newer build', 1, x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        set_theme(&pool, "dark").await;
+        let newer_backup = create(&pool, &backups_dir, BackupKind::Manual, at(9))
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(
+            db::incompatible_migrations(&mut conn, "main")
+                .await
+                .unwrap(),
+            vec![9999]
+        );
+        drop(conn);
+        let pool = db::reopen_pool(&pool, true).await.unwrap();
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "dark");
+        assert!(
+            sqlx::query("UPDATE app_settings SET theme = 'system'")
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+        assert!(matches!(
+            verify(&pool, &backups_dir, &newer_backup.file_name).await,
+            Err(BackupError::Incompatible)
+        ));
+        verify(&pool, &backups_dir, &older_backup.file_name)
+            .await
+            .unwrap();
+
+        let pool = db::reopen_pool(&pool, false).await.unwrap();
+        restore(
+            &pool,
+            &db_path,
+            &backups_dir,
+            &older_backup.file_name,
+            at(10),
+        )
+        .await
+        .unwrap();
+
+        let pool = db::open_pool(&db_path, None).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(
+            db::incompatible_migrations(&mut conn, "main")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        drop(conn);
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "light");
+        pool.close().await;
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap() + chrono::TimeDelta::hours(hour.into())
+    }
+}
@@ -12,64 +12,6 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    Serialize,
-    Deserialize,
-    Type,
-    JsonSchema,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-)]
-#[serde(rename_all = "snake_case")]
-pub enum AccountTypeName {
-    Current,
-    Savings,
-    CreditCard,
-    Isa,
-    Investment,
-    Pension,
-    Cash,
-    Loan,
-}
-
-impl AccountTypeName {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            AccountTypeName::Current => "current",
-            AccountTypeName::Savings => "savings",
-            AccountTypeName::CreditCard => "credit_card",
-            AccountTypeName::Isa => "isa",
-            AccountTypeName::Investment => "investment",
-            AccountTypeName::Pension => "pension",
-            AccountTypeName::Cash => "cash",
-            AccountTypeName::Loan => "loan",
-        }
-    }
-}
-
-impl FromStr for AccountTypeName {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "current" => Ok(AccountTypeName::Current),
-            "savings" => Ok(AccountTypeName::Savings),
-            "credit_card" => Ok(AccountTypeName::CreditCard),
-            "isa" => Ok(AccountTypeName::Isa),
-            "investment" => Ok(AccountTypeName::Investment),
-            "pension" => Ok(AccountTypeName::Pension),
-            "cash" => Ok(AccountTypeName::Cash),
-            "loan" => Ok(AccountTypeName::Loan),
-            _ => Err("Invalid account type"),
-        }
-    }
-}
-
 #[crate::export_schema]
 #[derive(
     Debug,
@@ -111,40 +53,238 @@ impl FromStr for AccountClassification {
     }
 }
 
-#[crate::export_schema]
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    Serialize,
-    Deserialize,
-    Type,
-    JsonSchema,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-)]
-pub enum CurrencyCode {
-    GBP,
-}
+// ISO 4217 currencies that have a defined minor unit, paired with the number of
+// decimal places that unit represents. Funds and precious-metal codes without a
+// minor unit (XAU, XDR, ...) are deliberately left out.
+macro_rules! currency_codes {
+    ($($code:ident => $minor_unit_exponent:literal,)+) => {
+        #[crate::export_schema]
+        #[derive(
+            Debug,
+            Clone,
+            Copy,
+            Serialize,
+            Deserialize,
+            Type,
+            JsonSchema,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+        )]
+        pub enum CurrencyCode {
+            $($code,)+
+        }
 
-impl CurrencyCode {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            CurrencyCode::GBP => "GBP",
+        impl CurrencyCode {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(CurrencyCode::$code => stringify!($code),)+
+                }
+            }
+
+            /// Number of decimal places between the major and minor unit, e.g. 2
+            /// for GBP (pence), 0 for JPY and 3 for BHD.
+            pub fn minor_unit_exponent(self) -> u32 {
+                match self {
+                    $(CurrencyCode::$code => $minor_unit_exponent,)+
+                }
+            }
         }
-    }
-}
 
-impl FromStr for CurrencyCode {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "GBP" => Ok(CurrencyCode::GBP),
-            _ => Err("Invalid currency code"),
+        impl FromStr for CurrencyCode {
+            type Err = &'static str;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($code) => Ok(CurrencyCode::$code),)+
+                    _ => Err("Invalid currency code"),
+                }
+            }
         }
+    };
+}
+
+currency_codes! {
+    AED => 2,
+    AFN => 2,
+    ALL => 2,
+    AMD => 2,
+    AOA => 2,
+    ARS => 2,
+    AUD => 2,
+    AWG => 2,
+    AZN => 2,
+    BAM => 2,
+    BBD => 2,
+    BDT => 2,
+    BGN => 2,
+    BHD => 3,
+    BIF => 0,
+    BMD => 2,
+    BND => 2,
+    BOB => 2,
+    BOV => 2,
+    BRL => 2,
+    BSD => 2,
+    BTN => 2,
+    BWP => 2,
+    BYN => 2,
+    BZD => 2,
+    CAD => 2,
+    CDF => 2,
+    CHE => 2,
+    CHF => 2,
+    CHW => 2,
+    CLF => 4,
+    CLP => 0,
+    CNY => 2,
+    COP => 2,
+    COU => 2,
+    CRC => 2,
+    CUP => 2,
+    CVE => 2,
+    CZK => 2,
+    DJF => 0,
+    DKK => 2,
+    DOP => 2,
+    DZD => 2,
+    EGP => 2,
+    ERN => 2,
+    ETB => 2,
+    EUR => 2,
+    FJD => 2,
+    FKP => 2,
+    GBP => 2,
+    GEL => 2,
+    GHS => 2,
+    GIP => 2,
+    GMD => 2,
+    GNF => 0,
+    GTQ => 2,
+    GYD => 2,
+    HKD => 2,
+    HNL => 2,
+    HTG => 2,
+    HUF => 2,
+    IDR => 2,
+    ILS => 2,
+    INR => 2,
+    IQD => 3,
+    IRR => 2,
+    ISK => 0,
+    JMD => 2,
+    JOD => 3,
+    JPY => 0,
+    KES => 2,
+    KGS => 2,
+    KHR => 2,
+    KMF => 0,
+    KPW => 2,
+    KRW => 0,
+    KWD => 3,
+    KYD => 2,
+    KZT => 2,
+    LAK => 2,
+    LBP => 2,
+    LKR => 2,
+    LRD => 2,
+    LSL => 2,
+    LYD => 3,
+    MAD => 2,
+    MDL => 2,
+    MGA => 2,
+    MKD => 2,
+    MMK => 2,
+    MNT => 2,
+    MOP => 2,
+    MRU => 2,
+    MUR => 2,
+    MVR => 2,
+    MWK => 2,
+    MXN => 2,
+    MXV => 2,
+    MYR => 2,
+    MZN => 2,
+    NAD => 2,
+    NGN => 2,
+    NIO => 2,
+    NOK => 2,
+    NPR => 2,
+    NZD => 2,
+    OMR => 3,
+    PAB => 2,
+    PEN => 2,
+    PGK => 2,
+    PHP => 2,
+    PKR => 2,
+    PLN => 2,
+    PYG => 0,
+    QAR => 2,
+    RON => 2,
+    RSD => 2,
+    RUB => 2,
+    RWF => 0,
+    SAR => 2,
+    SBD => 2,
+    SCR => 2,
+    SDG => 2,
+    SEK => 2,
+    SGD => 2,
+    SHP => 2,
+    SLE => 2,
+    SOS => 2,
+    SRD => 2,
+    SSP => 2,
+    STN => 2,
+    SVC => 2,
+    SYP => 2,
+    SZL => 2,
+    THB => 2,
+    TJS => 2,
+    TMT => 2,
+    TND => 3,
+    TOP => 2,
+    TRY => 2,
+    TTD => 2,
+    TWD => 2,
+    TZS => 2,
+    UAH => 2,
+    UGX => 0,
+    USD => 2,
+    USN => 2,
+    UYI => 0,
+    UYU => 2,
+    UYW => 4,
+    UZS => 2,
+    VED => 2,
+    VES => 2,
+    VND => 0,
+    VUV => 0,
+    WST => 2,
+    XAF => 0,
+    XCD => 2,
+    XCG => 2,
+    XOF => 0,
+    XPF => 0,
+    YER => 2,
+    ZAR => 2,
+    ZMW => 2,
+    ZWG => 2,
+}
+
+impl CurrencyCode {
+    /// Largest absolute balance accepted for this currency, in minor units.
+    ///
+    /// Every currency gets the same range of whole units as a two-decimal
+    /// currency (just under one trillion), capped by [`BALANCE_MINOR_ABS_MAX`]
+    /// so currencies with three or four decimals stay within the stored range.
+    pub fn balance_minor_abs_max(self) -> i64 {
+        10_i64
+            .checked_pow(12 + self.minor_unit_exponent())
+            .map_or(BALANCE_MINOR_ABS_MAX, |limit| {
+                (limit - 1).min(BALANCE_MINOR_ABS_MAX)
+            })
     }
 }
 
@@ -234,6 +374,52 @@ impl FromStr for AppLocaleCode {
     }
 }
 
+// `Startup` backs up every time the app starts; `Daily` backs up once the newest backup is
+// at least a day old.
+#[crate::export_schema]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    Type,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupSchedule {
+    Off,
+    Startup,
+    Daily,
+}
+
+impl BackupSchedule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackupSchedule::Off => "off",
+            BackupSchedule::Startup => "startup",
+            BackupSchedule::Daily => "daily",
+        }
+    }
+}
+
+impl FromStr for BackupSchedule {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(BackupSchedule::Off),
+            "startup" => Ok(BackupSchedule::Startup),
+            "daily" => Ok(BackupSchedule::Daily),
+            _ => Err("Invalid backup schedule"),
+        }
+    }
+}
+
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
 pub struct AppSettingsUpdateInput {
@@ -249,10 +435,35 @@ pub struct AppSettingsUpdateInput {
     #[garde(skip)]
     #[specta(optional)]
     pub theme: Option<ThemePreference>,
+    #[garde(skip)]
+    #[specta(optional)]
+    pub backup_schedule: Option<BackupSchedule>,
+    #[garde(custom(validate_backup_retention_count))]
+    #[schemars(
+        range(min = 1, max = 100),
+        extend("x-validation" = ::serde_json::json!({
+            "minimum": BACKUP_RETENTION_COUNT_RANGE,
+            "maximum": BACKUP_RETENTION_COUNT_RANGE,
+            "type": BACKUP_RETENTION_COUNT_RANGE
+        }))
+    )]
+    #[specta(optional)]
+    pub backup_retention_count: Option<u32>,
+    // Absolute path of the backup folder; an empty string switches back to the default folder.
+    #[garde(custom(validate_backup_folder))]
+    #[schemars(
+        length(max = 1024),
+        extend("x-validation" = ::serde_json::json!({
+            "maxLength": BACKUP_FOLDER_MAX_LENGTH
+        }))
+    )]
+    #[specta(optional)]
+    pub backup_folder: Option<String>,
 }
 
 // Keep balances comfortably within JavaScript's safe integer range because
-// generated IPC bindings cast Rust i64 values to TypeScript number.
+// generated IPC bindings cast Rust i64 values to TypeScript number. Per-currency
+// limits come from `CurrencyCode::balance_minor_abs_max`.
 pub(crate) const BALANCE_MINOR_ABS_MAX: i64 = 99_999_999_999_999;
 
 const INSTITUTION_NAME_REQUIRED: &str = "Enter an institution name";
@@ -261,12 +472,27 @@ const ACCOUNT_NAME_REQUIRED: &str = "Enter an account name";
 const ACCOUNT_NAME_MAX_LENGTH: &str = "Account name must be 80 characters or fewer";
 const INSTITUTION_REQUIRED: &str = "Select or create an institution";
 const ACCOUNT_TYPE_REQUIRED: &str = "Select an account type";
+const ACCOUNT_TYPE_NAME_REQUIRED: &str = "Enter an account type name";
+const ACCOUNT_TYPE_NAME_MAX_LENGTH: &str = "Account type name must be 80 characters or fewer";
+const ACCOUNT_TYPE_COLOR_INVALID: &str = "Choose a colour";
+const TAG_NAME_REQUIRED: &str = "Enter a tag name";
+const TAG_NAME_MAX_LENGTH: &str = "Tags must be 40 characters or fewer";
+const TAGS_TOO_MANY: &str = "Add 20 tags or fewer";
 const CURRENCY_REQUIRED: &str = "Select a currency";
 const ACCOUNT_CLASSIFICATION_REQUIRED: &str = "Select a balance type";
 const SNAPSHOT_REQUIRED: &str = "Add at least one snapshot";
 const SNAPSHOT_SELECTION_REQUIRED: &str = "Select at least one snapshot";
 const BALANCE_REQUIRED: &str = "Enter a balance";
 const BALANCE_TOO_LARGE: &str = "Balance is too large";
+const SNAPSHOT_NOTE_MAX_LENGTH: &str = "Note must be 500 characters or fewer";
+const SNAPSHOT_SOURCE_REQUIRED: &str = "Enter where this balance came from";
+const SNAPSHOT_SOURCE_MAX_LENGTH: &str = "Source must be 255 characters or fewer";
+const FX_RATE_REQUIRED: &str = "Enter a rate";
+const FX_RATE_NOT_POSITIVE: &str = "Rate must be greater than 0";
+const FX_RATE_SELECTION_REQUIRED: &str = "Select at least one rate";
+const BACKUP_RETENTION_COUNT_RANGE: &str = "Keep between 1 and 100 backups";
+const BACKUP_FOLDER_NOT_ABSOLUTE: &str = "Enter a full folder path";
+const BACKUP_FOLDER_MAX_LENGTH: &str = "Folder path must be 1024 characters or fewer";
 
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
@@ -309,6 +535,43 @@ pub enum InstitutionRef {
     },
 }
 
+#[crate::export_schema]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+pub struct AccountTypeUpsertInput {
+    #[garde(custom(validate_account_type_name))]
+    #[schemars(
+        length(min = 1, max = 80),
+        pattern(r".*\S.*"),
+        extend("x-validation" = ::serde_json::json!({
+            "required": ACCOUNT_TYPE_NAME_REQUIRED,
+            "blank": ACCOUNT_TYPE_NAME_REQUIRED,
+            "maxLength": ACCOUNT_TYPE_NAME_MAX_LENGTH,
+            "type": ACCOUNT_TYPE_NAME_REQUIRED
+        }))
+    )]
+    pub name: String,
+    #[garde(custom(validate_account_type_color))]
+    #[schemars(
+        pattern(r"^#[0-9A-Fa-f]{6}$"),
+        extend("x-validation" = ::serde_json::json!({
+            "required": ACCOUNT_TYPE_COLOR_INVALID,
+            "pattern": ACCOUNT_TYPE_COLOR_INVALID,
+            "type": ACCOUNT_TYPE_COLOR_INVALID
+        }))
+    )]
+    // Hex colour in `#rrggbb` form; stored lowercase.
+    pub color: String,
+}
+
+#[crate::export_schema]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+pub struct AccountTypeDeleteInput {
+    // Type that receives the deleted type's accounts; required when any account uses it.
+    #[garde(skip)]
+    #[specta(optional)]
+    pub replacement_type_id: Option<i64>,
+}
+
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
 pub struct AccountUpsertInput {
@@ -331,13 +594,16 @@ pub struct AccountUpsertInput {
         }))
     )]
     pub name: String,
-    #[garde(skip)]
-    #[schemars(extend("x-validation" = ::serde_json::json!({
-        "required": ACCOUNT_TYPE_REQUIRED,
-        "invalid": ACCOUNT_TYPE_REQUIRED,
-        "type": ACCOUNT_TYPE_REQUIRED
-    })))]
-    pub account_type: AccountTypeName,
+    #[garde(custom(validate_account_type_id))]
+    #[schemars(
+        range(min = 1),
+        extend("x-validation" = ::serde_json::json!({
+            "required": ACCOUNT_TYPE_REQUIRED,
+            "minimum": ACCOUNT_TYPE_REQUIRED,
+            "type": ACCOUNT_TYPE_REQUIRED
+        }))
+    )]
+    pub account_type_id: i64,
     #[garde(skip)]
     #[schemars(extend("x-validation" = ::serde_json::json!({
         "required": CURRENCY_REQUIRED,
@@ -358,10 +624,64 @@ pub struct AccountUpsertInput {
     #[garde(skip)]
     #[specta(optional)]
     pub closed_date: Option<NaiveDate>,
+    // Tag names; matched case-insensitively and created when they do not exist yet.
+    #[garde(custom(validate_account_tags))]
+    #[schemars(extend("x-validation" = ::serde_json::json!({
+        "type": TAG_NAME_REQUIRED
+    })))]
+    pub tags: Vec<String>,
+}
+
+/// Where a snapshot balance came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type, JsonSchema)]
+#[schemars(extend("discriminator" = ::serde_json::json!({"propertyName": "kind"})))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotSource {
+    Manual,
+    CsvImport { file_name: String },
+    OfxImport { file_name: String },
+    SpreadsheetImport { file_name: String },
+    Api { provider: String },
+}
+
+impl SnapshotSource {
+    pub fn kind_str(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::CsvImport { .. } => "csv_import",
+            Self::OfxImport { .. } => "ofx_import",
+            Self::SpreadsheetImport { .. } => "spreadsheet_import",
+            Self::Api { .. } => "api",
+        }
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Manual => None,
+            Self::CsvImport { file_name }
+            | Self::OfxImport { file_name }
+            | Self::SpreadsheetImport { file_name } => Some(file_name),
+            Self::Api { provider } => Some(provider),
+        }
+    }
+
+    /// Rebuilds a source from its stored `source_kind` and `source_detail` columns.
+    pub fn from_columns(kind: Option<&str>, detail: Option<&str>) -> Option<Self> {
+        let detail = detail.unwrap_or_default().to_string();
+        match kind? {
+            "manual" => Some(Self::Manual),
+            "csv_import" => Some(Self::CsvImport { file_name: detail }),
+            "ofx_import" => Some(Self::OfxImport { file_name: detail }),
+            "spreadsheet_import" => Some(Self::SpreadsheetImport { file_name: detail }),
+            "api" => Some(Self::Api { provider: detail }),
+            _ => None,
+        }
+    }
 }
 
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+#[garde(context(CurrencyCode))]
 pub struct AccountSnapshotWriteInput {
     #[garde(skip)]
     pub date: NaiveDate,
@@ -377,12 +697,26 @@ pub struct AccountSnapshotWriteInput {
     )]
     // Signed balance in minor units; liability accounts are entered as negative balances.
     pub balance_minor: i64,
+    #[garde(custom(validate_snapshot_note))]
+    #[schemars(
+        length(max = 500),
+        extend("x-validation" = ::serde_json::json!({
+            "maxLength": SNAPSHOT_NOTE_MAX_LENGTH
+        }))
+    )]
+    #[specta(optional)]
+    pub note: Option<String>,
+    // Defaults to manual entry when omitted.
+    #[garde(custom(validate_snapshot_source))]
+    #[specta(optional)]
+    pub source: Option<SnapshotSource>,
     #[garde(skip)]
     pub overwrite_existing: bool,
 }
 
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+#[garde(context(CurrencyCode))]
 pub struct AccountSnapshotsCreateInput {
     #[garde(custom(validate_snapshots_non_empty), dive)]
     #[schemars(length(min = 1), extend("x-validation" = ::serde_json::json!({
@@ -395,6 +729,7 @@ pub struct AccountSnapshotsCreateInput {
 
 #[crate::export_schema]
 #[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+#[garde(context(CurrencyCode))]
 pub struct AccountSnapshotUpdateInput {
     #[garde(skip)]
     pub date: NaiveDate,
@@ -409,6 +744,16 @@ pub struct AccountSnapshotUpdateInput {
         }))
     )]
     pub balance_minor: i64,
+    // Editing keeps the snapshot's recorded source.
+    #[garde(custom(validate_snapshot_note))]
+    #[schemars(
+        length(max = 500),
+        extend("x-validation" = ::serde_json::json!({
+            "maxLength": SNAPSHOT_NOTE_MAX_LENGTH
+        }))
+    )]
+    #[specta(optional)]
+    pub note: Option<String>,
     #[garde(skip)]
     pub overwrite_existing: bool,
 }
@@ -425,6 +770,46 @@ pub struct AccountSnapshotsDeleteInput {
     pub snapshot_ids: Vec<i64>,
 }
 
+#[crate::export_schema]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+pub struct FxRateWriteInput {
+    #[garde(skip)]
+    #[schemars(extend("x-validation" = ::serde_json::json!({
+        "required": CURRENCY_REQUIRED,
+        "invalid": CURRENCY_REQUIRED,
+        "type": CURRENCY_REQUIRED
+    })))]
+    pub base_currency_code: CurrencyCode,
+    #[garde(skip)]
+    #[schemars(extend("x-validation" = ::serde_json::json!({
+        "required": CURRENCY_REQUIRED,
+        "invalid": CURRENCY_REQUIRED,
+        "type": CURRENCY_REQUIRED
+    })))]
+    pub quote_currency_code: CurrencyCode,
+    #[garde(skip)]
+    pub date: NaiveDate,
+    #[garde(custom(validate_fx_rate))]
+    #[schemars(extend("x-validation" = ::serde_json::json!({
+        "required": FX_RATE_REQUIRED,
+        "type": FX_RATE_REQUIRED
+    })))]
+    // Units of the quote currency per one unit of the base currency.
+    pub rate: f64,
+}
+
+#[crate::export_schema]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema, Validate)]
+pub struct FxRatesDeleteInput {
+    #[garde(custom(validate_fx_rate_ids_non_empty))]
+    #[schemars(length(min = 1), extend("x-validation" = ::serde_json::json!({
+        "required": FX_RATE_SELECTION_REQUIRED,
+        "minItems": FX_RATE_SELECTION_REQUIRED,
+        "type": FX_RATE_SELECTION_REQUIRED
+    })))]
+    pub rate_ids: Vec<i64>,
+}
+
 fn validate_institution_name(value: &str, _ctx: &()) -> garde::Result {
     validate_name(
         value,
@@ -437,6 +822,52 @@ fn validate_account_name(value: &str, _ctx: &()) -> garde::Result {
     validate_name(value, ACCOUNT_NAME_REQUIRED, ACCOUNT_NAME_MAX_LENGTH)
 }
 
+fn validate_account_type_name(value: &str, _ctx: &()) -> garde::Result {
+    validate_name(
+        value,
+        ACCOUNT_TYPE_NAME_REQUIRED,
+        ACCOUNT_TYPE_NAME_MAX_LENGTH,
+    )
+}
+
+fn validate_account_type_color(value: &str, _ctx: &()) -> garde::Result {
+    let is_hex_color = value.len() == 7
+        && value.starts_with('#')
+        && value[1..].chars().all(|ch| ch.is_ascii_hexdigit());
+    if !is_hex_color {
+        return Err(garde::Error::new(ACCOUNT_TYPE_COLOR_INVALID));
+    }
+
+    Ok(())
+}
+
+fn validate_backup_retention_count(value: &Option<u32>, _ctx: &()) -> garde::Result {
+    if let Some(count) = value
+        && !(1..=100).contains(count)
+    {
+        return Err(garde::Error::new(BACKUP_RETENTION_COUNT_RANGE));
+    }
+
+    Ok(())
+}
+
+fn validate_backup_folder(value: &Option<String>, _ctx: &()) -> garde::Result {
+    let Some(folder) = value.as_deref().map(str::trim) else {
+        return Ok(());
+    };
+    if folder.is_empty() {
+        return Ok(());
+    }
+    if folder.chars().count() > 1024 {
+        return Err(garde::Error::new(BACKUP_FOLDER_MAX_LENGTH));
+    }
+    if !std::path::Path::new(folder).is_absolute() {
+        return Err(garde::Error::new(BACKUP_FOLDER_NOT_ABSOLUTE));
+    }
+
+    Ok(())
+}
+
 fn validate_name(value: &str, empty_message: &str, max_length_message: &str) -> garde::Result {
     if value.is_empty() {
         return Err(garde::Error::new(empty_message));
@@ -449,14 +880,68 @@ fn validate_name(value: &str, empty_message: &str, max_length_message: &str) ->
     Ok(())
 }
 
-fn validate_balance_minor(value: &i64, _ctx: &()) -> garde::Result {
-    if !(-BALANCE_MINOR_ABS_MAX..=BALANCE_MINOR_ABS_MAX).contains(value) {
+fn validate_balance_minor(value: &i64, currency_code: &CurrencyCode) -> garde::Result {
+    let abs_max = currency_code.balance_minor_abs_max();
+    if !(-abs_max..=abs_max).contains(value) {
         return Err(garde::Error::new(BALANCE_TOO_LARGE));
     }
 
     Ok(())
 }
 
+fn validate_account_type_id(value: &i64, _ctx: &()) -> garde::Result {
+    if *value < 1 {
+        return Err(garde::Error::new(ACCOUNT_TYPE_REQUIRED));
+    }
+
+    Ok(())
+}
+
+fn validate_account_tags(value: &[String], _ctx: &()) -> garde::Result {
+    if value.len() > 20 {
+        return Err(garde::Error::new(TAGS_TOO_MANY));
+    }
+
+    for tag in value {
+        if tag.trim().is_empty() {
+            return Err(garde::Error::new(TAG_NAME_REQUIRED));
+        }
+
+        if tag.chars().count() > 40 {
+            return Err(garde::Error::new(TAG_NAME_MAX_LENGTH));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_snapshot_note(value: &Option<String>, _ctx: &CurrencyCode) -> garde::Result {
+    if value
+        .as_deref()
+        .is_some_and(|note| note.chars().count() > 500)
+    {
+        return Err(garde::Error::new(SNAPSHOT_NOTE_MAX_LENGTH));
+    }
+
+    Ok(())
+}
+
+fn validate_snapshot_source(value: &Option<SnapshotSource>, _ctx: &CurrencyCode) -> garde::Result {
+    let Some(detail) = value.as_ref().and_then(SnapshotSource::detail) else {
+        return Ok(());
+    };
+
+    if detail.trim().is_empty() {
+        return Err(garde::Error::new(SNAPSHOT_SOURCE_REQUIRED));
+    }
+
+    if detail.chars().count() > 255 {
+        return Err(garde::Error::new(SNAPSHOT_SOURCE_MAX_LENGTH));
+    }
+
+    Ok(())
+}
+
 fn validate_institution_id(value: &i64, _ctx: &()) -> garde::Result {
     if *value < 1 {
         return Err(garde::Error::new(INSTITUTION_REQUIRED));
@@ -465,7 +950,10 @@ fn validate_institution_id(value: &i64, _ctx: &()) -> garde::Result {
     Ok(())
 }
 
-fn validate_snapshots_non_empty(value: &[AccountSnapshotWriteInput], _ctx: &()) -> garde::Result {
+fn validate_snapshots_non_empty(
+    value: &[AccountSnapshotWriteInput],
+    _ctx: &CurrencyCode,
+) -> garde::Result {
     if value.is_empty() {
         return Err(garde::Error::new(SNAPSHOT_REQUIRED));
     }
@@ -481,14 +969,34 @@ fn validate_snapshot_ids_non_empty(value: &[i64], _ctx: &()) -> garde::Result {
     Ok(())
 }
 
+fn validate_fx_rate(value: &f64, _ctx: &()) -> garde::Result {
+    if !value.is_finite() || *value <= 0.0 {
+        return Err(garde::Error::new(FX_RATE_NOT_POSITIVE));
+    }
+
+    Ok(())
+}
+
+fn validate_fx_rate_ids_non_empty(value: &[i64], _ctx: &()) -> garde::Result {
+    if value.is_empty() {
+        return Err(garde::Error::new(FX_RATE_SELECTION_REQUIRED));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDate;
     use garde::Validate;
 
     use super::{
-        AccountSnapshotUpdateInput, AccountSnapshotWriteInput, BALANCE_MINOR_ABS_MAX,
-        BALANCE_TOO_LARGE, InstitutionUpsertInput,
+        ACCOUNT_TYPE_COLOR_INVALID, AccountClassification, AccountSnapshotUpdateInput,
+        AccountSnapshotWriteInput, AccountTypeUpsertInput, AccountUpsertInput,
+        BALANCE_MINOR_ABS_MAX, BALANCE_TOO_LARGE, CurrencyCode, FX_RATE_NOT_POSITIVE,
+        FxRateWriteInput, InstitutionRef, InstitutionUpsertInput, SNAPSHOT_NOTE_MAX_LENGTH,
+        SNAPSHOT_SOURCE_MAX_LENGTH, SNAPSHOT_SOURCE_REQUIRED, SnapshotSource, TAG_NAME_MAX_LENGTH,
+        TAG_NAME_REQUIRED, TAGS_TOO_MANY,
     };
 
     #[test]
@@ -496,10 +1004,12 @@ mod tests {
         let input = AccountSnapshotWriteInput {
             date: date(),
             balance_minor: BALANCE_MINOR_ABS_MAX + 1,
+            note: None,
+            source: None,
             overwrite_existing: false,
         };
 
-        assert_validation_message(input.validate(), BALANCE_TOO_LARGE);
+        assert_validation_message(input.validate_with(&CurrencyCode::GBP), BALANCE_TOO_LARGE);
     }
 
     #[test]
@@ -507,10 +1017,152 @@ mod tests {
         let input = AccountSnapshotUpdateInput {
             date: date(),
             balance_minor: -BALANCE_MINOR_ABS_MAX - 1,
+            note: None,
+            overwrite_existing: false,
+        };
+
+        assert_validation_message(input.validate_with(&CurrencyCode::GBP), BALANCE_TOO_LARGE);
+    }
+
+    #[test]
+    fn snapshot_balance_limits_follow_the_currency_minor_unit_exponent() {
+        let input = |balance_minor| AccountSnapshotWriteInput {
+            date: date(),
+            balance_minor,
+            note: None,
+            source: None,
             overwrite_existing: false,
         };
 
-        assert_validation_message(input.validate(), BALANCE_TOO_LARGE);
+        assert!(
+            input(999_999_999_999)
+                .validate_with(&CurrencyCode::JPY)
+                .is_ok()
+        );
+        assert_validation_message(
+            input(1_000_000_000_000).validate_with(&CurrencyCode::JPY),
+            BALANCE_TOO_LARGE,
+        );
+        assert!(
+            input(BALANCE_MINOR_ABS_MAX)
+                .validate_with(&CurrencyCode::BHD)
+                .is_ok()
+        );
+        assert_validation_message(
+            input(BALANCE_MINOR_ABS_MAX + 1).validate_with(&CurrencyCode::BHD),
+            BALANCE_TOO_LARGE,
+        );
+    }
+
+    #[test]
+    fn currency_codes_round_trip_with_iso_4217_minor_units() {
+        for (code, exponent) in [("GBP", 2), ("EUR", 2), ("USD", 2), ("JPY", 0), ("BHD", 3)] {
+            let currency_code = code.parse::<CurrencyCode>().unwrap();
+
+            assert_eq!(currency_code.as_str(), code);
+            assert_eq!(currency_code.minor_unit_exponent(), exponent);
+        }
+        assert!("XAU".parse::<CurrencyCode>().is_err());
+        assert_eq!(
+            CurrencyCode::GBP.balance_minor_abs_max(),
+            BALANCE_MINOR_ABS_MAX
+        );
+    }
+
+    #[test]
+    fn fx_rate_inputs_require_a_finite_positive_rate() {
+        let input = |rate| FxRateWriteInput {
+            base_currency_code: CurrencyCode::GBP,
+            quote_currency_code: CurrencyCode::EUR,
+            date: date(),
+            rate,
+        };
+
+        assert!(input(1.1612).validate().is_ok());
+        for rate in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert_validation_message(input(rate).validate(), FX_RATE_NOT_POSITIVE);
+        }
+    }
+
+    #[test]
+    fn snapshot_notes_and_sources_are_limited_in_length() {
+        let input =
+            |note: Option<String>, source: Option<SnapshotSource>| AccountSnapshotWriteInput {
+                date: date(),
+                balance_minor: 100,
+                note,
+                source,
+                overwrite_existing: false,
+            };
+
+        assert!(
+            input(Some("💷".repeat(500)), Some(SnapshotSource::Manual))
+                .validate_with(&CurrencyCode::GBP)
+                .is_ok()
+        );
+        assert_validation_message(
+            input(Some("💷".repeat(501)), None).validate_with(&CurrencyCode::GBP),
+            SNAPSHOT_NOTE_MAX_LENGTH,
+        );
+        assert_validation_message(
+            input(
+                None,
+                Some(SnapshotSource::Api {
+                    provider: " ".to_string(),
+                }),
+            )
+            .validate_with(&CurrencyCode::GBP),
+            SNAPSHOT_SOURCE_REQUIRED,
+        );
+        assert_validation_message(
+            input(
+                None,
+                Some(SnapshotSource::CsvImport {
+                    file_name: "a".repeat(256),
+                }),
+            )
+            .validate_with(&CurrencyCode::GBP),
+            SNAPSHOT_SOURCE_MAX_LENGTH,
+        );
+    }
+
+    #[test]
+    fn account_type_inputs_require_a_hex_colour() {
+        let input = |color: &str| AccountTypeUpsertInput {
+            name: "Premium Bonds".to_string(),
+            color: color.to_string(),
+        };
+
+        assert!(input("#0ea5E9").validate().is_ok());
+        for color in ["0ea5e9", "#0ea5e", "#0ea5eg", "blue"] {
+            assert_validation_message(input(color).validate(), ACCOUNT_TYPE_COLOR_INVALID);
+        }
+    }
+
+    #[test]
+    fn account_tags_are_limited_in_length_and_count() {
+        let input = |tags: Vec<String>| AccountUpsertInput {
+            institution: InstitutionRef::Existing { id: 1 },
+            name: "Everyday".to_string(),
+            account_type_id: 1,
+            currency_code: CurrencyCode::GBP,
+            account_classification: AccountClassification::Asset,
+            opened_date: None,
+            closed_date: None,
+            tags,
+        };
+
+        assert!(
+            input(vec!["Joint".to_string(), "💷".repeat(40)])
+                .validate()
+                .is_ok()
+        );
+        assert_validation_message(input(vec![" ".to_string()]).validate(), TAG_NAME_REQUIRED);
+        assert_validation_message(input(vec!["💷".repeat(41)]).validate(), TAG_NAME_MAX_LENGTH);
+        assert_validation_message(
+            input((0..21).map(|i| format!("Tag {i}")).collect()).validate(),
+            TAGS_TOO_MANY,
+        );
     }
 
     #[test]
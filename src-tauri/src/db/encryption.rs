@@ -0,0 +1,185 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::io::Read;
+use std::path::Path;
+
+/// First bytes of every plaintext SQLite database. SQLCipher encrypts the whole file,
+/// header included, so an encrypted database never starts with them.
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+/// `SQLITE_NOTADB`, which SQLCipher returns for a wrong key.
+const SQLITE_NOTADB: &str = "26";
+
+pub const PASSPHRASE_MIN_LENGTH: usize = 8;
+
+/// Whether the database at `db_path` is encrypted. A missing or empty file is not, since
+/// SQLite creates new databases as plaintext.
+pub fn is_encrypted(db_path: &Path) -> std::io::Result<bool> {
+    let file = match std::fs::File::open(db_path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(error),
+    };
+
+    let mut header = Vec::with_capacity(PLAINTEXT_HEADER.len());
+    file.take(PLAINTEXT_HEADER.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(!header.is_empty() && header != PLAINTEXT_HEADER)
+}
+
+/// `passphrase` as a quoted SQL string, for `PRAGMA key`, which cannot take a bound value.
+pub fn key_literal(passphrase: &str) -> String {
+    format!("'{}'", passphrase.replace('\'', "''"))
+}
+
+/// Whether `error` means the database was opened or attached with the wrong passphrase,
+/// or is not a database at all. SQLCipher cannot tell the two apart.
+pub fn is_wrong_passphrase(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|error| error.code())
+        .is_some_and(|code| code == SQLITE_NOTADB)
+}
+
+/// Checks `passphrase` against the database at `db_path` on a separate read-only
+/// connection, so it also works while the database is open.
+pub async fn passphrase_matches(db_path: &Path, passphrase: &str) -> Result<bool, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(db_path)
+        .read_only(true)
+        .pragma("key", key_literal(passphrase));
+    let pool = match SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+    {
+        Ok(pool) => pool,
+        Err(error) if is_wrong_passphrase(&error) => return Ok(false),
+        Err(error) => return Err(error),
+    };
+
+    let result = sqlx::query("SELECT COUNT(*) FROM sqlite_master")
+        .execute(&pool)
+        .await;
+    pool.close().await;
+
+    match result {
+        Ok(_) => Ok(true),
+        Err(error) if is_wrong_passphrase(&error) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Copies the schema and data of the database attached as `source` on `conn` into a new
+/// file at `target` with `sqlcipher_export`.
+///
+/// The copy is encrypted with `passphrase`, or written as plaintext when it is `Some("")`.
+/// `None` uses the key of the connection's main database, so the copy matches it.
+pub async fn export(
+    conn: &mut SqliteConnection,
+    source: &str,
+    target: &Path,
+    passphrase: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let target = target.to_string_lossy().into_owned();
+    match passphrase {
+        Some(passphrase) => {
+            sqlx::query("ATTACH DATABASE ? AS exported KEY ?")
+                .bind(target)
+                .bind(passphrase)
+                .execute(&mut *conn)
+                .await?;
+        }
+        None => {
+            sqlx::query("ATTACH DATABASE ? AS exported")
+                .bind(target)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+
+    let exported = sqlx::query("SELECT sqlcipher_export('exported', ?)")
+        .bind(source)
+        .execute(&mut *conn)
+        .await;
+    let detached = sqlx::query("DETACH DATABASE exported")
+        .execute(&mut *conn)
+        .await;
+    exported?;
+    detached?;
+    Ok(())
+}
+
+/// Rewrites the database at `db_path` encrypted with `passphrase`, or as plaintext when
+/// it is `None`.
+///
+/// The new file is written next to the database and only swapped in once it is complete.
+/// `pool` is closed by the swap, so the app must restart to reopen the database.
+pub async fn rewrite(
+    pool: &SqlitePool,
+    db_path: &Path,
+    passphrase: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let staged_path = super::with_suffix(db_path, ".rekeying");
+    if staged_path.exists() {
+        std::fs::remove_file(&staged_path)?;
+    }
+
+    let mut conn = pool.acquire().await?;
+    let exported = export(
+        &mut conn,
+        "main",
+        &staged_path,
+        Some(passphrase.unwrap_or("")),
+    )
+    .await;
+    drop(conn);
+    if let Err(error) = exported {
+        let _ = std::fs::remove_file(&staged_path);
+        return Err(error);
+    }
+
+    super::replace_database(pool, db_path, &staged_path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_encrypted, passphrase_matches, rewrite};
+    use crate::db;
+    use crate::db::test_support::{migrated_pool, set_theme};
+
+    #[tokio::test]
+    async fn rewrite_encrypts_rekeys_and_decrypts_the_database() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("worth.sqlite");
+        assert!(!is_encrypted(&db_path).unwrap());
+
+        let pool = migrated_pool(&db_path, None).await;
+        set_theme(&pool, "dark").await;
+        assert!(!is_encrypted(&db_path).unwrap());
+
+        rewrite(&pool, &db_path, Some("it's a secret"))
+            .await
+            .unwrap();
+        assert!(pool.is_closed());
+        assert!(is_encrypted(&db_path).unwrap());
+        assert!(passphrase_matches(&db_path, "it's a secret").await.unwrap());
+        assert!(!passphrase_matches(&db_path, "wrong").await.unwrap());
+        assert!(db::open_pool(&db_path, None).await.is_err());
+
+        let pool = migrated_pool(&db_path, Some("it's a secret")).await;
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "dark");
+        rewrite(&pool, &db_path, Some("new passphrase"))
+            .await
+            .unwrap();
+        assert!(!passphrase_matches(&db_path, "it's a secret").await.unwrap());
+
+        let pool = migrated_pool(&db_path, Some("new passphrase")).await;
+        rewrite(&pool, &db_path, None).await.unwrap();
+        assert!(!is_encrypted(&db_path).unwrap());
+
+        let pool = migrated_pool(&db_path, None).await;
+        assert_eq!(db::app_settings_get(&pool).await.unwrap().theme, "dark");
+        pool.close().await;
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use sqlx::migrate::{MigrateError, Migrator};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::backups::{self, BackupError, BackupMigrationDto};
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+    #[error(transparent)]
+    Backup(#[from] BackupError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Db(#[from] sqlx::Error),
+}
+
+/// Applies `migrator`'s pending migrations to the database at `db_path`, which `pool` has
+/// open.
+///
+/// When the database already has migrations applied, a `PreMigration` backup named after
+/// `app_version` and the newest applied migration is first written to the configured
+/// backup folder, or `default_backups_dir`, and older pre-migration backups are pruned. SQLx commits each migration on its own, so if one fails
+/// `pool` is closed and the backup is copied back over the database, undoing the ones
+/// before it too. The app must restart to reopen it.
+pub async fn run(
+    migrator: &Migrator,
+    pool: &SqlitePool,
+    db_path: &Path,
+    default_backups_dir: &Path,
+    app_version: &str,
+    now: DateTime<Utc>,
+) -> Result<(), MigrationError> {
+    let mut conn = pool.acquire().await?;
+    let applied_version = super::applied_migration_version(&mut conn, "main").await?;
+    let pending = match applied_version {
+        Some(_) => has_pending_migrations(migrator, &mut conn).await?,
+        None => false,
+    };
+    drop(conn);
+
+    let backup_path = match applied_version {
+        Some(migration_version) if pending => {
+            let migration = BackupMigrationDto {
+                app_version: app_version.to_string(),
+                migration_version,
+            };
+            Some(backup(pool, default_backups_dir, migration, now).await?)
+        }
+        _ => None,
+    };
+
+    let Err(error) = migrator.run(pool).await else {
+        return Ok(());
+    };
+    if let Some(backup_path) = backup_path {
+        pool.close().await;
+        copy_file_set(&backup_path, db_path)?;
+    }
+    Err(error.into())
+}
+
+async fn has_pending_migrations(
+    migrator: &Migrator,
+    conn: &mut sqlx::SqliteConnection,
+) -> Result<bool, sqlx::Error> {
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(&mut *conn)
+            .await?;
+    Ok(migrator.iter().any(|migration| {
+        migration.migration_type.is_up_migration() && !applied.contains(&migration.version)
+    }))
+}
+
+/// Writes a `PreMigration` backup and prunes older ones, returning the backup's path.
+async fn backup(
+    pool: &SqlitePool,
+    default_backups_dir: &Path,
+    migration: BackupMigrationDto,
+    now: DateTime<Utc>,
+) -> Result<PathBuf, MigrationError> {
+    let dir = backup_folder(pool, default_backups_dir).await?;
+    let backup = backups::create_pre_migration(pool, &dir, migration, now).await?;
+    backups::prune_pre_migration(&dir)?;
+    Ok(dir.join(backup.file_name))
+}
+
+/// The backup folder from app settings, or the default one for a database from before
+/// that setting was added.
+async fn backup_folder(
+    pool: &SqlitePool,
+    default_backups_dir: &Path,
+) -> Result<PathBuf, sqlx::Error> {
+    let has_settings: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info('app_settings') WHERE name = 'backup_folder')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !has_settings {
+        return Ok(default_backups_dir.to_path_buf());
+    }
+
+    let folder: Option<String> =
+        sqlx::query_scalar("SELECT backup_folder FROM app_settings WHERE id = 1")
+            .fetch_one(pool)
+            .await?;
+    Ok(folder.map_or_else(|| default_backups_dir.to_path_buf(), PathBuf::from))
+}
+
+/// Copies every file in `from`'s set over the matching file in `to`'s, and removes the
+/// files in `to`'s set that `from` does not have, so stale `-wal` files are not applied.
+fn copy_file_set(from: &Path, to: &Path) -> std::io::Result<()> {
+    for (from, to) in super::file_set(from).iter().zip(&super::file_set(to)) {
+        if from.is_file() {
+            std::fs::copy(from, to)?;
+        } else if to.exists() {
+            std::fs::remove_file(to)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use sqlx::migrate::Migrator;
+    use std::path::Path;
+
+    use super::{MigrationError, run};
+    use crate::backups::{self, BackupKind, BackupMigrationDto};
+    use crate::db;
+
+    #[tokio::test]
+    async fn failed_migrations_roll_back_to_the_pre_migration_backup() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("worth.sqlite");
+        let backups_dir = data_dir.path().join("backups");
+        let migrations_dir = data_dir.path().join("migrations");
+        std::fs::create_dir(&migrations_dir).unwrap();
+        write_migration(
+            &migrations_dir,
+            "0001_items.sql",
+            "CREATE TABLE items (name TEXT);",
+        );
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap();
+
+        let pool = db::open_pool(&db_path, None).await.unwrap();
+        let migrator = Migrator::new(migrations_dir.as_path()).await.unwrap();
+        run(&migrator, &pool, &db_path, &backups_dir, "0.5.0", now)
+            .await
+            .unwrap();
+        assert!(!backups_dir.exists());
+
+        write_migration(
+            &migrations_dir,
+            "0002_seed_items.sql",
+            "INSERT INTO items (name) VALUES ('seeded');",
+        );
+        write_migration(
+            &migrations_dir,
+            "0003_broken.sql",
+            "ALTER TABLE missing ADD x;",
+        );
+        let migrator = Migrator::new(migrations_dir.as_path()).await.unwrap();
+        assert!(matches!(
+            run(&migrator, &pool, &db_path, &backups_dir, "0.5.0", now).await,
+            Err(MigrationError::Migrate(_))
+        ));
+
+        assert!(pool.is_closed());
+        let backups = backups::list(&backups_dir).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].kind, BackupKind::PreMigration);
+        assert_eq!(backups[0].created_at, now);
+        assert_eq!(
+            backups[0].migration,
+            Some(BackupMigrationDto {
+                app_version: "0.5.0".to_string(),
+                migration_version: 1,
+            })
+        );
+        let pool = db::open_pool(&db_path, None).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(
+            db::applied_migration_version(&mut conn, "main")
+                .await
+                .unwrap(),
+            Some(1)
+        );
+        let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(items, 0);
+        drop(conn);
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn pre_migration_backups_use_the_backup_folder_without_pruning_other_backups() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("worth.sqlite");
+        let default_dir = data_dir.path().join("backups");
+        let backups_dir = data_dir.path().join("configured");
+        let migrations_dir = data_dir.path().join("migrations");
+        std::fs::create_dir(&migrations_dir).unwrap();
+        write_migration(
+            &migrations_dir,
+            "0001_app_settings.sql",
+            &format!(
+                "CREATE TABLE app_settings (id INTEGER PRIMARY KEY, backup_folder TEXT, backup_retention_count INTEGER NOT NULL);
+                INSERT INTO app_settings VALUES (1, '{}', 1);",
+                backups_dir.display()
+            ),
+        );
+        let earlier = Utc.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap();
+
+        let pool = db::open_pool(&db_path, None).await.unwrap();
+        let migrator = Migrator::new(migrations_dir.as_path()).await.unwrap();
+        run(&migrator, &pool, &db_path, &default_dir, "0.4.0", earlier)
+            .await
+            .unwrap();
+        backups::create(&pool, &backups_dir, BackupKind::Manual, earlier)
+            .await
+            .unwrap();
+
+        write_migration(
+            &migrations_dir,
+            "0002_items.sql",
+            "CREATE TABLE items (name TEXT);",
+        );
+        let migrator = Migrator::new(migrations_dir.as_path()).await.unwrap();
+        run(&migrator, &pool, &db_path, &default_dir, "0.5.0", now)
+            .await
+            .unwrap();
+
+        assert!(!default_dir.exists());
+        let kinds = backups::list(&backups_dir)
+            .unwrap()
+            .into_iter()
+            .map(|backup| backup.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![BackupKind::PreMigration, BackupKind::Manual]);
+        pool.close().await;
+    }
+
+    fn write_migration(dir: &Path, name: &str, sql: &str) {
+        std::fs::write(dir.join(name), sql).unwrap();
+    }
+}
@@ -1,41 +1,172 @@
+pub mod encryption;
+pub mod migrate;
 pub mod rows;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 use chrono::NaiveDate;
 use itertools::Itertools;
 use sqlx::{
-    QueryBuilder, Sqlite, SqlitePool,
+    QueryBuilder, Sqlite, SqliteConnection, SqlitePool,
+    migrate::Migrator,
     sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tauri::Manager;
 use tauri::path::BaseDirectory;
 
+pub static MIGRATOR: Migrator = sqlx::migrate!("./db/migrations");
+
 pub fn database_dir(app: &tauri::AppHandle) -> tauri::Result<PathBuf> {
     app.path().resolve("db", BaseDirectory::AppLocalData)
 }
 
+pub fn database_path(app: &tauri::AppHandle) -> tauri::Result<PathBuf> {
+    Ok(database_dir(app)?.join("worth.sqlite"))
+}
+
 pub async fn init_pool(app: &tauri::AppHandle) -> tauri::Result<SqlitePool> {
     let db_dir = database_dir(app)?;
     std::fs::create_dir_all(&db_dir)?;
 
-    let db_path: PathBuf = db_dir.join("worth.sqlite");
+    open_pool(&database_path(app)?, None)
+        .await
+        .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))
+}
 
-    let options = SqliteConnectOptions::new()
-        .filename(&db_path)
+/// Opens the database at `db_path`, keyed with `passphrase` when it is encrypted.
+pub async fn open_pool(
+    db_path: &Path,
+    passphrase: Option<&str>,
+) -> Result<SqlitePool, sqlx::Error> {
+    let mut options = SqliteConnectOptions::new()
+        .filename(db_path)
         .create_if_missing(true)
         .journal_mode(SqliteJournalMode::Wal)
         .synchronous(SqliteSynchronous::Normal)
         .foreign_keys(true)
         .busy_timeout(Duration::from_secs(5));
+    if let Some(passphrase) = passphrase {
+        // sqlx always sends `key` before the other pragmas, as SQLCipher requires.
+        options = options.pragma("key", encryption::key_literal(passphrase));
+    }
+
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+}
 
-    let pool = SqlitePoolOptions::new()
+/// Closes `pool` and opens the same database again, read-only or not, with the same
+/// options and key.
+pub async fn reopen_pool(pool: &SqlitePool, read_only: bool) -> Result<SqlitePool, sqlx::Error> {
+    let options = (*pool.connect_options()).clone().read_only(read_only);
+    pool.close().await;
+
+    SqlitePoolOptions::new()
         .max_connections(1)
         .connect_with(options)
         .await
-        .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))?;
+}
+
+/// Moves `staged_path` over the database at `db_path`.
+///
+/// `pool` is closed first and the old database's `-wal`, `-shm`, and `-journal` files are
+/// removed, so none of them are applied to the new file. The app must restart to reopen it.
+pub async fn replace_database(
+    pool: &SqlitePool,
+    db_path: &Path,
+    staged_path: &Path,
+) -> std::io::Result<()> {
+    pool.close().await;
+    for path in file_set(db_path).iter().skip(1) {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    std::fs::rename(staged_path, db_path)
+}
+
+/// The database at `db_path` followed by the `-wal`, `-shm`, and `-journal` files SQLite
+/// may keep beside it.
+pub fn file_set(db_path: &Path) -> [PathBuf; 4] {
+    [
+        db_path.to_path_buf(),
+        with_suffix(db_path, "-wal"),
+        with_suffix(db_path, "-shm"),
+        with_suffix(db_path, "-journal"),
+    ]
+}
+
+/// `path` with `suffix` appended to its file name, such as `worth.sqlite-wal`.
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Version of the newest migration this build knows about.
+pub fn latest_migration_version() -> i64 {
+    MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Newest migration applied to the database attached as `schema`, or `None` before any.
+pub async fn applied_migration_version(
+    conn: &mut SqliteConnection,
+    schema: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    if !has_migrations_table(conn, schema).await? {
+        return Ok(None);
+    }
+    sqlx::query_scalar(&format!(
+        "SELECT MAX(version) FROM {schema}._sqlx_migrations WHERE success = 1"
+    ))
+    .fetch_one(&mut *conn)
+    .await
+}
+
+/// Migrations applied to the database attached as `schema` that this build cannot run
+/// alongside: versions it does not know, usually from a newer build, and versions whose
+/// checksum differs from its own copy.
+pub async fn incompatible_migrations(
+    conn: &mut SqliteConnection,
+    schema: &str,
+) -> Result<Vec<i64>, sqlx::Error> {
+    if !has_migrations_table(conn, schema).await? {
+        return Ok(Vec::new());
+    }
+    let applied = sqlx::query_as::<_, (i64, Vec<u8>)>(&format!(
+        "SELECT version, checksum FROM {schema}._sqlx_migrations WHERE success = 1 ORDER BY version"
+    ))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(applied
+        .into_iter()
+        .filter(|(version, checksum)| {
+            !MIGRATOR
+                .iter()
+                .any(|migration| migration.version == *version && *migration.checksum == **checksum)
+        })
+        .map(|(version, _)| version)
+        .collect())
+}
 
-    Ok(pool)
+async fn has_migrations_table(
+    conn: &mut SqliteConnection,
+    schema: &str,
+) -> Result<bool, sqlx::Error> {
+    let count = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT COUNT(*) FROM {schema}.sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'"
+    ))
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(count > 0)
 }
 
 pub async fn app_settings_get(pool: &SqlitePool) -> Result<rows::AppSettingsRow, sqlx::Error> {
@@ -47,6 +178,9 @@ pub async fn app_settings_get(pool: &SqlitePool) -> Result<rows::AppSettingsRow,
             default_display_currency_code,
             display_locale,
             theme,
+            backup_schedule,
+            backup_retention_count,
+            backup_folder,
             created_at,
             updated_at
         FROM
@@ -65,6 +199,10 @@ pub struct AppSettingsMutationInput {
     pub default_display_currency_code: Option<String>,
     pub display_locale: Option<String>,
     pub theme: Option<String>,
+    pub backup_schedule: Option<String>,
+    pub backup_retention_count: Option<i64>,
+    /// `Some(None)` switches back to the default backup folder.
+    pub backup_folder: Option<Option<String>>,
 }
 
 pub async fn app_settings_update(
@@ -97,6 +235,24 @@ pub async fn app_settings_update(
         separated.push("theme = ").push_bind_unseparated(theme);
         changed = true;
     }
+    if let Some(backup_schedule) = &input.backup_schedule {
+        separated
+            .push("backup_schedule = ")
+            .push_bind_unseparated(backup_schedule);
+        changed = true;
+    }
+    if let Some(backup_retention_count) = input.backup_retention_count {
+        separated
+            .push("backup_retention_count = ")
+            .push_bind_unseparated(backup_retention_count);
+        changed = true;
+    }
+    if let Some(backup_folder) = &input.backup_folder {
+        separated
+            .push("backup_folder = ")
+            .push_bind_unseparated(backup_folder);
+        changed = true;
+    }
 
     if !changed {
         return app_settings_get(pool).await;
@@ -122,7 +278,8 @@ pub struct AccountListRow {
     pub institution_name: String,
 
     pub type_id: i64,
-    pub type_name: String, // e.g. "current"
+    pub type_name: String,  // e.g. "Current"
+    pub type_color: String, // e.g. "#3b82f6"
 
     pub first_snapshot_date: Option<NaiveDate>,
     pub latest_snapshot_date: Option<NaiveDate>,
@@ -141,7 +298,31 @@ pub struct InstitutionSummaryRow {
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct InstitutionAccountTypeRow {
     pub institution_id: i64,
+    pub type_id: i64,
     pub type_name: String,
+    pub type_color: String,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccountTypeSummaryRow {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    pub account_count: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TagSummaryRow {
+    pub id: i64,
+    pub name: String,
+    pub account_count: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccountTagListRow {
+    pub account_id: i64,
+    pub tag_id: i64,
+    pub tag_name: String,
 }
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -164,7 +345,9 @@ pub enum GlobalSearchRow {
     Account {
         id: i64,
         name: String,
+        type_id: i64,
         type_name: String,
+        type_color: String,
         institution_name: String,
     },
     Institution {
@@ -204,7 +387,9 @@ pub async fn search_global(
         kind: String,
         id: i64,
         name: String,
+        type_id: Option<i64>,
         type_name: Option<String>,
+        type_color: Option<String>,
         institution_name: Option<String>,
     }
 
@@ -234,7 +419,7 @@ pub async fn search_global(
                     search_fts
                 WHERE
                     search_fts MATCH ?
-                    AND rank MATCH 'bm25(0.0, 0.0, 10.0, 2.0, 1.0)'
+                    AND rank MATCH 'bm25(0.0, 0.0, 10.0, 2.0, 1.0, 2.0)'
             ),
             account_hits AS (
                 SELECT
@@ -244,7 +429,9 @@ pub async fn search_global(
                     'account' AS kind,
                     a.id,
                     a.name,
+                    t.id AS type_id,
                     t.name AS type_name,
+                    t.color AS type_color,
                     i.name AS institution_name
                 FROM
                     matched AS m
@@ -261,7 +448,9 @@ pub async fn search_global(
                     'institution' AS kind,
                     i.id,
                     i.name,
+                    NULL AS type_id,
                     NULL AS type_name,
+                    NULL AS type_color,
                     NULL AS institution_name
                 FROM
                     matched AS m
@@ -272,7 +461,9 @@ pub async fn search_global(
             results.kind,
             results.id,
             results.name,
+            results.type_id,
             results.type_name,
+            results.type_color,
             results.institution_name
         FROM
             (
@@ -309,9 +500,11 @@ pub async fn search_global(
     rows.into_iter()
         .map(|row| match row.kind.as_str() {
             "account" => {
-                let Some(type_name) = row.type_name else {
+                let (Some(type_id), Some(type_name), Some(type_color)) =
+                    (row.type_id, row.type_name, row.type_color)
+                else {
                     return Err(sqlx::Error::Decode(
-                        "search_global: NULL type_name for account row".into(),
+                        "search_global: NULL account type for account row".into(),
                     ));
                 };
                 let Some(institution_name) = row.institution_name else {
@@ -323,7 +516,9 @@ pub async fn search_global(
                 Ok(GlobalSearchRow::Account {
                     id: row.id,
                     name: row.name,
+                    type_id,
                     type_name,
+                    type_color,
                     institution_name,
                 })
             }
@@ -352,6 +547,7 @@ pub async fn accounts_list_full(pool: &SqlitePool) -> Result<Vec<AccountListRow>
             i.name AS institution_name,
             t.id AS type_id,
             t.name AS type_name,
+            t.color AS type_color,
             first.balance_date AS first_snapshot_date,
             latest.balance_date AS latest_snapshot_date,
             latest.balance_minor AS latest_balance_minor
@@ -413,6 +609,7 @@ pub async fn accounts_list_full_for_institution(
             i.name AS institution_name,
             t.id AS type_id,
             t.name AS type_name,
+            t.color AS type_color,
             first.balance_date AS first_snapshot_date,
             latest.balance_date AS latest_snapshot_date,
             latest.balance_minor AS latest_balance_minor
@@ -517,13 +714,15 @@ pub async fn institutions_account_types(
         r"
         SELECT
             a.institution_id,
-            t.name AS type_name
+            t.id AS type_id,
+            t.name AS type_name,
+            t.color AS type_color
         FROM
             accounts AS a
             INNER JOIN account_types AS t ON t.id = a.type_id
         GROUP BY
             a.institution_id,
-            t.name
+            t.id
         ORDER BY
             a.institution_id ASC,
             COUNT(a.id) DESC,
@@ -575,6 +774,7 @@ pub async fn account_get_full(
             i.name AS institution_name,
             t.id AS type_id,
             t.name AS type_name,
+            t.color AS type_color,
             first.balance_date AS first_snapshot_date,
             latest.balance_date AS latest_snapshot_date,
             latest.balance_minor AS latest_balance_minor
@@ -638,6 +838,39 @@ pub async fn snapshots_for_account(
             account_id,
             balance_date,
             balance_minor,
+            note,
+            source_kind,
+            source_detail,
+            created_at
+        FROM
+            account_balance_snapshots
+        WHERE
+            account_id = ?
+        ORDER BY
+            balance_date DESC
+        ",
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn snapshots_for_account_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    account_id: i64,
+) -> Result<Vec<rows::AccountBalanceSnapshotRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::AccountBalanceSnapshotRow>(
+        r"
+        SELECT
+            id,
+            account_id,
+            balance_date,
+            balance_minor,
+            note,
+            source_kind,
+            source_detail,
             created_at
         FROM
             account_balance_snapshots
@@ -648,6 +881,35 @@ pub async fn snapshots_for_account(
         ",
     )
     .bind(account_id)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Audit entries for an account and its snapshots, newest first.
+pub async fn audit_log_for_account(
+    pool: &SqlitePool,
+    account_id: i64,
+) -> Result<Vec<rows::AuditLogRow>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, rows::AuditLogRow>(
+        r"
+        SELECT
+            id,
+            entity_kind,
+            entity_id,
+            account_id,
+            action,
+            before_json,
+            after_json,
+            changed_at
+        FROM
+            audit_log
+        WHERE
+            account_id = ?
+        ORDER BY
+            id DESC
+        ",
+    )
+    .bind(account_id)
     .fetch_all(pool)
     .await?;
 
@@ -670,6 +932,9 @@ pub async fn snapshots_for_account_dates(
             account_id,
             balance_date,
             balance_minor,
+            note,
+            source_kind,
+            source_detail,
             created_at
         FROM
             account_balance_snapshots
@@ -703,6 +968,9 @@ pub async fn account_snapshot_get(
             account_id,
             balance_date,
             balance_minor,
+            note,
+            source_kind,
+            source_detail,
             created_at
         FROM
             account_balance_snapshots
@@ -717,11 +985,19 @@ pub async fn account_snapshot_get(
     .await
 }
 
+#[derive(Debug, Clone)]
+pub struct AccountSnapshotMutationInput {
+    pub balance_date: NaiveDate,
+    pub balance_minor: i64,
+    pub note: Option<String>,
+    pub source_kind: Option<String>,
+    pub source_detail: Option<String>,
+}
+
 pub async fn account_snapshot_create_tx(
     tx: &mut sqlx::Transaction<'_, Sqlite>,
     account_id: i64,
-    balance_date: NaiveDate,
-    balance_minor: i64,
+    input: &AccountSnapshotMutationInput,
 ) -> Result<i64, sqlx::Error> {
     let result = sqlx::query(
         r"
@@ -729,15 +1005,21 @@ pub async fn account_snapshot_create_tx(
             account_balance_snapshots (
                 account_id,
                 balance_date,
-                balance_minor
+                balance_minor,
+                note,
+                source_kind,
+                source_detail
             )
         VALUES
-            (?, ?, ?)
+            (?, ?, ?, ?, ?, ?)
         ",
     )
     .bind(account_id)
-    .bind(balance_date)
-    .bind(balance_minor)
+    .bind(input.balance_date)
+    .bind(input.balance_minor)
+    .bind(&input.note)
+    .bind(&input.source_kind)
+    .bind(&input.source_detail)
     .execute(&mut **tx)
     .await?;
 
@@ -748,22 +1030,27 @@ pub async fn account_snapshot_update_tx(
     tx: &mut sqlx::Transaction<'_, Sqlite>,
     account_id: i64,
     snapshot_id: i64,
-    balance_date: NaiveDate,
-    balance_minor: i64,
+    input: &AccountSnapshotMutationInput,
 ) -> Result<bool, sqlx::Error> {
     let result = sqlx::query(
         r"
         UPDATE account_balance_snapshots
         SET
             balance_date = ?,
-            balance_minor = ?
+            balance_minor = ?,
+            note = ?,
+            source_kind = ?,
+            source_detail = ?
         WHERE
             account_id = ?
             AND id = ?
         ",
     )
-    .bind(balance_date)
-    .bind(balance_minor)
+    .bind(input.balance_date)
+    .bind(input.balance_minor)
+    .bind(&input.note)
+    .bind(&input.source_kind)
+    .bind(&input.source_detail)
     .bind(account_id)
     .bind(snapshot_id)
     .execute(&mut **tx)
@@ -797,133 +1084,508 @@ pub async fn account_snapshot_delete_many_tx(
     Ok(result.rows_affected())
 }
 
-pub async fn snapshots_for_accounts_between(
-    pool: &SqlitePool,
-    account_ids: &[i64],
-    start_date: NaiveDate,
-    end_date: NaiveDate,
-) -> Result<Vec<AccountSnapshotRow>, sqlx::Error> {
-    if account_ids.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let mut qb = QueryBuilder::<Sqlite>::new(
-        "SELECT account_id, balance_date, balance_minor \
-         FROM account_balance_snapshots \
-         WHERE balance_date >= ",
-    );
-    qb.push_bind(start_date);
-    qb.push(" AND balance_date <= ");
-    qb.push_bind(end_date);
-    qb.push(" AND account_id IN (");
-
-    {
-        let mut separated = qb.separated(", ");
-        for id in account_ids {
-            separated.push_bind(id);
-        }
-    }
-    qb.push(")");
+#[derive(Debug, Clone)]
+pub struct SnapshotImportBatchMutationInput {
+    pub account_id: i64,
+    pub file_name: String,
+    pub options_json: String,
+    pub created_count: u32,
+    pub overwritten_count: u32,
+    pub skipped_count: u32,
+}
 
-    qb.push(" ORDER BY account_id ASC, balance_date ASC");
+pub async fn snapshot_import_batch_create_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    input: &SnapshotImportBatchMutationInput,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        INSERT INTO
+            snapshot_import_batches (
+                account_id,
+                file_name,
+                options_json,
+                created_count,
+                overwritten_count,
+                skipped_count
+            )
+        VALUES
+            (?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(input.account_id)
+    .bind(&input.file_name)
+    .bind(&input.options_json)
+    .bind(input.created_count)
+    .bind(input.overwritten_count)
+    .bind(input.skipped_count)
+    .execute(&mut **tx)
+    .await?;
 
-    let rows = qb
-        .build_query_as::<AccountSnapshotRow>()
-        .fetch_all(pool)
-        .await?;
-    Ok(rows)
+    Ok(result.last_insert_rowid())
 }
 
-pub async fn last_snapshots_before(
-    pool: &SqlitePool,
-    account_ids: &[i64],
-    start_date: NaiveDate,
-) -> Result<Vec<AccountSnapshotRow>, sqlx::Error> {
-    if account_ids.is_empty() {
-        return Ok(Vec::new());
-    }
+/// What one import write did to a snapshot; `previous` is the overwritten snapshot, if any.
+#[derive(Debug, Clone)]
+pub struct SnapshotImportBatchWriteInput {
+    pub snapshot_id: i64,
+    pub balance_date: NaiveDate,
+    pub balance_minor: i64,
+    pub previous: Option<AccountSnapshotMutationInput>,
+}
 
-    // For each account: the latest snapshot strictly before `start_date`.
-    let mut qb = QueryBuilder::<Sqlite>::new(
-        r"
-        SELECT
-            s.account_id,
-            s.balance_date,
-            s.balance_minor
-        FROM
-            account_balance_snapshots AS s
-            INNER JOIN (
-                SELECT
-                    account_id,
-                    MAX(balance_date) AS max_date
-                FROM
-                    account_balance_snapshots
-                WHERE
-                    balance_date < ",
-    );
-    qb.push_bind(start_date);
-    qb.push(" AND account_id IN (");
-    {
-        let mut separated = qb.separated(", ");
-        for id in account_ids {
-            separated.push_bind(id);
-        }
-    }
-    qb.push(")");
-    qb.push(
+pub async fn snapshot_import_batch_write_create_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    batch_id: i64,
+    input: &SnapshotImportBatchWriteInput,
+) -> Result<(), sqlx::Error> {
+    let previous = input.previous.as_ref();
+    sqlx::query(
         r"
-            GROUP BY account_id
-        ) AS m
-        ON m.account_id = s.account_id
-        AND m.max_date = s.balance_date
-        ORDER BY s.account_id ASC
+        INSERT INTO
+            snapshot_import_batch_writes (
+                batch_id,
+                snapshot_id,
+                action,
+                balance_date,
+                balance_minor,
+                previous_balance_minor,
+                previous_note,
+                previous_source_kind,
+                previous_source_detail
+            )
+        VALUES
+            (?, ?, ?, ?, ?, ?, ?, ?, ?)
         ",
-    );
-
-    let rows = qb
-        .build_query_as::<AccountSnapshotRow>()
-        .fetch_all(pool)
-        .await?;
-    Ok(rows)
-}
+    )
+    .bind(batch_id)
+    .bind(input.snapshot_id)
+    .bind(if previous.is_some() {
+        "overwrite"
+    } else {
+        "create"
+    })
+    .bind(input.balance_date)
+    .bind(input.balance_minor)
+    .bind(previous.map(|p| p.balance_minor))
+    .bind(previous.and_then(|p| p.note.as_deref()))
+    .bind(previous.and_then(|p| p.source_kind.as_deref()))
+    .bind(previous.and_then(|p| p.source_detail.as_deref()))
+    .execute(&mut **tx)
+    .await?;
 
-pub async fn earliest_snapshot_date(pool: &SqlitePool) -> Result<Option<NaiveDate>, sqlx::Error> {
-    let min_date: Option<NaiveDate> =
-        sqlx::query_scalar("SELECT MIN(balance_date) FROM account_balance_snapshots")
-            .fetch_one(pool)
-            .await?;
-    Ok(min_date)
+    Ok(())
 }
 
-pub async fn earliest_snapshot_date_for_account(
+pub async fn snapshot_import_batches_for_account(
     pool: &SqlitePool,
     account_id: i64,
-) -> Result<Option<NaiveDate>, sqlx::Error> {
-    let min_date: Option<NaiveDate> = sqlx::query_scalar(
+    limit: i64,
+) -> Result<Vec<rows::SnapshotImportBatchRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::SnapshotImportBatchRow>(
         r"
         SELECT
-            MIN(balance_date)
+            id,
+            account_id,
+            file_name,
+            options_json,
+            created_count,
+            overwritten_count,
+            skipped_count,
+            created_at,
+            rolled_back_at
         FROM
-            account_balance_snapshots
+            snapshot_import_batches
         WHERE
             account_id = ?
+        ORDER BY
+            id DESC
+        LIMIT
+            ?
         ",
     )
     .bind(account_id)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(min_date)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
 }
 
-pub async fn institution_exists(
+pub async fn snapshot_import_batch_get(
     pool: &SqlitePool,
-    institution_id: i64,
-) -> Result<bool, sqlx::Error> {
-    let exists: Option<i64> = sqlx::query_scalar(
+    account_id: i64,
+    batch_id: i64,
+) -> Result<Option<rows::SnapshotImportBatchRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::SnapshotImportBatchRow>(
         r"
         SELECT
-            id
+            id,
+            account_id,
+            file_name,
+            options_json,
+            created_count,
+            overwritten_count,
+            skipped_count,
+            created_at,
+            rolled_back_at
+        FROM
+            snapshot_import_batches
+        WHERE
+            account_id = ?
+            AND id = ?
+        ",
+    )
+    .bind(account_id)
+    .bind(batch_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn snapshot_import_batch_writes_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    batch_id: i64,
+) -> Result<Vec<rows::SnapshotImportBatchWriteRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::SnapshotImportBatchWriteRow>(
+        r"
+        SELECT
+            id,
+            batch_id,
+            snapshot_id,
+            action,
+            balance_date,
+            balance_minor,
+            previous_balance_minor,
+            previous_note,
+            previous_source_kind,
+            previous_source_detail
+        FROM
+            snapshot_import_batch_writes
+        WHERE
+            batch_id = ?
+        ORDER BY
+            id
+        ",
+    )
+    .bind(batch_id)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Dates in `batch_id` that a later batch, not yet rolled back, also wrote. Rolling back
+/// out of order would restore values the later import had replaced.
+pub async fn snapshot_import_batch_later_overlaps_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    account_id: i64,
+    batch_id: i64,
+) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    sqlx::query_scalar::<_, NaiveDate>(
+        r"
+        SELECT DISTINCT
+            w.balance_date
+        FROM
+            snapshot_import_batch_writes AS w
+            INNER JOIN snapshot_import_batch_writes AS later_w ON later_w.snapshot_id = w.snapshot_id
+            INNER JOIN snapshot_import_batches AS later_b ON later_b.id = later_w.batch_id
+        WHERE
+            w.batch_id = ?
+            AND later_b.account_id = ?
+            AND later_b.id > w.batch_id
+            AND later_b.rolled_back_at IS NULL
+        ORDER BY
+            w.balance_date
+        ",
+    )
+    .bind(batch_id)
+    .bind(account_id)
+    .fetch_all(&mut **tx)
+    .await
+}
+
+/// Marks a batch rolled back; returns false when it already was, so a second rollback
+/// racing the first cannot apply twice.
+pub async fn snapshot_import_batch_mark_rolled_back_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    batch_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        UPDATE snapshot_import_batches
+        SET
+            rolled_back_at = STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')
+        WHERE
+            id = ?
+            AND rolled_back_at IS NULL
+        ",
+    )
+    .bind(batch_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotImportProfileMutationInput {
+    pub account_id: i64,
+    pub name: String,
+    pub columns_json: String,
+    pub options_json: String,
+}
+
+/// Saves a profile, replacing the columns and options of an existing profile with the
+/// same name on the account.
+pub async fn snapshot_import_profile_upsert(
+    pool: &SqlitePool,
+    input: &SnapshotImportProfileMutationInput,
+) -> Result<rows::SnapshotImportProfileRow, sqlx::Error> {
+    sqlx::query_as::<_, rows::SnapshotImportProfileRow>(
+        r"
+        INSERT INTO
+            snapshot_import_profiles (account_id, name, columns_json, options_json)
+        VALUES
+            (?, ?, ?, ?)
+        ON CONFLICT (account_id, name) DO UPDATE
+        SET
+            name = excluded.name,
+            columns_json = excluded.columns_json,
+            options_json = excluded.options_json,
+            updated_at = STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')
+        RETURNING
+            id,
+            account_id,
+            name,
+            columns_json,
+            options_json,
+            created_at,
+            updated_at
+        ",
+    )
+    .bind(input.account_id)
+    .bind(&input.name)
+    .bind(&input.columns_json)
+    .bind(&input.options_json)
+    .fetch_one(pool)
+    .await
+}
+
+/// Profiles for an account, most recently saved first.
+pub async fn snapshot_import_profiles_for_account(
+    pool: &SqlitePool,
+    account_id: i64,
+) -> Result<Vec<rows::SnapshotImportProfileRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::SnapshotImportProfileRow>(
+        r"
+        SELECT
+            id,
+            account_id,
+            name,
+            columns_json,
+            options_json,
+            created_at,
+            updated_at
+        FROM
+            snapshot_import_profiles
+        WHERE
+            account_id = ?
+        ORDER BY
+            updated_at DESC,
+            id DESC
+        ",
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn snapshot_import_profile_delete(
+    pool: &SqlitePool,
+    account_id: i64,
+    profile_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        DELETE FROM snapshot_import_profiles
+        WHERE
+            account_id = ?
+            AND id = ?
+        ",
+    )
+    .bind(account_id)
+    .bind(profile_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn snapshots_for_accounts_between(
+    pool: &SqlitePool,
+    account_ids: &[i64],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<Vec<AccountSnapshotRow>, sqlx::Error> {
+    if account_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        "SELECT account_id, balance_date, balance_minor \
+         FROM account_balance_snapshots \
+         WHERE balance_date >= ",
+    );
+    qb.push_bind(start_date);
+    qb.push(" AND balance_date <= ");
+    qb.push_bind(end_date);
+    qb.push(" AND account_id IN (");
+
+    {
+        let mut separated = qb.separated(", ");
+        for id in account_ids {
+            separated.push_bind(id);
+        }
+    }
+    qb.push(")");
+
+    qb.push(" ORDER BY account_id ASC, balance_date ASC");
+
+    let rows = qb
+        .build_query_as::<AccountSnapshotRow>()
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+/// Full snapshots for the given accounts, optionally bounded by inclusive dates, for exports.
+pub async fn snapshots_for_export(
+    pool: &SqlitePool,
+    account_ids: &[i64],
+    from_date: Option<NaiveDate>,
+    to_date: Option<NaiveDate>,
+) -> Result<Vec<rows::AccountBalanceSnapshotRow>, sqlx::Error> {
+    if account_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        "SELECT id, account_id, balance_date, balance_minor, note, source_kind, source_detail, \
+         created_at \
+         FROM account_balance_snapshots \
+         WHERE account_id IN (",
+    );
+    {
+        let mut separated = qb.separated(", ");
+        for id in account_ids {
+            separated.push_bind(id);
+        }
+    }
+    qb.push(")");
+    if let Some(from_date) = from_date {
+        qb.push(" AND balance_date >= ");
+        qb.push_bind(from_date);
+    }
+    if let Some(to_date) = to_date {
+        qb.push(" AND balance_date <= ");
+        qb.push_bind(to_date);
+    }
+
+    qb.push(" ORDER BY balance_date ASC, account_id ASC");
+
+    let rows = qb
+        .build_query_as::<rows::AccountBalanceSnapshotRow>()
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn last_snapshots_before(
+    pool: &SqlitePool,
+    account_ids: &[i64],
+    start_date: NaiveDate,
+) -> Result<Vec<AccountSnapshotRow>, sqlx::Error> {
+    if account_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // For each account: the latest snapshot strictly before `start_date`.
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        r"
+        SELECT
+            s.account_id,
+            s.balance_date,
+            s.balance_minor
+        FROM
+            account_balance_snapshots AS s
+            INNER JOIN (
+                SELECT
+                    account_id,
+                    MAX(balance_date) AS max_date
+                FROM
+                    account_balance_snapshots
+                WHERE
+                    balance_date < ",
+    );
+    qb.push_bind(start_date);
+    qb.push(" AND account_id IN (");
+    {
+        let mut separated = qb.separated(", ");
+        for id in account_ids {
+            separated.push_bind(id);
+        }
+    }
+    qb.push(")");
+    qb.push(
+        r"
+            GROUP BY account_id
+        ) AS m
+        ON m.account_id = s.account_id
+        AND m.max_date = s.balance_date
+        ORDER BY s.account_id ASC
+        ",
+    );
+
+    let rows = qb
+        .build_query_as::<AccountSnapshotRow>()
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn earliest_snapshot_date(pool: &SqlitePool) -> Result<Option<NaiveDate>, sqlx::Error> {
+    let min_date: Option<NaiveDate> =
+        sqlx::query_scalar("SELECT MIN(balance_date) FROM account_balance_snapshots")
+            .fetch_one(pool)
+            .await?;
+    Ok(min_date)
+}
+
+pub async fn earliest_snapshot_date_for_account(
+    pool: &SqlitePool,
+    account_id: i64,
+) -> Result<Option<NaiveDate>, sqlx::Error> {
+    let min_date: Option<NaiveDate> = sqlx::query_scalar(
+        r"
+        SELECT
+            MIN(balance_date)
+        FROM
+            account_balance_snapshots
+        WHERE
+            account_id = ?
+        ",
+    )
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(min_date)
+}
+
+pub async fn institution_exists(
+    pool: &SqlitePool,
+    institution_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let exists: Option<i64> = sqlx::query_scalar(
+        r"
+        SELECT
+            id
         FROM
             institutions
         WHERE
@@ -1056,121 +1718,244 @@ pub async fn institution_update(
     Ok(updated)
 }
 
-pub async fn account_type_id_by_name(
+pub async fn account_types_list_summary(
     pool: &SqlitePool,
-    type_name: &str,
-) -> Result<Option<i64>, sqlx::Error> {
-    let id: Option<i64> = sqlx::query_scalar(
+) -> Result<Vec<AccountTypeSummaryRow>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, AccountTypeSummaryRow>(
         r"
         SELECT
-            id
+            t.id,
+            t.name,
+            t.color,
+            COUNT(a.id) AS account_count
+        FROM
+            account_types AS t
+            LEFT JOIN accounts AS a ON a.type_id = t.id
+        GROUP BY
+            t.id
+        ORDER BY
+            t.id ASC
+        ",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn account_type_get(
+    pool: &SqlitePool,
+    type_id: i64,
+) -> Result<Option<rows::AccountTypeRow>, sqlx::Error> {
+    let row = sqlx::query_as::<_, rows::AccountTypeRow>(
+        r"
+        SELECT
+            id,
+            name,
+            color
         FROM
             account_types
         WHERE
-            name = ?
+            id = ?
         ",
     )
-    .bind(type_name)
+    .bind(type_id)
     .fetch_optional(pool)
     .await?;
 
-    Ok(id)
+    Ok(row)
 }
 
-pub async fn account_name_exists_in_institution(
+pub async fn account_type_name_exists(
     pool: &SqlitePool,
-    institution_id: i64,
     name: &str,
-    exclude_account_id: Option<i64>,
+    exclude_type_id: Option<i64>,
 ) -> Result<bool, sqlx::Error> {
     let mut qb = QueryBuilder::<Sqlite>::new(
         r"
         SELECT
             id
         FROM
-            accounts
+            account_types
         WHERE
-            institution_id = ",
+            name = ",
     );
-    qb.push_bind(institution_id);
-    qb.push(" AND name = ");
     qb.push_bind(name);
-    if let Some(exclude_id) = exclude_account_id {
+    if let Some(exclude_id) = exclude_type_id {
         qb.push(" AND id <> ");
         qb.push_bind(exclude_id);
     }
     qb.push(" LIMIT 1");
 
-    let existing = qb.build_query_scalar::<i64>().fetch_optional(pool).await?;
-    Ok(existing.is_some())
+    let exists = qb.build_query_scalar::<i64>().fetch_optional(pool).await?;
+    Ok(exists.is_some())
 }
 
-#[derive(Debug, Clone)]
-pub struct AccountMutationInput {
-    pub institution_id: i64,
-    pub name: String,
-    pub type_id: i64,
-    pub currency_code: String,
-    pub account_classification: String,
-    pub opened_date: Option<NaiveDate>,
-    pub closed_date: Option<NaiveDate>,
+pub async fn account_type_create(
+    pool: &SqlitePool,
+    name: &str,
+    color: &str,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        INSERT INTO
+            account_types (name, color)
+        VALUES
+            (?, ?)
+        ",
+    )
+    .bind(name)
+    .bind(color)
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
 }
 
-pub async fn account_create(
-    pool: &SqlitePool,
-    input: &AccountMutationInput,
-) -> Result<rows::AccountRow, sqlx::Error> {
+pub async fn account_type_create_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    name: &str,
+    color: &str,
+) -> Result<i64, sqlx::Error> {
     let result = sqlx::query(
         r"
         INSERT INTO
-            accounts (
-                name,
-                institution_id,
-                type_id,
-                currency_code,
-                account_classification,
-                opened_date,
-                closed_date
-            )
+            account_types (name, color)
         VALUES
-            (?, ?, ?, ?, ?, ?, ?)
+            (?, ?)
         ",
     )
-    .bind(&input.name)
-    .bind(input.institution_id)
-    .bind(input.type_id)
-    .bind(&input.currency_code)
-    .bind(&input.account_classification)
-    .bind(input.opened_date)
-    .bind(input.closed_date)
+    .bind(name)
+    .bind(color)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn account_type_update(
+    pool: &SqlitePool,
+    type_id: i64,
+    name: &str,
+    color: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        UPDATE account_types
+        SET
+            name = ?,
+            color = ?
+        WHERE
+            id = ?
+        ",
+    )
+    .bind(name)
+    .bind(color)
+    .bind(type_id)
     .execute(pool)
     .await?;
 
-    let id = result.last_insert_rowid();
-    let created = sqlx::query_as::<_, rows::AccountRow>(
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn account_type_account_count(
+    pool: &SqlitePool,
+    type_id: i64,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
         r"
         SELECT
-            id,
-            name,
-            institution_id,
-            type_id,
-            currency_code,
-            account_classification,
-            opened_date,
-            closed_date,
-            created_at,
-            updated_at
+            COUNT(*)
         FROM
             accounts
         WHERE
-            id = ?
+            type_id = ?
         ",
     )
-    .bind(id)
+    .bind(type_id)
     .fetch_one(pool)
+    .await
+}
+
+/// Moves every account of `type_id` to `replacement_type_id`.
+pub async fn accounts_reassign_type_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    type_id: i64,
+    replacement_type_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        UPDATE accounts
+        SET
+            type_id = ?,
+            updated_at = STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')
+        WHERE
+            type_id = ?
+        ",
+    )
+    .bind(replacement_type_id)
+    .bind(type_id)
+    .execute(&mut **tx)
     .await?;
 
-    Ok(created)
+    Ok(result.rows_affected())
+}
+
+pub async fn account_type_delete_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    type_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r"
+        DELETE FROM account_types
+        WHERE
+            id = ?
+        ",
+    )
+    .bind(type_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn account_name_exists_in_institution(
+    pool: &SqlitePool,
+    institution_id: i64,
+    name: &str,
+    exclude_account_id: Option<i64>,
+) -> Result<bool, sqlx::Error> {
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        r"
+        SELECT
+            id
+        FROM
+            accounts
+        WHERE
+            institution_id = ",
+    );
+    qb.push_bind(institution_id);
+    qb.push(" AND name = ");
+    qb.push_bind(name);
+    if let Some(exclude_id) = exclude_account_id {
+        qb.push(" AND id <> ");
+        qb.push_bind(exclude_id);
+    }
+    qb.push(" LIMIT 1");
+
+    let existing = qb.build_query_scalar::<i64>().fetch_optional(pool).await?;
+    Ok(existing.is_some())
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountMutationInput {
+    pub institution_id: i64,
+    pub name: String,
+    pub type_id: i64,
+    pub currency_code: String,
+    pub account_classification: String,
+    pub opened_date: Option<NaiveDate>,
+    pub closed_date: Option<NaiveDate>,
 }
 
 pub async fn account_create_tx(
@@ -1205,11 +1990,11 @@ pub async fn account_create_tx(
     Ok(result.last_insert_rowid())
 }
 
-pub async fn account_update(
-    pool: &SqlitePool,
+pub async fn account_update_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
     account_id: i64,
     input: &AccountMutationInput,
-) -> Result<Option<rows::AccountRow>, sqlx::Error> {
+) -> Result<bool, sqlx::Error> {
     let result = sqlx::query(
         r"
         UPDATE accounts
@@ -1234,72 +2019,173 @@ pub async fn account_update(
     .bind(input.opened_date)
     .bind(input.closed_date)
     .bind(account_id)
-    .execute(pool)
+    .execute(&mut **tx)
     .await?;
 
-    if result.rows_affected() == 0 {
-        return Ok(None);
-    }
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn tags_list_summary(pool: &SqlitePool) -> Result<Vec<TagSummaryRow>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, TagSummaryRow>(
+        r"
+        SELECT
+            tg.id,
+            tg.name,
+            COUNT(at.account_id) AS account_count
+        FROM
+            tags AS tg
+            LEFT JOIN account_tags AS at ON at.tag_id = tg.id
+        GROUP BY
+            tg.id,
+            tg.name
+        ORDER BY
+            tg.name COLLATE nocase ASC
+        ",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
 
-    let updated = sqlx::query_as::<_, rows::AccountRow>(
+pub async fn tag_get(pool: &SqlitePool, tag_id: i64) -> Result<Option<rows::TagRow>, sqlx::Error> {
+    let row = sqlx::query_as::<_, rows::TagRow>(
         r"
         SELECT
             id,
-            name,
-            institution_id,
-            type_id,
-            currency_code,
-            account_classification,
-            opened_date,
-            closed_date,
-            created_at,
-            updated_at
+            name
         FROM
-            accounts
+            tags
         WHERE
             id = ?
         ",
     )
-    .bind(account_id)
+    .bind(tag_id)
     .fetch_optional(pool)
     .await?;
 
-    Ok(updated)
+    Ok(row)
 }
 
-pub async fn account_update_tx(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
-    account_id: i64,
-    input: &AccountMutationInput,
-) -> Result<bool, sqlx::Error> {
-    let result = sqlx::query(
+pub async fn account_tags_for_accounts(
+    pool: &SqlitePool,
+    account_ids: &[i64],
+) -> Result<Vec<AccountTagListRow>, sqlx::Error> {
+    if account_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        "SELECT at.account_id, tg.id AS tag_id, tg.name AS tag_name \
+         FROM account_tags AS at \
+         INNER JOIN tags AS tg ON tg.id = at.tag_id \
+         WHERE at.account_id IN (",
+    );
+
+    {
+        let mut separated = qb.separated(", ");
+        for id in account_ids {
+            separated.push_bind(id);
+        }
+    }
+    qb.push(")");
+
+    qb.push(" ORDER BY at.account_id ASC, tg.name COLLATE nocase ASC");
+
+    let rows = qb
+        .build_query_as::<AccountTagListRow>()
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn account_ids_with_tag(pool: &SqlitePool, tag_id: i64) -> Result<Vec<i64>, sqlx::Error> {
+    let ids = sqlx::query_scalar::<_, i64>(
         r"
-        UPDATE accounts
-        SET
-            institution_id = ?,
-            name = ?,
-            type_id = ?,
-            currency_code = ?,
-            account_classification = ?,
-            opened_date = ?,
-            closed_date = ?,
-            updated_at = STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')
+        SELECT
+            account_id
+        FROM
+            account_tags
         WHERE
-            id = ?
+            tag_id = ?
         ",
     )
-    .bind(input.institution_id)
-    .bind(&input.name)
-    .bind(input.type_id)
-    .bind(&input.currency_code)
-    .bind(&input.account_classification)
-    .bind(input.opened_date)
-    .bind(input.closed_date)
-    .bind(account_id)
-    .execute(&mut **tx)
+    .bind(tag_id)
+    .fetch_all(pool)
     .await?;
 
-    Ok(result.rows_affected() > 0)
+    Ok(ids)
+}
+
+/// Replaces an account's tags with `tag_names`, creating tags that do not exist yet.
+/// Existing tags keep their stored casing; tags left without accounts are removed by
+/// the `account_tags_ad` trigger.
+pub async fn account_tags_set_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    account_id: i64,
+    tag_names: &[String],
+) -> Result<(), sqlx::Error> {
+    let mut tag_ids = Vec::with_capacity(tag_names.len());
+    for name in tag_names {
+        sqlx::query(
+            r"
+            INSERT INTO
+                tags (name)
+            VALUES
+                (?)
+            ON CONFLICT (name) DO NOTHING
+            ",
+        )
+        .bind(name)
+        .execute(&mut **tx)
+        .await?;
+
+        let tag_id = sqlx::query_scalar::<_, i64>(
+            r"
+            SELECT
+                id
+            FROM
+                tags
+            WHERE
+                name = ?
+            ",
+        )
+        .bind(name)
+        .fetch_one(&mut **tx)
+        .await?;
+        tag_ids.push(tag_id);
+    }
+
+    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM account_tags WHERE account_id = ");
+    qb.push_bind(account_id);
+    if !tag_ids.is_empty() {
+        qb.push(" AND tag_id NOT IN (");
+        {
+            let mut separated = qb.separated(", ");
+            for id in &tag_ids {
+                separated.push_bind(*id);
+            }
+        }
+        qb.push(")");
+    }
+    qb.build().execute(&mut **tx).await?;
+
+    for tag_id in tag_ids {
+        sqlx::query(
+            r"
+            INSERT OR IGNORE INTO
+                account_tags (account_id, tag_id)
+            VALUES
+                (?, ?)
+            ",
+        )
+        .bind(account_id)
+        .bind(tag_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
 }
 
 pub async fn account_delete_preview(
@@ -1394,6 +2280,146 @@ pub async fn institution_delete(
     Ok(result.rows_affected() > 0)
 }
 
+pub async fn fx_rates_list(pool: &SqlitePool) -> Result<Vec<rows::FxRateRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::FxRateRow>(
+        r"
+        SELECT
+            id,
+            base_currency_code,
+            quote_currency_code,
+            rate_date,
+            rate,
+            created_at,
+            updated_at
+        FROM
+            fx_rates
+        ORDER BY
+            base_currency_code ASC,
+            quote_currency_code ASC,
+            rate_date DESC
+        ",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Rates that can convert into or out of `currency_code`, in either direction.
+pub async fn fx_rates_for_currency(
+    pool: &SqlitePool,
+    currency_code: &str,
+) -> Result<Vec<rows::FxRateRow>, sqlx::Error> {
+    sqlx::query_as::<_, rows::FxRateRow>(
+        r"
+        SELECT
+            id,
+            base_currency_code,
+            quote_currency_code,
+            rate_date,
+            rate,
+            created_at,
+            updated_at
+        FROM
+            fx_rates
+        WHERE
+            base_currency_code = ?
+            OR quote_currency_code = ?
+        ORDER BY
+            rate_date ASC
+        ",
+    )
+    .bind(currency_code)
+    .bind(currency_code)
+    .fetch_all(pool)
+    .await
+}
+
+/// Inserts a rate, or replaces the rate already stored for the same pair and date.
+///
+/// Returns `true` when a new row was created.
+pub async fn fx_rate_upsert_tx(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    base_currency_code: &str,
+    quote_currency_code: &str,
+    rate_date: NaiveDate,
+    rate: f64,
+) -> Result<bool, sqlx::Error> {
+    let existing_id: Option<i64> = sqlx::query_scalar(
+        r"
+        SELECT
+            id
+        FROM
+            fx_rates
+        WHERE
+            base_currency_code = ?
+            AND quote_currency_code = ?
+            AND rate_date = ?
+        ",
+    )
+    .bind(base_currency_code)
+    .bind(quote_currency_code)
+    .bind(rate_date)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    if let Some(existing_id) = existing_id {
+        sqlx::query(
+            r"
+            UPDATE fx_rates
+            SET
+                rate = ?,
+                updated_at = STRFTIME('%Y-%m-%dT%H:%M:%SZ', 'now')
+            WHERE
+                id = ?
+            ",
+        )
+        .bind(rate)
+        .bind(existing_id)
+        .execute(&mut **tx)
+        .await?;
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r"
+        INSERT INTO
+            fx_rates (
+                base_currency_code,
+                quote_currency_code,
+                rate_date,
+                rate
+            )
+        VALUES
+            (?, ?, ?, ?)
+        ",
+    )
+    .bind(base_currency_code)
+    .bind(quote_currency_code)
+    .bind(rate_date)
+    .bind(rate)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(true)
+}
+
+pub async fn fx_rates_delete_many(pool: &SqlitePool, rate_ids: &[i64]) -> Result<u64, sqlx::Error> {
+    if rate_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM fx_rates WHERE id IN (");
+    {
+        let mut separated = qb.separated(", ");
+        for rate_id in rate_ids {
+            separated.push_bind(rate_id);
+        }
+    }
+    qb.push(")");
+
+    let result = qb.build().execute(pool).await?;
+    Ok(result.rows_affected())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1418,6 +2444,9 @@ mod tests {
                 default_display_currency_code: None,
                 display_locale: None,
                 theme: None,
+                backup_schedule: None,
+                backup_retention_count: None,
+                backup_folder: None,
             },
         )
         .await
@@ -1425,4 +2454,227 @@ mod tests {
 
         assert!(!updated.analytics_enabled);
     }
+
+    #[tokio::test]
+    async fn custom_account_types_are_searchable_and_protected_while_in_use() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("connect in-memory db");
+
+        sqlx::migrate!("./db/migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+
+        let type_id = account_type_create(&pool, "Premium Bonds", "#0ea5e9")
+            .await
+            .expect("create account type");
+        let institution = institution_create(&pool, "NS&I")
+            .await
+            .expect("create institution");
+        let mut tx = pool.begin().await.expect("begin");
+        account_create_tx(
+            &mut tx,
+            &AccountMutationInput {
+                institution_id: institution.id,
+                name: "Holder's number".to_string(),
+                type_id,
+                currency_code: "GBP".to_string(),
+                account_classification: "asset".to_string(),
+                opened_date: None,
+                closed_date: None,
+            },
+        )
+        .await
+        .expect("create account");
+        tx.commit().await.expect("commit");
+
+        account_type_update(&pool, type_id, "Bonds", "#0ea5e9")
+            .await
+            .expect("rename account type");
+        let results = search_global(&pool, "bonds").await.expect("search");
+        assert!(matches!(
+            results.as_slice(),
+            [GlobalSearchRow::Account { type_name, .. }] if type_name == "Bonds"
+        ));
+
+        let mut tx = pool.begin().await.expect("begin");
+        assert!(account_type_delete_tx(&mut tx, type_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn account_tags_are_searchable_and_removed_with_their_last_account() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("connect in-memory db");
+
+        sqlx::migrate!("./db/migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+
+        let institution = institution_create(&pool, "Monzo")
+            .await
+            .expect("create institution");
+        let type_id = sqlx::query_scalar::<_, i64>("SELECT id FROM account_types LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .expect("account type");
+        let mut tx = pool.begin().await.expect("begin");
+        let account_id = account_create_tx(
+            &mut tx,
+            &AccountMutationInput {
+                institution_id: institution.id,
+                name: "Pot".to_string(),
+                type_id,
+                currency_code: "GBP".to_string(),
+                account_classification: "asset".to_string(),
+                opened_date: None,
+                closed_date: None,
+            },
+        )
+        .await
+        .expect("create account");
+        account_tags_set_tx(&mut tx, account_id, &["Emergency fund".to_string()])
+            .await
+            .expect("set tags");
+        tx.commit().await.expect("commit");
+
+        let results = search_global(&pool, "emergency").await.expect("search");
+        assert!(matches!(
+            results.as_slice(),
+            [GlobalSearchRow::Account { id, .. }] if *id == account_id
+        ));
+
+        let mut tx = pool.begin().await.expect("begin");
+        account_tags_set_tx(
+            &mut tx,
+            account_id,
+            &["emergency FUND".to_string(), "Kids".to_string()],
+        )
+        .await
+        .expect("replace tags");
+        tx.commit().await.expect("commit");
+        let tags = tags_list_summary(&pool).await.expect("list tags");
+        assert_eq!(
+            tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["Emergency fund", "Kids"]
+        );
+
+        assert!(account_delete(&pool, account_id).await.expect("delete"));
+        assert!(
+            tags_list_summary(&pool)
+                .await
+                .expect("list tags")
+                .is_empty()
+        );
+        assert!(
+            search_global(&pool, "kids")
+                .await
+                .expect("search")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn audit_log_records_writes_and_cascades_and_is_append_only() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("connect in-memory db");
+
+        sqlx::migrate!("./db/migrations")
+            .run(&pool)
+            .await
+            .expect("run migrations");
+
+        let institution = institution_create(&pool, "Nationwide")
+            .await
+            .expect("create institution");
+        let type_id = sqlx::query_scalar::<_, i64>("SELECT id FROM account_types LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .expect("account type");
+        let mut tx = pool.begin().await.expect("begin");
+        let account_id = account_create_tx(
+            &mut tx,
+            &AccountMutationInput {
+                institution_id: institution.id,
+                name: "FlexDirect".to_string(),
+                type_id,
+                currency_code: "GBP".to_string(),
+                account_classification: "asset".to_string(),
+                opened_date: None,
+                closed_date: None,
+            },
+        )
+        .await
+        .expect("create account");
+        let mut snapshot = AccountSnapshotMutationInput {
+            balance_date: NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+            balance_minor: 10_000,
+            note: None,
+            source_kind: Some("manual".to_string()),
+            source_detail: None,
+        };
+        let snapshot_id = account_snapshot_create_tx(&mut tx, account_id, &snapshot)
+            .await
+            .expect("create snapshot");
+        snapshot.balance_minor = 12_500;
+        account_snapshot_update_tx(&mut tx, account_id, snapshot_id, &snapshot)
+            .await
+            .expect("update snapshot");
+        // Rewriting identical values is not a change.
+        account_snapshot_update_tx(&mut tx, account_id, snapshot_id, &snapshot)
+            .await
+            .expect("repeat update");
+        tx.commit().await.expect("commit");
+
+        assert!(
+            institution_delete(&pool, institution.id)
+                .await
+                .expect("delete institution")
+        );
+
+        let entries = audit_log_for_account(&pool, account_id)
+            .await
+            .expect("audit log");
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.entity_kind.as_str(), e.action.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("account", "delete"),
+                ("snapshot", "delete"),
+                ("snapshot", "update"),
+                ("snapshot", "create"),
+                ("account", "create"),
+            ]
+        );
+        assert_eq!(
+            entries[2].after_json.as_deref(),
+            Some(
+                r#"{"balance_date":"2026-01-31","balance_minor":12500,"note":null,"source_kind":"manual","source_detail":null}"#
+            )
+        );
+
+        assert!(
+            sqlx::query("DELETE FROM audit_log")
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("UPDATE audit_log SET action = 'create'")
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+    }
 }
@@ -11,7 +11,8 @@ pub struct InstitutionRow {
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct AccountTypeRow {
     pub id: i64,
-    pub name: String, // unique, e.g. "current"
+    pub name: String,  // unique, e.g. "Current"
+    pub color: String, // CHECK (color GLOB '#[0-9a-f]...'), e.g. "#3b82f6"
 }
 
 #[allow(dead_code)]
@@ -36,6 +37,9 @@ pub struct AccountBalanceSnapshotRow {
     pub account_id: i64,
     pub balance_date: NaiveDate,
     pub balance_minor: i64,
+    pub note: Option<String>,
+    pub source_kind: Option<String>, // 'manual' | 'csv_import' | 'ofx_import' | 'spreadsheet_import' | 'api'
+    pub source_detail: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -47,6 +51,89 @@ pub struct AppSettingsRow {
     pub default_display_currency_code: String,
     pub display_locale: String,
     pub theme: String,
+    pub backup_schedule: String,
+    pub backup_retention_count: i64,
+    pub backup_folder: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FxRateRow {
+    pub id: i64,
+    pub base_currency_code: String,
+    pub quote_currency_code: String,
+    pub rate_date: NaiveDate,
+    pub rate: f64, // CHECK (rate > 0)
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TagRow {
+    pub id: i64,
+    pub name: String, // UNIQUE COLLATE NOCASE
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccountTagRow {
+    pub account_id: i64,
+    pub tag_id: i64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SnapshotImportBatchRow {
+    pub id: i64,
+    pub account_id: i64,
+    pub file_name: String,
+    pub options_json: String, // serialized SnapshotImportOptionsInput
+    pub created_count: i64,
+    pub overwritten_count: i64,
+    pub skipped_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub rolled_back_at: Option<DateTime<Utc>>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SnapshotImportBatchWriteRow {
+    pub id: i64,
+    pub batch_id: i64,
+    pub snapshot_id: i64,
+    pub action: String, // CHECK (action IN ('create', 'overwrite'))
+    pub balance_date: NaiveDate,
+    pub balance_minor: i64,
+    pub previous_balance_minor: Option<i64>,
+    pub previous_note: Option<String>,
+    pub previous_source_kind: Option<String>,
+    pub previous_source_detail: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SnapshotImportProfileRow {
+    pub id: i64,
+    pub account_id: i64,
+    pub name: String,
+    pub columns_json: String, // JSON array of CSV column names
+    pub options_json: String, // serialized SnapshotImportOptionsInput
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuditLogRow {
+    pub id: i64,
+    pub entity_kind: String, // CHECK (entity_kind IN ('institution', 'account', 'snapshot'))
+    pub entity_id: i64,
+    pub account_id: Option<i64>,
+    pub action: String, // CHECK (action IN ('create', 'update', 'delete'))
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
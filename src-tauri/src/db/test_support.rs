@@ -0,0 +1,30 @@
+//! Database fixtures shared by tests across modules.
+
+use sqlx::SqlitePool;
+use std::path::Path;
+
+use super::{MIGRATOR, app_settings_get, open_pool};
+
+/// Opens the database at `path`, creating it if needed, and runs every migration.
+pub async fn migrated_pool(path: &Path, passphrase: Option<&str>) -> SqlitePool {
+    let pool = open_pool(path, passphrase).await.unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    pool
+}
+
+/// Sets the theme, the app setting tests use to tell copies of a database apart.
+pub async fn set_theme(pool: &SqlitePool, theme: &str) {
+    sqlx::query("UPDATE app_settings SET theme = ?")
+        .bind(theme)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+/// Reads the theme from the unencrypted database at `path`.
+pub async fn theme(path: &Path) -> String {
+    let pool = open_pool(path, None).await.unwrap();
+    let theme = app_settings_get(&pool).await.unwrap().theme;
+    pool.close().await;
+    theme
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use sqlx::SqlitePool;
+
+use crate::contracts::BALANCE_MINOR_ABS_MAX;
+
+/// Most rows `PRAGMA integrity_check` reports before it stops.
+const INTEGRITY_CHECK_MAX_ERRORS: i64 = 100;
+
+/// What `search_fts` should hold: one row per institution and one per account, built the
+/// same way as the triggers that keep it up to date.
+const EXPECTED_SEARCH_ROWS: &str = r"
+    SELECT 'institution' AS kind, id AS entity_id, name, '' AS institution_name, '' AS account_type, '' AS tags
+    FROM institutions
+    UNION ALL
+    SELECT 'account', entity_id, name, institution_name, account_type, tags
+    FROM search_fts_accounts
+";
+const ACTUAL_SEARCH_ROWS: &str =
+    "SELECT kind, entity_id, name, institution_name, account_type, tags FROM search_fts";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorCheck {
+    /// `PRAGMA integrity_check`.
+    Integrity,
+    /// `PRAGMA foreign_key_check`.
+    ForeignKeys,
+    /// `search_fts` against institutions and accounts.
+    SearchIndex,
+    /// Snapshot balances within `BALANCE_MINOR_ABS_MAX`. Out-of-range balances also fail the
+    /// table's `CHECK` constraint, so they are usually reported by [`Self::Integrity`] too.
+    SnapshotBalances,
+    /// Snapshot dates that parse as `YYYY-MM-DD`.
+    SnapshotDates,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorRepair {
+    /// Rebuilds `search_fts` from institutions and accounts. It only holds derived data,
+    /// so nothing can be lost.
+    RebuildSearchIndex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DoctorIssueDto {
+    pub check: DoctorCheck,
+    pub message: String,
+    /// Repair that fixes this issue, when there is a safe one.
+    pub repair: Option<DoctorRepair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DoctorReportDto {
+    /// Every problem found. Empty when the database is healthy.
+    pub issues: Vec<DoctorIssueDto>,
+}
+
+/// Runs every check. None of them write, so this works on a read-only database.
+pub async fn check(pool: &SqlitePool) -> Result<DoctorReportDto, sqlx::Error> {
+    let mut issues = Vec::new();
+    check_integrity(pool, &mut issues).await?;
+    check_foreign_keys(pool, &mut issues).await?;
+    check_search_index(pool, &mut issues).await?;
+    check_snapshots(pool, &mut issues).await?;
+    Ok(DoctorReportDto { issues })
+}
+
+pub async fn repair(pool: &SqlitePool, repair: DoctorRepair) -> Result<(), sqlx::Error> {
+    match repair {
+        DoctorRepair::RebuildSearchIndex => rebuild_search_index(pool).await,
+    }
+}
+
+async fn check_integrity(
+    pool: &SqlitePool,
+    issues: &mut Vec<DoctorIssueDto>,
+) -> Result<(), sqlx::Error> {
+    let messages: Vec<String> = sqlx::query_scalar(&format!(
+        "PRAGMA integrity_check({INTEGRITY_CHECK_MAX_ERRORS})"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    issues.extend(
+        messages
+            .into_iter()
+            .filter(|message| message != "ok")
+            .map(|message| DoctorIssueDto {
+                check: DoctorCheck::Integrity,
+                message,
+                repair: None,
+            }),
+    );
+    Ok(())
+}
+
+async fn check_foreign_keys(
+    pool: &SqlitePool,
+    issues: &mut Vec<DoctorIssueDto>,
+) -> Result<(), sqlx::Error> {
+    let violations = sqlx::query_as::<_, (String, Option<i64>, String)>(
+        r#"SELECT "table", rowid, parent FROM pragma_foreign_key_check"#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    issues.extend(
+        violations
+            .into_iter()
+            .map(|(table, rowid, parent)| DoctorIssueDto {
+                check: DoctorCheck::ForeignKeys,
+                message: match rowid {
+                    Some(rowid) => format!("{table} row {rowid} refers to a missing {parent} row"),
+                    None => format!("A {table} row refers to a missing {parent} row"),
+                },
+                repair: None,
+            }),
+    );
+    Ok(())
+}
+
+async fn check_search_index(
+    pool: &SqlitePool,
+    issues: &mut Vec<DoctorIssueDto>,
+) -> Result<(), sqlx::Error> {
+    let (expected, actual, missing): (i64, i64, i64) = sqlx::query_as(&format!(
+        r"
+        SELECT
+            (SELECT COUNT(*) FROM ({EXPECTED_SEARCH_ROWS})),
+            (SELECT COUNT(*) FROM search_fts),
+            (SELECT COUNT(*) FROM ({EXPECTED_SEARCH_ROWS} EXCEPT {ACTUAL_SEARCH_ROWS}))
+        "
+    ))
+    .fetch_one(pool)
+    .await?;
+    // Rows that are not expected, including duplicates of expected ones.
+    let stale = actual - (expected - missing);
+
+    if missing > 0 || stale > 0 {
+        issues.push(DoctorIssueDto {
+            check: DoctorCheck::SearchIndex,
+            message: format!(
+                "The search index is out of date: {missing} missing and {stale} stale entries"
+            ),
+            repair: Some(DoctorRepair::RebuildSearchIndex),
+        });
+    }
+    Ok(())
+}
+
+async fn check_snapshots(
+    pool: &SqlitePool,
+    issues: &mut Vec<DoctorIssueDto>,
+) -> Result<(), sqlx::Error> {
+    let balances = sqlx::query_as::<_, (String, String)>(
+        r"
+        SELECT a.name, CAST(s.balance_date AS TEXT)
+        FROM account_balance_snapshots AS s
+        INNER JOIN accounts AS a ON a.id = s.account_id
+        WHERE typeof(s.balance_minor) <> 'integer' OR ABS(s.balance_minor) > ?
+        ORDER BY a.name, s.balance_date
+        ",
+    )
+    .bind(BALANCE_MINOR_ABS_MAX)
+    .fetch_all(pool)
+    .await?;
+    issues.extend(balances.into_iter().map(|(account, date)| DoctorIssueDto {
+        check: DoctorCheck::SnapshotBalances,
+        message: format!(
+            "{account} has a balance on {date} that is not a whole number within the allowed range"
+        ),
+        repair: None,
+    }));
+
+    // `date` normalizes valid dates and returns NULL for unparsable ones, so anything that
+    // does not survive it unchanged is not a plain `YYYY-MM-DD` date.
+    let dates = sqlx::query_as::<_, (String, String)>(
+        r"
+        SELECT a.name, CAST(s.balance_date AS TEXT)
+        FROM account_balance_snapshots AS s
+        INNER JOIN accounts AS a ON a.id = s.account_id
+        WHERE date(s.balance_date) IS NOT s.balance_date
+        ORDER BY a.name, s.balance_date
+        ",
+    )
+    .fetch_all(pool)
+    .await?;
+    issues.extend(dates.into_iter().map(|(account, date)| DoctorIssueDto {
+        check: DoctorCheck::SnapshotDates,
+        message: format!("{account} has a snapshot dated \"{date}\", which is not a valid date"),
+        repair: None,
+    }));
+    Ok(())
+}
+
+async fn rebuild_search_index(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM search_fts")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!(
+        "INSERT INTO search_fts (kind, entity_id, name, institution_name, account_type, tags) {EXPECTED_SEARCH_ROWS}"
+    ))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DoctorCheck, DoctorRepair, check, repair};
+    use crate::db::test_support::migrated_pool;
+
+    #[tokio::test]
+    async fn doctor_finds_drift_and_bad_snapshots_and_rebuilds_the_search_index() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let pool = migrated_pool(&data_dir.path().join("worth.sqlite"), None).await;
+        assert!(check(&pool).await.unwrap().issues.is_empty());
+
+        sqlx::query(
+            r"
+            INSERT INTO institutions (name) VALUES ('Bank');
+            INSERT INTO accounts (name, institution_id, type_id, currency_code, account_classification)
+            SELECT 'Savings', i.id, t.id, 'GBP', 'asset' FROM institutions AS i, account_types AS t LIMIT 1;
+            DELETE FROM search_fts WHERE kind = 'account';
+            INSERT INTO search_fts (kind, entity_id, name, institution_name, account_type, tags)
+            VALUES ('institution', 999, 'Gone', '', '', '');
+            PRAGMA ignore_check_constraints = ON;
+            INSERT INTO account_balance_snapshots (account_id, balance_date, balance_minor)
+            SELECT id, '2026-02-30', 100 FROM accounts;
+            INSERT INTO account_balance_snapshots (account_id, balance_date, balance_minor)
+            SELECT id, '2026-03-01', 100000000000000 FROM accounts;
+            PRAGMA ignore_check_constraints = OFF;
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = check(&pool).await.unwrap();
+        let checks: Vec<_> = report.issues.iter().map(|issue| issue.check).collect();
+        assert_eq!(
+            checks,
+            [
+                DoctorCheck::Integrity,
+                DoctorCheck::SearchIndex,
+                DoctorCheck::SnapshotBalances,
+                DoctorCheck::SnapshotDates
+            ]
+        );
+        assert_eq!(
+            report.issues[1].message,
+            "The search index is out of date: 1 missing and 1 stale entries"
+        );
+        assert_eq!(
+            report.issues[1].repair,
+            Some(DoctorRepair::RebuildSearchIndex)
+        );
+
+        repair(&pool, DoctorRepair::RebuildSearchIndex)
+            .await
+            .unwrap();
+        let checks: Vec<_> = check(&pool)
+            .await
+            .unwrap()
+            .issues
+            .into_iter()
+            .map(|issue| issue.check)
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                DoctorCheck::Integrity,
+                DoctorCheck::SnapshotBalances,
+                DoctorCheck::SnapshotDates
+            ]
+        );
+    }
+}
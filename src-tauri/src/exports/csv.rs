@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{DataExportAccount, DataExportDocument, DataExportFile};
+use crate::contracts::CurrencyCode;
+
+/// One CSV per table plus the long and wide snapshot layouts. Balances are written
+/// both as decimals in the account currency and, in the long file, as minor units.
+pub(super) fn files(document: &DataExportDocument) -> Result<Vec<DataExportFile>, std::io::Error> {
+    let institution_names = document
+        .institutions
+        .iter()
+        .map(|institution| (institution.id, institution.name.as_str()))
+        .collect::<HashMap<_, _>>();
+    let type_names = document
+        .account_types
+        .iter()
+        .map(|account_type| (account_type.id, account_type.name.as_str()))
+        .collect::<HashMap<_, _>>();
+    let accounts_by_id = document
+        .accounts
+        .iter()
+        .map(|account| (account.id, account))
+        .collect::<HashMap<_, _>>();
+    let institution_name = |account: &DataExportAccount| {
+        institution_names
+            .get(&account.institution_id)
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let mut account_types = writer();
+    account_types.write_record(["id", "name", "color"])?;
+    for account_type in &document.account_types {
+        account_types.write_record([
+            account_type.id.to_string().as_str(),
+            &account_type.name,
+            &account_type.color,
+        ])?;
+    }
+
+    let mut institutions = writer();
+    institutions.write_record(["id", "name"])?;
+    for institution in &document.institutions {
+        institutions.write_record([institution.id.to_string().as_str(), &institution.name])?;
+    }
+
+    let mut accounts = writer();
+    accounts.write_record([
+        "id",
+        "institution_id",
+        "institution",
+        "type_id",
+        "type",
+        "name",
+        "currency_code",
+        "account_classification",
+        "opened_date",
+        "closed_date",
+        "tags",
+    ])?;
+    for account in &document.accounts {
+        accounts.write_record([
+            account.id.to_string().as_str(),
+            &account.institution_id.to_string(),
+            institution_name(account),
+            &account.type_id.to_string(),
+            type_names
+                .get(&account.type_id)
+                .copied()
+                .unwrap_or_default(),
+            &account.name,
+            account.currency_code.as_str(),
+            account.account_classification.as_str(),
+            &optional_date(account.opened_date),
+            &optional_date(account.closed_date),
+            &account.tags.join("; "),
+        ])?;
+    }
+
+    let mut snapshots = writer();
+    snapshots.write_record([
+        "date",
+        "account_id",
+        "institution",
+        "account",
+        "currency_code",
+        "balance",
+        "balance_minor",
+        "note",
+        "source_kind",
+        "source_detail",
+    ])?;
+    // Dates down the side and one column per account, blank where an account has no
+    // snapshot on that date.
+    let mut wide_rows = BTreeMap::<_, HashMap<i64, String>>::new();
+    for snapshot in &document.snapshots {
+        let Some(account) = accounts_by_id.get(&snapshot.account_id) else {
+            continue;
+        };
+        let balance = format_minor(snapshot.balance_minor, account.currency_code);
+        let (source_kind, source_detail) = match &snapshot.source {
+            Some(source) => (source.kind_str(), source.detail().unwrap_or_default()),
+            None => ("", ""),
+        };
+        snapshots.write_record([
+            snapshot.date.to_string().as_str(),
+            &snapshot.account_id.to_string(),
+            institution_name(account),
+            &account.name,
+            account.currency_code.as_str(),
+            &balance,
+            &snapshot.balance_minor.to_string(),
+            snapshot.note.as_deref().unwrap_or_default(),
+            source_kind,
+            source_detail,
+        ])?;
+        wide_rows
+            .entry(snapshot.date)
+            .or_default()
+            .insert(snapshot.account_id, balance);
+    }
+
+    let mut wide = writer();
+    wide.write_record(
+        std::iter::once("Date".to_string()).chain(
+            document
+                .accounts
+                .iter()
+                .map(|account| format!("{} / {}", institution_name(account), account.name)),
+        ),
+    )?;
+    for (date, balances) in wide_rows {
+        wide.write_record(
+            std::iter::once(date.to_string()).chain(
+                document
+                    .accounts
+                    .iter()
+                    .map(|account| balances.get(&account.id).cloned().unwrap_or_default()),
+            ),
+        )?;
+    }
+
+    [
+        ("account_types.csv", account_types),
+        ("institutions.csv", institutions),
+        ("accounts.csv", accounts),
+        ("snapshots.csv", snapshots),
+        ("snapshots_wide.csv", wide),
+    ]
+    .into_iter()
+    .map(|(name, writer)| {
+        Ok(DataExportFile {
+            name: name.to_string(),
+            content: writer.into_inner().map_err(|error| error.into_error())?,
+        })
+    })
+    .collect()
+}
+
+fn writer() -> csv::Writer<Vec<u8>> {
+    csv::Writer::from_writer(Vec::new())
+}
+
+fn optional_date(date: Option<chrono::NaiveDate>) -> String {
+    date.map(|date| date.to_string()).unwrap_or_default()
+}
+
+/// Writes a minor-unit amount as a plain decimal, e.g. `-1234.50` for GBP.
+fn format_minor(balance_minor: i64, currency_code: CurrencyCode) -> String {
+    let exponent = currency_code.minor_unit_exponent();
+    if exponent == 0 {
+        return balance_minor.to_string();
+    }
+
+    let divisor = 10_u64.pow(exponent);
+    let magnitude = balance_minor.unsigned_abs();
+    let sign = if balance_minor < 0 { "-" } else { "" };
+    format!(
+        "{sign}{}.{:0width$}",
+        magnitude / divisor,
+        magnitude % divisor,
+        width = exponent as usize
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::super::tests::rows;
+    use super::super::{DataExportFormat, DataExportOptionsInput, document};
+    use super::{files, format_minor};
+    use crate::contracts::CurrencyCode;
+
+    #[test]
+    fn files_write_long_and_wide_snapshot_layouts() {
+        let options = DataExportOptionsInput {
+            format: DataExportFormat::Csv,
+            from_date: None,
+            to_date: None,
+            account_ids: None,
+        };
+        let document = document(
+            rows(),
+            &options,
+            Utc.with_ymd_and_hms(2026, 2, 1, 9, 30, 0).unwrap(),
+            "1.2.3",
+        )
+        .unwrap();
+
+        let files = files(&document).unwrap();
+        let file = |name: &str| {
+            let file = files.iter().find(|file| file.name == name).unwrap();
+            String::from_utf8(file.content.clone()).unwrap()
+        };
+
+        assert_eq!(files.len(), 5);
+        assert_eq!(
+            file("accounts.csv").lines().nth(2),
+            Some("2,2,Broker,2,Savings,ISA,EUR,asset,,,Emergency fund")
+        );
+        assert_eq!(
+            file("snapshots.csv"),
+            "date,account_id,institution,account,currency_code,balance,balance_minor,note,source_kind,source_detail\n\
+             2026-01-01,1,Bank,Everyday,GBP,123.45,12345,,csv_import,bank.csv\n\
+             2026-01-01,2,Broker,ISA,EUR,-0.50,-50,,,\n\
+             2026-01-02,1,Bank,Everyday,GBP,1.00,100,\"Salary, paid early\",manual,\n"
+        );
+        assert_eq!(
+            file("snapshots_wide.csv"),
+            "Date,Bank / Everyday,Broker / ISA\n2026-01-01,123.45,-0.50\n2026-01-02,1.00,\n"
+        );
+    }
+
+    #[test]
+    fn format_minor_uses_the_currency_exponent() {
+        assert_eq!(format_minor(-123_456, CurrencyCode::GBP), "-1234.56");
+        assert_eq!(format_minor(5, CurrencyCode::GBP), "0.05");
+        assert_eq!(format_minor(-1_500, CurrencyCode::JPY), "-1500");
+        assert_eq!(format_minor(1_234, CurrencyCode::BHD), "1.234");
+    }
+}
@@ -0,0 +1,395 @@
+mod csv;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::Manager;
+use tauri::path::BaseDirectory;
+
+use crate::contracts::{AccountClassification, CurrencyCode, SnapshotSource};
+use crate::db::rows::AccountBalanceSnapshotRow;
+use crate::db::{AccountListRow, AccountTagListRow, AccountTypeSummaryRow, InstitutionSummaryRow};
+
+/// Identifies a Worth export document regardless of its version.
+pub const DATA_EXPORT_FORMAT: &str = "worth-export";
+/// Bumped whenever a field is removed or changes meaning; new optional fields keep the version.
+pub const DATA_EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DataExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataExportOptionsInput {
+    pub format: DataExportFormat,
+    /// Earliest snapshot date to include, inclusive.
+    pub from_date: Option<NaiveDate>,
+    /// Latest snapshot date to include, inclusive.
+    pub to_date: Option<NaiveDate>,
+    /// `None` exports every account.
+    pub account_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataExportDto {
+    /// The JSON file, or the folder holding the CSV files.
+    pub path: String,
+    pub file_names: Vec<String>,
+    pub account_count: u32,
+    pub snapshot_count: u32,
+}
+
+/// The versioned JSON export. Ids are only meaningful within one document; they tie
+/// accounts to their institution and type and snapshots to their account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub app_version: String,
+    pub filters: DataExportFilters,
+    pub account_types: Vec<DataExportAccountType>,
+    pub institutions: Vec<DataExportInstitution>,
+    pub accounts: Vec<DataExportAccount>,
+    pub snapshots: Vec<DataExportSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportFilters {
+    pub from_date: Option<NaiveDate>,
+    pub to_date: Option<NaiveDate>,
+    pub account_ids: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportAccountType {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportInstitution {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportAccount {
+    pub id: i64,
+    pub institution_id: i64,
+    pub type_id: i64,
+    pub name: String,
+    pub currency_code: CurrencyCode,
+    pub account_classification: AccountClassification,
+    pub opened_date: Option<NaiveDate>,
+    pub closed_date: Option<NaiveDate>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExportSnapshot {
+    pub account_id: i64,
+    pub date: NaiveDate,
+    pub balance_minor: i64,
+    pub note: Option<String>,
+    pub source: Option<SnapshotSource>,
+}
+
+/// Everything read from the database for one export, already limited to the chosen
+/// accounts and dates.
+#[derive(Debug)]
+pub struct DataExportRows {
+    pub account_types: Vec<AccountTypeSummaryRow>,
+    pub institutions: Vec<InstitutionSummaryRow>,
+    pub accounts: Vec<AccountListRow>,
+    pub tags: Vec<AccountTagListRow>,
+    pub snapshots: Vec<AccountBalanceSnapshotRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataExportFile {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+pub fn exports_dir(app: &tauri::AppHandle) -> tauri::Result<PathBuf> {
+    app.path().resolve("exports", BaseDirectory::AppLocalData)
+}
+
+/// Builds the export document. An account subset keeps only the institutions and
+/// types those accounts use; a full export keeps unused ones too, so it describes
+/// the whole database.
+pub fn document(
+    rows: DataExportRows,
+    options: &DataExportOptionsInput,
+    exported_at: DateTime<Utc>,
+    app_version: &str,
+) -> Result<DataExportDocument, &'static str> {
+    let used_institution_ids = rows
+        .accounts
+        .iter()
+        .map(|account| account.institution_id)
+        .collect::<HashSet<_>>();
+    let used_type_ids = rows
+        .accounts
+        .iter()
+        .map(|account| account.type_id)
+        .collect::<HashSet<_>>();
+    let keep_unused = options.account_ids.is_none();
+
+    let mut tags_by_account = HashMap::<i64, Vec<String>>::new();
+    for tag in rows.tags {
+        tags_by_account
+            .entry(tag.account_id)
+            .or_default()
+            .push(tag.tag_name);
+    }
+
+    let mut accounts = rows
+        .accounts
+        .into_iter()
+        .map(|account| {
+            Ok(DataExportAccount {
+                tags: tags_by_account.remove(&account.id).unwrap_or_default(),
+                id: account.id,
+                institution_id: account.institution_id,
+                type_id: account.type_id,
+                name: account.name,
+                currency_code: account.currency_code.parse()?,
+                account_classification: account.account_classification.parse()?,
+                opened_date: account.opened_date,
+                closed_date: account.closed_date,
+            })
+        })
+        .collect::<Result<Vec<_>, &'static str>>()?;
+    accounts.sort_by_key(|account| account.id);
+
+    let mut account_types = rows
+        .account_types
+        .into_iter()
+        .filter(|account_type| keep_unused || used_type_ids.contains(&account_type.id))
+        .map(|account_type| DataExportAccountType {
+            id: account_type.id,
+            name: account_type.name,
+            color: account_type.color,
+        })
+        .collect::<Vec<_>>();
+    account_types.sort_by_key(|account_type| account_type.id);
+
+    let mut institutions = rows
+        .institutions
+        .into_iter()
+        .filter(|institution| keep_unused || used_institution_ids.contains(&institution.id))
+        .map(|institution| DataExportInstitution {
+            id: institution.id,
+            name: institution.name,
+        })
+        .collect::<Vec<_>>();
+    institutions.sort_by_key(|institution| institution.id);
+
+    let snapshots = rows
+        .snapshots
+        .into_iter()
+        .map(|snapshot| DataExportSnapshot {
+            source: SnapshotSource::from_columns(
+                snapshot.source_kind.as_deref(),
+                snapshot.source_detail.as_deref(),
+            ),
+            account_id: snapshot.account_id,
+            date: snapshot.balance_date,
+            balance_minor: snapshot.balance_minor,
+            note: snapshot.note,
+        })
+        .collect();
+
+    Ok(DataExportDocument {
+        format: DATA_EXPORT_FORMAT.to_string(),
+        version: DATA_EXPORT_VERSION,
+        exported_at,
+        app_version: app_version.to_string(),
+        filters: DataExportFilters {
+            from_date: options.from_date,
+            to_date: options.to_date,
+            account_ids: options.account_ids.clone(),
+        },
+        account_types,
+        institutions,
+        accounts,
+        snapshots,
+    })
+}
+
+/// Renders the document as the files for the chosen format: a single `{base_name}.json`,
+/// or CSVs with fixed names that belong in a folder called `base_name`.
+pub fn files(
+    document: &DataExportDocument,
+    format: DataExportFormat,
+    base_name: &str,
+) -> Result<Vec<DataExportFile>, std::io::Error> {
+    match format {
+        DataExportFormat::Json => Ok(vec![DataExportFile {
+            name: format!("{base_name}.json"),
+            content: serde_json::to_vec_pretty(document)?,
+        }]),
+        DataExportFormat::Csv => csv::files(document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::{DataExportFormat, DataExportOptionsInput, DataExportRows, document, files};
+    use crate::contracts::SnapshotSource;
+    use crate::db::rows::AccountBalanceSnapshotRow;
+    use crate::db::{
+        AccountListRow, AccountTagListRow, AccountTypeSummaryRow, InstitutionSummaryRow,
+    };
+
+    #[test]
+    fn document_round_trips_through_json_and_keeps_unused_rows_only_for_full_exports() {
+        let full = options(None);
+        let subset = options(Some(vec![1]));
+
+        let full_document = document(rows(), &full, exported_at(), "1.2.3").unwrap();
+        let mut subset_rows = rows();
+        subset_rows.accounts.retain(|account| account.id == 1);
+        let subset_document = document(subset_rows, &subset, exported_at(), "1.2.3").unwrap();
+        let json = &files(&full_document, DataExportFormat::Json, "worth-export").unwrap()[0];
+        let parsed = serde_json::from_slice::<serde_json::Value>(&json.content).unwrap();
+
+        assert_eq!(json.name, "worth-export.json");
+        assert_eq!(parsed["format"], "worth-export");
+        assert_eq!(parsed["version"], 1);
+        assert_eq!(
+            parsed["accounts"][1]["tags"],
+            serde_json::json!(["Emergency fund"])
+        );
+        assert_eq!(parsed["snapshots"][0]["source"]["kind"], "csv_import");
+        assert_eq!(
+            serde_json::from_slice::<super::DataExportDocument>(&json.content).unwrap(),
+            full_document
+        );
+        assert_eq!(full_document.institutions.len(), 3);
+        assert_eq!(full_document.account_types.len(), 2);
+        assert_eq!(subset_document.institutions.len(), 1);
+        assert_eq!(subset_document.account_types.len(), 1);
+        assert_eq!(subset_document.filters.account_ids, Some(vec![1]));
+        assert_eq!(
+            full_document.snapshots[0].source,
+            Some(SnapshotSource::CsvImport {
+                file_name: "bank.csv".to_string()
+            })
+        );
+    }
+
+    fn options(account_ids: Option<Vec<i64>>) -> DataExportOptionsInput {
+        DataExportOptionsInput {
+            format: DataExportFormat::Json,
+            from_date: None,
+            to_date: None,
+            account_ids,
+        }
+    }
+
+    fn exported_at() -> chrono::DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 2, 1, 9, 30, 0).unwrap()
+    }
+
+    pub(super) fn rows() -> DataExportRows {
+        DataExportRows {
+            account_types: vec![account_type(1, "Current"), account_type(2, "Savings")],
+            institutions: vec![
+                institution(1, "Bank"),
+                institution(2, "Broker"),
+                institution(3, "Unused"),
+            ],
+            accounts: vec![
+                account(2, "ISA", 2, "Broker", 2, "EUR"),
+                account(1, "Everyday", 1, "Bank", 1, "GBP"),
+            ],
+            tags: vec![AccountTagListRow {
+                account_id: 2,
+                tag_id: 1,
+                tag_name: "Emergency fund".to_string(),
+            }],
+            snapshots: vec![
+                snapshot(1, 2026, 1, 1, 12_345, Some("csv_import"), Some("bank.csv")),
+                snapshot(2, 2026, 1, 1, -50, None, None),
+                snapshot(1, 2026, 1, 2, 100, Some("manual"), None),
+            ],
+        }
+    }
+
+    fn account_type(id: i64, name: &str) -> AccountTypeSummaryRow {
+        AccountTypeSummaryRow {
+            id,
+            name: name.to_string(),
+            color: "#3b82f6".to_string(),
+            account_count: 1,
+        }
+    }
+
+    fn institution(id: i64, name: &str) -> InstitutionSummaryRow {
+        InstitutionSummaryRow {
+            id,
+            name: name.to_string(),
+            account_count: 1,
+            empty_account_count: 0,
+            total_balance_minor: 0,
+        }
+    }
+
+    fn account(
+        id: i64,
+        name: &str,
+        institution_id: i64,
+        institution_name: &str,
+        type_id: i64,
+        currency_code: &str,
+    ) -> AccountListRow {
+        AccountListRow {
+            id,
+            name: name.to_string(),
+            currency_code: currency_code.to_string(),
+            account_classification: "asset".to_string(),
+            opened_date: None,
+            closed_date: None,
+            institution_id,
+            institution_name: institution_name.to_string(),
+            type_id,
+            type_name: "Current".to_string(),
+            type_color: "#3b82f6".to_string(),
+            first_snapshot_date: None,
+            latest_snapshot_date: None,
+            latest_balance_minor: None,
+        }
+    }
+
+    fn snapshot(
+        account_id: i64,
+        year: i32,
+        month: u32,
+        day: u32,
+        balance_minor: i64,
+        source_kind: Option<&str>,
+        source_detail: Option<&str>,
+    ) -> AccountBalanceSnapshotRow {
+        AccountBalanceSnapshotRow {
+            id: account_id * 100 + i64::from(day),
+            account_id,
+            balance_date: NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+            balance_minor,
+            note: (day == 2).then(|| "Salary, paid early".to_string()),
+            source_kind: source_kind.map(str::to_string),
+            source_detail: source_detail.map(str::to_string),
+            created_at: Utc.with_ymd_and_hms(2026, 1, 3, 0, 0, 0).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::contracts::CurrencyCode;
+use crate::db::rows::FxRateRow;
+
+type CurrencyPair = (CurrencyCode, CurrencyCode);
+
+/// Dated exchange rates indexed by currency pair.
+///
+/// A stored rate converts in both directions; when a pair has rates stored both
+/// ways on the same date, the one entered for that direction wins.
+#[derive(Debug, Clone, Default)]
+pub struct FxRates {
+    rates_by_pair: HashMap<CurrencyPair, Vec<(NaiveDate, f64)>>,
+}
+
+impl FxRates {
+    pub fn from_rows(rows: &[FxRateRow]) -> Result<Self, &'static str> {
+        let mut entries: HashMap<CurrencyPair, Vec<(NaiveDate, bool, f64)>> = HashMap::new();
+        for row in rows {
+            let base: CurrencyCode = row.base_currency_code.parse()?;
+            let quote: CurrencyCode = row.quote_currency_code.parse()?;
+            entries
+                .entry((base, quote))
+                .or_default()
+                .push((row.rate_date, true, row.rate));
+            entries
+                .entry((quote, base))
+                .or_default()
+                .push((row.rate_date, false, 1.0 / row.rate));
+        }
+
+        let rates_by_pair = entries
+            .into_iter()
+            .map(|(pair, mut pair_entries)| {
+                // Sorting direct entries after inverted ones lets dedup keep the direct rate.
+                pair_entries.sort_by_key(|(date, is_direct, _rate)| (*date, *is_direct));
+                let mut rates: Vec<(NaiveDate, f64)> = Vec::with_capacity(pair_entries.len());
+                for (date, _is_direct, rate) in pair_entries {
+                    match rates.last_mut() {
+                        Some(last) if last.0 == date => last.1 = rate,
+                        _ => rates.push((date, rate)),
+                    }
+                }
+                (pair, rates)
+            })
+            .collect();
+
+        Ok(Self { rates_by_pair })
+    }
+
+    /// The latest rate dated on or before `date`, as units of `to` per unit of `from`.
+    pub fn rate_on(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+
+        let rates = self.rates_by_pair.get(&(from, to))?;
+        let in_effect = rates.partition_point(|(rate_date, _rate)| *rate_date <= date);
+        in_effect.checked_sub(1).map(|index| rates[index].1)
+    }
+
+    /// Converts a minor-unit balance, rounding to the nearest minor unit of `to`.
+    ///
+    /// Returns `None` when no rate is in effect on `date`; a zero balance needs no rate.
+    pub fn convert_minor(
+        &self,
+        balance_minor: i64,
+        from: CurrencyCode,
+        to: CurrencyCode,
+        date: NaiveDate,
+    ) -> Option<i64> {
+        if from == to || balance_minor == 0 {
+            return Some(balance_minor);
+        }
+
+        let rate = self.rate_on(from, to, date)?;
+        let exponent_shift = to.minor_unit_exponent() as i32 - from.minor_unit_exponent() as i32;
+        let converted = balance_minor as f64 * rate * 10f64.powi(exponent_shift);
+        Some(converted.round() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    use super::FxRates;
+    use crate::contracts::CurrencyCode;
+    use crate::db::rows::FxRateRow;
+
+    #[test]
+    fn rate_on_uses_the_latest_rate_on_or_before_the_date() {
+        let rates = FxRates::from_rows(&[
+            row("GBP", "EUR", date(2026, 1, 1), 1.15),
+            row("GBP", "EUR", date(2026, 2, 1), 1.2),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            rates.rate_on(CurrencyCode::GBP, CurrencyCode::EUR, date(2025, 12, 31)),
+            None
+        );
+        assert_eq!(
+            rates.rate_on(CurrencyCode::GBP, CurrencyCode::EUR, date(2026, 1, 31)),
+            Some(1.15)
+        );
+        assert_eq!(
+            rates.rate_on(CurrencyCode::GBP, CurrencyCode::EUR, date(2026, 3, 1)),
+            Some(1.2)
+        );
+    }
+
+    #[test]
+    fn rate_on_inverts_rates_and_prefers_the_direct_rate_on_the_same_date() {
+        let rates = FxRates::from_rows(&[
+            row("GBP", "EUR", date(2026, 1, 1), 1.25),
+            row("EUR", "GBP", date(2026, 2, 1), 0.5),
+            row("GBP", "EUR", date(2026, 2, 1), 4.0),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            rates.rate_on(CurrencyCode::EUR, CurrencyCode::GBP, date(2026, 1, 15)),
+            Some(0.8)
+        );
+        assert_eq!(
+            rates.rate_on(CurrencyCode::EUR, CurrencyCode::GBP, date(2026, 2, 1)),
+            Some(0.5)
+        );
+        assert_eq!(
+            rates.rate_on(CurrencyCode::GBP, CurrencyCode::EUR, date(2026, 2, 1)),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn convert_minor_scales_between_minor_unit_exponents() {
+        let rates = FxRates::from_rows(&[
+            row("GBP", "JPY", date(2026, 1, 1), 190.0),
+            row("GBP", "BHD", date(2026, 1, 1), 0.475),
+        ])
+        .unwrap();
+        let on = date(2026, 1, 2);
+
+        assert_eq!(
+            rates.convert_minor(12_340, CurrencyCode::GBP, CurrencyCode::JPY, on),
+            Some(23_446)
+        );
+        assert_eq!(
+            rates.convert_minor(19_000, CurrencyCode::JPY, CurrencyCode::GBP, on),
+            Some(10_000)
+        );
+        assert_eq!(
+            rates.convert_minor(-10_000, CurrencyCode::GBP, CurrencyCode::BHD, on),
+            Some(-47_500)
+        );
+        assert_eq!(
+            rates.convert_minor(100, CurrencyCode::GBP, CurrencyCode::GBP, on),
+            Some(100)
+        );
+        assert_eq!(
+            rates.convert_minor(100, CurrencyCode::USD, CurrencyCode::GBP, on),
+            None
+        );
+        assert_eq!(
+            rates.convert_minor(0, CurrencyCode::USD, CurrencyCode::GBP, on),
+            Some(0)
+        );
+    }
+
+    fn row(base: &str, quote: &str, rate_date: NaiveDate, rate: f64) -> FxRateRow {
+        FxRateRow {
+            id: 0,
+            base_currency_code: base.to_string(),
+            quote_currency_code: quote.to_string(),
+            rate_date,
+            rate,
+            created_at: DateTime::<Utc>::UNIX_EPOCH,
+            updated_at: DateTime::<Utc>::UNIX_EPOCH,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+}
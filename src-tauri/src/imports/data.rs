@@ -0,0 +1,504 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::contracts::CurrencyCode;
+use crate::exports::{
+    DATA_EXPORT_FORMAT, DATA_EXPORT_VERSION, DataExportAccount, DataExportAccountType,
+    DataExportDocument, DataExportInstitution, DataExportRows, DataExportSnapshot,
+};
+use crate::imports::snapshots::{
+    SnapshotImportExistingDatePolicy, SnapshotImportPreviewAction, SnapshotImportPreviewSummaryDto,
+    SnapshotImportValidationIssue, issue,
+};
+
+const NOT_AN_EXPORT: &str = "The selected file is not a Worth JSON export";
+const DAMAGED_EXPORT: &str = "The selected export is damaged or incomplete";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportSourceInput {
+    pub file_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportOptionsInput {
+    /// What to do when a matched account already has a snapshot on an imported date.
+    pub existing_date_policy: SnapshotImportExistingDatePolicy,
+    pub overwrite_existing_confirmed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataImportMatchAction {
+    Create,
+    /// Reuses the existing row with the same name.
+    Merge,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportEntityPreviewDto {
+    pub name: String,
+    pub action: DataImportMatchAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportAccountPreviewDto {
+    pub institution_name: String,
+    pub name: String,
+    pub currency_code: CurrencyCode,
+    pub action: DataImportMatchAction,
+    /// `None` for accounts the import will create.
+    pub account_id: Option<i64>,
+    pub summary: SnapshotImportPreviewSummaryDto,
+    /// Problems that stop this account being imported.
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportPreviewDto {
+    pub exported_at: DateTime<Utc>,
+    pub app_version: String,
+    pub account_types: Vec<DataImportEntityPreviewDto>,
+    pub institutions: Vec<DataImportEntityPreviewDto>,
+    pub accounts: Vec<DataImportAccountPreviewDto>,
+    pub summary: SnapshotImportPreviewSummaryDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct DataImportCommitDto {
+    pub created_account_type_count: u32,
+    pub created_institution_count: u32,
+    pub created_account_count: u32,
+    pub merged_account_count: u32,
+    pub created_count: u32,
+    pub overwritten_count: u32,
+    pub skipped_count: u32,
+}
+
+/// A document row paired with the existing row it matched by name, if any.
+#[derive(Debug, Clone)]
+pub struct DataImportMatch<T> {
+    pub item: T,
+    pub existing_id: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DataImportAccountPlan {
+    pub account: DataImportMatch<DataExportAccount>,
+    /// Full tag list to set on the account, or `None` when a merged account keeps its tags.
+    pub tags: Option<Vec<String>>,
+    pub writes: Vec<DataImportSnapshotWrite>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DataImportSnapshotWrite {
+    pub action: SnapshotImportPreviewAction,
+    pub existing_snapshot_id: Option<i64>,
+    pub snapshot: DataExportSnapshot,
+}
+
+#[derive(Debug, Clone)]
+pub struct DataImportPlan {
+    pub preview: DataImportPreviewDto,
+    pub account_types: Vec<DataImportMatch<DataExportAccountType>>,
+    pub institutions: Vec<DataImportMatch<DataExportInstitution>>,
+    pub accounts: Vec<DataImportAccountPlan>,
+}
+
+/// Reads a Worth JSON export, rejecting other files and versions newer than this app
+/// understands, and checks that its ids refer to rows within the document.
+pub fn parse_document(
+    input: &DataImportSourceInput,
+) -> Result<DataExportDocument, Vec<SnapshotImportValidationIssue>> {
+    let value = serde_json::from_str::<serde_json::Value>(&input.content)
+        .map_err(|_| vec![issue("source", NOT_AN_EXPORT)])?;
+    if value.get("format").and_then(serde_json::Value::as_str) != Some(DATA_EXPORT_FORMAT) {
+        return Err(vec![issue("source", NOT_AN_EXPORT)]);
+    }
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+        Some(version) if version > u64::from(DATA_EXPORT_VERSION) => {
+            return Err(vec![issue(
+                "source",
+                "This export was made by a newer version of Worth. Update Worth to import it",
+            )]);
+        }
+        Some(version) if version >= 1 => {}
+        _ => return Err(vec![issue("source", NOT_AN_EXPORT)]),
+    }
+
+    let document = serde_json::from_value::<DataExportDocument>(value)
+        .map_err(|_| vec![issue("source", DAMAGED_EXPORT)])?;
+    if is_consistent(&document) {
+        Ok(document)
+    } else {
+        Err(vec![issue("source", DAMAGED_EXPORT)])
+    }
+}
+
+fn is_consistent(document: &DataExportDocument) -> bool {
+    let type_ids = document
+        .account_types
+        .iter()
+        .map(|account_type| account_type.id)
+        .collect::<HashSet<_>>();
+    let institution_ids = document
+        .institutions
+        .iter()
+        .map(|institution| institution.id)
+        .collect::<HashSet<_>>();
+    let account_ids = document
+        .accounts
+        .iter()
+        .map(|account| account.id)
+        .collect::<HashSet<_>>();
+    let type_names = document
+        .account_types
+        .iter()
+        .map(|account_type| account_type.name.as_str())
+        .collect::<HashSet<_>>();
+    let institution_names = document
+        .institutions
+        .iter()
+        .map(|institution| institution.name.as_str())
+        .collect::<HashSet<_>>();
+    let account_keys = document
+        .accounts
+        .iter()
+        .map(|account| (account.institution_id, account.name.as_str()))
+        .collect::<HashSet<_>>();
+    let snapshot_keys = document
+        .snapshots
+        .iter()
+        .map(|snapshot| (snapshot.account_id, snapshot.date))
+        .collect::<HashSet<_>>();
+
+    type_ids.len() == document.account_types.len()
+        && type_names.len() == document.account_types.len()
+        && institution_ids.len() == document.institutions.len()
+        && institution_names.len() == document.institutions.len()
+        && account_ids.len() == document.accounts.len()
+        && account_keys.len() == document.accounts.len()
+        && snapshot_keys.len() == document.snapshots.len()
+        && document.accounts.iter().all(|account| {
+            institution_ids.contains(&account.institution_id) && type_ids.contains(&account.type_id)
+        })
+        && document
+            .snapshots
+            .iter()
+            .all(|snapshot| account_ids.contains(&snapshot.account_id))
+}
+
+/// Matches the document against the database by name, following the unique names
+/// of account types and institutions and `UNIQUE (institution_id, name)` for accounts,
+/// then plans each snapshot against the matched account's existing snapshots. Matched
+/// rows keep their settings; merged accounts only gain tags and snapshots.
+pub fn plan(
+    document: DataExportDocument,
+    existing: &DataExportRows,
+    options: &DataImportOptionsInput,
+) -> DataImportPlan {
+    let existing_type_ids = existing
+        .account_types
+        .iter()
+        .map(|account_type| (account_type.name.as_str(), account_type.id))
+        .collect::<HashMap<_, _>>();
+    let existing_institution_ids = existing
+        .institutions
+        .iter()
+        .map(|institution| (institution.name.as_str(), institution.id))
+        .collect::<HashMap<_, _>>();
+    let existing_accounts = existing
+        .accounts
+        .iter()
+        .map(|account| ((account.institution_id, account.name.as_str()), account))
+        .collect::<HashMap<_, _>>();
+    let mut existing_tags = HashMap::<i64, Vec<String>>::new();
+    for tag in &existing.tags {
+        existing_tags
+            .entry(tag.account_id)
+            .or_default()
+            .push(tag.tag_name.clone());
+    }
+    let mut existing_snapshots = HashMap::<(i64, NaiveDate), _>::new();
+    for snapshot in &existing.snapshots {
+        existing_snapshots.insert((snapshot.account_id, snapshot.balance_date), snapshot);
+    }
+
+    let account_types = document
+        .account_types
+        .into_iter()
+        .map(|account_type| DataImportMatch {
+            existing_id: existing_type_ids.get(account_type.name.as_str()).copied(),
+            item: account_type,
+        })
+        .collect::<Vec<_>>();
+    let institutions = document
+        .institutions
+        .into_iter()
+        .map(|institution| DataImportMatch {
+            existing_id: existing_institution_ids
+                .get(institution.name.as_str())
+                .copied(),
+            item: institution,
+        })
+        .collect::<Vec<_>>();
+    let institutions_by_id = institutions
+        .iter()
+        .map(|institution| (institution.item.id, institution))
+        .collect::<HashMap<_, _>>();
+
+    let mut snapshots_by_account = BTreeMap::<i64, Vec<DataExportSnapshot>>::new();
+    for snapshot in document.snapshots {
+        snapshots_by_account
+            .entry(snapshot.account_id)
+            .or_default()
+            .push(snapshot);
+    }
+
+    let mut accounts = Vec::with_capacity(document.accounts.len());
+    let mut account_previews = Vec::with_capacity(document.accounts.len());
+    for account in document.accounts {
+        let institution = institutions_by_id[&account.institution_id];
+        let existing_account = institution.existing_id.and_then(|institution_id| {
+            existing_accounts
+                .get(&(institution_id, account.name.as_str()))
+                .copied()
+        });
+        let mut issues = Vec::new();
+        if let Some(existing_account) = existing_account
+            && existing_account.currency_code != account.currency_code.as_str()
+        {
+            issues.push(format!(
+                "The existing account uses {}, but the export uses {}",
+                existing_account.currency_code,
+                account.currency_code.as_str()
+            ));
+        }
+
+        let tags = match existing_account {
+            None => Some(account.tags.clone()),
+            Some(existing_account) => {
+                let mut tags = existing_tags
+                    .get(&existing_account.id)
+                    .cloned()
+                    .unwrap_or_default();
+                let known = tags
+                    .iter()
+                    .map(|tag| tag.to_lowercase())
+                    .collect::<HashSet<_>>();
+                let added = account
+                    .tags
+                    .iter()
+                    .filter(|tag| !known.contains(&tag.to_lowercase()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (!added.is_empty()).then(|| {
+                    tags.extend(added);
+                    tags
+                })
+            }
+        };
+
+        let mut summary = empty_summary();
+        let mut writes = Vec::new();
+        let mut has_existing_date_conflict = false;
+        for snapshot in snapshots_by_account.remove(&account.id).unwrap_or_default() {
+            summary.total_rows += 1;
+            if !issues.is_empty() {
+                summary.invalid_count += 1;
+                continue;
+            }
+
+            let existing_snapshot = existing_account.and_then(|existing_account| {
+                existing_snapshots.get(&(existing_account.id, snapshot.date))
+            });
+            let action = match existing_snapshot {
+                None => SnapshotImportPreviewAction::Create,
+                Some(existing_snapshot)
+                    if existing_snapshot.balance_minor == snapshot.balance_minor =>
+                {
+                    SnapshotImportPreviewAction::SkipUnchanged
+                }
+                Some(_) => match options.existing_date_policy {
+                    SnapshotImportExistingDatePolicy::Overwrite => {
+                        SnapshotImportPreviewAction::Overwrite
+                    }
+                    SnapshotImportExistingDatePolicy::Skip => {
+                        SnapshotImportPreviewAction::SkipExisting
+                    }
+                    SnapshotImportExistingDatePolicy::Error => {
+                        has_existing_date_conflict = true;
+                        SnapshotImportPreviewAction::Invalid
+                    }
+                },
+            };
+
+            match action {
+                SnapshotImportPreviewAction::Create => summary.create_count += 1,
+                SnapshotImportPreviewAction::Overwrite => summary.overwrite_count += 1,
+                SnapshotImportPreviewAction::Invalid => summary.invalid_count += 1,
+                _ => summary.skip_count += 1,
+            }
+            if matches!(
+                action,
+                SnapshotImportPreviewAction::Create | SnapshotImportPreviewAction::Overwrite
+            ) {
+                writes.push(DataImportSnapshotWrite {
+                    action,
+                    existing_snapshot_id: existing_snapshot.map(|snapshot| snapshot.id),
+                    snapshot,
+                });
+            }
+        }
+        if has_existing_date_conflict {
+            issues.push("Snapshots already exist on some of the imported dates".to_string());
+        }
+
+        account_previews.push(DataImportAccountPreviewDto {
+            institution_name: institution.item.name.clone(),
+            name: account.name.clone(),
+            currency_code: account.currency_code,
+            action: match_action(existing_account.is_some()),
+            account_id: existing_account.map(|existing_account| existing_account.id),
+            summary,
+            issues,
+        });
+        accounts.push(DataImportAccountPlan {
+            account: DataImportMatch {
+                existing_id: existing_account.map(|existing_account| existing_account.id),
+                item: account,
+            },
+            tags,
+            writes,
+        });
+    }
+
+    let summary = account_previews
+        .iter()
+        .fold(empty_summary(), |total, account| {
+            SnapshotImportPreviewSummaryDto {
+                total_rows: total.total_rows + account.summary.total_rows,
+                create_count: total.create_count + account.summary.create_count,
+                overwrite_count: total.overwrite_count + account.summary.overwrite_count,
+                skip_count: total.skip_count + account.summary.skip_count,
+                invalid_count: total.invalid_count + account.summary.invalid_count,
+            }
+        });
+
+    DataImportPlan {
+        preview: DataImportPreviewDto {
+            exported_at: document.exported_at,
+            app_version: document.app_version,
+            account_types: account_types
+                .iter()
+                .map(|account_type| DataImportEntityPreviewDto {
+                    name: account_type.item.name.clone(),
+                    action: match_action(account_type.existing_id.is_some()),
+                })
+                .collect(),
+            institutions: institutions
+                .iter()
+                .map(|institution| DataImportEntityPreviewDto {
+                    name: institution.item.name.clone(),
+                    action: match_action(institution.existing_id.is_some()),
+                })
+                .collect(),
+            accounts: account_previews,
+            summary,
+        },
+        account_types,
+        institutions,
+        accounts,
+    }
+}
+
+fn match_action(matched: bool) -> DataImportMatchAction {
+    if matched {
+        DataImportMatchAction::Merge
+    } else {
+        DataImportMatchAction::Create
+    }
+}
+
+fn empty_summary() -> SnapshotImportPreviewSummaryDto {
+    SnapshotImportPreviewSummaryDto {
+        total_rows: 0,
+        create_count: 0,
+        overwrite_count: 0,
+        skip_count: 0,
+        invalid_count: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataImportSourceInput, parse_document};
+
+    fn input(content: &str) -> DataImportSourceInput {
+        DataImportSourceInput {
+            file_name: "backup.json".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn messages(content: &str) -> Vec<String> {
+        parse_document(&input(content))
+            .unwrap_err()
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect()
+    }
+
+    const DOCUMENT: &str = r##"{
+        "format": "worth-export",
+        "version": 1,
+        "exported_at": "2026-02-01T09:30:00Z",
+        "app_version": "1.2.3",
+        "filters": { "from_date": null, "to_date": null, "account_ids": null },
+        "account_types": [{ "id": 1, "name": "Current", "color": "#3b82f6" }],
+        "institutions": [{ "id": 1, "name": "Bank" }],
+        "accounts": [{
+            "id": 1, "institution_id": 1, "type_id": 1, "name": "Everyday",
+            "currency_code": "GBP", "account_classification": "asset",
+            "opened_date": null, "closed_date": null, "tags": []
+        }],
+        "snapshots": [{
+            "account_id": 1, "date": "2026-01-01", "balance_minor": 100,
+            "note": null, "source": { "kind": "manual" }
+        }]
+    }"##;
+
+    #[test]
+    fn parse_document_reads_a_current_export() {
+        let document = parse_document(&input(DOCUMENT)).unwrap();
+
+        assert_eq!(document.accounts[0].name, "Everyday");
+        assert_eq!(document.snapshots.len(), 1);
+    }
+
+    #[test]
+    fn parse_document_rejects_other_files_newer_versions_and_broken_references() {
+        assert_eq!(
+            messages("Date,Balance\n"),
+            ["The selected file is not a Worth JSON export"]
+        );
+        assert_eq!(
+            messages(r#"{ "format": "other", "version": 1 }"#),
+            ["The selected file is not a Worth JSON export"]
+        );
+        assert_eq!(
+            messages(&DOCUMENT.replace(r#""version": 1"#, r#""version": 2"#)),
+            ["This export was made by a newer version of Worth. Update Worth to import it"]
+        );
+        assert_eq!(
+            messages(&DOCUMENT.replace(r#""account_id": 1"#, r#""account_id": 9"#)),
+            ["The selected export is damaged or incomplete"]
+        );
+        assert_eq!(
+            messages(&DOCUMENT.replace(r#""balance_minor": 100"#, r#""balance_minor": "1""#)),
+            ["The selected export is damaged or incomplete"]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+use super::snapshots::{
+    CsvSnapshotImportDateFormat, CsvSnapshotImportSourceInput, SnapshotImportValidationIssue,
+    issue, parse_date_as_written, read_column_pairs,
+};
+use crate::contracts::{AppLocaleCode, CurrencyCode};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FxRateCsvImportInput {
+    pub source: CsvSnapshotImportSourceInput,
+    pub base_currency_code: CurrencyCode,
+    pub quote_currency_code: CurrencyCode,
+    pub date_column: String,
+    pub rate_column: String,
+    pub date_format: CsvSnapshotImportDateFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FxRateImportCommitDto {
+    pub created_count: u32,
+    pub updated_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxRateImportRow {
+    pub date: NaiveDate,
+    pub rate: f64,
+}
+
+/// Parses every row of a rate CSV, rejecting the whole file if any row is invalid
+/// so an import never leaves a partially updated rate history.
+pub fn parse_csv_rates(
+    input: &FxRateCsvImportInput,
+    locale: AppLocaleCode,
+) -> Result<Vec<FxRateImportRow>, Vec<SnapshotImportValidationIssue>> {
+    if input.base_currency_code == input.quote_currency_code {
+        return Err(vec![issue(
+            "quote_currency_code",
+            "Choose two different currencies",
+        )]);
+    }
+
+    let pairs = read_column_pairs(
+        &input.source,
+        (&input.date_column, "date_column"),
+        (&input.rate_column, "rate_column"),
+    )?;
+
+    let mut issues = Vec::new();
+    let mut rows = Vec::with_capacity(pairs.len());
+    let mut rows_by_date = HashMap::<NaiveDate, u32>::new();
+    for (source_row_number, raw_date, raw_rate) in pairs {
+        let date = parse_date_as_written(&raw_date, input.date_format, locale);
+        let rate = parse_rate(&raw_rate);
+
+        match (date, rate) {
+            (Some(date), Some(rate)) => {
+                if let Some(first_row_number) = rows_by_date.insert(date, source_row_number) {
+                    issues.push(issue(
+                        "source",
+                        &format!(
+                            "Row {source_row_number} repeats the date from row {first_row_number}"
+                        ),
+                    ));
+                    continue;
+                }
+                rows.push(FxRateImportRow { date, rate });
+            }
+            (None, _) => issues.push(issue(
+                "source",
+                &format!("Row {source_row_number}: date does not match the selected format"),
+            )),
+            (_, None) => issues.push(issue(
+                "source",
+                &format!("Row {source_row_number}: rate must be a number greater than 0"),
+            )),
+        }
+    }
+
+    if !issues.is_empty() {
+        return Err(issues);
+    }
+    if rows.is_empty() {
+        return Err(vec![issue("source", "CSV must include at least one rate")]);
+    }
+
+    rows.sort_by_key(|row| row.date);
+    Ok(rows)
+}
+
+pub(crate) fn parse_rate(raw: &str) -> Option<f64> {
+    let value = raw.trim();
+    if value.is_empty() || !value.chars().all(|ch| ch.is_ascii_digit() || ch == '.') {
+        return None;
+    }
+
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{FxRateCsvImportInput, FxRateImportRow, parse_csv_rates, parse_rate};
+    use crate::contracts::{AppLocaleCode, CurrencyCode};
+    use crate::imports::snapshots::{CsvSnapshotImportDateFormat, CsvSnapshotImportSourceInput};
+
+    #[test]
+    fn parse_csv_rates_reads_selected_columns_in_date_order() {
+        let rows = parse_csv_rates(
+            &input("Date,GBP/EUR\n10/01/2026,1.1612\n09/01/2026,1.16\n"),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                FxRateImportRow {
+                    date: date(2026, 1, 9),
+                    rate: 1.16,
+                },
+                FxRateImportRow {
+                    date: date(2026, 1, 10),
+                    rate: 1.1612,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_rates_rejects_invalid_and_repeated_rows() {
+        let issues = parse_csv_rates(
+            &input("Date,GBP/EUR\n09/01/2026,1.16\nnope,1.17\n10/01/2026,0\n09/01/2026,1.18\n"),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap_err();
+
+        let messages = issues
+            .iter()
+            .map(|issue| issue.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "Row 3: date does not match the selected format",
+                "Row 4: rate must be a number greater than 0",
+                "Row 5 repeats the date from row 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_csv_rates_requires_different_currencies() {
+        let issues = parse_csv_rates(
+            &FxRateCsvImportInput {
+                quote_currency_code: CurrencyCode::GBP,
+                ..input("Date,GBP/EUR\n09/01/2026,1.16\n")
+            },
+            AppLocaleCode::EnGb,
+        )
+        .unwrap_err();
+
+        assert_eq!(issues[0].field, "quote_currency_code");
+    }
+
+    #[test]
+    fn parse_rate_accepts_plain_positive_decimals_only() {
+        assert_eq!(parse_rate(" 1.25 "), Some(1.25));
+        assert_eq!(parse_rate("190"), Some(190.0));
+        assert_eq!(parse_rate("-1.25"), None);
+        assert_eq!(parse_rate("1,25"), None);
+        assert_eq!(parse_rate("0.0"), None);
+    }
+
+    fn input(content: &str) -> FxRateCsvImportInput {
+        FxRateCsvImportInput {
+            source: CsvSnapshotImportSourceInput {
+                file_name: "rates.csv".to_string(),
+                content: content.as_bytes().to_vec(),
+                has_header_row: true,
+                encoding: None,
+                delimiter: None,
+                quote: None,
+            },
+            base_currency_code: CurrencyCode::GBP,
+            quote_currency_code: CurrencyCode::EUR,
+            date_column: "Date".to_string(),
+            rate_column: "GBP/EUR".to_string(),
+            date_format: CsvSnapshotImportDateFormat::DdMmYyyySlash,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+}
@@ -1 +1,3 @@
+pub mod data;
+pub mod fx_rates;
 pub mod snapshots;
@@ -1,17 +1,30 @@
-use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::cmp::Reverse;
 use std::collections::HashMap;
 
+use super::csv_format::{
+    CsvSnapshotImportDelimiter, CsvSnapshotImportEncoding, CsvSnapshotImportFormatDto,
+    CsvSnapshotImportQuote, decode, detect_delimiter, detect_quote,
+};
+use super::known_profiles::{
+    CsvSnapshotImportKnownProfileDto, KnownProfile, file_column, known_profile,
+};
+use super::month_names::month_from_name;
 use super::{
-    SnapshotImportCandidate, SnapshotImportDuplicateDatePolicy, SnapshotImportValidationIssue,
-    issue,
+    SnapshotImportCandidate, SnapshotImportDuplicateDatePolicy, SnapshotImportProfileDto,
+    SnapshotImportValidationIssue, issue,
 };
-use crate::contracts::BALANCE_MINOR_ABS_MAX;
+use crate::contracts::{AppLocaleCode, CurrencyCode};
+
+// Inspection runs before an account is chosen, so balance guesses assume the
+// common two-decimal minor unit.
+const GUESS_CURRENCY_CODE: CurrencyCode = CurrencyCode::GBP;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ParseAmountMinorError {
+pub(super) enum ParseAmountMinorError {
     Invalid,
     TooLarge,
 }
@@ -19,8 +32,13 @@ enum ParseAmountMinorError {
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct CsvSnapshotImportSourceInput {
     pub file_name: String,
-    pub content: String,
+    /// Raw file bytes; the encoding is detected unless `encoding` is set.
+    pub content: Vec<u8>,
     pub has_header_row: bool,
+    /// Overrides for the detected format; `None` detects from the content.
+    pub encoding: Option<CsvSnapshotImportEncoding>,
+    pub delimiter: Option<CsvSnapshotImportDelimiter>,
+    pub quote: Option<CsvSnapshotImportQuote>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -47,6 +65,17 @@ pub enum CsvSnapshotImportDateFormat {
     YyyyMmDdSlash,
     #[serde(rename = "iso_8601_date_time")]
     Iso8601DateTime,
+    /// Day, month name and year, such as "03 Jan 2024" or "3-January-24".
+    DdMonYyyy,
+    /// Month name, day and year, such as "January 3, 2024".
+    MonDdYyyy,
+    /// Year, month name and day, such as "2024-Jan-03".
+    YyyyMonDd,
+    /// Eight digits with no separators, such as "20240103".
+    YyyyMmDdCompact,
+    /// Spreadsheet day numbers counted from 1899-12-30, such as "45294". Any time of
+    /// day after the decimal point is ignored.
+    ExcelSerial,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
@@ -70,6 +99,31 @@ pub enum CsvSnapshotImportMissingTimezonePolicy {
 pub enum CsvSnapshotImportBalanceFormat {
     ThousandsCommaDecimalDot,
     ThousandsDotDecimalComma,
+    /// Plain, no-break, or narrow no-break spaces between thousands.
+    ThousandsSpaceDecimalComma,
+    ThousandsSpaceDecimalDot,
+    /// Swiss style, with a straight or typographic apostrophe between thousands.
+    ThousandsApostropheDecimalDot,
+    /// Whole numbers already in the currency's minor unit, such as pence.
+    MinorUnits,
+    /// Like `ThousandsCommaDecimalDot`, but negative amounts may end in `-`, as in
+    /// "12.50-".
+    ThousandsCommaDecimalDotTrailingMinus,
+    /// Like `ThousandsDotDecimalComma`, but negative amounts may end in `-`, as in
+    /// "12,50-".
+    ThousandsDotDecimalCommaTrailingMinus,
+    /// Like `ThousandsCommaDecimalDot`, but amounts may end in `CR` for credits or `DR`
+    /// for debits, which are negative.
+    ThousandsCommaDecimalDotCreditDebit,
+}
+
+/// How a balance format marks negative amounts besides a leading `-` or parentheses,
+/// which every format accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignSuffix {
+    None,
+    Minus,
+    CreditDebit,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
@@ -110,27 +164,55 @@ pub struct CsvSnapshotImportInspectionDto {
     pub sample_rows: Vec<CsvSnapshotImportSampleRowDto>,
     pub guesses: CsvSnapshotImportGuessesDto,
     pub total_rows: u32,
+    pub format: CsvSnapshotImportFormatDto,
+    /// Built-in bank or platform layout recognised from the headers; `guesses` follow it
+    /// when it is a confident match.
+    pub known_profile: Option<CsvSnapshotImportKnownProfileDto>,
+    /// Saved profile whose columns match this file; its options take the place of
+    /// `guesses` when set.
+    pub profile: Option<Box<SnapshotImportProfileDto>>,
 }
 
+/// Rows read from a tabular source as text, shared with spreadsheet imports so both
+/// guess columns and formats the same way.
 #[derive(Debug, Clone)]
-struct CsvData {
-    file_name: String,
-    columns: Vec<String>,
-    rows: Vec<CsvRow>,
+pub(super) struct CsvData {
+    pub columns: Vec<String>,
+    pub rows: Vec<CsvRow>,
 }
 
 #[derive(Debug, Clone)]
-struct CsvRow {
-    source_row_number: u32,
-    values: Vec<String>,
+pub(super) struct CsvRow {
+    pub source_row_number: u32,
+    pub values: Vec<String>,
 }
 
 pub fn inspect(
     input: &CsvSnapshotImportSourceInput,
+    locale: AppLocaleCode,
 ) -> Result<CsvSnapshotImportInspectionDto, Vec<SnapshotImportValidationIssue>> {
-    let data = parse_csv(input)?;
-    let columns = data
-        .columns
+    let (data, format) = parse_csv(input)?;
+    let known_profile = known_profile(&data.columns);
+
+    Ok(CsvSnapshotImportInspectionDto {
+        file_name: input.file_name.clone(),
+        columns: column_dtos(&data),
+        sample_rows: sample_row_dtos(&data),
+        guesses: guesses(
+            &data,
+            &data,
+            known_profile.filter(|profile| profile.guides_options()),
+            locale,
+        ),
+        total_rows: u32::try_from(data.rows.len()).expect("row count should fit in u32"),
+        format,
+        known_profile: known_profile.map(|profile| profile.dto()),
+        profile: None,
+    })
+}
+
+pub(super) fn column_dtos(data: &CsvData) -> Vec<CsvSnapshotImportColumnDto> {
+    data.columns
         .iter()
         .enumerate()
         .map(|(index, name)| CsvSnapshotImportColumnDto {
@@ -138,74 +220,242 @@ pub fn inspect(
             index: u32::try_from(index).expect("column index should fit in u32"),
             sample_values: sample_values_for_column(&data.rows, index),
         })
-        .collect::<Vec<_>>();
-    let date_column = guess_date_column(&data);
-    let amount_column = guess_amount_column(&data);
-    let date_format = date_column
-        .as_ref()
-        .and_then(|column| column_index(&data.columns, column))
-        .and_then(|index| guess_date_format(&data.rows, index));
-    let balance_format = guess_balance_format(&data, amount_column.as_ref());
-    let timestamp_missing_timezone_policy =
-        guess_timestamp_missing_timezone_policy(date_column.as_ref());
-    let duplicate_date_policy =
-        guess_duplicate_date_policy(&data, date_column.as_ref(), date_format);
+        .collect()
+}
 
-    Ok(CsvSnapshotImportInspectionDto {
-        file_name: data.file_name,
-        columns,
-        sample_rows: data
-            .rows
-            .iter()
-            .take(5)
-            .map(|row| CsvSnapshotImportSampleRowDto {
-                source_row_number: row.source_row_number,
-                values: row.values.clone(),
-            })
-            .collect(),
-        guesses: CsvSnapshotImportGuessesDto {
-            date_column,
-            amount_column,
-            date_format,
-            balance_format,
-            timestamp_missing_timezone_policy,
-            duplicate_date_policy,
-        },
-        total_rows: u32::try_from(data.rows.len()).expect("row count should fit in u32"),
-    })
+pub(super) fn sample_row_dtos(data: &CsvData) -> Vec<CsvSnapshotImportSampleRowDto> {
+    data.rows
+        .iter()
+        .take(5)
+        .map(|row| CsvSnapshotImportSampleRowDto {
+            source_row_number: row.source_row_number,
+            values: row.values.clone(),
+        })
+        .collect()
+}
+
+/// Column and format guesses, following `known_profile` where it names them.
+///
+/// Columns are guessed from `data`, and the date and balance formats from `text_data`,
+/// which holds only the values the formats will be applied to. For a CSV both are the
+/// same rows; a spreadsheet leaves out its native date and number cells.
+pub(super) fn guesses(
+    data: &CsvData,
+    text_data: &CsvData,
+    known_profile: Option<&KnownProfile>,
+    locale: AppLocaleCode,
+) -> CsvSnapshotImportGuessesDto {
+    let date_column = match known_profile {
+        Some(profile) => file_column(&data.columns, profile.date_column),
+        None => guess_date_column(data, locale),
+    };
+    let amount_column = match known_profile {
+        Some(profile) => file_column(&data.columns, profile.amount_column),
+        None => guess_amount_column(data),
+    };
+    let date_format = known_profile
+        .and_then(|profile| profile.date_format)
+        .or_else(|| {
+            date_column
+                .as_ref()
+                .and_then(|column| column_index(&data.columns, column))
+                .and_then(|index| guess_date_format(text_data, index, locale))
+        });
+    let balance_format = match known_profile {
+        Some(profile) => Some(profile.balance_format),
+        None => guess_balance_format(text_data, amount_column.as_ref()),
+    };
+    let timestamp_missing_timezone_policy = match known_profile {
+        Some(profile) => profile.timestamp_missing_timezone_policy,
+        None => guess_timestamp_missing_timezone_policy(date_column.as_ref()),
+    };
+    let duplicate_date_policy =
+        guess_duplicate_date_policy(data, date_column.as_ref(), date_format, locale);
+
+    CsvSnapshotImportGuessesDto {
+        date_column,
+        amount_column,
+        date_format,
+        balance_format,
+        timestamp_missing_timezone_policy,
+        duplicate_date_policy,
+    }
 }
 
 pub fn candidates(
     input: &CsvSnapshotImportSourceInput,
     options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
 ) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
-    let data = parse_csv(input)?;
-    let date_index = require_column(
+    read_candidates(input, options, currency_code, locale, "Balance")
+}
+
+/// Reads each row as a candidate whose `balance_minor` is the signed transaction
+/// amount, for imports that derive balances from a running sum.
+pub(super) fn transaction_candidates(
+    input: &CsvSnapshotImportSourceInput,
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    read_candidates(input, options, currency_code, locale, "Amount")
+}
+
+fn read_candidates(
+    input: &CsvSnapshotImportSourceInput,
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+    amount_label: &str,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    let (data, _) = parse_csv(input)?;
+    rows_candidates(
         &data.columns,
-        &options.date_column,
-        "options.source.date_column",
-    )?;
+        &data.rows,
+        options,
+        currency_code,
+        locale,
+        amount_label,
+    )
+}
+
+/// A row of a tabular source that can be read as a candidate. Sources with typed
+/// cells return native values, and every other cell is parsed from its text with the
+/// selected formats.
+pub(super) trait CandidateRow {
+    fn source_row_number(&self) -> u32;
+
+    /// The cell's value as shown to the user, blank when the row is short.
+    fn raw(&self, index: usize) -> String;
+
+    fn native_date(&self, _index: usize) -> Option<NaiveDate> {
+        None
+    }
+
+    fn native_amount_minor(
+        &self,
+        _index: usize,
+        _currency_code: CurrencyCode,
+    ) -> Option<Result<i64, ParseAmountMinorError>> {
+        None
+    }
+}
+
+impl CandidateRow for CsvRow {
+    fn source_row_number(&self) -> u32 {
+        self.source_row_number
+    }
+
+    fn raw(&self, index: usize) -> String {
+        self.values.get(index).cloned().unwrap_or_default()
+    }
+}
+
+/// Reads the selected date and amount columns of every row as a candidate.
+pub(super) fn rows_candidates<R: CandidateRow>(
+    columns: &[String],
+    rows: &[R],
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+    amount_label: &str,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    let date_index = require_column(columns, &options.date_column, "options.source.date_column")?;
     let amount_index = require_column(
-        &data.columns,
+        columns,
         &options.amount_column,
         "options.source.amount_column",
     )?;
 
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let raw_date = row.raw(date_index);
+            let raw_amount = row.raw(amount_index);
+            let date = row.native_date(date_index).or_else(|| {
+                parse_date(
+                    &raw_date,
+                    options.date_format,
+                    locale,
+                    options.timestamp_date_policy,
+                    options.timestamp_missing_timezone_policy,
+                    &options.timestamp_missing_timezone,
+                )
+            });
+            let balance_minor_result = row
+                .native_amount_minor(amount_index, currency_code)
+                .unwrap_or_else(|| {
+                    parse_amount_minor_result(&raw_amount, options.balance_format, currency_code)
+                });
+            row_candidate(
+                row.source_row_number(),
+                (raw_date, date),
+                (raw_amount, balance_minor_result),
+                options.blank_amount_policy,
+                amount_label,
+            )
+        })
+        .collect())
+}
+
+/// Reads two selected columns from every non-blank row as
+/// `(source_row_number, first_value, second_value)`, so other CSV-backed imports
+/// share header normalization and column errors with snapshot imports.
+pub(crate) fn read_column_pairs(
+    input: &CsvSnapshotImportSourceInput,
+    (first_column, first_field): (&str, &str),
+    (second_column, second_field): (&str, &str),
+) -> Result<Vec<(u32, String, String)>, Vec<SnapshotImportValidationIssue>> {
+    let (data, _) = parse_csv(input)?;
+    let first_index = require_column(&data.columns, first_column, first_field)?;
+    let second_index = require_column(&data.columns, second_column, second_field)?;
+
     Ok(data
         .rows
-        .iter()
-        .map(|row| parse_candidate(row, date_index, amount_index, options))
+        .into_iter()
+        .map(|row| {
+            (
+                row.source_row_number,
+                row.values.get(first_index).cloned().unwrap_or_default(),
+                row.values.get(second_index).cloned().unwrap_or_default(),
+            )
+        })
         .collect())
 }
 
+/// Parses a date cell without timezone conversion, as inspection guesses do.
+pub(crate) fn parse_date_as_written(
+    raw: &str,
+    format: CsvSnapshotImportDateFormat,
+    locale: AppLocaleCode,
+) -> Option<NaiveDate> {
+    parse_date(
+        raw,
+        format,
+        locale,
+        CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
+        CsvSnapshotImportMissingTimezonePolicy::Local,
+        "+00:00",
+    )
+}
+
 fn parse_csv(
     input: &CsvSnapshotImportSourceInput,
-) -> Result<CsvData, Vec<SnapshotImportValidationIssue>> {
+) -> Result<(CsvData, CsvSnapshotImportFormatDto), Vec<SnapshotImportValidationIssue>> {
+    let (text, encoding, byte_order_mark) = decode(&input.content, input.encoding)?;
+    let quote = input.quote.unwrap_or_else(|| detect_quote(&text));
+    let delimiter = input
+        .delimiter
+        .unwrap_or_else(|| detect_delimiter(&text, quote));
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(input.has_header_row)
         .flexible(true)
         .trim(csv::Trim::All)
-        .from_reader(input.content.as_bytes());
+        .delimiter(delimiter.byte())
+        .quoting(quote.byte().is_some())
+        .quote(quote.byte().unwrap_or(b'"'))
+        .from_reader(text.as_bytes());
 
     let header_columns = if input.has_header_row {
         let headers = reader.headers().map_err(|error| {
@@ -268,14 +518,18 @@ fn parse_csv(
         })
         .collect::<Vec<_>>();
 
-    Ok(CsvData {
-        file_name: input.file_name.clone(),
-        columns,
-        rows,
-    })
+    Ok((
+        CsvData { columns, rows },
+        CsvSnapshotImportFormatDto {
+            encoding,
+            byte_order_mark,
+            delimiter,
+            quote,
+        },
+    ))
 }
 
-fn normalize_headers(headers: Vec<&str>) -> Vec<String> {
+pub(super) fn normalize_headers(headers: Vec<&str>) -> Vec<String> {
     let mut seen = HashMap::<String, usize>::new();
     headers
         .into_iter()
@@ -297,7 +551,7 @@ fn normalize_headers(headers: Vec<&str>) -> Vec<String> {
         .collect()
 }
 
-fn require_column(
+pub(super) fn require_column(
     columns: &[String],
     column: &str,
     field: &str,
@@ -318,23 +572,16 @@ fn sample_values_for_column(rows: &[CsvRow], column_index: usize) -> Vec<String>
         .collect()
 }
 
-fn guess_date_column(data: &CsvData) -> Option<String> {
+fn guess_date_column(data: &CsvData, locale: AppLocaleCode) -> Option<String> {
     data.columns
         .iter()
         .enumerate()
         .max_by_key(|(index, column)| {
-            let name = column.to_lowercase();
-            let name_score = if name.contains("date") {
-                100
-            } else if name.contains("posted") || name.contains("as of") {
-                60
-            } else {
-                0
-            };
-            name_score + guess_date_format(&data.rows, *index).map_or(0, |_| 20)
+            date_column_name_score(column)
+                + guess_date_format(data, *index, locale).map_or(0, |_| 20)
         })
         .and_then(|(index, column)| {
-            if guess_date_format(&data.rows, index).is_some() {
+            if guess_date_format(data, index, locale).is_some() {
                 Some(column.clone())
             } else {
                 None
@@ -342,6 +589,17 @@ fn guess_date_column(data: &CsvData) -> Option<String> {
         })
 }
 
+fn date_column_name_score(column: &str) -> usize {
+    let name = column.to_lowercase();
+    if name.contains("date") {
+        100
+    } else if name.contains("posted") || name.contains("as of") {
+        60
+    } else {
+        0
+    }
+}
+
 fn guess_amount_column(data: &CsvData) -> Option<String> {
     data.columns
         .iter()
@@ -405,20 +663,38 @@ fn guess_balance_format(
                 .collect::<Vec<_>>()
         });
 
-    amount_formats()
-        .into_iter()
-        .max_by_key(|format| {
+    let named_in_minor_units = amount_column.is_some_and(|column| {
+        let name = column.to_lowercase();
+        ["pence", "pennies", "cents", "minor"]
+            .iter()
+            .any(|hint| name.contains(hint))
+    });
+    // Only the column name can say amounts are in minor units; plain whole numbers are far
+    // more often whole pounds or dollars.
+    if named_in_minor_units
+        && values.iter().any(|value| {
+            parse_amount_minor(value, CsvSnapshotImportBalanceFormat::MinorUnits).is_some()
+        })
+    {
+        return Some(CsvSnapshotImportBalanceFormat::MinorUnits);
+    }
+
+    // Earlier formats win ties, so plain integers keep reading as whole units.
+    let formats = amount_formats();
+    formats
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by_key(|(position, format)| {
             (
                 values
                     .iter()
                     .filter(|value| parse_amount_minor(value, *format).is_some())
                     .count(),
-                matches!(
-                    format,
-                    CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot
-                ),
+                Reverse(*position),
             )
         })
+        .map(|(_, format)| format)
         .filter(|format| {
             values
                 .iter()
@@ -432,51 +708,62 @@ fn parse_amount_minor_any_format(raw: &str) -> Option<i64> {
         .find_map(|format| parse_amount_minor(raw, format))
 }
 
+/// Formats tried when guessing, most common first. The sign suffix formats come after
+/// the plain ones, so they are only guessed when some value ends in a suffix. Minor
+/// units are left out because any whole number would match them; they are guessed
+/// separately.
 fn amount_formats() -> Vec<CsvSnapshotImportBalanceFormat> {
     vec![
         CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
         CsvSnapshotImportBalanceFormat::ThousandsDotDecimalComma,
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalComma,
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalDot,
+        CsvSnapshotImportBalanceFormat::ThousandsApostropheDecimalDot,
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotTrailingMinus,
+        CsvSnapshotImportBalanceFormat::ThousandsDotDecimalCommaTrailingMinus,
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotCreditDebit,
     ]
 }
 
-fn guess_date_format(rows: &[CsvRow], column_index: usize) -> Option<CsvSnapshotImportDateFormat> {
-    date_formats()
+fn guess_date_format(
+    data: &CsvData,
+    column_index: usize,
+    locale: AppLocaleCode,
+) -> Option<CsvSnapshotImportDateFormat> {
+    let values = data
+        .rows
+        .iter()
+        .take(20)
+        .filter_map(|row| row.values.get(column_index))
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>();
+    let parsed_count = |format: CsvSnapshotImportDateFormat| {
+        values
+            .iter()
+            .filter(|value| parse_date_as_written(value, format, locale).is_some())
+            .count()
+    };
+
+    let guessed = date_formats()
         .into_iter()
-        .max_by_key(|format| {
-            rows.iter()
-                .take(20)
-                .filter(|row| {
-                    row.values
-                        .get(column_index)
-                        .and_then(|value| {
-                            parse_date(
-                                value,
-                                *format,
-                                CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
-                                CsvSnapshotImportMissingTimezonePolicy::Local,
-                                "+00:00",
-                            )
-                        })
-                        .is_some()
-                })
-                .count()
-        })
-        .filter(|format| {
-            rows.iter().take(20).any(|row| {
-                row.values
-                    .get(column_index)
-                    .and_then(|value| {
-                        parse_date(
-                            value,
-                            *format,
-                            CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
-                            CsvSnapshotImportMissingTimezonePolicy::Local,
-                            "+00:00",
-                        )
-                    })
-                    .is_some()
-            })
-        })
+        .max_by_key(|format| parsed_count(*format))
+        .filter(|format| parsed_count(*format) > 0);
+    if guessed.is_some() {
+        return guessed;
+    }
+
+    // Any whole number is a valid day number, so spreadsheet serials are only
+    // guessed for date-like column names whose values all land in 1970-2099.
+    let plausible_serial = |value: &&String| {
+        parse_date_as_written(value, CsvSnapshotImportDateFormat::ExcelSerial, locale)
+            .is_some_and(|date| (1970..2100).contains(&date.year()))
+    };
+    let date_like_name = data
+        .columns
+        .get(column_index)
+        .is_some_and(|column| date_column_name_score(column) > 0);
+    (date_like_name && !values.is_empty() && values.iter().all(plausible_serial))
+        .then_some(CsvSnapshotImportDateFormat::ExcelSerial)
 }
 
 fn guess_timestamp_missing_timezone_policy(
@@ -491,6 +778,7 @@ fn guess_duplicate_date_policy(
     data: &CsvData,
     date_column: Option<&String>,
     date_format: Option<CsvSnapshotImportDateFormat>,
+    locale: AppLocaleCode,
 ) -> Option<SnapshotImportDuplicateDatePolicy> {
     let date_index = date_column.and_then(|column| column_index(&data.columns, column))?;
     let date_format = date_format?;
@@ -498,15 +786,9 @@ fn guess_duplicate_date_policy(
         .rows
         .iter()
         .filter_map(|row| {
-            row.values.get(date_index).and_then(|value| {
-                parse_date(
-                    value,
-                    date_format,
-                    CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
-                    CsvSnapshotImportMissingTimezonePolicy::Local,
-                    "+00:00",
-                )
-            })
+            row.values
+                .get(date_index)
+                .and_then(|value| parse_date_as_written(value, date_format, locale))
         })
         .collect::<Vec<_>>();
 
@@ -524,22 +806,15 @@ fn guess_duplicate_date_policy(
     })
 }
 
-fn parse_candidate(
-    row: &CsvRow,
-    date_index: usize,
-    amount_index: usize,
-    options: &CsvSnapshotImportOptionsInput,
+/// Checks one row's date and amount once each has been read, whether from CSV text
+/// or from native spreadsheet cells.
+fn row_candidate(
+    source_row_number: u32,
+    (raw_date, date): (String, Option<NaiveDate>),
+    (raw_amount, balance_minor_result): (String, Result<i64, ParseAmountMinorError>),
+    blank_amount_policy: CsvSnapshotImportBlankAmountPolicy,
+    amount_label: &str,
 ) -> SnapshotImportCandidate {
-    let raw_date = row.values.get(date_index).cloned().unwrap_or_default();
-    let raw_amount = row.values.get(amount_index).cloned().unwrap_or_default();
-    let date = parse_date(
-        &raw_date,
-        options.date_format,
-        options.timestamp_date_policy,
-        options.timestamp_missing_timezone_policy,
-        &options.timestamp_missing_timezone,
-    );
-    let balance_minor_result = parse_amount_minor_result(&raw_amount, options.balance_format);
     let balance_minor = balance_minor_result.ok();
     let blank_amount = raw_amount.trim().is_empty();
     let mut issues = Vec::new();
@@ -550,24 +825,24 @@ fn parse_candidate(
         issues.push("Date does not match the selected format".to_string());
     }
     if blank_amount {
-        match options.blank_amount_policy {
+        match blank_amount_policy {
             CsvSnapshotImportBlankAmountPolicy::Skip => {}
             CsvSnapshotImportBlankAmountPolicy::Zero => {}
             CsvSnapshotImportBlankAmountPolicy::Error => issues.push("Missing amount".to_string()),
         }
     } else if matches!(balance_minor_result, Err(ParseAmountMinorError::TooLarge)) {
-        issues.push("Balance is too large".to_string());
+        issues.push(format!("{amount_label} is too large"));
     } else if balance_minor.is_none() {
-        issues.push("Balance does not match the selected format".to_string());
+        issues.push(format!("{amount_label} does not match the selected format"));
     }
 
     SnapshotImportCandidate {
-        source_row_number: row.source_row_number,
+        source_row_number,
         raw_date,
         raw_amount,
         date,
         balance_minor: if blank_amount
-            && options.blank_amount_policy == CsvSnapshotImportBlankAmountPolicy::Zero
+            && blank_amount_policy == CsvSnapshotImportBlankAmountPolicy::Zero
         {
             Some(0)
         } else {
@@ -575,14 +850,15 @@ fn parse_candidate(
         },
         issues,
         skip_blank_amount: blank_amount
-            && options.blank_amount_policy == CsvSnapshotImportBlankAmountPolicy::Skip,
+            && blank_amount_policy == CsvSnapshotImportBlankAmountPolicy::Skip,
         skip_duplicate: false,
     }
 }
 
-fn parse_date(
+pub(super) fn parse_date(
     raw: &str,
     format: CsvSnapshotImportDateFormat,
+    locale: AppLocaleCode,
     timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy,
     timestamp_missing_timezone_policy: CsvSnapshotImportMissingTimezonePolicy,
     timestamp_missing_timezone: &str,
@@ -603,10 +879,88 @@ fn parse_date(
                 timestamp_missing_timezone,
             );
         }
+        CsvSnapshotImportDateFormat::DdMonYyyy => {
+            return parse_month_name_date(raw, locale, |[day, month, year]| (day, month, year));
+        }
+        CsvSnapshotImportDateFormat::MonDdYyyy => {
+            return parse_month_name_date(raw, locale, |[month, day, year]| (day, month, year));
+        }
+        CsvSnapshotImportDateFormat::YyyyMonDd => {
+            return parse_month_name_date(raw, locale, |[year, month, day]| (day, month, year));
+        }
+        CsvSnapshotImportDateFormat::YyyyMmDdCompact => {
+            let raw = raw.trim();
+            if raw.len() != 8 || !raw.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+            "%Y%m%d"
+        }
+        CsvSnapshotImportDateFormat::ExcelSerial => return parse_excel_serial(raw),
     };
     NaiveDate::parse_from_str(raw.trim(), pattern).ok()
 }
 
+/// Reads a date written as three parts, one of them a month name, in the order
+/// `arrange` maps to `(day, month, year)`. Parts may be separated by spaces, dashes,
+/// slashes, dots or commas, and days may carry an English ordinal such as "3rd".
+fn parse_month_name_date(
+    raw: &str,
+    locale: AppLocaleCode,
+    arrange: fn([&str; 3]) -> (&str, &str, &str),
+) -> Option<NaiveDate> {
+    let parts = raw
+        .split(|character: char| character.is_whitespace() || "-/.,".contains(character))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    let (day, month, year) = arrange(parts.try_into().ok()?);
+
+    let day = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| {
+            day.to_ascii_lowercase()
+                .strip_suffix(suffix)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| day.to_string());
+    if !(1..=2).contains(&day.len()) || !day.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    if !matches!(year.len(), 2 | 4) || !year.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let year = match (year.len(), year.parse::<i32>().ok()?) {
+        // Two-digit years follow the same 1970-2069 window as the numeric formats.
+        (2, year) if year < 70 => 2000 + year,
+        (2, year) => 1900 + year,
+        (_, year) => year,
+    };
+    NaiveDate::from_ymd_opt(year, month_from_name(month, locale)?, day.parse().ok()?)
+}
+
+fn parse_excel_serial(raw: &str) -> Option<NaiveDate> {
+    // Serial 1 is 1900-01-01 but Excel counts a 29 February 1900 that never existed,
+    // so counting from 1899-12-30 is only right from March 1900 onwards.
+    const EXCEL_EPOCH: NaiveDate = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    // 9999-12-31, the last date a spreadsheet can hold.
+    const EXCEL_MAX_SERIAL: i64 = 2_958_465;
+
+    let raw = raw.trim();
+    let (days, fraction) = raw.split_once('.').unwrap_or((raw, "0"));
+    if days.is_empty()
+        || !days.bytes().all(|byte| byte.is_ascii_digit())
+        || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let days = days.parse::<i64>().ok()?;
+    if !(61..=EXCEL_MAX_SERIAL).contains(&days) {
+        return None;
+    }
+    EXCEL_EPOCH.checked_add_days(chrono::Days::new(u64::try_from(days).ok()?))
+}
+
 fn parse_iso_8601_date_time_date(
     raw: &str,
     timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy,
@@ -713,6 +1067,8 @@ fn parse_iso_8601_date_time_without_offset(raw: &str) -> Option<NaiveDateTime> {
     .find_map(|pattern| NaiveDateTime::parse_from_str(raw, pattern).ok())
 }
 
+/// Formats tried when guessing. Spreadsheet serials are left out because any whole
+/// number would match them; `guess_date_format` only falls back to them.
 fn date_formats() -> Vec<CsvSnapshotImportDateFormat> {
     vec![
         CsvSnapshotImportDateFormat::YyyyMmDd,
@@ -723,21 +1079,55 @@ fn date_formats() -> Vec<CsvSnapshotImportDateFormat> {
         CsvSnapshotImportDateFormat::DdMmYyyyDash,
         CsvSnapshotImportDateFormat::YyyyMmDdSlash,
         CsvSnapshotImportDateFormat::Iso8601DateTime,
+        CsvSnapshotImportDateFormat::DdMonYyyy,
+        CsvSnapshotImportDateFormat::MonDdYyyy,
+        CsvSnapshotImportDateFormat::YyyyMonDd,
+        CsvSnapshotImportDateFormat::YyyyMmDdCompact,
     ]
 }
 
 fn parse_amount_minor(raw: &str, format: CsvSnapshotImportBalanceFormat) -> Option<i64> {
-    parse_amount_minor_result(raw, format).ok()
+    parse_amount_minor_result(raw, format, GUESS_CURRENCY_CODE).ok()
 }
 
-fn parse_amount_minor_result(
+pub(super) fn parse_amount_minor_result(
     raw: &str,
     format: CsvSnapshotImportBalanceFormat,
+    currency_code: CurrencyCode,
 ) -> Result<i64, ParseAmountMinorError> {
-    let (amount, negative) = normalize_amount_text(raw)?;
-    let (thousands_separator, decimal_separator) = match format {
-        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot => (',', '.'),
-        CsvSnapshotImportBalanceFormat::ThousandsDotDecimalComma => ('.', ','),
+    let (separators, sign_suffix) = match format {
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot => {
+            (Some((',', '.')), SignSuffix::None)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsDotDecimalComma => {
+            (Some(('.', ',')), SignSuffix::None)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalComma => {
+            (Some((' ', ',')), SignSuffix::None)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalDot => {
+            (Some((' ', '.')), SignSuffix::None)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsApostropheDecimalDot => {
+            (Some(('\'', '.')), SignSuffix::None)
+        }
+        CsvSnapshotImportBalanceFormat::MinorUnits => (None, SignSuffix::None),
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotTrailingMinus => {
+            (Some((',', '.')), SignSuffix::Minus)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsDotDecimalCommaTrailingMinus => {
+            (Some(('.', ',')), SignSuffix::Minus)
+        }
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotCreditDebit => {
+            (Some((',', '.')), SignSuffix::CreditDebit)
+        }
+    };
+    let (amount, negative) = normalize_amount_text(raw, sign_suffix)?;
+    let amount = amount
+        .replace(['\u{a0}', '\u{202f}'], " ")
+        .replace('\u{2019}', "'");
+    let Some((thousands_separator, decimal_separator)) = separators else {
+        return parse_minor_units(&amount, negative, currency_code);
     };
     let mut parts = amount.split(decimal_separator);
     let integer_part = parts.next().ok_or(ParseAmountMinorError::Invalid)?;
@@ -750,15 +1140,16 @@ fn parse_amount_minor_result(
         return Err(ParseAmountMinorError::Invalid);
     }
 
-    let cents = match decimal_part {
+    let minor_unit_exponent = currency_code.minor_unit_exponent();
+    let fraction_minor = match decimal_part {
         Some(part)
-            if part.len() <= 2
+            if part.len() <= minor_unit_exponent as usize
                 && !part.is_empty()
                 && part.chars().all(|ch| ch.is_ascii_digit()) =>
         {
             part.parse::<i64>()
                 .map_err(|_| ParseAmountMinorError::Invalid)?
-                * if part.len() == 1 { 10 } else { 1 }
+                * 10_i64.pow(minor_unit_exponent - part.len() as u32)
         }
         Some(_) => return Err(ParseAmountMinorError::Invalid),
         None => 0,
@@ -770,10 +1161,32 @@ fn parse_amount_minor_result(
         .parse::<i64>()
         .map_err(|_| ParseAmountMinorError::TooLarge)?;
     let amount_minor = units
-        .checked_mul(100)
-        .and_then(|units_minor| units_minor.checked_add(cents))
+        .checked_mul(10_i64.pow(minor_unit_exponent))
+        .and_then(|units_minor| units_minor.checked_add(fraction_minor))
         .ok_or(ParseAmountMinorError::TooLarge)?;
-    if amount_minor > BALANCE_MINOR_ABS_MAX {
+    if amount_minor > currency_code.balance_minor_abs_max() {
+        return Err(ParseAmountMinorError::TooLarge);
+    }
+
+    Ok(if negative {
+        -amount_minor
+    } else {
+        amount_minor
+    })
+}
+
+fn parse_minor_units(
+    amount: &str,
+    negative: bool,
+    currency_code: CurrencyCode,
+) -> Result<i64, ParseAmountMinorError> {
+    if amount.is_empty() || !amount.chars().all(|ch| ch.is_ascii_digit()) {
+        return Err(ParseAmountMinorError::Invalid);
+    }
+    let amount_minor = amount
+        .parse::<i64>()
+        .map_err(|_| ParseAmountMinorError::TooLarge)?;
+    if amount_minor > currency_code.balance_minor_abs_max() {
         return Err(ParseAmountMinorError::TooLarge);
     }
 
@@ -784,7 +1197,13 @@ fn parse_amount_minor_result(
     })
 }
 
-fn normalize_amount_text(raw: &str) -> Result<(&str, bool), ParseAmountMinorError> {
+/// Strips currency markers and reads the sign, which may be a leading `-`, accounting
+/// parentheses, or the suffix the format allows: a trailing `-`, or `CR`/`DR` where `DR`
+/// is negative.
+fn normalize_amount_text(
+    raw: &str,
+    sign_suffix: SignSuffix,
+) -> Result<(&str, bool), ParseAmountMinorError> {
     let mut value = raw.trim();
     if value.is_empty() {
         return Err(ParseAmountMinorError::Invalid);
@@ -831,26 +1250,59 @@ fn normalize_amount_text(raw: &str) -> Result<(&str, bool), ParseAmountMinorErro
         break;
     }
 
+    let mut trailing_sign = None;
     loop {
         value = value.trim_end();
         if let Some(rest) = strip_currency_suffix(value) {
             value = rest;
             continue;
         }
+        if trailing_sign.is_none() {
+            if sign_suffix == SignSuffix::Minus
+                && let Some(rest) = value.strip_suffix('-')
+            {
+                trailing_sign = Some(true);
+                value = rest;
+                continue;
+            }
+            if sign_suffix == SignSuffix::CreditDebit {
+                if let Some(rest) = strip_ascii_suffix_ignore_case(value, "DR") {
+                    trailing_sign = Some(true);
+                    value = rest;
+                    continue;
+                }
+                if let Some(rest) = strip_ascii_suffix_ignore_case(value, "CR") {
+                    trailing_sign = Some(false);
+                    value = rest;
+                    continue;
+                }
+            }
+        }
         break;
     }
 
     value = value.trim();
     if value.is_empty()
-        || value
-            .chars()
-            .any(|ch| !ch.is_ascii_digit() && !matches!(ch, '.' | ','))
-        || (negative_parentheses && negative_sign)
+        || value.chars().any(|ch| {
+            !ch.is_ascii_digit()
+                && !matches!(
+                    ch,
+                    '.' | ',' | ' ' | '\'' | '\u{a0}' | '\u{202f}' | '\u{2019}'
+                )
+        })
+        || [negative_parentheses, negative_sign, trailing_sign.is_some()]
+            .into_iter()
+            .filter(|sign| *sign)
+            .count()
+            > 1
     {
         return Err(ParseAmountMinorError::Invalid);
     }
 
-    Ok((value, negative_parentheses || negative_sign))
+    Ok((
+        value,
+        negative_parentheses || negative_sign || trailing_sign == Some(true),
+    ))
 }
 
 fn strip_currency_prefix(value: &str) -> Option<&str> {
@@ -911,20 +1363,42 @@ mod tests {
         CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput,
         CsvSnapshotImportTimestampDatePolicy, candidates, inspect, parse_amount_minor, parse_date,
     };
+    use crate::contracts::{AppLocaleCode, CurrencyCode};
     use crate::imports::snapshots::SnapshotImportDuplicateDatePolicy;
+    use crate::imports::snapshots::csv_format::{
+        CsvSnapshotImportDelimiter, CsvSnapshotImportEncoding, CsvSnapshotImportFormatDto,
+        CsvSnapshotImportQuote,
+    };
+    use crate::imports::snapshots::known_profiles::CsvSnapshotImportAmountKind;
 
     const COMMA_THOUSANDS: CsvSnapshotImportBalanceFormat =
         CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot;
     const DOT_THOUSANDS: CsvSnapshotImportBalanceFormat =
         CsvSnapshotImportBalanceFormat::ThousandsDotDecimalComma;
+    const SPACE_THOUSANDS_DECIMAL_COMMA: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalComma;
+    const SPACE_THOUSANDS_DECIMAL_DOT: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsSpaceDecimalDot;
+    const APOSTROPHE_THOUSANDS: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsApostropheDecimalDot;
+    const MINOR_UNITS: CsvSnapshotImportBalanceFormat = CsvSnapshotImportBalanceFormat::MinorUnits;
+    const COMMA_THOUSANDS_TRAILING_MINUS: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotTrailingMinus;
+    const DOT_THOUSANDS_TRAILING_MINUS: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsDotDecimalCommaTrailingMinus;
+    const COMMA_THOUSANDS_CREDIT_DEBIT: CsvSnapshotImportBalanceFormat =
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDotCreditDebit;
     const ISO_8601: CsvSnapshotImportDateFormat = CsvSnapshotImportDateFormat::Iso8601DateTime;
 
     #[test]
     fn inspect_normalizes_headers_and_samples_values() {
-        let inspection = inspect(&source(
-            "Date,,Balance,Balance\n2026-01-09,,1.00,2.00\n2026-01-10,,3.00,4.00\n",
-            true,
-        ))
+        let inspection = inspect(
+            &source(
+                "Date,,Balance,Balance\n2026-01-09,,1.00,2.00\n2026-01-10,,3.00,4.00\n",
+                true,
+            ),
+            AppLocaleCode::EnGb,
+        )
         .unwrap();
 
         let column_names = inspection
@@ -947,7 +1421,11 @@ mod tests {
 
     #[test]
     fn inspect_headerless_csv_generates_columns_and_skips_blank_rows() {
-        let inspection = inspect(&source("2026-01-09,1.00\n\n2026-01-10,2.00\n", false)).unwrap();
+        let inspection = inspect(
+            &source("2026-01-09,1.00\n\n2026-01-10,2.00\n", false),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
 
         let column_names = inspection
             .columns
@@ -975,7 +1453,7 @@ mod tests {
         let inspection = inspect(&source(
             "date,balance\n2026-01-01,1.00\n2026-01-02,2.00\n2026-01-03,3.00\n2026-01-04,4.00\n2026-01-05,5.00\n2026-01-06,6.00\n",
             true,
-        ))
+        ), AppLocaleCode::EnGb)
         .unwrap();
 
         assert_eq!(inspection.total_rows, 6);
@@ -985,11 +1463,90 @@ mod tests {
     }
 
     #[test]
-    fn inspect_guesses_columns_formats_timezone_and_duplicate_policy() {
+    fn inspect_reads_semicolon_separated_files_with_a_bom_and_reports_the_format() {
+        let mut input = source("", true);
+        input.content =
+            b"\xEF\xBB\xBFDatum;Saldo\n09/01/2026;1.234,56\n10/01/2026;\"2.345,67\"\n".to_vec();
+
+        let inspection = inspect(&input, AppLocaleCode::EnGb).unwrap();
+
+        assert_eq!(
+            inspection.format,
+            CsvSnapshotImportFormatDto {
+                encoding: CsvSnapshotImportEncoding::Utf8,
+                byte_order_mark: true,
+                delimiter: CsvSnapshotImportDelimiter::Semicolon,
+                quote: CsvSnapshotImportQuote::Double,
+            }
+        );
+        assert_eq!(inspection.guesses.date_column.as_deref(), Some("Datum"));
+        assert_eq!(
+            inspection.guesses.balance_format,
+            Some(CsvSnapshotImportBalanceFormat::ThousandsDotDecimalComma)
+        );
+
+        input.delimiter = Some(CsvSnapshotImportDelimiter::Comma);
+        assert_eq!(
+            inspect(&input, AppLocaleCode::EnGb).unwrap().columns[0].name,
+            "Datum;Saldo"
+        );
+    }
+
+    #[test]
+    fn inspect_uses_the_known_profile_matched_by_headers() {
         let inspection = inspect(&source(
-            "posted utc,value\n2026-01-10T02:00:00Z,1.234,56\n2026-01-09T02:00:00Z,2.345,67\n",
+            "Date,Counter Party,Reference,Type,Amount (GBP),Balance (GBP),Spending Category,Notes\n\
+             03/01/2026,Cafe,Card,CARD,-2.50,1497.50,EATING_OUT,\n\
+             02/01/2026,Employer,Salary,FASTER_PAYMENT,1500.00,1500.00,INCOME,\n",
             true,
-        ))
+        ), AppLocaleCode::EnGb)
+        .unwrap();
+        let unknown = inspect(
+            &source("Date,Balance\n2026-01-03,1.00\n", true),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+
+        let known_profile = inspection.known_profile.unwrap();
+        assert_eq!(known_profile.id, "starling");
+        assert_eq!(
+            known_profile.amount_kind,
+            CsvSnapshotImportAmountKind::Balances
+        );
+        assert_eq!(
+            inspection.guesses.amount_column.as_deref(),
+            Some("Balance (GBP)")
+        );
+        assert_eq!(
+            inspection.guesses.date_format,
+            Some(CsvSnapshotImportDateFormat::DdMmYyyySlash)
+        );
+        assert!(unknown.known_profile.is_none());
+
+        let nationwide = inspect(
+            &source(
+                "Date,Transaction type,Description,Paid out,Paid in,Balance\n\
+                 03 Jan 2024,Card,Cafe,£2.50,,£97.50\n",
+                true,
+            ),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+        assert_eq!(
+            nationwide.guesses.date_format,
+            Some(CsvSnapshotImportDateFormat::DdMonYyyy)
+        );
+    }
+
+    #[test]
+    fn inspect_guesses_columns_formats_timezone_and_duplicate_policy() {
+        let inspection = inspect(
+            &source(
+                "posted utc,value\n2026-01-10T02:00:00Z,1.234,56\n2026-01-09T02:00:00Z,2.345,67\n",
+                true,
+            ),
+            AppLocaleCode::EnGb,
+        )
         .unwrap();
 
         assert_eq!(
@@ -1015,6 +1572,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inspect_guesses_each_balance_format() {
+        for (content, expected) in [
+            ("date,balance\n2026-01-09,\"1,234\"\n", COMMA_THOUSANDS),
+            ("date,balance\n2026-01-09,\"1.234,56\"\n", DOT_THOUSANDS),
+            (
+                "date,balance\n2026-01-09,1 234,56\n",
+                SPACE_THOUSANDS_DECIMAL_COMMA,
+            ),
+            (
+                "date,balance\n2026-01-09,1 234.56\n",
+                SPACE_THOUSANDS_DECIMAL_DOT,
+            ),
+            ("date,balance\n2026-01-09,1'234.56\n", APOSTROPHE_THOUSANDS),
+            ("date,balance (pence)\n2026-01-09,123456\n", MINOR_UNITS),
+            (
+                "date,balance\n2026-01-09,123456\n2026-01-10,-50\n",
+                COMMA_THOUSANDS,
+            ),
+            (
+                "date,balance\n2026-01-09,12.50-\n2026-01-10,1.00\n",
+                COMMA_THOUSANDS_TRAILING_MINUS,
+            ),
+            (
+                "date,balance\n2026-01-09,\"1.234,50-\"\n",
+                DOT_THOUSANDS_TRAILING_MINUS,
+            ),
+            (
+                "date,balance\n2026-01-09,12.50 DR\n2026-01-10,1.00 CR\n",
+                COMMA_THOUSANDS_CREDIT_DEBIT,
+            ),
+        ] {
+            let inspection = inspect(&source(content, true), AppLocaleCode::EnGb).unwrap();
+
+            assert_eq!(
+                inspection.guesses.balance_format,
+                Some(expected),
+                "{content}"
+            );
+        }
+    }
+
+    #[test]
+    fn inspect_guesses_textual_dates_and_only_plausible_serials() {
+        for (content, expected) in [
+            (
+                "Date,Balance\n03 Jan 2024,1.00\n04 Jan 2024,2.00\n",
+                Some(CsvSnapshotImportDateFormat::DdMonYyyy),
+            ),
+            (
+                "Date,Balance\n\"January 3, 2024\",1.00\n",
+                Some(CsvSnapshotImportDateFormat::MonDdYyyy),
+            ),
+            (
+                "Date,Balance\n2024-Jan-03,1.00\n",
+                Some(CsvSnapshotImportDateFormat::YyyyMonDd),
+            ),
+            (
+                "Date,Balance\n20240103,1.00\n",
+                Some(CsvSnapshotImportDateFormat::YyyyMmDdCompact),
+            ),
+            (
+                "Date,Balance\n45294,1.00\n45295,2.00\n",
+                Some(CsvSnapshotImportDateFormat::ExcelSerial),
+            ),
+            ("Date,Balance\n45294,1.00\n12,2.00\n", None),
+        ] {
+            let inspection = inspect(&source(content, true), AppLocaleCode::EnGb).unwrap();
+
+            assert_eq!(inspection.guesses.date_format, expected, "{content}");
+        }
+
+        let serials_without_a_date_header = inspect(
+            &source("Day,Balance\n45294,1.00\n", true),
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+        assert_eq!(serials_without_a_date_header.guesses.date_column, None);
+    }
+
     #[test]
     fn candidates_reports_missing_and_invalid_row_values() {
         let rows = candidates(
@@ -1023,6 +1660,8 @@ mod tests {
                 true,
             ),
             &options(),
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
         )
         .unwrap();
 
@@ -1044,6 +1683,8 @@ mod tests {
         let rows = candidates(
             &source("date,balance\n2026-01-09,\"90,071,992,547,409.92\"\n", true),
             &options(),
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
         )
         .unwrap();
 
@@ -1059,6 +1700,8 @@ mod tests {
                 blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Skip,
                 ..options()
             },
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
         )
         .unwrap();
 
@@ -1075,6 +1718,8 @@ mod tests {
                 blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Zero,
                 ..options()
             },
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
         )
         .unwrap();
 
@@ -1091,6 +1736,8 @@ mod tests {
                 date_column: "missing".to_string(),
                 ..options()
             },
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
         )
         .unwrap_err();
 
@@ -1101,7 +1748,13 @@ mod tests {
 
     #[test]
     fn candidates_pads_flexible_rows_with_missing_trailing_columns() {
-        let rows = candidates(&source("date,balance\n2026-01-09\n", true), &options()).unwrap();
+        let rows = candidates(
+            &source("date,balance\n2026-01-09\n", true),
+            &options(),
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
 
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].source_row_number, 2);
@@ -1109,6 +1762,50 @@ mod tests {
         assert_eq!(rows[0].issues, vec!["Missing amount"]);
     }
 
+    #[test]
+    fn candidates_scale_amounts_by_currency_minor_unit_exponent() {
+        let content = "date,balance\n2026-01-09,\"1,234\"\n2026-01-10,1.5\n2026-01-11,1.234\n";
+
+        let yen = candidates(
+            &source(content, true),
+            &options(),
+            CurrencyCode::JPY,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+        assert_eq!(yen[0].balance_minor, Some(1234));
+        assert_eq!(yen[1].balance_minor, None);
+        assert_eq!(
+            yen[1].issues,
+            vec!["Balance does not match the selected format"]
+        );
+
+        let dinar = candidates(
+            &source(content, true),
+            &options(),
+            CurrencyCode::BHD,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+        assert_eq!(dinar[0].balance_minor, Some(1_234_000));
+        assert_eq!(dinar[1].balance_minor, Some(1500));
+        assert_eq!(dinar[2].balance_minor, Some(1234));
+    }
+
+    #[test]
+    fn candidates_reports_amounts_above_the_currency_limit_as_too_large() {
+        let rows = candidates(
+            &source("date,balance\n2026-01-09,\"1,000,000,000,000\"\n", true),
+            &options(),
+            CurrencyCode::JPY,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+
+        assert_eq!(rows[0].balance_minor, None);
+        assert_eq!(rows[0].issues, vec!["Balance is too large"]);
+    }
+
     #[test]
     fn parse_amount_minor_accepts_expected_currency_decoration() {
         assert_eq!(
@@ -1137,6 +1834,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_amount_minor_accepts_space_and_apostrophe_thousands() {
+        assert_eq!(
+            parse_amount_minor("1 234,56", SPACE_THOUSANDS_DECIMAL_COMMA),
+            Some(123456)
+        );
+        assert_eq!(
+            parse_amount_minor("-1\u{202f}234\u{a0}567,8 €", SPACE_THOUSANDS_DECIMAL_COMMA),
+            Some(-123456780)
+        );
+        assert_eq!(
+            parse_amount_minor("1 234.56", SPACE_THOUSANDS_DECIMAL_DOT),
+            Some(123456)
+        );
+        assert_eq!(
+            parse_amount_minor("1'234.56", APOSTROPHE_THOUSANDS),
+            Some(123456)
+        );
+        assert_eq!(
+            parse_amount_minor("1\u{2019}234.56", APOSTROPHE_THOUSANDS),
+            Some(123456)
+        );
+        assert_eq!(parse_amount_minor("1 234.56", COMMA_THOUSANDS), None);
+        assert_eq!(
+            parse_amount_minor("12 34,56", SPACE_THOUSANDS_DECIMAL_COMMA),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_amount_minor_reads_trailing_minus_and_credit_debit_suffixes() {
+        assert_eq!(
+            parse_amount_minor("1,234.56-", COMMA_THOUSANDS_TRAILING_MINUS),
+            Some(-123456)
+        );
+        assert_eq!(
+            parse_amount_minor("1.234,56-", DOT_THOUSANDS_TRAILING_MINUS),
+            Some(-123456)
+        );
+        assert_eq!(
+            parse_amount_minor("£1,234.56 DR", COMMA_THOUSANDS_CREDIT_DEBIT),
+            Some(-123456)
+        );
+        assert_eq!(
+            parse_amount_minor("1,234.56cr", COMMA_THOUSANDS_CREDIT_DEBIT),
+            Some(123456)
+        );
+        assert_eq!(
+            parse_amount_minor("-1,234.56-", COMMA_THOUSANDS_TRAILING_MINUS),
+            None
+        );
+        assert_eq!(
+            parse_amount_minor("(1,234.56) DR", COMMA_THOUSANDS_CREDIT_DEBIT),
+            None
+        );
+        assert_eq!(
+            parse_amount_minor("1,234.56 CR DR", COMMA_THOUSANDS_CREDIT_DEBIT),
+            None
+        );
+        assert_eq!(parse_amount_minor("1,234.56-", COMMA_THOUSANDS), None);
+        assert_eq!(parse_amount_minor("1,234.56 DR", COMMA_THOUSANDS), None);
+        assert_eq!(
+            parse_amount_minor("1,234.56 DR", COMMA_THOUSANDS_TRAILING_MINUS),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_amount_minor_reads_whole_minor_units() {
+        assert_eq!(parse_amount_minor("123456", MINOR_UNITS), Some(123456));
+        assert_eq!(parse_amount_minor("-50", MINOR_UNITS), Some(-50));
+        assert_eq!(parse_amount_minor("1,234", MINOR_UNITS), None);
+        assert_eq!(parse_amount_minor("12.34", MINOR_UNITS), None);
+    }
+
     #[test]
     fn parse_amount_minor_rejects_unexpected_text() {
         assert_eq!(parse_amount_minor("abc123", COMMA_THOUSANDS), None);
@@ -1200,6 +1972,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_date_reads_month_names_compact_dates_and_spreadsheet_serials() {
+        let expected = Some(NaiveDate::from_ymd_opt(2026, 1, 9).unwrap());
+
+        for (raw, format) in [
+            ("09 Jan 2026", CsvSnapshotImportDateFormat::DdMonYyyy),
+            ("9-January-26", CsvSnapshotImportDateFormat::DdMonYyyy),
+            ("January 9, 2026", CsvSnapshotImportDateFormat::MonDdYyyy),
+            ("Jan 9th 2026", CsvSnapshotImportDateFormat::MonDdYyyy),
+            ("2026-Jan-09", CsvSnapshotImportDateFormat::YyyyMonDd),
+            ("20260109", CsvSnapshotImportDateFormat::YyyyMmDdCompact),
+            ("46031", CsvSnapshotImportDateFormat::ExcelSerial),
+            ("46031.75", CsvSnapshotImportDateFormat::ExcelSerial),
+        ] {
+            assert_eq!(parse_plain_date(raw, format), expected, "{raw}");
+        }
+
+        for (raw, format) in [
+            ("09 Ja 2026", CsvSnapshotImportDateFormat::DdMonYyyy),
+            ("31 Feb 2026", CsvSnapshotImportDateFormat::DdMonYyyy),
+            ("Jan 9", CsvSnapshotImportDateFormat::MonDdYyyy),
+            ("2026019", CsvSnapshotImportDateFormat::YyyyMmDdCompact),
+            ("-46031", CsvSnapshotImportDateFormat::ExcelSerial),
+            ("60", CsvSnapshotImportDateFormat::ExcelSerial),
+        ] {
+            assert_eq!(parse_plain_date(raw, format), None, "{raw}");
+        }
+    }
+
     #[test]
     fn parse_date_accepts_timestamp_date_as_written() {
         assert_eq!(
@@ -1253,6 +2054,7 @@ mod tests {
             parse_date(
                 "2026-01-09T23:30:00",
                 ISO_8601,
+                AppLocaleCode::EnGb,
                 CsvSnapshotImportTimestampDatePolicy::ConvertToUtc,
                 CsvSnapshotImportMissingTimezonePolicy::Timezone,
                 "America/New_York",
@@ -1267,6 +2069,7 @@ mod tests {
             parse_date(
                 "2026-01-09T23:30:00",
                 ISO_8601,
+                AppLocaleCode::EnGb,
                 CsvSnapshotImportTimestampDatePolicy::ConvertToUtc,
                 CsvSnapshotImportMissingTimezonePolicy::Timezone,
                 "Not/AZone",
@@ -1278,8 +2081,11 @@ mod tests {
     fn source(content: &str, has_header_row: bool) -> CsvSnapshotImportSourceInput {
         CsvSnapshotImportSourceInput {
             file_name: "snapshots.csv".to_string(),
-            content: content.to_string(),
+            content: content.as_bytes().to_vec(),
             has_header_row,
+            encoding: None,
+            delimiter: None,
+            quote: None,
         }
     }
 
@@ -1300,6 +2106,7 @@ mod tests {
         parse_date(
             raw,
             format,
+            AppLocaleCode::EnGb,
             CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
             CsvSnapshotImportMissingTimezonePolicy::Local,
             "Europe/London",
@@ -1313,6 +2120,7 @@ mod tests {
         parse_date(
             raw,
             ISO_8601,
+            AppLocaleCode::EnGb,
             timestamp_date_policy,
             CsvSnapshotImportMissingTimezonePolicy::Local,
             "Europe/London",
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::{SnapshotImportValidationIssue, issue};
+
+const DETECTION_LINE_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSnapshotImportEncoding {
+    Utf8,
+    #[serde(rename = "utf16_le")]
+    Utf16Le,
+    #[serde(rename = "utf16_be")]
+    Utf16Be,
+    Windows1252,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSnapshotImportDelimiter {
+    Comma,
+    Semicolon,
+    Tab,
+    Pipe,
+}
+
+impl CsvSnapshotImportDelimiter {
+    pub fn byte(self) -> u8 {
+        match self {
+            Self::Comma => b',',
+            Self::Semicolon => b';',
+            Self::Tab => b'\t',
+            Self::Pipe => b'|',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSnapshotImportQuote {
+    Double,
+    Single,
+    /// Quote characters are read as part of the value.
+    None,
+}
+
+impl CsvSnapshotImportQuote {
+    pub fn byte(self) -> Option<u8> {
+        match self {
+            Self::Double => Some(b'"'),
+            Self::Single => Some(b'\''),
+            Self::None => None,
+        }
+    }
+}
+
+/// How a CSV file was read, whether detected or chosen by the user.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct CsvSnapshotImportFormatDto {
+    pub encoding: CsvSnapshotImportEncoding,
+    /// Whether the file started with a byte order mark, which is never part of the data.
+    pub byte_order_mark: bool,
+    pub delimiter: CsvSnapshotImportDelimiter,
+    pub quote: CsvSnapshotImportQuote,
+}
+
+/// Decodes the file to text, honouring a byte order mark before any chosen encoding
+/// so a UTF-8 BOM never ends up glued to the first header.
+pub fn decode(
+    bytes: &[u8],
+    encoding: Option<CsvSnapshotImportEncoding>,
+) -> Result<(String, CsvSnapshotImportEncoding, bool), Vec<SnapshotImportValidationIssue>> {
+    let (bom_encoding, body) = if let Some(body) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        (Some(CsvSnapshotImportEncoding::Utf8), body)
+    } else if let Some(body) = bytes.strip_prefix(b"\xFF\xFE") {
+        (Some(CsvSnapshotImportEncoding::Utf16Le), body)
+    } else if let Some(body) = bytes.strip_prefix(b"\xFE\xFF") {
+        (Some(CsvSnapshotImportEncoding::Utf16Be), body)
+    } else {
+        (None, bytes)
+    };
+    let (body, byte_order_mark) = match (bom_encoding, encoding) {
+        (Some(bom_encoding), Some(encoding)) if bom_encoding != encoding => (bytes, false),
+        (bom_encoding, _) => (body, bom_encoding.is_some()),
+    };
+    let encoding = encoding
+        .or(bom_encoding)
+        .unwrap_or_else(|| detect_encoding(body));
+
+    let text = match encoding {
+        CsvSnapshotImportEncoding::Utf8 => String::from_utf8(body.to_vec()).ok(),
+        CsvSnapshotImportEncoding::Utf16Le => decode_utf16(body, u16::from_le_bytes),
+        CsvSnapshotImportEncoding::Utf16Be => decode_utf16(body, u16::from_be_bytes),
+        CsvSnapshotImportEncoding::Windows1252 => Some(decode_windows_1252(body)),
+    };
+
+    text.map(|text| (text, encoding, byte_order_mark))
+        .ok_or_else(|| {
+            vec![issue(
+                "source.encoding",
+                "File could not be read with the selected encoding",
+            )]
+        })
+}
+
+/// UTF-16 without a BOM shows up as a NUL in every other byte of mostly-ASCII text;
+/// anything else that is not valid UTF-8 is assumed to be a Windows export.
+fn detect_encoding(bytes: &[u8]) -> CsvSnapshotImportEncoding {
+    let sample = &bytes[..bytes.len().min(1024) & !1];
+    let pairs = sample.len() / 2;
+    if pairs > 0 {
+        let even_nuls = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+        let odd_nuls = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count();
+        if odd_nuls * 2 > pairs && even_nuls == 0 {
+            return CsvSnapshotImportEncoding::Utf16Le;
+        }
+        if even_nuls * 2 > pairs && odd_nuls == 0 {
+            return CsvSnapshotImportEncoding::Utf16Be;
+        }
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        CsvSnapshotImportEncoding::Utf8
+    } else {
+        CsvSnapshotImportEncoding::Windows1252
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    String::from_utf16(&units).ok()
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    // 0x80-0x9F are printable in Windows-1252; the rest matches Latin-1.
+    const HIGH_CONTROL_RANGE: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+
+    bytes
+        .iter()
+        .map(|byte| match byte {
+            0x80..=0x9F => HIGH_CONTROL_RANGE[usize::from(byte - 0x80)],
+            _ => char::from(*byte),
+        })
+        .collect()
+}
+
+/// Picks the delimiter that splits the first lines into the same, largest number of
+/// fields. Comma wins ties, so single-column files read as they always have.
+pub fn detect_delimiter(text: &str, quote: CsvSnapshotImportQuote) -> CsvSnapshotImportDelimiter {
+    let lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(DETECTION_LINE_LIMIT)
+        .collect::<Vec<_>>();
+
+    [
+        CsvSnapshotImportDelimiter::Comma,
+        CsvSnapshotImportDelimiter::Semicolon,
+        CsvSnapshotImportDelimiter::Tab,
+        CsvSnapshotImportDelimiter::Pipe,
+    ]
+    .into_iter()
+    .rev()
+    .max_by_key(|delimiter| {
+        let counts = lines
+            .iter()
+            .map(|line| count_unquoted(line, char::from(delimiter.byte()), quote))
+            .collect::<Vec<_>>();
+        let first = counts.first().copied().unwrap_or(0);
+        if first == 0 {
+            return (0, 0);
+        }
+        (
+            counts.iter().filter(|count| **count == first).count(),
+            first,
+        )
+    })
+    .unwrap_or(CsvSnapshotImportDelimiter::Comma)
+}
+
+/// Double quotes unless values are wrapped in single quotes and never in double ones.
+pub fn detect_quote(text: &str) -> CsvSnapshotImportQuote {
+    let starts_field = |quote: char| {
+        text.lines().take(DETECTION_LINE_LIMIT).any(|line| {
+            line.starts_with(quote)
+                || [',', ';', '\t', '|']
+                    .iter()
+                    .any(|delimiter| line.contains(&format!("{delimiter}{quote}")))
+        })
+    };
+
+    if !starts_field('"') && starts_field('\'') {
+        CsvSnapshotImportQuote::Single
+    } else {
+        CsvSnapshotImportQuote::Double
+    }
+}
+
+fn count_unquoted(line: &str, delimiter: char, quote: CsvSnapshotImportQuote) -> usize {
+    let quote = quote.byte().map(char::from);
+    let mut quoted = false;
+    let mut count = 0;
+    for character in line.chars() {
+        if Some(character) == quote {
+            quoted = !quoted;
+        } else if character == delimiter && !quoted {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CsvSnapshotImportDelimiter, CsvSnapshotImportEncoding, CsvSnapshotImportQuote, decode,
+        detect_delimiter, detect_quote,
+    };
+
+    #[test]
+    fn decode_detects_boms_utf16_and_windows_1252() {
+        let utf16_le = [0xFF, 0xFE, b'D', 0, b'a', 0, b'y', 0];
+        let utf16_be_without_bom = [0, b'D', 0, b'a', 0, b'y'];
+
+        assert_eq!(
+            decode(b"\xEF\xBB\xBFDate", None).unwrap(),
+            ("Date".to_string(), CsvSnapshotImportEncoding::Utf8, true)
+        );
+        assert_eq!(
+            decode(&utf16_le, None).unwrap(),
+            ("Day".to_string(), CsvSnapshotImportEncoding::Utf16Le, true)
+        );
+        assert_eq!(
+            decode(&utf16_be_without_bom, None).unwrap(),
+            ("Day".to_string(), CsvSnapshotImportEncoding::Utf16Be, false)
+        );
+        assert_eq!(
+            decode(b"\x80100 caf\xE9", None).unwrap(),
+            (
+                "€100 café".to_string(),
+                CsvSnapshotImportEncoding::Windows1252,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn decode_honours_an_encoding_override() {
+        assert_eq!(
+            decode(
+                "café".as_bytes(),
+                Some(CsvSnapshotImportEncoding::Windows1252)
+            )
+            .unwrap()
+            .0,
+            "cafÃ©"
+        );
+        assert_eq!(
+            decode(b"caf\xE9", Some(CsvSnapshotImportEncoding::Utf8)).unwrap_err()[0].field,
+            "source.encoding"
+        );
+    }
+
+    #[test]
+    fn detect_delimiter_prefers_consistent_field_counts() {
+        let semicolons = "Datum;Saldo\n09.01.2026;\"1.234,56\"\n10.01.2026;2.345,67\n";
+        let tabs = "Date\tBalance\n2026-01-09\t1,234.56\n";
+
+        assert_eq!(
+            detect_delimiter(semicolons, CsvSnapshotImportQuote::Double),
+            CsvSnapshotImportDelimiter::Semicolon
+        );
+        assert_eq!(
+            detect_delimiter(tabs, CsvSnapshotImportQuote::Double),
+            CsvSnapshotImportDelimiter::Tab
+        );
+        assert_eq!(
+            detect_delimiter("Balance\n1.00\n", CsvSnapshotImportQuote::Double),
+            CsvSnapshotImportDelimiter::Comma
+        );
+    }
+
+    #[test]
+    fn detect_quote_recognises_single_quoted_values() {
+        assert_eq!(
+            detect_quote("'Date','Balance'\n'2026-01-09','1,234.56'\n"),
+            CsvSnapshotImportQuote::Single
+        );
+        assert_eq!(
+            detect_quote("Date,Description\n2026-01-09,\"Bob's, cafe\"\n"),
+            CsvSnapshotImportQuote::Double
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::csv::{
+    CsvSnapshotImportBalanceFormat, CsvSnapshotImportDateFormat,
+    CsvSnapshotImportMissingTimezonePolicy,
+};
+
+/// Built-in export layout recognised from a CSV's headers.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CsvSnapshotImportKnownProfileDto {
+    pub id: String,
+    pub name: String,
+    pub amount_kind: CsvSnapshotImportAmountKind,
+    pub confidence: CsvSnapshotImportKnownProfileConfidence,
+}
+
+/// How sure a header match is. `Low` matches are named to the user, but the columns and
+/// formats are guessed from the values rather than taken from the profile.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSnapshotImportKnownProfileConfidence {
+    /// The signature is generic enough that other exports share it.
+    Low,
+    High,
+}
+
+/// Whether the guessed amount column holds running balances or transaction amounts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSnapshotImportAmountKind {
+    Balances,
+    Transactions,
+}
+
+#[derive(Debug)]
+pub(super) struct KnownProfile {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Headers that must all be present, compared case-insensitively.
+    signature: &'static [&'static str],
+    confidence: CsvSnapshotImportKnownProfileConfidence,
+    pub date_column: &'static str,
+    pub amount_column: &'static str,
+    pub amount_kind: CsvSnapshotImportAmountKind,
+    /// `None` leaves the format to be guessed from the date values.
+    pub date_format: Option<CsvSnapshotImportDateFormat>,
+    pub balance_format: CsvSnapshotImportBalanceFormat,
+    pub timestamp_missing_timezone_policy: Option<CsvSnapshotImportMissingTimezonePolicy>,
+}
+
+impl KnownProfile {
+    pub fn dto(&self) -> CsvSnapshotImportKnownProfileDto {
+        CsvSnapshotImportKnownProfileDto {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            amount_kind: self.amount_kind,
+            confidence: self.confidence,
+        }
+    }
+
+    /// Whether guesses should follow this profile's columns and formats.
+    pub fn guides_options(&self) -> bool {
+        self.confidence == CsvSnapshotImportKnownProfileConfidence::High
+    }
+
+    fn matches(&self, columns: &[String]) -> bool {
+        self.signature.iter().all(|header| {
+            columns
+                .iter()
+                .any(|column| column.eq_ignore_ascii_case(header))
+        })
+    }
+}
+
+const KNOWN_PROFILES: &[KnownProfile] = &[
+    KnownProfile {
+        id: "monzo",
+        name: "Monzo",
+        signature: &[
+            "Transaction ID",
+            "Date",
+            "Time",
+            "Type",
+            "Name",
+            "Emoji",
+            "Category",
+            "Amount",
+        ],
+        confidence: CsvSnapshotImportKnownProfileConfidence::High,
+        date_column: "Date",
+        amount_column: "Amount",
+        amount_kind: CsvSnapshotImportAmountKind::Transactions,
+        date_format: Some(CsvSnapshotImportDateFormat::DdMmYyyySlash),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: None,
+    },
+    KnownProfile {
+        id: "starling",
+        name: "Starling",
+        signature: &[
+            "Date",
+            "Counter Party",
+            "Reference",
+            "Type",
+            "Amount (GBP)",
+            "Balance (GBP)",
+        ],
+        confidence: CsvSnapshotImportKnownProfileConfidence::High,
+        date_column: "Date",
+        amount_column: "Balance (GBP)",
+        amount_kind: CsvSnapshotImportAmountKind::Balances,
+        date_format: Some(CsvSnapshotImportDateFormat::DdMmYyyySlash),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: None,
+    },
+    // Nationwide writes dates such as "03 Jan 2024".
+    KnownProfile {
+        id: "nationwide",
+        name: "Nationwide",
+        signature: &[
+            "Date",
+            "Transaction type",
+            "Description",
+            "Paid out",
+            "Paid in",
+            "Balance",
+        ],
+        confidence: CsvSnapshotImportKnownProfileConfidence::High,
+        date_column: "Date",
+        amount_column: "Balance",
+        amount_kind: CsvSnapshotImportAmountKind::Balances,
+        date_format: Some(CsvSnapshotImportDateFormat::DdMonYyyy),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: None,
+    },
+    // Many exports use these three headers, so a match only suggests HSBC.
+    KnownProfile {
+        id: "hsbc",
+        name: "HSBC",
+        signature: &["Date", "Description", "Amount"],
+        confidence: CsvSnapshotImportKnownProfileConfidence::Low,
+        date_column: "Date",
+        amount_column: "Amount",
+        amount_kind: CsvSnapshotImportAmountKind::Transactions,
+        date_format: Some(CsvSnapshotImportDateFormat::DdMmYyyySlash),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: None,
+    },
+    KnownProfile {
+        id: "amex",
+        name: "American Express",
+        signature: &[
+            "Date",
+            "Description",
+            "Amount",
+            "Extended Details",
+            "Appears On Your Statement As",
+        ],
+        confidence: CsvSnapshotImportKnownProfileConfidence::High,
+        date_column: "Date",
+        amount_column: "Amount",
+        amount_kind: CsvSnapshotImportAmountKind::Transactions,
+        date_format: Some(CsvSnapshotImportDateFormat::DdMmYyyySlash),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: None,
+    },
+    // Trading 212 timestamps are written in UTC without an offset.
+    KnownProfile {
+        id: "trading_212",
+        name: "Trading 212",
+        signature: &[
+            "Action",
+            "Time",
+            "ISIN",
+            "Ticker",
+            "No. of shares",
+            "Price / share",
+            "Total",
+        ],
+        confidence: CsvSnapshotImportKnownProfileConfidence::High,
+        date_column: "Time",
+        amount_column: "Total",
+        amount_kind: CsvSnapshotImportAmountKind::Transactions,
+        date_format: Some(CsvSnapshotImportDateFormat::Iso8601DateTime),
+        balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        timestamp_missing_timezone_policy: Some(CsvSnapshotImportMissingTimezonePolicy::Utc),
+    },
+];
+
+/// Built-in profile whose header signature the columns contain, preferring confident
+/// matches and then the most specific signature.
+pub(super) fn known_profile(columns: &[String]) -> Option<&'static KnownProfile> {
+    KNOWN_PROFILES
+        .iter()
+        .filter(|profile| profile.matches(columns))
+        .max_by_key(|profile| (profile.confidence, profile.signature.len()))
+}
+
+/// The file's own spelling of a profile header, so guesses name real columns.
+pub(super) fn file_column(columns: &[String], header: &str) -> Option<String> {
+    columns
+        .iter()
+        .find(|column| column.eq_ignore_ascii_case(header))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvSnapshotImportKnownProfileConfidence, known_profile};
+
+    #[test]
+    fn known_profile_prefers_the_most_specific_signature() {
+        let columns =
+            |headers: &[&str]| headers.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let hsbc = known_profile(&columns(&["date", "description", "amount", "notes"])).unwrap();
+        assert_eq!(hsbc.id, "hsbc");
+        assert_eq!(
+            hsbc.confidence,
+            CsvSnapshotImportKnownProfileConfidence::Low
+        );
+        assert!(!hsbc.guides_options());
+        assert_eq!(
+            known_profile(&columns(&[
+                "Date",
+                "Description",
+                "Amount",
+                "Extended Details",
+                "Appears On Your Statement As",
+                "Category",
+            ]))
+            .map(|p| p.id),
+            Some("amex")
+        );
+        assert!(known_profile(&columns(&["Date", "Description", "Balance"])).is_none());
+    }
+}
@@ -1,26 +1,84 @@
 mod csv;
+mod csv_format;
+mod known_profiles;
+mod month_names;
+mod ofx;
+mod profiles;
+mod spreadsheet;
+mod transactions;
+mod wide;
 
 use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::{BTreeMap, HashMap};
 
+use crate::contracts::{AppLocaleCode, CurrencyCode, SnapshotSource};
 use crate::db::rows::AccountBalanceSnapshotRow;
 
 pub use csv::{
-    CsvSnapshotImportInspectionDto, CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput,
+    CsvSnapshotImportDateFormat, CsvSnapshotImportInspectionDto, CsvSnapshotImportOptionsInput,
+    CsvSnapshotImportSourceInput,
 };
+pub(crate) use csv::{parse_date_as_written, read_column_pairs};
+pub use ofx::{
+    OfxSnapshotImportInspectionDto, OfxSnapshotImportOptionsInput, OfxSnapshotImportSourceInput,
+};
+pub use profiles::{SnapshotImportProfileDto, SnapshotImportProfileSaveInput};
+pub(crate) use profiles::{matching_profile, validate_profile};
+#[cfg(test)]
+pub(crate) use spreadsheet::tests::xlsx as test_xlsx;
+pub use spreadsheet::{
+    SpreadsheetSnapshotImportInspectionDto, SpreadsheetSnapshotImportSourceInput,
+};
+pub use transactions::CsvTransactionsSnapshotImportOptionsInput;
+pub use wide::{
+    WideSnapshotImportAccountCommitDto, WideSnapshotImportAccountPreviewDto,
+    WideSnapshotImportCommitDto, WideSnapshotImportOptionsInput, WideSnapshotImportPreviewDto,
+    WideSnapshotImportTarget,
+};
+pub(crate) use wide::{validate_wide_columns, wide_column_options, wide_preview_summary};
+
+/// Matches the length limit on snapshot sources and import batch file names.
+const FILE_NAME_MAX_LENGTH: usize = 255;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum SnapshotImportSourceInput {
     Csv(CsvSnapshotImportSourceInput),
+    Ofx(OfxSnapshotImportSourceInput),
+    Spreadsheet(SpreadsheetSnapshotImportSourceInput),
+}
+
+impl SnapshotImportSourceInput {
+    /// Name of the imported file, recorded as the source of the snapshots it writes.
+    pub fn file_name(&self) -> &str {
+        match self {
+            Self::Csv(input) => &input.file_name,
+            Self::Ofx(input) => &input.file_name,
+            Self::Spreadsheet(input) => &input.file_name,
+        }
+    }
+
+    /// Source recorded on the snapshots this import writes.
+    pub fn snapshot_source(&self) -> SnapshotSource {
+        let file_name = self.file_name().trim().to_owned();
+        match self {
+            Self::Csv(_) => SnapshotSource::CsvImport { file_name },
+            Self::Ofx(_) => SnapshotSource::OfxImport { file_name },
+            Self::Spreadsheet(_) => SnapshotSource::SpreadsheetImport { file_name },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum SnapshotImportSourceOptionsInput {
     Csv(CsvSnapshotImportOptionsInput),
+    /// Reads a CSV or spreadsheet source as transactions and derives balances from an
+    /// anchor.
+    CsvTransactions(CsvTransactionsSnapshotImportOptionsInput),
+    Ofx(OfxSnapshotImportOptionsInput),
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
@@ -59,6 +117,8 @@ pub struct SnapshotImportOptionsInput {
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum SnapshotImportInspectionDto {
     Csv(CsvSnapshotImportInspectionDto),
+    Ofx(OfxSnapshotImportInspectionDto),
+    Spreadsheet(SpreadsheetSnapshotImportInspectionDto),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -115,6 +175,26 @@ pub struct SnapshotImportCommitDto {
     pub created_count: u32,
     pub overwritten_count: u32,
     pub skipped_count: u32,
+    /// Batch to roll back to undo this import; `None` when every row was skipped.
+    pub batch_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SnapshotImportBatchDto {
+    pub id: i64,
+    pub file_name: String,
+    pub options: SnapshotImportOptionsInput,
+    pub created_count: u32,
+    pub overwritten_count: u32,
+    pub skipped_count: u32,
+    pub created_at: chrono::DateTime<Utc>,
+    pub rolled_back_at: Option<chrono::DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SnapshotImportRollbackDto {
+    pub deleted_count: u32,
+    pub restored_count: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -134,6 +214,9 @@ pub struct SnapshotImportPlan {
 pub struct SnapshotImportPlanningContext {
     pub account_opened_date: Option<NaiveDate>,
     pub account_closed_date: Option<NaiveDate>,
+    pub currency_code: CurrencyCode,
+    /// Display locale, whose month names CSV dates may be written with.
+    pub locale: AppLocaleCode,
     pub today: NaiveDate,
 }
 
@@ -159,10 +242,18 @@ pub(crate) struct SnapshotImportCandidate {
 
 pub fn inspect_source(
     input: &SnapshotImportSourceInput,
+    locale: AppLocaleCode,
 ) -> Result<SnapshotImportInspectionDto, Vec<SnapshotImportValidationIssue>> {
+    validate_file_name(input.file_name())?;
     match input {
         SnapshotImportSourceInput::Csv(input) => {
-            csv::inspect(input).map(SnapshotImportInspectionDto::Csv)
+            csv::inspect(input, locale).map(SnapshotImportInspectionDto::Csv)
+        }
+        SnapshotImportSourceInput::Ofx(input) => {
+            ofx::inspect(input).map(SnapshotImportInspectionDto::Ofx)
+        }
+        SnapshotImportSourceInput::Spreadsheet(input) => {
+            spreadsheet::inspect(input, locale).map(SnapshotImportInspectionDto::Spreadsheet)
         }
     }
 }
@@ -173,9 +264,49 @@ pub fn plan_import(
     existing_snapshots: &[AccountBalanceSnapshotRow],
     context: SnapshotImportPlanningContext,
 ) -> Result<SnapshotImportPlan, Vec<SnapshotImportValidationIssue>> {
+    validate_file_name(input.file_name())?;
     let mut candidates = match (input, &options.source) {
         (SnapshotImportSourceInput::Csv(input), SnapshotImportSourceOptionsInput::Csv(options)) => {
-            csv::candidates(input, options)?
+            csv::candidates(input, options, context.currency_code, context.locale)?
+        }
+        (
+            SnapshotImportSourceInput::Spreadsheet(input),
+            SnapshotImportSourceOptionsInput::Csv(options),
+        ) => spreadsheet::candidates(input, options, context.currency_code, context.locale)?,
+        (
+            SnapshotImportSourceInput::Csv(input),
+            SnapshotImportSourceOptionsInput::CsvTransactions(options),
+        ) => transactions::candidates(
+            csv::transaction_candidates(
+                input,
+                &options.csv,
+                context.currency_code,
+                context.locale,
+            )?,
+            options.anchor,
+            context.currency_code,
+        )?,
+        (
+            SnapshotImportSourceInput::Spreadsheet(input),
+            SnapshotImportSourceOptionsInput::CsvTransactions(options),
+        ) => transactions::candidates(
+            spreadsheet::transaction_candidates(
+                input,
+                &options.csv,
+                context.currency_code,
+                context.locale,
+            )?,
+            options.anchor,
+            context.currency_code,
+        )?,
+        (SnapshotImportSourceInput::Ofx(input), SnapshotImportSourceOptionsInput::Ofx(options)) => {
+            ofx::candidates(input, options, context.currency_code)?
+        }
+        _ => {
+            return Err(vec![issue(
+                "options.source",
+                "Import options do not match the selected source",
+            )]);
         }
     };
 
@@ -523,6 +654,19 @@ fn existing_snapshot_dto(
     }
 }
 
+/// Checks the file name before it is stored as each snapshot's source and on the import
+/// batch.
+fn validate_file_name(file_name: &str) -> Result<(), Vec<SnapshotImportValidationIssue>> {
+    if file_name.trim().chars().count() > FILE_NAME_MAX_LENGTH {
+        return Err(vec![issue(
+            "file_name",
+            "File name must be 255 characters or fewer",
+        )]);
+    }
+
+    Ok(())
+}
+
 pub(crate) fn issue(field: &str, message: &str) -> SnapshotImportValidationIssue {
     SnapshotImportValidationIssue {
         field: field.to_string(),
@@ -540,13 +684,18 @@ mod tests {
         CsvSnapshotImportDateFormat, CsvSnapshotImportMissingTimezonePolicy,
         CsvSnapshotImportTimestampDatePolicy,
     };
+    use super::ofx::OfxSnapshotImportBalanceKind;
+    use super::transactions::CsvTransactionsSnapshotImportAnchor;
     use super::{
-        CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput, SnapshotImportCandidate,
-        SnapshotImportDuplicateDatePolicy, SnapshotImportExistingDatePolicy,
-        SnapshotImportOptionsInput, SnapshotImportPlanningContext, SnapshotImportPreviewAction,
-        SnapshotImportSourceInput, SnapshotImportSourceOptionsInput,
-        SnapshotImportUnchangedValuePolicy, plan_import, resolve_duplicate_dates,
+        CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput,
+        CsvTransactionsSnapshotImportOptionsInput, OfxSnapshotImportOptionsInput,
+        OfxSnapshotImportSourceInput, SnapshotImportCandidate, SnapshotImportDuplicateDatePolicy,
+        SnapshotImportExistingDatePolicy, SnapshotImportOptionsInput,
+        SnapshotImportPlanningContext, SnapshotImportPreviewAction, SnapshotImportSourceInput,
+        SnapshotImportSourceOptionsInput, SnapshotImportUnchangedValuePolicy, inspect_source,
+        plan_import, resolve_duplicate_dates,
     };
+    use crate::contracts::{AppLocaleCode, CurrencyCode};
     use crate::db::rows::AccountBalanceSnapshotRow;
 
     #[test]
@@ -631,6 +780,38 @@ mod tests {
         assert_eq!(plan.preview.summary.skip_count, 1);
     }
 
+    #[test]
+    fn inspect_and_plan_reject_file_names_too_long_to_store() {
+        let SnapshotImportSourceInput::Csv(mut source) =
+            csv_input("date,balance\n2026-01-09,1.00\n")
+        else {
+            unreachable!();
+        };
+        source.file_name = format!("{}.csv", "a".repeat(252));
+        let input = SnapshotImportSourceInput::Csv(source.clone());
+        let options = options(
+            SnapshotImportExistingDatePolicy::Overwrite,
+            SnapshotImportUnchangedValuePolicy::Exclude,
+            SnapshotImportDuplicateDatePolicy::KeepLast,
+        );
+
+        let inspect_issues = inspect_source(&input, AppLocaleCode::EnGb).unwrap_err();
+        let plan_issues = plan_import(&input, &options, &[], context()).unwrap_err();
+
+        for issues in [inspect_issues, plan_issues] {
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].field, "file_name");
+            assert_eq!(
+                issues[0].message,
+                "File name must be 255 characters or fewer"
+            );
+        }
+
+        source.file_name = format!("{}.csv", "a".repeat(251));
+        let input = SnapshotImportSourceInput::Csv(source);
+        assert!(plan_import(&input, &options, &[], context()).is_ok());
+    }
+
     #[test]
     fn plan_import_overwrites_existing_date_when_policy_allows_it() {
         let existing = vec![snapshot(7, 2026, 1, 9, 100)];
@@ -911,10 +1092,8 @@ mod tests {
             SnapshotImportUnchangedValuePolicy::Include,
             SnapshotImportDuplicateDatePolicy::KeepFirst,
         );
-        match &mut options.source {
-            SnapshotImportSourceOptionsInput::Csv(csv_options) => {
-                csv_options.blank_amount_policy = CsvSnapshotImportBlankAmountPolicy::Skip;
-            }
+        if let SnapshotImportSourceOptionsInput::Csv(csv_options) = &mut options.source {
+            csv_options.blank_amount_policy = CsvSnapshotImportBlankAmountPolicy::Skip;
         }
 
         let plan = plan_import(
@@ -944,10 +1123,8 @@ mod tests {
             SnapshotImportUnchangedValuePolicy::Include,
             SnapshotImportDuplicateDatePolicy::KeepLast,
         );
-        match &mut options.source {
-            SnapshotImportSourceOptionsInput::Csv(csv_options) => {
-                csv_options.blank_amount_policy = CsvSnapshotImportBlankAmountPolicy::Zero;
-            }
+        if let SnapshotImportSourceOptionsInput::Csv(csv_options) = &mut options.source {
+            csv_options.blank_amount_policy = CsvSnapshotImportBlankAmountPolicy::Zero;
         }
 
         let plan = plan_import(
@@ -971,6 +1148,8 @@ mod tests {
         let context = SnapshotImportPlanningContext {
             account_opened_date: date(2026, 1, 9),
             account_closed_date: date(2026, 1, 10),
+            currency_code: CurrencyCode::GBP,
+            locale: AppLocaleCode::EnGb,
             today: date(2026, 1, 8).unwrap(),
         };
         let plan = plan_import(
@@ -1002,6 +1181,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn plan_import_applies_policies_to_ofx_statement_balances() {
+        let existing = vec![snapshot(7, 2026, 1, 31, 100)];
+        let mut ofx_options = options(
+            SnapshotImportExistingDatePolicy::Skip,
+            SnapshotImportUnchangedValuePolicy::Exclude,
+            SnapshotImportDuplicateDatePolicy::KeepFirst,
+        );
+        ofx_options.source = SnapshotImportSourceOptionsInput::Ofx(OfxSnapshotImportOptionsInput {
+            balance_kind: OfxSnapshotImportBalanceKind::Ledger,
+        });
+        let statement = |date: &str, amount: &str| {
+            format!(
+                "<STMTRS><CURDEF>GBP<LEDGERBAL><BALAMT>{amount}<DTASOF>{date}</LEDGERBAL></STMTRS>"
+            )
+        };
+        let input = SnapshotImportSourceInput::Ofx(OfxSnapshotImportSourceInput {
+            file_name: "statement.qfx".to_string(),
+            content: format!(
+                "<OFX>{}{}{}</OFX>",
+                statement("20260130", "5.00"),
+                statement("20260130", "6.00"),
+                statement("20260131", "7.00"),
+            ),
+        });
+
+        let plan = plan_import(&input, &ofx_options, &existing, context()).unwrap();
+
+        assert_eq!(
+            actions(&plan.preview.rows),
+            vec![
+                SnapshotImportPreviewAction::Create,
+                SnapshotImportPreviewAction::SkipDuplicate,
+                SnapshotImportPreviewAction::SkipExisting,
+            ]
+        );
+        assert_eq!(plan.writes.len(), 1);
+        assert_eq!(plan.writes[0].balance_minor, 500);
+    }
+
+    #[test]
+    fn plan_import_derives_end_of_day_balances_from_transactions() {
+        let mut transaction_options = options(
+            SnapshotImportExistingDatePolicy::Overwrite,
+            SnapshotImportUnchangedValuePolicy::Exclude,
+            SnapshotImportDuplicateDatePolicy::Error,
+        );
+        let SnapshotImportSourceOptionsInput::Csv(mut csv) = transaction_options.source else {
+            panic!("expected CSV options");
+        };
+        csv.amount_column = "amount".to_string();
+        transaction_options.source = SnapshotImportSourceOptionsInput::CsvTransactions(
+            CsvTransactionsSnapshotImportOptionsInput {
+                csv,
+                anchor: CsvTransactionsSnapshotImportAnchor::Latest {
+                    balance_minor: 1_000,
+                },
+            },
+        );
+
+        let plan = plan_import(
+            &csv_input(
+                "date,amount\n2026-01-09,5.00\n2026-01-10,2.00\n2026-01-10,-2.00\n2026-01-11,1.00\n",
+            ),
+            &transaction_options,
+            &[],
+            context(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            actions(&plan.preview.rows),
+            vec![
+                SnapshotImportPreviewAction::Create,
+                SnapshotImportPreviewAction::SkipUnchanged,
+                SnapshotImportPreviewAction::Create,
+            ]
+        );
+        assert_eq!(
+            plan.writes
+                .iter()
+                .map(|write| write.balance_minor)
+                .collect::<Vec<_>>(),
+            vec![900, 1_000]
+        );
+    }
+
+    #[test]
+    fn plan_import_counts_skipped_blank_transaction_amounts() {
+        let mut transaction_options = options(
+            SnapshotImportExistingDatePolicy::Overwrite,
+            SnapshotImportUnchangedValuePolicy::Include,
+            SnapshotImportDuplicateDatePolicy::Error,
+        );
+        let SnapshotImportSourceOptionsInput::Csv(mut csv) = transaction_options.source else {
+            panic!("expected CSV options");
+        };
+        csv.amount_column = "amount".to_string();
+        csv.blank_amount_policy = CsvSnapshotImportBlankAmountPolicy::Skip;
+        transaction_options.source = SnapshotImportSourceOptionsInput::CsvTransactions(
+            CsvTransactionsSnapshotImportOptionsInput {
+                csv,
+                anchor: CsvTransactionsSnapshotImportAnchor::Latest {
+                    balance_minor: 1_000,
+                },
+            },
+        );
+
+        let plan = plan_import(
+            &csv_input(
+                "date,amount
+2026-01-09,5.00
+2026-01-10,
+2026-01-10,2.00
+",
+            ),
+            &transaction_options,
+            &[],
+            context(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            actions(&plan.preview.rows),
+            vec![
+                SnapshotImportPreviewAction::Create,
+                SnapshotImportPreviewAction::SkipBlankAmount,
+                SnapshotImportPreviewAction::Create,
+            ]
+        );
+        assert_eq!(plan.preview.summary.total_rows, 3);
+        assert_eq!(plan.preview.summary.skip_count, 1);
+        assert_eq!(plan.writes.len(), 2);
+    }
+
+    #[test]
+    fn plan_import_rejects_options_for_a_different_source() {
+        let mut ofx_options = options(
+            SnapshotImportExistingDatePolicy::Overwrite,
+            SnapshotImportUnchangedValuePolicy::Exclude,
+            SnapshotImportDuplicateDatePolicy::KeepLast,
+        );
+        ofx_options.source = SnapshotImportSourceOptionsInput::Ofx(OfxSnapshotImportOptionsInput {
+            balance_kind: OfxSnapshotImportBalanceKind::Ledger,
+        });
+
+        let issues = plan_import(
+            &csv_input("date,balance\n2026-01-09,1.00\n"),
+            &ofx_options,
+            &[],
+            context(),
+        )
+        .unwrap_err();
+
+        assert_eq!(issues[0].field, "options.source");
+    }
+
     fn duplicate_rows() -> Vec<SnapshotImportCandidate> {
         vec![
             candidate(1, 2026, 1, 9),
@@ -1031,8 +1367,11 @@ mod tests {
     fn csv_input(content: &str) -> SnapshotImportSourceInput {
         SnapshotImportSourceInput::Csv(CsvSnapshotImportSourceInput {
             file_name: "snapshots.csv".to_string(),
-            content: content.to_string(),
+            content: content.as_bytes().to_vec(),
             has_header_row: true,
+            encoding: None,
+            delimiter: None,
+            quote: None,
         })
     }
 
@@ -1063,6 +1402,8 @@ mod tests {
         SnapshotImportPlanningContext {
             account_opened_date: None,
             account_closed_date: None,
+            currency_code: CurrencyCode::GBP,
+            locale: AppLocaleCode::EnGb,
             today: date(2026, 1, 8).unwrap(),
         }
     }
@@ -1079,6 +1420,9 @@ mod tests {
             account_id: 1,
             balance_date: date(year, month, day).unwrap(),
             balance_minor,
+            note: None,
+            source_kind: None,
+            source_detail: None,
             created_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
         }
     }
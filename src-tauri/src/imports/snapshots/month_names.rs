@@ -0,0 +1,53 @@
+use crate::contracts::AppLocaleCode;
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Full month names for a display locale, lowercased. The backend cannot see the
+/// operating system locale, so `System` reads English like the only named locale.
+fn month_names(locale: AppLocaleCode) -> &'static [&'static str; 12] {
+    match locale {
+        AppLocaleCode::System | AppLocaleCode::EnGb => &ENGLISH_MONTHS,
+    }
+}
+
+/// Month number for a full or abbreviated month name, such as "January", "Jan" or
+/// "Sept". Abbreviations must be at least three letters so they stay unambiguous.
+pub fn month_from_name(name: &str, locale: AppLocaleCode) -> Option<u32> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    if name.chars().count() < 3 {
+        return None;
+    }
+
+    month_names(locale)
+        .iter()
+        .position(|month| month.starts_with(&name))
+        .and_then(|index| u32::try_from(index + 1).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::month_from_name;
+    use crate::contracts::AppLocaleCode;
+
+    #[test]
+    fn month_from_name_reads_full_and_abbreviated_names() {
+        assert_eq!(month_from_name("January", AppLocaleCode::EnGb), Some(1));
+        assert_eq!(month_from_name("SEPT", AppLocaleCode::EnGb), Some(9));
+        assert_eq!(month_from_name("dec.", AppLocaleCode::System), Some(12));
+        assert_eq!(month_from_name("Ma", AppLocaleCode::EnGb), None);
+        assert_eq!(month_from_name("Janvier", AppLocaleCode::EnGb), None);
+    }
+}
@@ -0,0 +1,421 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::csv::{
+    CsvSnapshotImportBalanceFormat, ParseAmountMinorError, parse_amount_minor_result,
+};
+use super::{SnapshotImportCandidate, SnapshotImportValidationIssue, issue};
+use crate::contracts::CurrencyCode;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OfxSnapshotImportSourceInput {
+    pub file_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OfxSnapshotImportOptionsInput {
+    pub balance_kind: OfxSnapshotImportBalanceKind,
+}
+
+/// Which statement balance aggregate becomes the snapshot balance.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OfxSnapshotImportBalanceKind {
+    /// `<LEDGERBAL>`, the booked balance.
+    Ledger,
+    /// `<AVAILBAL>`, the balance available to spend.
+    Available,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OfxSnapshotImportBalanceDto {
+    pub source_row_number: u32,
+    pub kind: OfxSnapshotImportBalanceKind,
+    pub raw_date: String,
+    pub raw_amount: String,
+    pub date: Option<NaiveDate>,
+    pub currency_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OfxSnapshotImportGuessesDto {
+    pub balance_kind: Option<OfxSnapshotImportBalanceKind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct OfxSnapshotImportInspectionDto {
+    pub file_name: String,
+    pub statement_count: u32,
+    pub balances: Vec<OfxSnapshotImportBalanceDto>,
+    pub guesses: OfxSnapshotImportGuessesDto,
+}
+
+#[derive(Debug, Clone, Default)]
+struct OfxData {
+    statement_count: u32,
+    balances: Vec<OfxBalance>,
+}
+
+#[derive(Debug, Clone)]
+struct OfxBalance {
+    source_row_number: u32,
+    kind: OfxSnapshotImportBalanceKind,
+    raw_date: String,
+    raw_amount: String,
+    currency_code: Option<String>,
+}
+
+pub fn inspect(
+    input: &OfxSnapshotImportSourceInput,
+) -> Result<OfxSnapshotImportInspectionDto, Vec<SnapshotImportValidationIssue>> {
+    let data = parse_ofx(&input.content)?;
+    let has_kind = |kind| data.balances.iter().any(|balance| balance.kind == kind);
+    let balance_kind = if has_kind(OfxSnapshotImportBalanceKind::Ledger) {
+        Some(OfxSnapshotImportBalanceKind::Ledger)
+    } else if has_kind(OfxSnapshotImportBalanceKind::Available) {
+        Some(OfxSnapshotImportBalanceKind::Available)
+    } else {
+        None
+    };
+
+    Ok(OfxSnapshotImportInspectionDto {
+        file_name: input.file_name.clone(),
+        statement_count: data.statement_count,
+        balances: data
+            .balances
+            .into_iter()
+            .map(|balance| OfxSnapshotImportBalanceDto {
+                source_row_number: balance.source_row_number,
+                kind: balance.kind,
+                date: parse_ofx_date(&balance.raw_date),
+                raw_date: balance.raw_date,
+                raw_amount: balance.raw_amount,
+                currency_code: balance.currency_code,
+            })
+            .collect(),
+        guesses: OfxSnapshotImportGuessesDto { balance_kind },
+    })
+}
+
+pub fn candidates(
+    input: &OfxSnapshotImportSourceInput,
+    options: &OfxSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    let data = parse_ofx(&input.content)?;
+    let candidates = data
+        .balances
+        .iter()
+        .filter(|balance| balance.kind == options.balance_kind)
+        .map(|balance| parse_candidate(balance, currency_code))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return Err(vec![issue(
+            "options.source.balance_kind",
+            "The file has no balances of the selected kind",
+        )]);
+    }
+
+    Ok(candidates)
+}
+
+fn parse_candidate(balance: &OfxBalance, currency_code: CurrencyCode) -> SnapshotImportCandidate {
+    let date = parse_ofx_date(&balance.raw_date);
+    let balance_minor_result = parse_ofx_amount_minor(&balance.raw_amount, currency_code);
+    let balance_minor = balance_minor_result.ok();
+    let mut issues = Vec::new();
+
+    if balance.raw_date.is_empty() {
+        issues.push("Missing date".to_string());
+    } else if date.is_none() {
+        issues.push("Date is not a valid OFX date".to_string());
+    }
+    if balance.raw_amount.is_empty() {
+        issues.push("Missing amount".to_string());
+    } else if matches!(balance_minor_result, Err(ParseAmountMinorError::TooLarge)) {
+        issues.push("Balance is too large".to_string());
+    } else if balance_minor.is_none() {
+        issues.push("Balance is not a valid OFX amount".to_string());
+    }
+    if let Some(statement_currency) = &balance.currency_code
+        && !statement_currency.eq_ignore_ascii_case(currency_code.as_str())
+    {
+        issues.push(format!(
+            "Statement currency {statement_currency} does not match the account currency"
+        ));
+    }
+
+    SnapshotImportCandidate {
+        source_row_number: balance.source_row_number,
+        raw_date: balance.raw_date.clone(),
+        raw_amount: balance.raw_amount.clone(),
+        date,
+        balance_minor,
+        issues,
+        skip_blank_amount: false,
+        skip_duplicate: false,
+    }
+}
+
+/// Reads statement balances from OFX 1.x SGML or OFX 2.x XML. SGML leaves element
+/// values unclosed, so a value runs to the next tag; aggregates are closed in both.
+fn parse_ofx(content: &str) -> Result<OfxData, Vec<SnapshotImportValidationIssue>> {
+    let Some(start) = content.to_ascii_uppercase().find("<OFX>") else {
+        return Err(vec![issue("source", "File is not an OFX or QFX statement")]);
+    };
+
+    let mut data = OfxData::default();
+    let mut statement_currency = None::<String>;
+    let mut current = None::<OfxBalance>;
+    let mut rest = &content[start..];
+
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = rest[open + 1..open + close].trim().to_ascii_uppercase();
+        rest = &rest[open + close + 1..];
+        let value = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+
+        match tag.as_str() {
+            "STMTRS" | "CCSTMTRS" => {
+                data.statement_count += 1;
+                statement_currency = None;
+            }
+            "CURDEF" => statement_currency = Some(value.to_ascii_uppercase()),
+            "LEDGERBAL" | "AVAILBAL" => {
+                if let Some(balance) = current.take() {
+                    data.balances.push(balance);
+                }
+                current = Some(OfxBalance {
+                    source_row_number: u32::try_from(data.balances.len() + 1)
+                        .expect("balance count should fit in u32"),
+                    kind: if tag == "LEDGERBAL" {
+                        OfxSnapshotImportBalanceKind::Ledger
+                    } else {
+                        OfxSnapshotImportBalanceKind::Available
+                    },
+                    raw_date: String::new(),
+                    raw_amount: String::new(),
+                    currency_code: statement_currency.clone(),
+                });
+            }
+            "/LEDGERBAL" | "/AVAILBAL" => {
+                if let Some(balance) = current.take() {
+                    data.balances.push(balance);
+                }
+            }
+            "BALAMT" => {
+                if let Some(balance) = current.as_mut() {
+                    balance.raw_amount = value.to_string();
+                }
+            }
+            "DTASOF" => {
+                if let Some(balance) = current.as_mut() {
+                    balance.raw_date = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(balance) = current.take() {
+        data.balances.push(balance);
+    }
+
+    if data.balances.is_empty() {
+        return Err(vec![issue(
+            "source",
+            "OFX file must include a ledger or available balance",
+        )]);
+    }
+
+    Ok(data)
+}
+
+/// OFX datetimes are `YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`. Statement balances are
+/// dated as the bank wrote them, so only the date part is used.
+fn parse_ofx_date(raw: &str) -> Option<NaiveDate> {
+    let date = raw.get(..8)?;
+    let rest = &raw[8..];
+    if !rest.is_empty() && !rest.starts_with(|ch: char| ch.is_ascii_digit() || ch == '[') {
+        return None;
+    }
+
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+/// OFX amounts have no thousands separator and may use either `.` or `,` as the
+/// decimal separator.
+fn parse_ofx_amount_minor(
+    raw: &str,
+    currency_code: CurrencyCode,
+) -> Result<i64, ParseAmountMinorError> {
+    parse_amount_minor_result(
+        &raw.replace(',', "."),
+        CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+        currency_code,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{
+        OfxSnapshotImportBalanceKind, OfxSnapshotImportOptionsInput, OfxSnapshotImportSourceInput,
+        candidates, inspect, parse_ofx_amount_minor, parse_ofx_date,
+    };
+    use crate::contracts::CurrencyCode;
+
+    const SGML_STATEMENT: &str = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n\n<OFX>\n<BANKMSGSRSV1>\n<STMTTRNRS>\n<STMTRS>\n<CURDEF>GBP\n<BANKACCTFROM>\n<ACCTID>12345678\n</BANKACCTFROM>\n<LEDGERBAL>\n<BALAMT>1234.56\n<DTASOF>20260131120000.000[0:GMT]\n</LEDGERBAL>\n<AVAILBAL>\n<BALAMT>1200.00\n<DTASOF>20260131\n</AVAILBAL>\n</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n";
+
+    const XML_STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220"?>
+<OFX>
+  <CREDITCARDMSGSRSV1>
+    <CCSTMTTRNRS>
+      <CCSTMTRS>
+        <CURDEF>GBP</CURDEF>
+        <LEDGERBAL><BALAMT>-250,75</BALAMT><DTASOF>20260215</DTASOF></LEDGERBAL>
+      </CCSTMTRS>
+    </CCSTMTTRNRS>
+  </CREDITCARDMSGSRSV1>
+</OFX>"#;
+
+    #[test]
+    fn inspect_reads_sgml_ledger_and_available_balances() {
+        let inspection = inspect(&source(SGML_STATEMENT)).unwrap();
+
+        assert_eq!(inspection.statement_count, 1);
+        assert_eq!(inspection.balances.len(), 2);
+        assert_eq!(
+            inspection.balances[0].kind,
+            OfxSnapshotImportBalanceKind::Ledger
+        );
+        assert_eq!(inspection.balances[0].raw_amount, "1234.56");
+        assert_eq!(inspection.balances[0].date, date(2026, 1, 31));
+        assert_eq!(inspection.balances[0].currency_code.as_deref(), Some("GBP"));
+        assert_eq!(
+            inspection.balances[1].kind,
+            OfxSnapshotImportBalanceKind::Available
+        );
+        assert_eq!(inspection.balances[1].source_row_number, 2);
+        assert_eq!(
+            inspection.guesses.balance_kind,
+            Some(OfxSnapshotImportBalanceKind::Ledger)
+        );
+    }
+
+    #[test]
+    fn candidates_use_only_the_selected_balance_kind() {
+        let ledger = candidates(
+            &source(SGML_STATEMENT),
+            &options(OfxSnapshotImportBalanceKind::Ledger),
+            CurrencyCode::GBP,
+        )
+        .unwrap();
+        let available = candidates(
+            &source(SGML_STATEMENT),
+            &options(OfxSnapshotImportBalanceKind::Available),
+            CurrencyCode::GBP,
+        )
+        .unwrap();
+
+        assert_eq!(ledger.len(), 1);
+        assert_eq!(ledger[0].balance_minor, Some(123_456));
+        assert_eq!(available.len(), 1);
+        assert_eq!(available[0].source_row_number, 2);
+        assert_eq!(available[0].balance_minor, Some(120_000));
+        assert!(ledger[0].issues.is_empty() && available[0].issues.is_empty());
+    }
+
+    #[test]
+    fn candidates_read_xml_credit_card_statements() {
+        let rows = candidates(
+            &source(XML_STATEMENT),
+            &options(OfxSnapshotImportBalanceKind::Ledger),
+            CurrencyCode::GBP,
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].date, date(2026, 2, 15));
+        assert_eq!(rows[0].balance_minor, Some(-25_075));
+    }
+
+    #[test]
+    fn candidates_flag_currency_mismatch_and_reject_missing_kind() {
+        let rows = candidates(
+            &source(XML_STATEMENT),
+            &options(OfxSnapshotImportBalanceKind::Ledger),
+            CurrencyCode::USD,
+        )
+        .unwrap();
+        let issues = candidates(
+            &source(XML_STATEMENT),
+            &options(OfxSnapshotImportBalanceKind::Available),
+            CurrencyCode::GBP,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            rows[0].issues,
+            vec!["Statement currency GBP does not match the account currency"]
+        );
+        assert_eq!(issues[0].field, "options.source.balance_kind");
+    }
+
+    #[test]
+    fn inspect_rejects_files_without_ofx_balances() {
+        let not_ofx = inspect(&source("date,balance\n2026-01-01,1.00\n")).unwrap_err();
+        let no_balances = inspect(&source("<OFX><STMTRS></STMTRS></OFX>")).unwrap_err();
+
+        assert_eq!(not_ofx[0].message, "File is not an OFX or QFX statement");
+        assert_eq!(
+            no_balances[0].message,
+            "OFX file must include a ledger or available balance"
+        );
+    }
+
+    #[test]
+    fn parse_ofx_date_accepts_times_and_offsets() {
+        assert_eq!(parse_ofx_date("20260131"), date(2026, 1, 31));
+        assert_eq!(parse_ofx_date("20260131235959"), date(2026, 1, 31));
+        assert_eq!(
+            parse_ofx_date("20260131235959.000[-5:EST]"),
+            date(2026, 1, 31)
+        );
+        assert_eq!(parse_ofx_date("20260131[0:GMT]"), date(2026, 1, 31));
+        assert_eq!(parse_ofx_date("2026-01-31"), None);
+        assert_eq!(parse_ofx_date("20261331"), None);
+        assert_eq!(parse_ofx_date("2026013"), None);
+    }
+
+    #[test]
+    fn parse_ofx_amount_minor_accepts_either_decimal_separator() {
+        assert_eq!(
+            parse_ofx_amount_minor("1234.5", CurrencyCode::GBP),
+            Ok(123_450)
+        );
+        assert_eq!(parse_ofx_amount_minor("-0,01", CurrencyCode::GBP), Ok(-1));
+        assert_eq!(parse_ofx_amount_minor("+10", CurrencyCode::JPY), Ok(10));
+        assert!(parse_ofx_amount_minor("1.234.56", CurrencyCode::GBP).is_err());
+    }
+
+    fn source(content: &str) -> OfxSnapshotImportSourceInput {
+        OfxSnapshotImportSourceInput {
+            file_name: "statement.ofx".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn options(balance_kind: OfxSnapshotImportBalanceKind) -> OfxSnapshotImportOptionsInput {
+        OfxSnapshotImportOptionsInput { balance_kind }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+}
@@ -0,0 +1,247 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::transactions::CsvTransactionsSnapshotImportAnchor;
+use super::{
+    CsvTransactionsSnapshotImportOptionsInput, SnapshotImportOptionsInput,
+    SnapshotImportSourceOptionsInput, SnapshotImportValidationIssue, issue,
+};
+
+const PROFILE_NAME_MAX_CHARS: usize = 80;
+
+/// Import options saved for an account, reapplied when a CSV with the same columns
+/// is inspected again.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SnapshotImportProfileDto {
+    pub id: i64,
+    pub name: String,
+    /// CSV column names the profile was saved against, in file order.
+    pub columns: Vec<String>,
+    pub options: SnapshotImportOptionsInput,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SnapshotImportProfileSaveInput {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub options: SnapshotImportOptionsInput,
+}
+
+/// Checks a profile before it is saved and returns it with a trimmed name, and with the
+/// overwrite confirmation and any transaction anchor balance cleared, since those belong
+/// to one import rather than to every later one.
+pub fn validate_profile(
+    input: &SnapshotImportProfileSaveInput,
+) -> Result<SnapshotImportProfileSaveInput, Vec<SnapshotImportValidationIssue>> {
+    let name = input.name.trim();
+    let mut issues = Vec::new();
+    if name.is_empty() {
+        issues.push(issue("name", "Enter a profile name"));
+    } else if name.chars().count() > PROFILE_NAME_MAX_CHARS {
+        issues.push(issue("name", "Profile name must be 80 characters or fewer"));
+    }
+
+    match csv_columns(&input.options) {
+        Some((date_column, amount_column)) => {
+            if !input.columns.iter().any(|column| column == date_column) {
+                issues.push(issue("options.source.date_column", "Select a valid column"));
+            }
+            if !input.columns.iter().any(|column| column == amount_column) {
+                issues.push(issue(
+                    "options.source.amount_column",
+                    "Select a valid column",
+                ));
+            }
+        }
+        None => issues.push(issue(
+            "options.source",
+            "Only CSV import options can be saved as a profile",
+        )),
+    }
+
+    if !issues.is_empty() {
+        return Err(issues);
+    }
+
+    let source = match &input.options.source {
+        SnapshotImportSourceOptionsInput::CsvTransactions(options) => {
+            SnapshotImportSourceOptionsInput::CsvTransactions(
+                CsvTransactionsSnapshotImportOptionsInput {
+                    csv: options.csv.clone(),
+                    anchor: CsvTransactionsSnapshotImportAnchor::Latest { balance_minor: 0 },
+                },
+            )
+        }
+        source => source.clone(),
+    };
+
+    Ok(SnapshotImportProfileSaveInput {
+        name: name.to_string(),
+        columns: input.columns.clone(),
+        options: SnapshotImportOptionsInput {
+            source,
+            overwrite_existing_confirmed: false,
+            ..input.options.clone()
+        },
+    })
+}
+
+/// First profile saved against exactly these columns; `profiles` should be ordered
+/// most recently saved first so a re-saved profile wins.
+pub fn matching_profile(
+    profiles: Vec<SnapshotImportProfileDto>,
+    columns: &[String],
+) -> Option<SnapshotImportProfileDto> {
+    profiles
+        .into_iter()
+        .find(|profile| profile.columns == columns && csv_columns(&profile.options).is_some())
+}
+
+fn csv_columns(options: &SnapshotImportOptionsInput) -> Option<(&str, &str)> {
+    let csv = match &options.source {
+        SnapshotImportSourceOptionsInput::Csv(csv) => csv,
+        SnapshotImportSourceOptionsInput::CsvTransactions(options) => &options.csv,
+        SnapshotImportSourceOptionsInput::Ofx(_) => return None,
+    };
+    Some((&csv.date_column, &csv.amount_column))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::{
+        SnapshotImportProfileDto, SnapshotImportProfileSaveInput, matching_profile,
+        validate_profile,
+    };
+    use crate::imports::snapshots::csv::{
+        CsvSnapshotImportBalanceFormat, CsvSnapshotImportBlankAmountPolicy,
+        CsvSnapshotImportMissingTimezonePolicy, CsvSnapshotImportTimestampDatePolicy,
+    };
+    use crate::imports::snapshots::ofx::{
+        OfxSnapshotImportBalanceKind, OfxSnapshotImportOptionsInput,
+    };
+    use crate::imports::snapshots::transactions::CsvTransactionsSnapshotImportAnchor;
+    use crate::imports::snapshots::{
+        CsvSnapshotImportDateFormat, CsvSnapshotImportOptionsInput,
+        CsvTransactionsSnapshotImportOptionsInput, SnapshotImportDuplicateDatePolicy,
+        SnapshotImportExistingDatePolicy, SnapshotImportOptionsInput,
+        SnapshotImportSourceOptionsInput, SnapshotImportUnchangedValuePolicy,
+    };
+
+    #[test]
+    fn validate_profile_trims_the_name_and_clears_overwrite_confirmation() {
+        let mut input = save_input("  Nationwide  ", &["Date", "Balance"]);
+        input.options.overwrite_existing_confirmed = true;
+
+        let profile = validate_profile(&input).unwrap();
+
+        assert_eq!(profile.name, "Nationwide");
+        assert!(!profile.options.overwrite_existing_confirmed);
+    }
+
+    #[test]
+    fn validate_profile_clears_the_transaction_anchor() {
+        let mut input = save_input("Card", &["Date", "Balance"]);
+        let SnapshotImportSourceOptionsInput::Csv(csv) = input.options.source else {
+            panic!("expected CSV options");
+        };
+        input.options.source = SnapshotImportSourceOptionsInput::CsvTransactions(
+            CsvTransactionsSnapshotImportOptionsInput {
+                csv,
+                anchor: CsvTransactionsSnapshotImportAnchor::OnDate {
+                    date: NaiveDate::from_ymd_opt(2026, 1, 31).unwrap(),
+                    balance_minor: 123_456,
+                },
+            },
+        );
+
+        let profile = validate_profile(&input).unwrap();
+
+        let SnapshotImportSourceOptionsInput::CsvTransactions(options) = profile.options.source
+        else {
+            panic!("expected CSV transaction options");
+        };
+        assert_eq!(
+            options.anchor,
+            CsvTransactionsSnapshotImportAnchor::Latest { balance_minor: 0 }
+        );
+        assert_eq!(options.csv.amount_column, "Balance");
+    }
+
+    #[test]
+    fn validate_profile_rejects_blank_names_unknown_columns_and_ofx_options() {
+        let issues = validate_profile(&save_input(" ", &["Date"])).unwrap_err();
+        let mut ofx = save_input("Statement", &[]);
+        ofx.options.source = SnapshotImportSourceOptionsInput::Ofx(OfxSnapshotImportOptionsInput {
+            balance_kind: OfxSnapshotImportBalanceKind::Ledger,
+        });
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| issue.field.as_str())
+                .collect::<Vec<_>>(),
+            vec!["name", "options.source.amount_column"]
+        );
+        assert_eq!(
+            validate_profile(&ofx).unwrap_err()[0].field,
+            "options.source"
+        );
+    }
+
+    #[test]
+    fn matching_profile_requires_the_same_columns_in_the_same_order() {
+        let profiles = vec![
+            profile(1, &["Balance", "Date"]),
+            profile(2, &["Date", "Balance"]),
+            profile(3, &["Date", "Balance"]),
+        ];
+        let columns = ["Date".to_string(), "Balance".to_string()];
+
+        assert_eq!(
+            matching_profile(profiles.clone(), &columns).map(|p| p.id),
+            Some(2)
+        );
+        assert!(matching_profile(profiles, &columns[..1]).is_none());
+    }
+
+    fn profile(id: i64, columns: &[&str]) -> SnapshotImportProfileDto {
+        let input = save_input("Profile", columns);
+        SnapshotImportProfileDto {
+            id,
+            name: input.name,
+            columns: input.columns,
+            options: input.options,
+            created_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    fn save_input(name: &str, columns: &[&str]) -> SnapshotImportProfileSaveInput {
+        SnapshotImportProfileSaveInput {
+            name: name.to_string(),
+            columns: columns.iter().map(ToString::to_string).collect(),
+            options: SnapshotImportOptionsInput {
+                source: SnapshotImportSourceOptionsInput::Csv(CsvSnapshotImportOptionsInput {
+                    date_column: "Date".to_string(),
+                    amount_column: "Balance".to_string(),
+                    date_format: CsvSnapshotImportDateFormat::DdMmYyyySlash,
+                    timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
+                    timestamp_missing_timezone_policy:
+                        CsvSnapshotImportMissingTimezonePolicy::Local,
+                    timestamp_missing_timezone: String::new(),
+                    balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+                    blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Error,
+                }),
+                existing_date_policy: SnapshotImportExistingDatePolicy::Overwrite,
+                unchanged_value_policy: SnapshotImportUnchangedValuePolicy::Exclude,
+                duplicate_date_policy: SnapshotImportDuplicateDatePolicy::KeepLast,
+                overwrite_existing_confirmed: false,
+            },
+        }
+    }
+}
@@ -0,0 +1,586 @@
+use calamine::{Data, DataType, Reader, open_workbook_auto_from_rs};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::Cursor;
+
+use super::csv::{
+    CandidateRow, CsvData, CsvRow, CsvSnapshotImportColumnDto, CsvSnapshotImportGuessesDto,
+    CsvSnapshotImportOptionsInput, CsvSnapshotImportSampleRowDto, ParseAmountMinorError,
+    column_dtos, guesses, normalize_headers, rows_candidates, sample_row_dtos,
+};
+use super::known_profiles::{CsvSnapshotImportKnownProfileDto, known_profile};
+use super::{
+    SnapshotImportCandidate, SnapshotImportProfileDto, SnapshotImportValidationIssue, issue,
+};
+use crate::contracts::{AppLocaleCode, CurrencyCode};
+
+/// One worksheet of a workbook, imported with the CSV options. Those options only
+/// apply to cells stored as text; date and number cells are read as they are.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SpreadsheetSnapshotImportSourceInput {
+    pub file_name: String,
+    /// Raw workbook bytes; .xlsx, .xls and .ods files are told apart by their content.
+    pub content: Vec<u8>,
+    /// Worksheet to read; `None` reads the first one.
+    pub sheet: Option<String>,
+    pub has_header_row: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SpreadsheetSnapshotImportInspectionDto {
+    pub file_name: String,
+    /// Worksheet names in workbook order.
+    pub sheets: Vec<String>,
+    /// The worksheet that was read.
+    pub sheet: String,
+    pub columns: Vec<CsvSnapshotImportColumnDto>,
+    pub sample_rows: Vec<CsvSnapshotImportSampleRowDto>,
+    pub guesses: CsvSnapshotImportGuessesDto,
+    pub total_rows: u32,
+    pub known_profile: Option<CsvSnapshotImportKnownProfileDto>,
+    pub profile: Option<Box<SnapshotImportProfileDto>>,
+}
+
+#[derive(Debug)]
+struct Worksheet {
+    sheets: Vec<String>,
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<WorksheetRow>,
+}
+
+#[derive(Debug)]
+struct WorksheetRow {
+    source_row_number: u32,
+    cells: Vec<SheetCell>,
+}
+
+/// A cell as the workbook stores it. Dates and numbers stay native so they never
+/// pass through the text formats chosen for cells stored as text.
+#[derive(Debug, Clone, PartialEq)]
+enum SheetCell {
+    Empty,
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+}
+
+impl SheetCell {
+    fn from_data(data: &Data) -> Self {
+        match data {
+            Data::Empty => Self::Empty,
+            Data::String(text) if text.trim().is_empty() => Self::Empty,
+            Data::String(text) => Self::Text(text.trim().to_string()),
+            Data::Int(number) => Self::Number(*number as f64),
+            Data::Float(number) => Self::Number(*number),
+            // Timestamps keep the date they were written with; spreadsheets store no offset.
+            Data::DateTime(date_time) if date_time.is_datetime() => data
+                .as_date()
+                .map_or_else(|| Self::Text(data.to_string()), Self::Date),
+            Data::DateTimeIso(_) => data
+                .as_date()
+                .map_or_else(|| Self::Text(data.to_string()), Self::Date),
+            _ => Self::Text(data.to_string()),
+        }
+    }
+
+    /// The cell as text, for samples, guesses and the raw values shown in previews.
+    fn text(&self) -> String {
+        match self {
+            Self::Empty => String::new(),
+            Self::Text(text) => text.clone(),
+            Self::Number(number) => number.to_string(),
+            Self::Date(date) => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+impl Worksheet {
+    /// Every cell as text, for samples and column guesses.
+    fn text_data(&self) -> CsvData {
+        self.data(SheetCell::text)
+    }
+
+    /// Only the cells stored as text, with native cells left blank, for guessing the
+    /// formats that are never applied to native cells.
+    fn stored_text_data(&self) -> CsvData {
+        self.data(|cell| match cell {
+            SheetCell::Text(text) => text.clone(),
+            _ => String::new(),
+        })
+    }
+
+    fn data(&self, cell_value: impl Fn(&SheetCell) -> String) -> CsvData {
+        CsvData {
+            columns: self.columns.clone(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| CsvRow {
+                    source_row_number: row.source_row_number,
+                    values: row.cells.iter().map(&cell_value).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn inspect(
+    input: &SpreadsheetSnapshotImportSourceInput,
+    locale: AppLocaleCode,
+) -> Result<SpreadsheetSnapshotImportInspectionDto, Vec<SnapshotImportValidationIssue>> {
+    let worksheet = read_worksheet(input)?;
+    let data = worksheet.text_data();
+    let stored_text_data = worksheet.stored_text_data();
+    let known_profile = known_profile(&data.columns);
+
+    Ok(SpreadsheetSnapshotImportInspectionDto {
+        file_name: input.file_name.clone(),
+        sheets: worksheet.sheets,
+        sheet: worksheet.name,
+        columns: column_dtos(&data),
+        sample_rows: sample_row_dtos(&data),
+        guesses: guesses(
+            &data,
+            &stored_text_data,
+            known_profile.filter(|profile| profile.guides_options()),
+            locale,
+        ),
+        total_rows: u32::try_from(data.rows.len()).expect("row count should fit in u32"),
+        known_profile: known_profile.map(|profile| profile.dto()),
+        profile: None,
+    })
+}
+
+pub fn candidates(
+    input: &SpreadsheetSnapshotImportSourceInput,
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    read_candidates(input, options, currency_code, locale, "Balance")
+}
+
+/// Reads each row as a candidate whose `balance_minor` is the signed transaction
+/// amount, for imports that derive balances from a running sum.
+pub(super) fn transaction_candidates(
+    input: &SpreadsheetSnapshotImportSourceInput,
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    read_candidates(input, options, currency_code, locale, "Amount")
+}
+
+fn read_candidates(
+    input: &SpreadsheetSnapshotImportSourceInput,
+    options: &CsvSnapshotImportOptionsInput,
+    currency_code: CurrencyCode,
+    locale: AppLocaleCode,
+    amount_label: &str,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    let worksheet = read_worksheet(input)?;
+    rows_candidates(
+        &worksheet.columns,
+        &worksheet.rows,
+        options,
+        currency_code,
+        locale,
+        amount_label,
+    )
+}
+
+impl CandidateRow for WorksheetRow {
+    fn source_row_number(&self) -> u32 {
+        self.source_row_number
+    }
+
+    fn raw(&self, index: usize) -> String {
+        self.cells
+            .get(index)
+            .map(SheetCell::text)
+            .unwrap_or_default()
+    }
+
+    fn native_date(&self, index: usize) -> Option<NaiveDate> {
+        match self.cells.get(index) {
+            Some(SheetCell::Date(date)) => Some(*date),
+            _ => None,
+        }
+    }
+
+    fn native_amount_minor(
+        &self,
+        index: usize,
+        currency_code: CurrencyCode,
+    ) -> Option<Result<i64, ParseAmountMinorError>> {
+        match self.cells.get(index) {
+            Some(SheetCell::Number(number)) => Some(number_minor(*number, currency_code)),
+            _ => None,
+        }
+    }
+}
+
+fn read_worksheet(
+    input: &SpreadsheetSnapshotImportSourceInput,
+) -> Result<Worksheet, Vec<SnapshotImportValidationIssue>> {
+    let read_error = |error: calamine::Error| {
+        vec![issue(
+            "source",
+            &format!("Could not read spreadsheet: {error}"),
+        )]
+    };
+    let mut workbook =
+        open_workbook_auto_from_rs(Cursor::new(input.content.as_slice())).map_err(read_error)?;
+    let sheets = workbook.sheet_names();
+    let name = match &input.sheet {
+        Some(sheet) if sheets.contains(sheet) => sheet.clone(),
+        Some(_) => return Err(vec![issue("source.sheet", "Select a sheet in the file")]),
+        None => sheets
+            .first()
+            .cloned()
+            .ok_or_else(|| vec![issue("source", "Spreadsheet has no sheets")])?,
+    };
+    let range = workbook.worksheet_range(&name).map_err(read_error)?;
+    // Ranges start at the first used cell, so row numbers are offset to match the sheet.
+    let first_row_number = range.start().map_or(1, |(row, _)| row + 1);
+
+    let mut rows = range.rows().enumerate().map(|(index, cells)| WorksheetRow {
+        source_row_number: first_row_number
+            + u32::try_from(index).expect("row index should fit in u32"),
+        cells: cells.iter().map(SheetCell::from_data).collect(),
+    });
+    let columns = if input.has_header_row {
+        let headers = rows
+            .next()
+            .map(|row| row.cells.iter().map(SheetCell::text).collect::<Vec<_>>())
+            .unwrap_or_default();
+        normalize_headers(headers.iter().map(String::as_str).collect())
+    } else {
+        (0..range.width())
+            .map(|index| format!("Column {}", index + 1))
+            .collect()
+    };
+    let rows = rows
+        .filter(|row| row.cells.iter().any(|cell| *cell != SheetCell::Empty))
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        return Err(vec![issue("source", "The selected sheet is empty")]);
+    }
+
+    Ok(Worksheet {
+        sheets,
+        name,
+        columns,
+        rows,
+    })
+}
+
+/// Scales a number cell to minor units, rounding away the float error a stored
+/// value such as 0.1 carries.
+fn number_minor(number: f64, currency_code: CurrencyCode) -> Result<i64, ParseAmountMinorError> {
+    let minor = (number * 10_f64.powi(currency_code.minor_unit_exponent() as i32)).round();
+    if !minor.is_finite() {
+        return Err(ParseAmountMinorError::Invalid);
+    }
+    if minor.abs() > currency_code.balance_minor_abs_max() as f64 {
+        return Err(ParseAmountMinorError::TooLarge);
+    }
+
+    Ok(minor as i64)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use chrono::NaiveDate;
+    use std::io::{Cursor, Write};
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    use super::{SpreadsheetSnapshotImportSourceInput, candidates, inspect};
+    use crate::contracts::{AppLocaleCode, CurrencyCode};
+    use crate::imports::snapshots::csv::{
+        CsvSnapshotImportBalanceFormat, CsvSnapshotImportBlankAmountPolicy,
+        CsvSnapshotImportMissingTimezonePolicy, CsvSnapshotImportTimestampDatePolicy,
+    };
+    use crate::imports::snapshots::{CsvSnapshotImportDateFormat, CsvSnapshotImportOptionsInput};
+
+    // Style 1 applies built-in number format 14, the short date format.
+    const BALANCES_SHEET: &str = r#"
+        <row r="1">
+            <c r="A1" t="inlineStr"><is><t>Date</t></is></c>
+            <c r="B1" t="inlineStr"><is><t>Balance</t></is></c>
+            <c r="C1" t="inlineStr"><is><t>Note</t></is></c>
+        </row>
+        <row r="2">
+            <c r="A2" s="1"><v>45294</v></c>
+            <c r="B2"><v>1234.5</v></c>
+            <c r="C2" t="inlineStr"><is><t>opening</t></is></c>
+        </row>
+        <row r="3">
+            <c r="A3" t="inlineStr"><is><t>13/01/2024</t></is></c>
+            <c r="B3" t="inlineStr"><is><t>1,300.25</t></is></c>
+        </row>
+        <row r="5">
+            <c r="A5" s="1"><v>45296.75</v></c>
+            <c r="B5"><v>1E+20</v></c>
+        </row>
+    "#;
+
+    #[test]
+    fn inspect_lists_sheets_and_reads_the_chosen_one() {
+        let content = xlsx(&[
+            (
+                "Summary",
+                r#"<row r="1"><c r="A1" t="inlineStr"><is><t>Totals</t></is></c></row>"#,
+            ),
+            ("Balances", BALANCES_SHEET),
+        ]);
+
+        let first = inspect(&source(content.clone(), None), AppLocaleCode::EnGb).unwrap();
+        let balances = inspect(&source(content, Some("Balances")), AppLocaleCode::EnGb).unwrap();
+
+        assert_eq!(first.sheets, vec!["Summary", "Balances"]);
+        assert_eq!(first.sheet, "Summary");
+        assert_eq!(balances.sheet, "Balances");
+        assert_eq!(
+            balances
+                .columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Date", "Balance", "Note"]
+        );
+        assert_eq!(
+            balances
+                .sample_rows
+                .iter()
+                .map(|row| (row.source_row_number, row.values.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    2,
+                    vec!["2024-01-03".into(), "1234.5".into(), "opening".into()]
+                ),
+                (
+                    3,
+                    vec!["13/01/2024".into(), "1,300.25".into(), String::new()]
+                ),
+                (
+                    5,
+                    vec![
+                        "2024-01-05".into(),
+                        "100000000000000000000".into(),
+                        String::new()
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(balances.guesses.date_column.as_deref(), Some("Date"));
+        assert_eq!(balances.guesses.amount_column.as_deref(), Some("Balance"));
+        // Only "13/01/2024" is stored as text; the native dates need no format.
+        assert_eq!(
+            balances.guesses.date_format,
+            Some(CsvSnapshotImportDateFormat::DdMmYyyySlash)
+        );
+        assert_eq!(
+            balances.guesses.balance_format,
+            Some(CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot)
+        );
+    }
+
+    #[test]
+    fn inspect_rejects_a_sheet_that_is_not_in_the_file() {
+        let content = xlsx(&[("Balances", BALANCES_SHEET)]);
+
+        let issues = inspect(&source(content, Some("Sheet9")), AppLocaleCode::EnGb).unwrap_err();
+
+        assert_eq!(issues[0].field, "source.sheet");
+    }
+
+    #[test]
+    fn candidates_read_native_cells_directly_and_text_cells_with_the_options() {
+        let xlsx_rows = candidates(
+            &source(xlsx(&[("Balances", BALANCES_SHEET)]), None),
+            &options(),
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+        let ods_rows = candidates(
+            &source(
+                // Real files have no whitespace between cells, which the reader rejects.
+                ods(concat!(
+                    "<table:table-row>",
+                    r#"<table:table-cell office:value-type="string"><text:p>Date</text:p></table:table-cell>"#,
+                    r#"<table:table-cell office:value-type="string"><text:p>Balance</text:p></table:table-cell>"#,
+                    "</table:table-row><table:table-row>",
+                    r#"<table:table-cell office:value-type="date" office:date-value="2024-01-03"><text:p>03/01/24</text:p></table:table-cell>"#,
+                    r#"<table:table-cell office:value-type="currency" office:currency="GBP" office:value="0.1"><text:p>£0.10</text:p></table:table-cell>"#,
+                    "</table:table-row>",
+                )),
+                None,
+            ),
+            &options(),
+            CurrencyCode::GBP,
+            AppLocaleCode::EnGb,
+        )
+        .unwrap();
+
+        assert_eq!(
+            xlsx_rows
+                .iter()
+                .map(|row| (
+                    row.source_row_number,
+                    row.date,
+                    row.balance_minor,
+                    row.issues.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, date(2024, 1, 3), Some(123_450), Vec::new()),
+                (3, date(2024, 1, 13), Some(130_025), Vec::new()),
+                (
+                    5,
+                    date(2024, 1, 5),
+                    None,
+                    vec!["Balance is too large".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(ods_rows[0].date, date(2024, 1, 3));
+        assert_eq!(ods_rows[0].balance_minor, Some(10));
+    }
+
+    fn source(content: Vec<u8>, sheet: Option<&str>) -> SpreadsheetSnapshotImportSourceInput {
+        SpreadsheetSnapshotImportSourceInput {
+            file_name: "balances.xlsx".to_string(),
+            content,
+            sheet: sheet.map(ToString::to_string),
+            has_header_row: true,
+        }
+    }
+
+    fn options() -> CsvSnapshotImportOptionsInput {
+        CsvSnapshotImportOptionsInput {
+            date_column: "Date".to_string(),
+            amount_column: "Balance".to_string(),
+            date_format: CsvSnapshotImportDateFormat::DdMmYyyySlash,
+            timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
+            timestamp_missing_timezone_policy: CsvSnapshotImportMissingTimezonePolicy::Local,
+            timestamp_missing_timezone: String::new(),
+            balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+            blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Error,
+        }
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    pub(crate) fn xlsx(sheets: &[(&str, &str)]) -> Vec<u8> {
+        let sheet_entries = sheets
+            .iter()
+            .enumerate()
+            .map(|(index, (name, _))| {
+                format!(
+                    r#"<sheet name="{name}" sheetId="{id}" r:id="rId{id}"/>"#,
+                    id = index + 1
+                )
+            })
+            .collect::<String>();
+        let sheet_relationships = sheets
+            .iter()
+            .enumerate()
+            .map(|(index, _)| {
+                format!(
+                    r#"<Relationship Id="rId{id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{id}.xml"/>"#,
+                    id = index + 1
+                )
+            })
+            .collect::<String>();
+        let sheet_overrides = sheets
+            .iter()
+            .enumerate()
+            .map(|(index, _)| {
+                format!(
+                    r#"<Override PartName="/xl/worksheets/sheet{id}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                    id = index + 1
+                )
+            })
+            .collect::<String>();
+
+        let mut files = vec![
+            (
+                "[Content_Types].xml".to_string(),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>{sheet_overrides}</Types>"#
+                ),
+            ),
+            (
+                "_rels/.rels".to_string(),
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/workbook.xml".to_string(),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>{sheet_entries}</sheets></workbook>"#
+                ),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels".to_string(),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{sheet_relationships}<Relationship Id="rIdStyles" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#
+                ),
+            ),
+            (
+                "xl/styles.xml".to_string(),
+                r#"<?xml version="1.0" encoding="UTF-8"?><styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><cellXfs count="2"><xf numFmtId="0"/><xf numFmtId="14" applyNumberFormat="1"/></cellXfs></styleSheet>"#.to_string(),
+            ),
+        ];
+        files.extend(sheets.iter().enumerate().map(|(index, (_, rows))| {
+            (
+                format!("xl/worksheets/sheet{}.xml", index + 1),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>{rows}</sheetData></worksheet>"#
+                ),
+            )
+        }));
+        zip(&files)
+    }
+
+    fn ods(rows: &str) -> Vec<u8> {
+        zip(&[
+            (
+                "mimetype".to_string(),
+                "application/vnd.oasis.opendocument.spreadsheet".to_string(),
+            ),
+            (
+                "META-INF/manifest.xml".to_string(),
+                r#"<?xml version="1.0" encoding="UTF-8"?><manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2"><manifest:file-entry manifest:full-path="/" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#.to_string(),
+            ),
+            (
+                "content.xml".to_string(),
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2"><office:body><office:spreadsheet><table:table table:name="Balances">{rows}</table:table></office:spreadsheet></office:body></office:document-content>"#
+                ),
+            ),
+        ])
+    }
+
+    fn zip(files: &[(String, String)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(
+                    name.as_str(),
+                    SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored),
+                )
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+}
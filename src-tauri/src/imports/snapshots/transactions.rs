@@ -0,0 +1,299 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use super::{
+    CsvSnapshotImportOptionsInput, SnapshotImportCandidate, SnapshotImportValidationIssue, issue,
+};
+use crate::contracts::CurrencyCode;
+
+/// Options for a CSV or spreadsheet of transactions with no running-balance column.
+/// Balances are derived by summing the amount column away from one known balance.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CsvTransactionsSnapshotImportOptionsInput {
+    /// Column and format options; `amount_column` holds signed transaction amounts.
+    pub csv: CsvSnapshotImportOptionsInput,
+    pub anchor: CsvTransactionsSnapshotImportAnchor,
+}
+
+/// The known end-of-day balance that derived balances are summed from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CsvTransactionsSnapshotImportAnchor {
+    /// Balance at the end of the latest transaction date; earlier dates walk backwards.
+    Latest { balance_minor: i64 },
+    /// Balance at the end of `date`; later dates walk forwards and earlier dates backwards.
+    OnDate { date: NaiveDate, balance_minor: i64 },
+}
+
+impl CsvTransactionsSnapshotImportAnchor {
+    fn balance_minor(self) -> i64 {
+        match self {
+            Self::Latest { balance_minor } | Self::OnDate { balance_minor, .. } => balance_minor,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TransactionDay {
+    source_row_number: u32,
+    raw_date: String,
+    raw_amounts: Vec<String>,
+    net_minor: i128,
+}
+
+/// Turns transaction rows, whose `balance_minor` is the signed amount, into one
+/// end-of-day balance candidate per transaction date, in source order of each
+/// date's last row. Rows that cannot be read are returned as they are, so the plan
+/// reports them as invalid instead of silently shifting every balance. Rows skipped for
+/// a blank amount are returned too, so they count as skipped like they do for balances.
+pub fn candidates(
+    transactions: Vec<SnapshotImportCandidate>,
+    anchor: CsvTransactionsSnapshotImportAnchor,
+    currency_code: CurrencyCode,
+) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+    let balance_minor_abs_max = currency_code.balance_minor_abs_max();
+    let anchor_balance_minor = anchor.balance_minor();
+    if anchor_balance_minor.abs() > balance_minor_abs_max {
+        return Err(vec![issue(
+            "options.source.anchor.balance_minor",
+            "Balance is too large",
+        )]);
+    }
+
+    let mut candidates = Vec::new();
+    let mut days = BTreeMap::<NaiveDate, TransactionDay>::new();
+    for row in transactions {
+        if !row.issues.is_empty() || row.skip_blank_amount {
+            candidates.push(row);
+            continue;
+        }
+        let (Some(date), Some(amount_minor)) = (row.date, row.balance_minor) else {
+            continue;
+        };
+
+        let day = days.entry(date).or_insert_with(|| TransactionDay {
+            source_row_number: row.source_row_number,
+            raw_date: row.raw_date.clone(),
+            raw_amounts: Vec::new(),
+            net_minor: 0,
+        });
+        if row.source_row_number >= day.source_row_number {
+            day.source_row_number = row.source_row_number;
+            day.raw_date = row.raw_date;
+        }
+        day.raw_amounts.push(row.raw_amount);
+        day.net_minor += i128::from(amount_minor);
+    }
+
+    let anchor_date = match anchor {
+        CsvTransactionsSnapshotImportAnchor::Latest { .. } => days.keys().next_back().copied(),
+        CsvTransactionsSnapshotImportAnchor::OnDate { date, .. } => Some(date),
+    };
+    if let Some(anchor_date) = anchor_date {
+        let mut balance_minor = i128::from(anchor_balance_minor);
+        for (date, day) in days.range(..=anchor_date).rev() {
+            candidates.push(day_candidate(
+                *date,
+                day,
+                balance_minor,
+                balance_minor_abs_max,
+            ));
+            balance_minor -= day.net_minor;
+        }
+
+        let mut balance_minor = i128::from(anchor_balance_minor);
+        for (date, day) in days.range((Bound::Excluded(anchor_date), Bound::Unbounded)) {
+            balance_minor += day.net_minor;
+            candidates.push(day_candidate(
+                *date,
+                day,
+                balance_minor,
+                balance_minor_abs_max,
+            ));
+        }
+    }
+
+    candidates.sort_by_key(|candidate| candidate.source_row_number);
+    Ok(candidates)
+}
+
+fn day_candidate(
+    date: NaiveDate,
+    day: &TransactionDay,
+    balance_minor: i128,
+    balance_minor_abs_max: i64,
+) -> SnapshotImportCandidate {
+    let balance_minor = i64::try_from(balance_minor)
+        .ok()
+        .filter(|balance_minor| balance_minor.abs() <= balance_minor_abs_max);
+
+    SnapshotImportCandidate {
+        source_row_number: day.source_row_number,
+        raw_date: day.raw_date.clone(),
+        raw_amount: day.raw_amounts.join(", "),
+        date: Some(date),
+        balance_minor,
+        issues: if balance_minor.is_none() {
+            vec!["Balance is too large".to_string()]
+        } else {
+            Vec::new()
+        },
+        skip_blank_amount: false,
+        skip_duplicate: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::CsvTransactionsSnapshotImportAnchor;
+    use crate::contracts::{AppLocaleCode, BALANCE_MINOR_ABS_MAX, CurrencyCode};
+    use crate::imports::snapshots::csv::{
+        CsvSnapshotImportBalanceFormat, CsvSnapshotImportBlankAmountPolicy,
+        CsvSnapshotImportMissingTimezonePolicy, CsvSnapshotImportTimestampDatePolicy,
+        transaction_candidates,
+    };
+    use crate::imports::snapshots::{
+        CsvSnapshotImportDateFormat, CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput,
+        SnapshotImportCandidate, SnapshotImportValidationIssue,
+    };
+
+    #[test]
+    fn candidates_walk_backwards_from_the_latest_balance() {
+        let rows = candidates(
+            "date,amount\n2026-01-03,-5.00\n2026-01-03,-2.50\n2026-01-02,20.00\n2026-01-01,-1.00\n",
+            CsvTransactionsSnapshotImportAnchor::Latest {
+                balance_minor: 10_000,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            balances(&rows),
+            vec![
+                (3, date(2026, 1, 3), Some(10_000)),
+                (4, date(2026, 1, 2), Some(10_750)),
+                (5, date(2026, 1, 1), Some(8_750)),
+            ]
+        );
+        assert_eq!(rows[0].raw_amount, "-5.00, -2.50");
+    }
+
+    #[test]
+    fn candidates_walk_both_ways_from_a_dated_balance() {
+        let rows = candidates(
+            "date,amount\n2026-01-01,1.00\n2026-01-02,2.00\n2026-01-04,4.00\n",
+            CsvTransactionsSnapshotImportAnchor::OnDate {
+                date: date(2026, 1, 3).unwrap(),
+                balance_minor: 1_000,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            balances(&rows),
+            vec![
+                (2, date(2026, 1, 1), Some(800)),
+                (3, date(2026, 1, 2), Some(1_000)),
+                (4, date(2026, 1, 4), Some(1_400)),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_keep_unreadable_and_blank_amount_rows() {
+        let rows = candidates(
+            "date,amount\n2026-01-01,1.00\nnot a date,2.00\n2026-01-02,\n2026-01-02,abc\n",
+            CsvTransactionsSnapshotImportAnchor::Latest { balance_minor: 0 },
+        )
+        .unwrap();
+
+        assert_eq!(
+            rows.iter()
+                .map(|row| (
+                    row.source_row_number,
+                    row.skip_blank_amount,
+                    row.issues.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, false, Vec::new()),
+                (
+                    3,
+                    false,
+                    vec!["Date does not match the selected format".to_string()]
+                ),
+                (4, true, Vec::new()),
+                (
+                    5,
+                    false,
+                    vec!["Amount does not match the selected format".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_reject_anchor_and_derived_balances_over_the_limit() {
+        let anchor = candidates(
+            "date,amount\n2026-01-01,1.00\n",
+            CsvTransactionsSnapshotImportAnchor::Latest {
+                balance_minor: BALANCE_MINOR_ABS_MAX + 1,
+            },
+        )
+        .unwrap_err();
+        let derived = candidates(
+            "date,amount\n2026-01-01,1.00\n2026-01-02,-1.00\n",
+            CsvTransactionsSnapshotImportAnchor::Latest {
+                balance_minor: BALANCE_MINOR_ABS_MAX,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(anchor[0].field, "options.source.anchor.balance_minor");
+        assert_eq!(derived[0].balance_minor, None);
+        assert_eq!(derived[0].issues, vec!["Balance is too large"]);
+        assert_eq!(derived[1].balance_minor, Some(BALANCE_MINOR_ABS_MAX));
+    }
+
+    fn balances(rows: &[SnapshotImportCandidate]) -> Vec<(u32, Option<NaiveDate>, Option<i64>)> {
+        rows.iter()
+            .map(|row| (row.source_row_number, row.date, row.balance_minor))
+            .collect()
+    }
+
+    fn candidates(
+        content: &str,
+        anchor: CsvTransactionsSnapshotImportAnchor,
+    ) -> Result<Vec<SnapshotImportCandidate>, Vec<SnapshotImportValidationIssue>> {
+        let source = CsvSnapshotImportSourceInput {
+            file_name: "transactions.csv".to_string(),
+            content: content.as_bytes().to_vec(),
+            has_header_row: true,
+            encoding: None,
+            delimiter: None,
+            quote: None,
+        };
+        let options = CsvSnapshotImportOptionsInput {
+            date_column: "date".to_string(),
+            amount_column: "amount".to_string(),
+            date_format: CsvSnapshotImportDateFormat::YyyyMmDd,
+            timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
+            timestamp_missing_timezone_policy: CsvSnapshotImportMissingTimezonePolicy::Local,
+            timestamp_missing_timezone: String::new(),
+            balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+            blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Skip,
+        };
+        let transactions =
+            transaction_candidates(&source, &options, CurrencyCode::GBP, AppLocaleCode::EnGb)?;
+        super::candidates(transactions, anchor, CurrencyCode::GBP)
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+}
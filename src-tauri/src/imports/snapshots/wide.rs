@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+
+use super::csv::{
+    CsvSnapshotImportBalanceFormat, CsvSnapshotImportBlankAmountPolicy,
+    CsvSnapshotImportMissingTimezonePolicy, CsvSnapshotImportTimestampDatePolicy,
+};
+use super::{
+    CsvSnapshotImportDateFormat, CsvSnapshotImportOptionsInput, CsvSnapshotImportSourceInput,
+    SnapshotImportCommitDto, SnapshotImportDuplicateDatePolicy, SnapshotImportExistingDatePolicy,
+    SnapshotImportOptionsInput, SnapshotImportPreviewDto, SnapshotImportPreviewSummaryDto,
+    SnapshotImportSourceOptionsInput, SnapshotImportUnchangedValuePolicy,
+    SnapshotImportValidationIssue, issue,
+};
+use crate::contracts::{AccountUpsertInput, AppLocaleCode, CurrencyCode};
+
+/// Options for a "dates down the side, one column per account" CSV. Every mapped
+/// column is planned as its own single-account import with the shared policies.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportOptionsInput {
+    pub date_column: String,
+    pub date_format: CsvSnapshotImportDateFormat,
+    pub balance_format: CsvSnapshotImportBalanceFormat,
+    pub blank_amount_policy: CsvSnapshotImportBlankAmountPolicy,
+    /// Columns to import; unmapped columns are ignored.
+    pub columns: Vec<WideSnapshotImportColumnInput>,
+    pub existing_date_policy: SnapshotImportExistingDatePolicy,
+    pub unchanged_value_policy: SnapshotImportUnchangedValuePolicy,
+    pub duplicate_date_policy: SnapshotImportDuplicateDatePolicy,
+    pub overwrite_existing_confirmed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportColumnInput {
+    pub column: String,
+    pub target: WideSnapshotImportTarget,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WideSnapshotImportTarget {
+    Existing { account_id: i64 },
+    New { input: AccountUpsertInput },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportAccountPreviewDto {
+    pub column: String,
+    /// `None` for accounts the commit will create.
+    pub account_id: Option<i64>,
+    pub account_name: String,
+    pub currency_code: CurrencyCode,
+    pub preview: SnapshotImportPreviewDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportPreviewDto {
+    pub summary: SnapshotImportPreviewSummaryDto,
+    pub accounts: Vec<WideSnapshotImportAccountPreviewDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportAccountCommitDto {
+    pub column: String,
+    pub account_id: i64,
+    pub created_account: bool,
+    pub result: SnapshotImportCommitDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct WideSnapshotImportCommitDto {
+    pub accounts: Vec<WideSnapshotImportAccountCommitDto>,
+}
+
+/// Checks the column mapping against the file before any account is looked up.
+pub fn validate_wide_columns(
+    input: &CsvSnapshotImportSourceInput,
+    options: &WideSnapshotImportOptionsInput,
+) -> Result<(), Vec<SnapshotImportValidationIssue>> {
+    if !input.has_header_row {
+        return Err(vec![issue(
+            "source",
+            "Spreadsheet imports need a header row naming each account column",
+        )]);
+    }
+
+    // Only the column names are read here, and those do not depend on the locale.
+    let columns = super::csv::inspect(input, AppLocaleCode::System)?
+        .columns
+        .into_iter()
+        .map(|column| column.name)
+        .collect::<HashSet<_>>();
+    let mut issues = Vec::new();
+
+    if !columns.contains(&options.date_column) {
+        issues.push(issue("options.date_column", "Select a valid column"));
+    }
+    if options.columns.is_empty() {
+        issues.push(issue(
+            "options.columns",
+            "Map at least one column to an account",
+        ));
+    }
+
+    let mut seen_columns = HashSet::new();
+    let mut seen_accounts = HashSet::new();
+    for (index, mapping) in options.columns.iter().enumerate() {
+        let field = format!("options.columns.{index}.column");
+        if !columns.contains(&mapping.column) {
+            issues.push(issue(&field, "Select a valid column"));
+        } else if mapping.column == options.date_column {
+            issues.push(issue(
+                &field,
+                "The date column cannot also be imported as an account",
+            ));
+        } else if !seen_columns.insert(mapping.column.as_str()) {
+            issues.push(issue(&field, "Each column can only be imported once"));
+        }
+
+        if let WideSnapshotImportTarget::Existing { account_id } = mapping.target
+            && !seen_accounts.insert(account_id)
+        {
+            issues.push(issue(
+                &format!("options.columns.{index}.target.account_id"),
+                "Each account can only be imported from one column",
+            ));
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Options that plan one mapped column as an ordinary single-account CSV import.
+pub fn wide_column_options(
+    options: &WideSnapshotImportOptionsInput,
+    column: &str,
+) -> SnapshotImportOptionsInput {
+    SnapshotImportOptionsInput {
+        source: SnapshotImportSourceOptionsInput::Csv(CsvSnapshotImportOptionsInput {
+            date_column: options.date_column.clone(),
+            amount_column: column.to_string(),
+            date_format: options.date_format,
+            timestamp_date_policy: CsvSnapshotImportTimestampDatePolicy::DateAsWritten,
+            timestamp_missing_timezone_policy: CsvSnapshotImportMissingTimezonePolicy::Local,
+            timestamp_missing_timezone: String::new(),
+            balance_format: options.balance_format,
+            blank_amount_policy: options.blank_amount_policy,
+        }),
+        existing_date_policy: options.existing_date_policy,
+        unchanged_value_policy: options.unchanged_value_policy,
+        duplicate_date_policy: options.duplicate_date_policy,
+        overwrite_existing_confirmed: options.overwrite_existing_confirmed,
+    }
+}
+
+pub fn wide_preview_summary<'a>(
+    previews: impl IntoIterator<Item = &'a SnapshotImportPreviewDto>,
+) -> SnapshotImportPreviewSummaryDto {
+    previews.into_iter().fold(
+        SnapshotImportPreviewSummaryDto {
+            total_rows: 0,
+            create_count: 0,
+            overwrite_count: 0,
+            skip_count: 0,
+            invalid_count: 0,
+        },
+        |total, preview| SnapshotImportPreviewSummaryDto {
+            total_rows: total.total_rows + preview.summary.total_rows,
+            create_count: total.create_count + preview.summary.create_count,
+            overwrite_count: total.overwrite_count + preview.summary.overwrite_count,
+            skip_count: total.skip_count + preview.summary.skip_count,
+            invalid_count: total.invalid_count + preview.summary.invalid_count,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        WideSnapshotImportColumnInput, WideSnapshotImportOptionsInput, WideSnapshotImportTarget,
+        validate_wide_columns, wide_column_options,
+    };
+    use crate::imports::snapshots::csv::{
+        CsvSnapshotImportBalanceFormat, CsvSnapshotImportBlankAmountPolicy,
+    };
+    use crate::imports::snapshots::{
+        CsvSnapshotImportDateFormat, CsvSnapshotImportSourceInput,
+        SnapshotImportDuplicateDatePolicy, SnapshotImportExistingDatePolicy,
+        SnapshotImportSourceOptionsInput, SnapshotImportUnchangedValuePolicy,
+    };
+
+    const CONTENT: &str = "Date,Current,Savings\n2026-01-01,1.00,2.00\n";
+
+    #[test]
+    fn validate_wide_columns_accepts_distinct_columns_and_accounts() {
+        let options = options(vec![existing("Current", 1), existing("Savings", 2)]);
+
+        assert!(validate_wide_columns(&source(CONTENT, true), &options).is_ok());
+    }
+
+    #[test]
+    fn validate_wide_columns_rejects_unknown_repeated_and_date_columns() {
+        let options = options(vec![
+            existing("Current", 1),
+            existing("Current", 2),
+            existing("Date", 3),
+            existing("Missing", 4),
+            existing("Savings", 1),
+        ]);
+
+        let issues = validate_wide_columns(&source(CONTENT, true), &options).unwrap_err();
+        let fields = issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.message.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "options.columns.1.column",
+                    "Each column can only be imported once"
+                ),
+                (
+                    "options.columns.2.column",
+                    "The date column cannot also be imported as an account"
+                ),
+                ("options.columns.3.column", "Select a valid column"),
+                (
+                    "options.columns.4.target.account_id",
+                    "Each account can only be imported from one column"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_wide_columns_requires_a_header_row_and_a_mapping() {
+        let headerless = validate_wide_columns(
+            &source(CONTENT, false),
+            &options(vec![existing("Column 2", 1)]),
+        )
+        .unwrap_err();
+        let unmapped =
+            validate_wide_columns(&source(CONTENT, true), &options(Vec::new())).unwrap_err();
+
+        assert_eq!(headerless[0].field, "source");
+        assert_eq!(unmapped[0].field, "options.columns");
+    }
+
+    #[test]
+    fn column_options_plan_the_column_as_the_amount() {
+        let options = options(vec![existing("Savings", 2)]);
+
+        let column = wide_column_options(&options, "Savings");
+
+        let SnapshotImportSourceOptionsInput::Csv(csv) = column.source else {
+            panic!("expected CSV options");
+        };
+        assert_eq!(csv.date_column, "Date");
+        assert_eq!(csv.amount_column, "Savings");
+        assert_eq!(
+            csv.blank_amount_policy,
+            CsvSnapshotImportBlankAmountPolicy::Skip
+        );
+        assert_eq!(
+            column.duplicate_date_policy,
+            SnapshotImportDuplicateDatePolicy::Error
+        );
+    }
+
+    fn source(content: &str, has_header_row: bool) -> CsvSnapshotImportSourceInput {
+        CsvSnapshotImportSourceInput {
+            file_name: "balances.csv".to_string(),
+            content: content.as_bytes().to_vec(),
+            has_header_row,
+            encoding: None,
+            delimiter: None,
+            quote: None,
+        }
+    }
+
+    fn existing(column: &str, account_id: i64) -> WideSnapshotImportColumnInput {
+        WideSnapshotImportColumnInput {
+            column: column.to_string(),
+            target: WideSnapshotImportTarget::Existing { account_id },
+        }
+    }
+
+    fn options(columns: Vec<WideSnapshotImportColumnInput>) -> WideSnapshotImportOptionsInput {
+        WideSnapshotImportOptionsInput {
+            date_column: "Date".to_string(),
+            date_format: CsvSnapshotImportDateFormat::YyyyMmDd,
+            balance_format: CsvSnapshotImportBalanceFormat::ThousandsCommaDecimalDot,
+            blank_amount_policy: CsvSnapshotImportBlankAmountPolicy::Skip,
+            columns,
+            existing_date_policy: SnapshotImportExistingDatePolicy::Overwrite,
+            unchanged_value_policy: SnapshotImportUnchangedValuePolicy::Exclude,
+            duplicate_date_policy: SnapshotImportDuplicateDatePolicy::Error,
+            overwrite_existing_confirmed: false,
+        }
+    }
+}
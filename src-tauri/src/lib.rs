@@ -1,15 +1,20 @@
 extern crate self as worth_lib;
 
 pub mod api;
+mod backups;
 pub mod contracts;
-mod db;
+pub mod db;
+pub mod doctor;
+mod exports;
+mod fx;
 mod imports;
 mod state;
 mod updates;
 
 pub use worth_macros::export_schema;
 
-use state::AppState;
+use backups::ScheduledBackupStatus;
+use state::{AppState, DatabaseLock};
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -41,16 +46,19 @@ pub fn run() {
         .setup(|app| {
             let handle = app.handle().clone();
             let updates = updates::AppUpdateManager::new(app.package_info().version.to_string());
+            app.manage(updates.clone());
+            app.manage(DatabaseLock::default());
+            app.manage(ScheduledBackupStatus::default());
             tauri::async_runtime::block_on(async move {
-                let pool = db::init_pool(&handle).await?;
-                sqlx::migrate!("./db/migrations")
-                    .run(&pool)
-                    .await
-                    .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))?;
-                handle.manage(AppState {
-                    pool,
-                    updates: updates.clone(),
-                });
+                // An encrypted database stays closed until `database_unlock` is given its
+                // passphrase, so only commands that do not need it work until then.
+                let db_path = db::database_path(&handle)?;
+                if !db::encryption::is_encrypted(&db_path)? {
+                    let pool = db::init_pool(&handle).await?;
+                    AppState::start(&handle, pool, &db_path)
+                        .await
+                        .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))?;
+                }
                 updates.check_on_startup(handle.clone());
                 Ok::<(), tauri::Error>(())
             })?;
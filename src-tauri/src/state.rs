@@ -1,9 +1,71 @@
+use chrono::Utc;
 use sqlx::SqlitePool;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
 
-use crate::updates::AppUpdateManager;
+use crate::db;
+use crate::db::migrate::MigrationError;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: SqlitePool,
-    pub updates: AppUpdateManager,
+    /// Applied migrations this build does not know, from a newer build. While there are
+    /// any, `pool` is read-only, migrations have not run, and no backups are scheduled.
+    pub incompatible_migrations: Vec<i64>,
+}
+
+impl AppState {
+    /// Migrates the open database at `db_path`, starts the backup schedule, and manages the
+    /// state so database commands can run.
+    ///
+    /// A database migrated by a newer build is reopened read-only instead, so this build
+    /// can show its data without writing anything it may not understand.
+    pub async fn start(
+        app: &AppHandle,
+        pool: SqlitePool,
+        db_path: &Path,
+    ) -> Result<(), MigrationError> {
+        let mut conn = pool.acquire().await?;
+        let incompatible_migrations = db::incompatible_migrations(&mut conn, "main").await?;
+        drop(conn);
+
+        if !incompatible_migrations.is_empty() {
+            let pool = db::reopen_pool(&pool, true).await?;
+            app.manage(AppState {
+                pool,
+                incompatible_migrations,
+            });
+            return Ok(());
+        }
+
+        let default_backups_dir = crate::backups::default_backups_dir(app)
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        db::migrate::run(
+            &db::MIGRATOR,
+            &pool,
+            db_path,
+            &default_backups_dir,
+            &app.package_info().version.to_string(),
+            Utc::now(),
+        )
+        .await?;
+        app.manage(AppState {
+            pool,
+            incompatible_migrations,
+        });
+        crate::backups::start_schedule(app.clone());
+        Ok(())
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        !self.incompatible_migrations.is_empty()
+    }
+}
+
+/// Managed from startup, before [`AppState`] exists for an encrypted database.
+#[derive(Default)]
+pub struct DatabaseLock {
+    /// Held while an unlock attempt opens the database, so two attempts cannot both
+    /// manage an [`AppState`].
+    pub unlocking: tokio::sync::Mutex<()>,
 }